@@ -0,0 +1,248 @@
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::app::AppState;
+use crate::load_report::{LoadReportBuilder, ReportFilters};
+use crate::response::{CommandResult, Envelope};
+use crate::scheduler;
+use crate::utils::random_hex;
+use crate::workspace::WorkspaceFilters;
+
+const DEFAULT_PORT: u16 = 7878;
+/// Request bodies larger than this are rejected.
+const MAX_BODY: u64 = 1 << 20;
+const DEFAULT_SCAN_LIMIT: usize = 200;
+
+// The running server, if any; at most one per app instance
+static SERVER: Lazy<Mutex<Option<RunningServer>>> = Lazy::new(|| Mutex::new(None));
+
+struct RunningServer {
+    server: Arc<Server>,
+    info: ApiServerInfo,
+}
+
+/// Where the local API listens and the bearer token scripts must send.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiServerInfo {
+    pub url: String,
+    pub token: String,
+}
+
+/// Body of `POST /api/scan`: a one-off filtered read of a connection's topic.
+#[derive(Debug, Default, Deserialize)]
+struct ScanArgs {
+    /// Connection to read with; the active one when omitted
+    connection: Option<String>,
+    /// Topic override; the connection's topic when omitted
+    topic: Option<String>,
+    #[serde(flatten)]
+    filters: WorkspaceFilters,
+    limit: Option<usize>,
+}
+
+struct ApiError(u16, String);
+
+impl From<String> for ApiError {
+    fn from(e: String) -> Self {
+        ApiError(500, e)
+    }
+}
+
+fn json_response(status: u16, body: &Value) -> Response<std::io::Cursor<Vec<u8>>> {
+    text_response(status, body.to_string(), "application/json")
+}
+
+fn text_response(status: u16, body: String, content_type: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    let mut resp = Response::from_string(body).with_status_code(status);
+    if let Ok(h) = Header::from_bytes("Content-Type", content_type) {
+        resp.add_header(h);
+    }
+    resp
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Value, ApiError> {
+    serde_json::to_value(value).map_err(|e| ApiError(500, format!("Failed to serialize response: {e}")))
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == name)
+        .map(|(_, v)| v)
+}
+
+fn respond(app: &AppHandle, token: &str, mut request: Request) {
+    let expected = format!("Bearer {token}");
+    let authorized = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("Authorization") && h.value.as_str() == expected);
+    if !authorized {
+        let _ = request.respond(json_response(401, &json!({ "error": "Missing or invalid bearer token" })));
+        return;
+    }
+    let mut body = String::new();
+    if let Err(e) = request.as_reader().take(MAX_BODY).read_to_string(&mut body) {
+        let _ = request.respond(json_response(400, &json!({ "error": format!("Failed to read request body: {e}") })));
+        return;
+    }
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let result = route(app, request.method(), &segments, query, &body);
+    let resp = match result {
+        Ok(Routed::Json(v)) => json_response(200, &v),
+        Ok(Routed::Text(text, content_type)) => text_response(200, text, content_type),
+        Err(ApiError(status, e)) => json_response(status, &json!({ "error": e })),
+    };
+    let _ = request.respond(resp);
+}
+
+enum Routed {
+    Json(Value),
+    Text(String, &'static str),
+}
+
+fn route(app: &AppHandle, method: &Method, segments: &[&str], query: &str, body: &str) -> Result<Routed, ApiError> {
+    let state = app.state::<AppState>();
+    match (method, segments) {
+        (Method::Get, ["api", "status"]) => {
+            let guard = state.kafka.lock().map_err(|e| format!("Failed to access state: {e}"))?;
+            Ok(Routed::Json(json!({
+                "connections": guard.list(),
+                "loading": state.load_session.lock().map(|s| s.is_some()).unwrap_or(false),
+            })))
+        }
+        (Method::Post, ["api", "scan"]) => {
+            let args: ScanArgs = if body.trim().is_empty() {
+                ScanArgs::default()
+            } else {
+                serde_json::from_str(body).map_err(|e| ApiError(400, format!("Invalid scan request: {e}")))?
+            };
+            Ok(Routed::Json(scan(&state, args)?))
+        }
+        (Method::Get, ["api", "report"]) => {
+            let report = state
+                .last_load_report
+                .lock()
+                .map_err(|e| format!("Failed to access load report: {e}"))?
+                .clone()
+                .ok_or_else(|| ApiError(404, "No filtered load has finished yet".into()))?;
+            match query_param(query, "format").unwrap_or("json") {
+                "json" => Ok(Routed::Json(to_json(&report)?)),
+                format => {
+                    let text = report.render(format).map_err(|e| ApiError(400, e))?;
+                    Ok(Routed::Text(text, "text/markdown; charset=utf-8"))
+                }
+            }
+        }
+        (Method::Get, ["api", "scheduled-scans"]) => {
+            let scans = scheduler::list(&crate::profiles::profiles_dir(app)?)?;
+            Ok(Routed::Json(to_json(&scans)?))
+        }
+        (Method::Post, ["api", "scheduled-scans", id, "run"]) => {
+            let summary = scheduler::run_now(app, id)?;
+            Ok(Routed::Json(to_json(&summary)?))
+        }
+        _ => Err(ApiError(404, "Unknown endpoint".into())),
+    }
+}
+
+/// Run a filtered read on a fresh consumer (the UI session is not disturbed) and keep its report
+/// as the latest load report.
+fn scan(state: &AppState, args: ScanArgs) -> Result<Value, ApiError> {
+    let mut config = {
+        let guard = state.kafka.lock().map_err(|e| format!("Failed to access state: {e}"))?;
+        let k = guard
+            .get(args.connection.as_deref())
+            .ok_or_else(|| ApiError(409, "Kafka is not configured".into()))?;
+        k.config.clone()
+    };
+    if let Some(topic) = args.topic.filter(|t| !t.is_empty()) {
+        config.topic = topic;
+    }
+    let limit = args.limit.unwrap_or(DEFAULT_SCAN_LIMIT).max(1);
+    let filters = args.filters;
+    let mut report = LoadReportBuilder::new(
+        config.topic.clone(),
+        args.connection,
+        ReportFilters {
+            key_filter: filters.key_filter.clone(),
+            message_filter: filters.message_filter.clone(),
+            message_filter_mode: filters.message_filter_mode.clone().unwrap_or_else(|| "plain".into()),
+            plain: filters.plain,
+            limit,
+        },
+        &[],
+    );
+    let mut messages = Vec::new();
+    let complete = scheduler::scan_snapshot(config, &filters, |m, matched| {
+        let ts_ms = chrono::DateTime::parse_from_rfc3339(&m.timestamp)
+            .map(|t| t.timestamp_millis())
+            .unwrap_or(i64::MAX);
+        report.record(&m, ts_ms, matched);
+        if matched {
+            messages.push(m);
+        }
+        messages.len() < limit
+    })?;
+    let report = report.finish(if complete { "completed" } else { "limit_reached" });
+    if let Ok(mut last) = state.last_load_report.lock() {
+        *last = Some(report.clone());
+    }
+    Ok(json!({ "report": report, "messages": messages }))
+}
+
+/// Start the local API on 127.0.0.1. Requests must carry `Authorization: Bearer <token>`;
+/// a random token is generated when none is given.
+#[tauri::command]
+pub async fn start_api_server(app: AppHandle, port: Option<u16>, token: Option<String>) -> CommandResult<ApiServerInfo> {
+    let mut guard = SERVER.lock().map_err(Envelope::state)?;
+    if let Some(running) = guard.as_ref() {
+        return Ok(running.info.clone());
+    }
+    let token = match token.filter(|t| !t.trim().is_empty()) {
+        Some(t) => t,
+        None => random_hex(24).map_err(|e| Envelope::failed("start_api_server", e))?,
+    };
+    let addr = format!("127.0.0.1:{}", port.unwrap_or(DEFAULT_PORT));
+    let server =
+        Arc::new(Server::http(&addr).map_err(|e| Envelope::failed("start_api_server", e.to_string()).with("address", &addr))?);
+    let bound = server.server_addr().to_ip().map(|a| a.to_string()).unwrap_or(addr);
+    let info = ApiServerInfo { url: format!("http://{bound}"), token: token.clone() };
+
+    let listener = server.clone();
+    thread::spawn(move || {
+        // Ends when the server is unblocked by stop_api_server
+        for request in listener.incoming_requests() {
+            let app = app.clone();
+            let token = token.clone();
+            thread::spawn(move || respond(&app, &token, request));
+        }
+    });
+    *guard = Some(RunningServer { server, info: info.clone() });
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_api_server() -> CommandResult<()> {
+    let mut guard = SERVER.lock().map_err(Envelope::state)?;
+    if let Some(running) = guard.take() {
+        running.server.unblock();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_api_server_status() -> CommandResult<Option<ApiServerInfo>> {
+    let guard = SERVER.lock().map_err(Envelope::state)?;
+    Ok(guard.as_ref().map(|r| r.info.clone()))
+}
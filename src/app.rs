@@ -1,38 +1,223 @@
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-use crate::kafka::{Kafka, KafkaConfig};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::kafka::{ConsumeBatch, Kafka, KafkaConfig};
+use crate::load_report::LoadReport;
+use crate::proto_decoder::DescriptorRegistry;
+use crate::response::{Envelope, ErrorKind};
+
+/// Name used when the UI configures Kafka without naming the connection.
+pub const DEFAULT_CONNECTION: &str = "default";
+
+// Read-only builds cannot be switched back; other builds opt in with RKUI_READ_ONLY=1
+static READ_ONLY: Lazy<bool> = Lazy::new(|| {
+    cfg!(feature = "read-only")
+        || std::env::var("RKUI_READ_ONLY").is_ok_and(|v| matches!(v.trim(), "1" | "true" | "yes"))
+});
+
+/// Whether mutating commands are disabled.
+pub fn read_only() -> bool {
+    *READ_ONLY
+}
+
+/// Refuse a mutating command in read-only mode; `action` names it for the error.
+pub fn ensure_writable(action: &str) -> Result<(), Envelope> {
+    if read_only() {
+        let message = format!("{action} is disabled: rkui is running in read-only mode");
+        return Err(Envelope::error("read_only", message).with("action", action).kind(ErrorKind::State));
+    }
+    Ok(())
+}
 
 /// Cancellation session for an in-flight streaming load.
 #[derive(Clone)]
 pub struct LoadSession {
     pub cancel_tx: tokio::sync::broadcast::Sender<()>,
+    /// True while the user holds the load; sessions that can't pause ignore it
+    pub pause_tx: tokio::sync::watch::Sender<bool>,
+}
+
+impl LoadSession {
+    pub fn new(cancel_tx: tokio::sync::broadcast::Sender<()>) -> Self {
+        Self { cancel_tx, pause_tx: tokio::sync::watch::Sender::new(false) }
+    }
+}
+
+/// Background read of a topic's first page ahead of the user's "Load" (see prewarm_topic).
+pub struct Prewarm {
+    /// Page size the read was started with
+    pub limit: usize,
+    pub task: tokio::task::JoinHandle<anyhow::Result<ConsumeBatch>>,
+}
+
+impl Prewarm {
+    /// Abort the read and wait until it has released the reader.
+    pub async fn stop(self) {
+        self.task.abort();
+        let _ = self.task.await;
+    }
+}
+
+/// Named Kafka readers; one of them is active and used when a command doesn't name a connection.
+#[derive(Default)]
+pub struct Connections {
+    pub active: Option<String>,
+    pub map: HashMap<String, Arc<Kafka>>,
+}
+
+/// Connection summary for the UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionInfo {
+    pub name: String,
+    pub broker: String,
+    pub topic: String,
+    pub active: bool,
+}
+
+impl Connections {
+    /// Name of the given connection, or of the active one when `name` is None.
+    pub fn resolve(&self, name: Option<&str>) -> Option<String> {
+        name.or(self.active.as_deref()).map(str::to_string)
+    }
+
+    /// The named connection, or the active one when `name` is None.
+    pub fn get(&self, name: Option<&str>) -> Option<&Kafka> {
+        self.map.get(name.or(self.active.as_deref())?).map(Arc::as_ref)
+    }
+
+    /// A handle to the named (or active) connection that outlives the state lock, for async reads.
+    pub fn get_shared(&self, name: Option<&str>) -> Option<Arc<Kafka>> {
+        self.map.get(name.or(self.active.as_deref())?).cloned()
+    }
+
+    /// The active connection.
+    pub fn active(&self) -> Option<&Kafka> {
+        self.get(None)
+    }
+
+    pub fn list(&self) -> Vec<ConnectionInfo> {
+        let mut out: Vec<ConnectionInfo> = self
+            .map
+            .iter()
+            .map(|(name, k)| ConnectionInfo {
+                name: name.clone(),
+                broker: k.config.broker.clone(),
+                topic: k.config.topic.clone(),
+                active: self.active.as_deref() == Some(name.as_str()),
+            })
+            .collect();
+        out.sort_by(|a, b| a.name.cmp(&b.name));
+        out
+    }
 }
 
 /// Global application state shared with Tauri commands.
 #[derive(Clone)]
 pub struct AppState {
-    /// Kafka reader instance; None until configured from the UI.
-    pub kafka: Arc<Mutex<Option<Kafka>>>,
+    /// Kafka reader instances by connection name; empty until configured from the UI.
+    pub kafka: Arc<Mutex<Connections>>,
     /// Current streaming load session (if any).
     pub load_session: Arc<Mutex<Option<LoadSession>>>,
+    /// Summary of the last finished filtered load (see export_load_report).
+    pub last_load_report: Arc<Mutex<Option<LoadReport>>>,
+    /// Current background export (if any).
+    pub export_session: Arc<Mutex<Option<LoadSession>>>,
+    /// Current topic-to-topic copy (if any).
+    pub copy_session: Arc<Mutex<Option<LoadSession>>>,
+    /// Current consumer lag simulation (if any).
+    pub lag_sim_session: Arc<Mutex<Option<LoadSession>>>,
+    /// Background reads of a topic's first page by connection name.
+    pub prewarm: Arc<Mutex<HashMap<String, Prewarm>>>,
+    /// Linked .proto descriptors shared by the connections' decoders.
+    pub descriptors: Arc<DescriptorRegistry>,
 }
 
 impl AppState {
     /// Construct an empty application state.
     pub fn new() -> Self {
         Self {
-            kafka: Arc::new(Mutex::new(None)),
+            kafka: Arc::new(Mutex::new(Connections::default())),
             load_session: Arc::new(Mutex::new(None)),
+            last_load_report: Arc::new(Mutex::new(None)),
+            export_session: Arc::new(Mutex::new(None)),
+            copy_session: Arc::new(Mutex::new(None)),
+            lag_sim_session: Arc::new(Mutex::new(None)),
+            prewarm: Arc::new(Mutex::new(HashMap::new())),
+            descriptors: Arc::new(DescriptorRegistry::default()),
         }
     }
 
-    /// Create/replace Kafka reader according to new config.
-    pub fn reconfigure_kafka(&self, cfg: KafkaConfig) -> anyhow::Result<()> {
+    /// Create/replace the active Kafka reader according to new config.
+    pub async fn reconfigure_kafka(&self, cfg: KafkaConfig) -> anyhow::Result<()> {
+        let name = {
+            let guard = self.kafka.lock().map_err(|e| anyhow::anyhow!("Failed to access state: {e}"))?;
+            guard.active.clone().unwrap_or_else(|| DEFAULT_CONNECTION.to_string())
+        };
+        self.add_connection(&name, cfg).await
+    }
+
+    /// Create/replace a named connection and make it active. Other connections stay open.
+    /// The clients are created off the async runtime and before the state lock is taken: that opens the
+    /// SSH tunnel and fetches the first OAuth token.
+    pub async fn add_connection(&self, name: &str, cfg: KafkaConfig) -> anyhow::Result<()> {
+        let descriptors = self.descriptors.clone();
+        let kafka = tokio::task::spawn_blocking(move || Kafka::with_descriptors(cfg, &descriptors)).await??;
+        let previous = {
+            let mut guard = self.kafka.lock().map_err(|e| anyhow::anyhow!("Failed to access state: {e}"))?;
+            self.cancel_prewarm(name);
+            guard.active = Some(name.to_string());
+            guard.map.insert(name.to_string(), Arc::new(kafka))
+        };
+        // Closing the previous clients can block too
+        if let Some(previous) = previous {
+            tokio::task::spawn_blocking(move || drop(previous));
+        }
+        Ok(())
+    }
+
+    /// Make an existing connection active.
+    pub fn switch_connection(&self, name: &str) -> anyhow::Result<()> {
         let mut guard = self.kafka.lock().map_err(|e| anyhow::anyhow!("Failed to access state: {e}"))?;
-        // Drop previous (it will close on drop)
-        *guard = None;
-        let kafka = Kafka::new(cfg)?;
-        *guard = Some(kafka);
+        if !guard.map.contains_key(name) {
+            return Err(anyhow::anyhow!("Unknown connection '{}'", name));
+        }
+        guard.active = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Close a connection. When it was active, another open connection (if any) becomes active.
+    pub fn close_connection(&self, name: &str) -> anyhow::Result<()> {
+        let closed = {
+            let mut guard = self.kafka.lock().map_err(|e| anyhow::anyhow!("Failed to access state: {e}"))?;
+            let Some(closed) = guard.map.remove(name) else {
+                return Err(anyhow::anyhow!("Unknown connection '{}'", name));
+            };
+            self.cancel_prewarm(name);
+            if guard.active.as_deref() == Some(name) {
+                let mut names: Vec<&String> = guard.map.keys().collect();
+                names.sort();
+                guard.active = names.first().map(|n| n.to_string());
+            }
+            closed
+        };
+        // Closing the clients can block; this runs outside the async runtime, so use tauri's pool
+        tauri::async_runtime::spawn_blocking(move || drop(closed));
         Ok(())
     }
+
+    /// Take the read-ahead started for a connection, if any.
+    pub fn take_prewarm(&self, name: &str) -> anyhow::Result<Option<Prewarm>> {
+        let mut guard = self.prewarm.lock().map_err(|e| anyhow::anyhow!("Failed to access state: {e}"))?;
+        Ok(guard.remove(name))
+    }
+
+    /// Stop a connection's read-ahead; its records no longer fit the reader.
+    pub fn cancel_prewarm(&self, name: &str) {
+        if let Ok(Some(prewarm)) = self.take_prewarm(name) {
+            prewarm.task.abort();
+        }
+    }
 }
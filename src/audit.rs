@@ -0,0 +1,127 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::kafka::KafkaConfig;
+use crate::response::{CommandResult, Envelope};
+
+const AUDIT_FILE: &str = "audit.log";
+const DEFAULT_VIEW_LIMIT: usize = 500;
+
+// Appends from concurrent commands must not interleave
+static WRITE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// One destructive action against a cluster, as stored in the audit file (one JSON object per line).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub at: String,
+    /// OS account that ran rkui
+    pub user: String,
+    /// e.g. "produce", "replay", "copy", "import", "alter_topic_config", "add_partitions", "reset_offsets"
+    pub action: String,
+    pub broker: String,
+    pub target: String,
+    /// Request parameters (no payloads or secrets)
+    pub details: Value,
+    /// "ok" | "error"
+    pub result: String,
+    pub error: Option<String>,
+}
+
+fn audit_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {e}"))?;
+    Ok(dir.join(AUDIT_FILE))
+}
+
+fn os_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".into())
+}
+
+pub(crate) fn append(path: &Path, entry: &AuditEntry) -> Result<(), String> {
+    let _guard = WRITE_LOCK.lock().map_err(|e| format!("Failed to access audit log: {e}"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create app data directory: {e}"))?;
+    }
+    let mut line = serde_json::to_string(entry).map_err(|e| format!("Failed to serialize audit entry: {e}"))?;
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open audit log: {e}"))?;
+    file.write_all(line.as_bytes()).map_err(|e| format!("Failed to write audit log: {e}"))
+}
+
+/// Record the outcome of a destructive command. Audit failures are reported on stderr
+/// and never change the command's result.
+pub(crate) fn record<T, E: std::fmt::Display>(
+    app: &AppHandle,
+    action: &str,
+    config: &KafkaConfig,
+    target: &str,
+    details: Value,
+    result: &Result<T, E>,
+) {
+    let entry = AuditEntry {
+        at: chrono::Utc::now().to_rfc3339(),
+        user: os_user(),
+        action: action.to_string(),
+        broker: config.broker.clone(),
+        target: target.to_string(),
+        details,
+        result: if result.is_ok() { "ok" } else { "error" }.to_string(),
+        error: result.as_ref().err().map(|e| e.to_string()),
+    };
+    if let Err(e) = audit_path(app).and_then(|path| append(&path, &entry)) {
+        log::warn!("{e}");
+    }
+}
+
+/// Entries of an audit file, newest first.
+pub(crate) fn read(path: &Path, action: Option<&str>, limit: usize) -> Result<Vec<AuditEntry>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(path).map_err(|e| format!("Failed to read audit log: {e}"))?;
+    Ok(data
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        .filter(|e| action.is_none_or(|a| e.action == a))
+        .take(limit)
+        .collect())
+}
+
+/// Latest audit entries, optionally only one action.
+#[tauri::command]
+pub async fn get_audit_log(app: AppHandle, action: Option<String>, limit: Option<usize>) -> CommandResult<Vec<AuditEntry>> {
+    audit_path(&app)
+        .and_then(|path| read(&path, action.as_deref(), limit.unwrap_or(DEFAULT_VIEW_LIMIT)))
+        .map_err(|e| Envelope::failed("read_audit_log", e))
+}
+
+/// Copy the audit log (NDJSON) to `path`; returns the number of entries.
+#[tauri::command]
+pub async fn export_audit_log(app: AppHandle, path: String) -> CommandResult<usize> {
+    let entries = audit_path(&app)
+        .and_then(|audit| read(&audit, None, usize::MAX))
+        .map_err(|e| Envelope::failed("read_audit_log", e))?;
+    let mut out = String::new();
+    for entry in entries.iter().rev() {
+        out.push_str(&serde_json::to_string(entry).map_err(|e| Envelope::failed("export_audit_log", e.to_string()))?);
+        out.push('\n');
+    }
+    fs::write(&path, out).map_err(|e| Envelope::failed("export_audit_log", e).with("path", &path))?;
+    Ok(entries.len())
+}
@@ -0,0 +1,253 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::app::ensure_writable;
+use crate::audit;
+use crate::kafka::{Kafka, KafkaConfig, PartitionOffset};
+use crate::response::{CommandResult, Envelope, ErrorKind};
+use crate::utils::random_hex;
+
+/// How long a prepared operation can be confirmed.
+const TOKEN_TTL: Duration = Duration::from_secs(120);
+
+// Prepared operations by token; a token is consumed by its first use for the action it was issued for
+static PENDING: Lazy<Mutex<HashMap<String, Pending>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+struct Pending {
+    created: Instant,
+    config: KafkaConfig,
+    action: DangerousAction,
+}
+
+/// A destructive operation awaiting confirmation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action")]
+pub enum DangerousAction {
+    #[serde(rename = "delete_topic")] DeleteTopic { topic: String },
+    #[serde(rename = "delete_records")] DeleteRecords { topic: String, offsets: Vec<PartitionOffset> },
+    #[serde(rename = "reset_offsets")] ResetOffsets { group: String, topic: String, offsets: Vec<PartitionOffset> },
+}
+
+impl DangerousAction {
+    fn name(&self) -> &'static str {
+        match self {
+            DangerousAction::DeleteTopic { .. } => "delete_topic",
+            DangerousAction::DeleteRecords { .. } => "delete_records",
+            DangerousAction::ResetOffsets { .. } => "reset_offsets",
+        }
+    }
+}
+
+/// What an operation would do to one partition.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionImpact {
+    pub partition: i32,
+    #[serde(rename = "low_watermark")]
+    pub low_watermark: i64,
+    #[serde(rename = "high_watermark")]
+    pub high_watermark: i64,
+    /// Committed offset of the group (offset resets only)
+    pub current: Option<i64>,
+    /// New log start or committed offset; None when the partition is deleted with its topic
+    pub target: Option<i64>,
+    /// Records deleted, or for resets the records skipped (positive) or re-read (negative)
+    #[serde(rename = "affected_records")]
+    pub affected_records: i64,
+}
+
+/// Returned by `prepare_*`; pass `token` to the matching command to execute.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfirmationToken {
+    pub token: String,
+    #[serde(flatten)]
+    pub action: DangerousAction,
+    pub broker: String,
+    pub partitions: Vec<PartitionImpact>,
+    /// Sum of affected_records
+    #[serde(rename = "total_records")]
+    pub total_records: i64,
+    #[serde(rename = "expires_at")]
+    pub expires_at: String,
+}
+
+fn issue(config: KafkaConfig, action: DangerousAction, partitions: Vec<PartitionImpact>) -> CommandResult<ConfirmationToken> {
+    let token = random_hex(16).map_err(|e| Envelope::failed("issue_confirmation", e))?;
+    let expires_at = chrono::Utc::now() + chrono::Duration::from_std(TOKEN_TTL).unwrap_or_default();
+    let out = ConfirmationToken {
+        token: token.clone(),
+        action: action.clone(),
+        broker: config.broker.clone(),
+        total_records: partitions.iter().map(|p| p.affected_records).sum(),
+        partitions,
+        expires_at: expires_at.to_rfc3339(),
+    };
+    let mut pending = PENDING.lock().map_err(Envelope::state)?;
+    pending.retain(|_, p| p.created.elapsed() < TOKEN_TTL);
+    pending.insert(token, Pending { created: Instant::now(), config, action });
+    Ok(out)
+}
+
+/// Consume a token issued for `action`. A token presented for another action stays valid for its own.
+fn redeem(token: &str, action: &str) -> CommandResult<(KafkaConfig, DangerousAction)> {
+    let rejected = |code: &str, message: String| Envelope::error(code, message).kind(ErrorKind::Invalid);
+    let mut pending = PENDING.lock().map_err(Envelope::state)?;
+    let Entry::Occupied(entry) = pending.entry(token.to_string()) else {
+        return Err(rejected("confirmation_unknown", "Unknown or already used confirmation token".into()));
+    };
+    if entry.get().created.elapsed() >= TOKEN_TTL {
+        entry.remove();
+        return Err(rejected("confirmation_expired", "Confirmation token expired; prepare the operation again".into()));
+    }
+    let issued_for = entry.get().action.name();
+    if issued_for != action {
+        return Err(rejected("confirmation_mismatch", format!("Confirmation token was issued for {issued_for}, not {action}"))
+            .with("issued_for", issued_for));
+    }
+    let p = entry.remove();
+    Ok((p.config, p.action))
+}
+
+/// Watermarks through a short-lived consumer, fetched off the async runtime.
+async fn watermarks(config: &KafkaConfig, topic: &str) -> CommandResult<Vec<(i32, i64, i64)>> {
+    let (config, topic) = (config.clone(), topic.to_string());
+    tokio::task::spawn_blocking(move || Kafka::watermarks_for(&config, &topic))
+        .await
+        .map_err(|e| Envelope::failed("fetch_watermarks", e))?
+        .map(|ws| ws.into_iter().map(|w| (w.partition, w.low, w.high)).collect())
+        .map_err(|e| Envelope::failed("fetch_watermarks", e))
+}
+
+fn unknown_partition(partition: i32, topic: &str) -> Envelope {
+    Envelope::invalid(format!("Partition {partition} does not exist in '{topic}'"))
+        .with("partition", partition)
+        .with("topic", topic)
+}
+
+/// Impact of deleting a topic: every retained record is lost.
+#[tauri::command]
+pub async fn prepare_delete_topic(config: KafkaConfig, topic: String) -> CommandResult<ConfirmationToken> {
+    ensure_writable("Deleting topics")?;
+    let partitions = watermarks(&config, &topic)
+        .await?
+        .into_iter()
+        .map(|(partition, low, high)| PartitionImpact {
+            partition,
+            low_watermark: low,
+            high_watermark: high,
+            current: None,
+            target: None,
+            affected_records: high - low,
+        })
+        .collect();
+    issue(config, DangerousAction::DeleteTopic { topic }, partitions)
+}
+
+#[tauri::command]
+pub async fn delete_topic(app: AppHandle, token: String) -> CommandResult<()> {
+    ensure_writable("Deleting topics")?;
+    let (config, action) = redeem(&token, "delete_topic")?;
+    let DangerousAction::DeleteTopic { topic } = &action else { unreachable!("checked by redeem") };
+    let result = Kafka::delete_topic(&config, topic).await.map_err(|e| Envelope::failed("delete_topic", e));
+    audit::record(&app, "delete_topic", &config, topic, serde_json::json!({}), &result);
+    result
+}
+
+/// Impact of deleting records before the given offsets (-1 = everything currently in the partition).
+#[tauri::command]
+pub async fn prepare_delete_records(
+    config: KafkaConfig,
+    topic: String,
+    offsets: Vec<PartitionOffset>,
+) -> CommandResult<ConfirmationToken> {
+    ensure_writable("Deleting records")?;
+    let marks = watermarks(&config, &topic).await?;
+    let mut partitions = Vec::with_capacity(offsets.len());
+    for o in &offsets {
+        let &(_, low, high) = marks
+            .iter()
+            .find(|(p, _, _)| *p == o.partition)
+            .ok_or_else(|| unknown_partition(o.partition, &topic))?;
+        let target = if o.offset < 0 { high } else { o.offset.clamp(low, high) };
+        partitions.push(PartitionImpact {
+            partition: o.partition,
+            low_watermark: low,
+            high_watermark: high,
+            current: None,
+            target: Some(target),
+            affected_records: target - low,
+        });
+    }
+    issue(config, DangerousAction::DeleteRecords { topic, offsets }, partitions)
+}
+
+#[tauri::command]
+pub async fn delete_records(app: AppHandle, token: String) -> CommandResult<Vec<PartitionOffset>> {
+    ensure_writable("Deleting records")?;
+    let (config, action) = redeem(&token, "delete_records")?;
+    let DangerousAction::DeleteRecords { topic, offsets } = &action else { unreachable!("checked by redeem") };
+    let result = Kafka::delete_records(&config, topic, offsets)
+        .await
+        .map_err(|e| Envelope::failed("delete_records", e));
+    audit::record(&app, "delete_records", &config, topic, serde_json::json!({ "offsets": offsets }), &result);
+    result
+}
+
+/// Impact of moving a group's committed offsets: how many records it would skip or re-read.
+#[tauri::command]
+pub async fn prepare_reset_offsets(
+    config: KafkaConfig,
+    group: String,
+    topic: String,
+    offsets: Vec<PartitionOffset>,
+) -> CommandResult<ConfirmationToken> {
+    ensure_writable("Resetting consumer group offsets")?;
+    let lag = {
+        let (config, group, topic) = (config.clone(), group.clone(), topic.clone());
+        tokio::task::spawn_blocking(move || Kafka::consumer_lag(&config, &group, &topic))
+            .await
+            .map_err(|e| Envelope::failed("fetch_committed_offsets", e))?
+            .map_err(|e| Envelope::failed("fetch_committed_offsets", e))?
+    };
+    let mut partitions = Vec::with_capacity(offsets.len());
+    for o in &offsets {
+        let p = lag
+            .partitions
+            .iter()
+            .find(|p| p.partition == o.partition)
+            .ok_or_else(|| unknown_partition(o.partition, &topic))?;
+        // Without a commit the group would start from the log start
+        let current = p.committed.unwrap_or(p.low_watermark);
+        partitions.push(PartitionImpact {
+            partition: o.partition,
+            low_watermark: p.low_watermark,
+            high_watermark: p.high_watermark,
+            current: p.committed,
+            target: Some(o.offset),
+            affected_records: o.offset - current,
+        });
+    }
+    issue(config, DangerousAction::ResetOffsets { group, topic, offsets }, partitions)
+}
+
+#[tauri::command]
+pub async fn reset_offsets(app: AppHandle, token: String) -> CommandResult<Vec<PartitionOffset>> {
+    ensure_writable("Resetting consumer group offsets")?;
+    let (config, action) = redeem(&token, "reset_offsets")?;
+    let DangerousAction::ResetOffsets { group, topic, offsets } = &action else { unreachable!("checked by redeem") };
+    let result = {
+        let (config, group, topic, offsets) = (config.clone(), group.clone(), topic.clone(), offsets.clone());
+        tokio::task::spawn_blocking(move || Kafka::prepare_group_offsets(&config, &group, &topic, &offsets, true))
+            .await
+            .map_err(|e| Envelope::failed("reset_offsets", e))
+            .and_then(|r| r.map_err(|e| Envelope::failed("reset_offsets", e)))
+    };
+    let details = serde_json::json!({ "group": group, "offsets": offsets, "overwrite": true });
+    audit::record(&app, "reset_offsets", &config, topic, details, &result);
+    result
+}
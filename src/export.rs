@@ -0,0 +1,604 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::data_type::{ByteArray, ByteArrayType, Int32Type, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, State, Window};
+use tokio::sync::broadcast;
+
+use crate::app::{AppState, LoadSession};
+use crate::kafka::{Kafka, ReplayRange, UiMessage};
+use crate::response::{CommandResult, Envelope};
+use crate::utils::json::json_path_get;
+use crate::utils::random_hex;
+
+#[derive(Debug, Serialize)]
+struct ExportedHeader<'a> {
+    key: &'a str,
+    value: &'a str,
+}
+
+/// One line of an NDJSON export.
+#[derive(Debug, Serialize)]
+struct ExportedRecord<'a> {
+    topic: &'a str,
+    partition: i32,
+    offset: i64,
+    timestamp: &'a str,
+    key: &'a str,
+    headers: Vec<ExportedHeader<'a>>,
+    /// The decoded payload, embedded as JSON when it parses and as a string otherwise; null for tombstones
+    value: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decoding_error: Option<&'a str>,
+    /// False when key or value were decoded or masked and cannot be imported back
+    verbatim: bool,
+}
+
+/// CSV columns used when the caller selects none.
+const DEFAULT_CSV_COLUMNS: [&str; 6] = ["topic", "partition", "offset", "timestamp", "key", "value"];
+
+/// Rows buffered per Parquet row group.
+const PARQUET_ROW_GROUP_ROWS: usize = 100_000;
+
+const PARQUET_SCHEMA: &str = "message rkui_export {
+    REQUIRED BYTE_ARRAY topic (UTF8);
+    REQUIRED INT32 partition;
+    REQUIRED INT64 offset;
+    REQUIRED BYTE_ARRAY timestamp (UTF8);
+    REQUIRED BYTE_ARRAY key (UTF8);
+    REQUIRED BYTE_ARRAY value (UTF8);
+    REQUIRED BYTE_ARRAY headers (UTF8);
+    OPTIONAL BYTE_ARRAY decoding_error (UTF8);
+}";
+
+/// Column buffers of the row group being assembled.
+#[derive(Default)]
+struct ParquetRows {
+    partition: Vec<i32>,
+    offset: Vec<i64>,
+    timestamp: Vec<ByteArray>,
+    key: Vec<ByteArray>,
+    value: Vec<ByteArray>,
+    /// Headers as a JSON array of {key, value}
+    headers: Vec<ByteArray>,
+    decoding_error: Vec<ByteArray>,
+    /// Definition levels of decoding_error (1 = present)
+    decoding_error_def: Vec<i16>,
+}
+
+enum Sink {
+    Ndjson(BufWriter<File>),
+    /// Column specs: metadata names or jq-style paths into the payload
+    Csv { out: BufWriter<File>, columns: Vec<String> },
+    Parquet { writer: Box<SerializedFileWriter<File>>, rows: ParquetRows },
+    /// One `messages` table, filled inside a single transaction
+    Sqlite(rusqlite::Connection),
+}
+
+/// Export file written under a temporary name next to `path` and renamed into place by `commit`. A failed
+/// or cancelled export removes only its own partial file, never `path` (which a newer export may be writing).
+struct PartialFile {
+    path: PathBuf,
+    partial: PathBuf,
+    committed: bool,
+}
+
+impl PartialFile {
+    fn new(path: &str) -> Result<Self, String> {
+        let path = PathBuf::from(path);
+        let mut name = path.file_name().ok_or_else(|| format!("Invalid export path '{}'", path.display()))?.to_os_string();
+        name.push(format!(".{}.part", random_hex(4)?));
+        Ok(Self { partial: path.with_file_name(name), path, committed: false })
+    }
+
+    fn commit(mut self) -> Result<(), String> {
+        fs::rename(&self.partial, &self.path).map_err(|e| format!("Failed to write export: {e}"))?;
+        self.committed = true;
+        Ok(())
+    }
+}
+
+impl Drop for PartialFile {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = fs::remove_file(&self.partial);
+        }
+    }
+}
+
+/// Writes messages to disk in the requested format.
+pub(crate) struct MessageWriter {
+    // Dropped before `file`, so the partial file is closed by the time it is removed
+    sink: Sink,
+    file: PartialFile,
+    topic: String,
+    written: usize,
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn exported_headers(m: &UiMessage) -> Vec<ExportedHeader<'_>> {
+    m.headers.iter().map(|(key, value)| ExportedHeader { key, value }).collect()
+}
+
+fn parquet_err(e: parquet::errors::ParquetError) -> String {
+    format!("Failed to write export: {e}")
+}
+
+fn sqlite_err(e: rusqlite::Error) -> String {
+    format!("Failed to write export: {e}")
+}
+
+const SQLITE_SCHEMA: &str = r#"
+    CREATE TABLE messages (
+        topic TEXT NOT NULL,
+        partition INTEGER NOT NULL,
+        "offset" INTEGER NOT NULL,
+        timestamp TEXT NOT NULL,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        headers TEXT NOT NULL,
+        decoding_error TEXT,
+        PRIMARY KEY (partition, "offset")
+    );
+    BEGIN;
+"#;
+
+/// Write the buffered rows as one row group.
+fn flush_row_group(writer: &mut SerializedFileWriter<File>, topic: &str, rows: &mut ParquetRows) -> Result<(), String> {
+    let n = rows.partition.len();
+    if n == 0 {
+        return Ok(());
+    }
+    let topics = vec![ByteArray::from(topic); n];
+    let mut group = writer.next_row_group().map_err(parquet_err)?;
+    let mut index = 0;
+    while let Some(mut column) = group.next_column().map_err(parquet_err)? {
+        match index {
+            0 => column.typed::<ByteArrayType>().write_batch(&topics, None, None),
+            1 => column.typed::<Int32Type>().write_batch(&rows.partition, None, None),
+            2 => column.typed::<Int64Type>().write_batch(&rows.offset, None, None),
+            3 => column.typed::<ByteArrayType>().write_batch(&rows.timestamp, None, None),
+            4 => column.typed::<ByteArrayType>().write_batch(&rows.key, None, None),
+            5 => column.typed::<ByteArrayType>().write_batch(&rows.value, None, None),
+            6 => column.typed::<ByteArrayType>().write_batch(&rows.headers, None, None),
+            _ => column
+                .typed::<ByteArrayType>()
+                .write_batch(&rows.decoding_error, Some(&rows.decoding_error_def), None),
+        }
+        .map_err(parquet_err)?;
+        column.close().map_err(parquet_err)?;
+        index += 1;
+    }
+    group.close().map_err(parquet_err)?;
+    *rows = ParquetRows::default();
+    Ok(())
+}
+
+impl MessageWriter {
+    /// `format` is "ndjson", "csv", "parquet" or "sqlite". `columns` selects CSV columns: "topic", "partition",
+    /// "offset", "timestamp", "key", "value" or a jq-style path into the JSON payload (`.order.id`);
+    /// the other formats always carry the full record.
+    pub(crate) fn create(path: &str, format: &str, topic: String, columns: Option<Vec<String>>) -> Result<Self, String> {
+        if !matches!(format, "ndjson" | "csv" | "parquet" | "sqlite") {
+            return Err(format!("Unsupported export format '{format}' (expected ndjson, csv, parquet or sqlite)"));
+        }
+        let target = PartialFile::new(path)?;
+        let file = File::create(&target.partial).map_err(|e| format!("Failed to create export file: {e}"))?;
+        let sink = match format {
+            "csv" => {
+                let columns = columns
+                    .filter(|c| !c.is_empty())
+                    .unwrap_or_else(|| DEFAULT_CSV_COLUMNS.iter().map(|c| c.to_string()).collect());
+                let mut out = BufWriter::new(file);
+                let header: Vec<String> = columns.iter().map(|c| csv_field(c)).collect();
+                writeln!(out, "{}", header.join(",")).map_err(|e| format!("Failed to write export: {e}"))?;
+                Sink::Csv { out, columns }
+            }
+            "parquet" => {
+                let schema = Arc::new(parse_message_type(PARQUET_SCHEMA).map_err(parquet_err)?);
+                let props = WriterProperties::builder()
+                    .set_compression(Compression::ZSTD(ZstdLevel::default()))
+                    .build();
+                let writer = SerializedFileWriter::new(file, schema, Arc::new(props)).map_err(parquet_err)?;
+                Sink::Parquet { writer: Box::new(writer), rows: ParquetRows::default() }
+            }
+            // The truncated file opens as an empty database
+            "sqlite" => {
+                drop(file);
+                let conn = rusqlite::Connection::open(&target.partial).map_err(sqlite_err)?;
+                conn.execute_batch(SQLITE_SCHEMA).map_err(sqlite_err)?;
+                Sink::Sqlite(conn)
+            }
+            _ => Sink::Ndjson(BufWriter::new(file)),
+        };
+        Ok(Self { sink, file: target, topic, written: 0 })
+    }
+
+    pub(crate) fn write(&mut self, m: &UiMessage) -> Result<(), String> {
+        match &mut self.sink {
+            Sink::Csv { out, columns } => {
+                let payload = serde_json::from_str::<serde_json::Value>(&m.message).ok();
+                let row: Vec<String> = columns
+                    .iter()
+                    .map(|column| {
+                        let text = match column.as_str() {
+                            "topic" => self.topic.clone(),
+                            "partition" => m.partition.to_string(),
+                            "offset" => m.offset.to_string(),
+                            "timestamp" => m.timestamp.clone(),
+                            "key" => m.key.clone(),
+                            "value" => m.message.clone(),
+                            path => match payload.as_ref().and_then(|p| json_path_get(p, path)) {
+                                None | Some(serde_json::Value::Null) => String::new(),
+                                Some(serde_json::Value::String(s)) => s,
+                                Some(other) => other.to_string(),
+                            },
+                        };
+                        csv_field(&text)
+                    })
+                    .collect();
+                writeln!(out, "{}", row.join(",")).map_err(|e| format!("Failed to write export: {e}"))?;
+            }
+            Sink::Parquet { writer, rows } => {
+                let headers =
+                    serde_json::to_string(&exported_headers(m)).map_err(|e| format!("Failed to write export: {e}"))?;
+                rows.partition.push(m.partition);
+                rows.offset.push(m.offset);
+                rows.timestamp.push(ByteArray::from(m.timestamp.as_str()));
+                rows.key.push(ByteArray::from(m.key.as_str()));
+                rows.value.push(ByteArray::from(m.message.as_str()));
+                rows.headers.push(ByteArray::from(headers.as_str()));
+                match &m.decoding_error {
+                    Some(e) => {
+                        rows.decoding_error.push(ByteArray::from(e.as_str()));
+                        rows.decoding_error_def.push(1);
+                    }
+                    None => rows.decoding_error_def.push(0),
+                }
+                if rows.partition.len() >= PARQUET_ROW_GROUP_ROWS {
+                    flush_row_group(writer, &self.topic, rows)?;
+                }
+            }
+            Sink::Sqlite(conn) => {
+                let headers =
+                    serde_json::to_string(&exported_headers(m)).map_err(|e| format!("Failed to write export: {e}"))?;
+                conn.prepare_cached("INSERT OR REPLACE INTO messages VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")
+                    .and_then(|mut insert| {
+                        insert.execute(rusqlite::params![
+                            self.topic,
+                            m.partition,
+                            m.offset,
+                            m.timestamp,
+                            m.key,
+                            m.message,
+                            headers,
+                            m.decoding_error,
+                        ])
+                    })
+                    .map_err(sqlite_err)?;
+            }
+            Sink::Ndjson(out) => {
+                let record = ExportedRecord {
+                    topic: &self.topic,
+                    partition: m.partition,
+                    offset: m.offset,
+                    timestamp: &m.timestamp,
+                    key: &m.key,
+                    headers: exported_headers(m),
+                    value: if m.is_tombstone {
+                        serde_json::Value::Null
+                    } else {
+                        serde_json::from_str(&m.message).unwrap_or_else(|_| serde_json::Value::String(m.message.clone()))
+                    },
+                    decoding_error: m.decoding_error.as_deref(),
+                    verbatim: m.verbatim,
+                };
+                serde_json::to_writer(&mut *out, &record).map_err(|e| format!("Failed to write export: {e}"))?;
+                out.write_all(b"\n").map_err(|e| format!("Failed to write export: {e}"))?;
+            }
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    pub(crate) fn written(&self) -> usize {
+        self.written
+    }
+
+    pub(crate) fn finish(self) -> Result<usize, String> {
+        match self.sink {
+            Sink::Ndjson(mut out) | Sink::Csv { mut out, .. } => {
+                out.flush().map_err(|e| format!("Failed to write export: {e}"))?;
+            }
+            Sink::Parquet { mut writer, mut rows } => {
+                flush_row_group(&mut writer, &self.topic, &mut rows)?;
+                writer.close().map_err(parquet_err)?;
+            }
+            Sink::Sqlite(conn) => {
+                conn.execute_batch("CREATE INDEX messages_key ON messages (key); COMMIT;").map_err(sqlite_err)?;
+            }
+        }
+        self.file.commit()?;
+        Ok(self.written)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportMessagesArgs {
+    pub path: String,
+    /// "ndjson" (default) | "csv" | "parquet" | "sqlite"
+    pub format: Option<String>,
+    /// CSV columns (see `MessageWriter::create`)
+    pub columns: Option<Vec<String>>,
+    /// The result set currently shown in the UI; exported as is
+    pub messages: Option<Vec<UiMessage>>,
+    /// Offset ranges to re-consume instead (used when `messages` is omitted)
+    pub ranges: Option<Vec<ReplayRange>>,
+    /// Connection to read ranges from; the active one when omitted
+    pub connection: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub format: String,
+    pub written: usize,
+}
+
+/// Write the current result set, or freshly consumed offset ranges, to a file.
+#[tauri::command]
+pub async fn export_messages(state: State<'_, AppState>, args: ExportMessagesArgs) -> CommandResult<ExportSummary> {
+    let format = args.format.unwrap_or_else(|| "ndjson".into()).to_lowercase();
+    let failed = |e: String| Envelope::failed("export_messages", e);
+    let config = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        guard.get(args.connection.as_deref()).map(|k| k.config.clone())
+    };
+    let topic = config.as_ref().map(|c| c.topic.clone()).unwrap_or_default();
+
+    let written = match (args.messages, args.ranges) {
+        (Some(messages), _) => {
+            let mut writer = MessageWriter::create(&args.path, &format, topic, args.columns).map_err(failed)?;
+            for m in &messages {
+                writer.write(m).map_err(failed)?;
+            }
+            writer.finish().map_err(failed)?
+        }
+        (None, Some(ranges)) => {
+            let config = config.ok_or_else(Envelope::not_configured)?;
+            let mut writer = MessageWriter::create(&args.path, &format, topic, args.columns).map_err(failed)?;
+            tokio::task::spawn_blocking(move || {
+                // A dedicated reader keeps the UI session's consumer position untouched
+                let kafka = Kafka::new(config).map_err(|e| format!("Failed to create consumer: {e}"))?;
+                kafka
+                    .read_ranges(&ranges, |m| writer.write(&m).map_err(anyhow::Error::msg))
+                    .map_err(|e| format!("Failed to export messages: {e}"))?;
+                writer.finish()
+            })
+            .await
+            .map_err(|e| Envelope::failed("export_messages", e))?
+            .map_err(failed)?
+        }
+        (None, None) => return Err(Envelope::invalid("Nothing to export: pass messages or offset ranges")),
+    };
+    Ok(ExportSummary { path: args.path, format, written })
+}
+
+/// Rows between `export:progress` events.
+const PROGRESS_EVERY: usize = 10_000;
+
+#[derive(Debug, Deserialize)]
+pub struct StartExportArgs {
+    pub path: String,
+    /// "parquet" (default) | "ndjson" | "csv" | "sqlite"
+    pub format: Option<String>,
+    /// CSV columns (see `MessageWriter::create`)
+    pub columns: Option<Vec<String>>,
+    /// Offset ranges to export; every partition from its low to its high watermark when omitted
+    pub ranges: Option<Vec<ReplayRange>>,
+    pub connection: Option<String>,
+}
+
+/// Export a whole topic or offset ranges in the background. Emits `export:progress` ({written}),
+/// then one of `export:done` (ExportSummary), `export:cancelled` or `export:error`; cancelled and
+/// failed exports leave no file behind.
+#[tauri::command]
+pub async fn start_export(window: Window, state: State<'_, AppState>, args: StartExportArgs) -> CommandResult<()> {
+    let format = args.format.unwrap_or_else(|| "parquet".into()).to_lowercase();
+    let config = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        guard
+            .get(args.connection.as_deref())
+            .map(|k| k.config.clone())
+            .ok_or_else(Envelope::not_configured)?
+    };
+    let ranges = match args.ranges {
+        Some(ranges) => ranges,
+        None => Kafka::watermarks_for(&config, &config.topic)
+            .map_err(|e| Envelope::failed("fetch_watermarks", e))?
+            .into_iter()
+            .map(|w| ReplayRange { partition: w.partition, start_offset: w.low, end_offset: Some(w.high) })
+            .collect(),
+    };
+    let mut writer = MessageWriter::create(&args.path, &format, config.topic.clone(), args.columns)
+        .map_err(|e| Envelope::failed("start_export", e))?;
+    let mut rx = begin_session(&state)?;
+    let sessions = state.export_session.clone();
+    let path = args.path;
+
+    tokio::task::spawn_blocking(move || {
+        use tokio::sync::broadcast::error::TryRecvError;
+
+        let mut cancelled = false;
+        let result = Kafka::new(config)
+            .map_err(|e| format!("Failed to create consumer: {e}"))
+            .and_then(|kafka| {
+                kafka
+                    .read_ranges(&ranges, |m| {
+                        if matches!(rx.try_recv(), Ok(_) | Err(TryRecvError::Closed)) {
+                            cancelled = true;
+                            return Err(anyhow::anyhow!("Export cancelled"));
+                        }
+                        writer.write(&m).map_err(anyhow::Error::msg)?;
+                        if writer.written() % PROGRESS_EVERY == 0 {
+                            let _ = window.emit("export:progress", &serde_json::json!({ "written": writer.written() }));
+                        }
+                        Ok(())
+                    })
+                    .map_err(|e| format!("Failed to export messages: {e}"))
+            })
+            .and_then(|_| writer.finish());
+
+        match result {
+            Ok(written) => {
+                let _ = window.emit("export:done", &ExportSummary { path, format, written });
+            }
+            Err(e) => {
+                if cancelled {
+                    let _ = window.emit("export:cancelled", &serde_json::json!({}));
+                } else {
+                    let _ = window.emit("export:error", &serde_json::json!({ "error": e }));
+                }
+            }
+        }
+        end_session(&sessions, rx);
+    });
+    Ok(())
+}
+
+/// Cancel the previous export if any, then install a new one.
+fn begin_session(state: &AppState) -> CommandResult<broadcast::Receiver<()>> {
+    let mut sess_guard = state.export_session.lock().map_err(Envelope::state)?;
+    if let Some(prev) = sess_guard.take() {
+        let _ = prev.cancel_tx.send(());
+    }
+    let (tx, rx) = broadcast::channel::<()>(1);
+    *sess_guard = Some(LoadSession::new(tx));
+    Ok(rx)
+}
+
+fn end_session(sessions: &Mutex<Option<LoadSession>>, rx: broadcast::Receiver<()>) {
+    // A newer export may have replaced this session already
+    drop(rx);
+    if let Ok(mut guard) = sessions.lock() {
+        if guard.as_ref().is_some_and(|s| s.cancel_tx.receiver_count() == 0) {
+            *guard = None;
+        }
+    }
+}
+
+/// Keys kept by a state export when the caller sets no limit.
+const DEFAULT_STATE_KEYS: usize = 1_000_000;
+
+#[derive(Debug, Deserialize)]
+pub struct StartStateExportArgs {
+    pub path: String,
+    /// "ndjson" (default) | "sqlite" | "parquet" | "csv"
+    pub format: Option<String>,
+    /// CSV columns (see `MessageWriter::create`)
+    pub columns: Option<Vec<String>>,
+    /// Keys kept at most; the export is marked truncated beyond it
+    #[serde(rename = "max_keys", alias = "maxKeys")]
+    pub max_keys: Option<usize>,
+    pub connection: Option<String>,
+}
+
+/// Result of a state export.
+#[derive(Debug, Serialize)]
+pub struct StateExportSummary {
+    #[serde(flatten)]
+    pub export: ExportSummary,
+    pub scanned: usize,
+    /// Keys left out because their newest record is a tombstone
+    pub deleted: usize,
+    pub truncated: bool,
+}
+
+/// Dump the newest value of every live key of a (compacted) topic, like a state store restored from it.
+/// Emits `export:state_progress` ({scanned, keys}) while scanning, then `export:done` (StateExportSummary),
+/// `export:cancelled` or `export:error`; shares the export session, so `cancel_export` stops it.
+#[tauri::command]
+pub async fn start_state_export(window: Window, state: State<'_, AppState>, args: StartStateExportArgs) -> CommandResult<()> {
+    let format = args.format.unwrap_or_else(|| "ndjson".into()).to_lowercase();
+    let config = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        guard
+            .get(args.connection.as_deref())
+            .map(|k| k.config.clone())
+            .ok_or_else(Envelope::not_configured)?
+    };
+    let mut writer = MessageWriter::create(&args.path, &format, config.topic.clone(), args.columns)
+        .map_err(|e| Envelope::failed("start_state_export", e))?;
+    let max_keys = args.max_keys.unwrap_or(DEFAULT_STATE_KEYS);
+    let mut rx = begin_session(&state)?;
+    let sessions = state.export_session.clone();
+    let path = args.path;
+
+    tokio::task::spawn_blocking(move || {
+        use tokio::sync::broadcast::error::TryRecvError;
+
+        let result = Kafka::new(config)
+            .map_err(|e| format!("Failed to create consumer: {e}"))
+            .and_then(|kafka| {
+                kafka
+                    .latest_by_key(max_keys, PROGRESS_EVERY, |scanned, keys| {
+                        let _ = window.emit("export:state_progress", &serde_json::json!({ "scanned": scanned, "keys": keys }));
+                        !matches!(rx.try_recv(), Ok(_) | Err(TryRecvError::Closed))
+                    })
+                    .map_err(|e| format!("Failed to read topic state: {e}"))
+            })
+            .and_then(|snapshot| {
+                if snapshot.cancelled {
+                    return Ok(None);
+                }
+                for m in &snapshot.messages {
+                    writer.write(m)?;
+                }
+                let written = writer.finish()?;
+                Ok(Some(StateExportSummary {
+                    export: ExportSummary { path: path.clone(), format, written },
+                    scanned: snapshot.scanned,
+                    deleted: snapshot.deleted,
+                    truncated: snapshot.truncated,
+                }))
+            });
+
+        match result {
+            Ok(Some(summary)) => {
+                let _ = window.emit("export:done", &summary);
+            }
+            Ok(None) => {
+                let _ = window.emit("export:cancelled", &serde_json::json!({}));
+            }
+            Err(e) => {
+                let _ = window.emit("export:error", &serde_json::json!({ "error": e }));
+            }
+        }
+        end_session(&sessions, rx);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn cancel_export(state: State<'_, AppState>) -> CommandResult<()> {
+    let mut sess_guard = state.export_session.lock().map_err(Envelope::state)?;
+    if let Some(s) = sess_guard.take() {
+        let _ = s.cancel_tx.send(());
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use serde::Deserialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::app::ensure_writable;
+use crate::audit;
+use crate::kafka::{Kafka, KafkaConfig, PartitionStrategy, ProduceRecord, ProduceRequest, ReplaySummary};
+use crate::response::{CommandResult, Envelope};
+use crate::utils::json::json_path_get;
+
+/// Records sent per produce call.
+const IMPORT_BATCH: usize = 500;
+
+/// Where the fields of a record live in each NDJSON line. Defaults match `export_messages`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ImportMapping {
+    /// jq-style path of the key (default `.key`)
+    pub key: Option<String>,
+    /// jq-style path of the value (default `.value`); non-string values are produced as compact JSON
+    pub value: Option<String>,
+    /// jq-style path of the headers (default `.headers`): [{key, value}], [[key, value]] or {key: value}
+    pub headers: Option<String>,
+    /// `explicit` keeps each record's `.partition`; otherwise partitions are assigned anew
+    #[serde(default)]
+    pub partitioning: PartitionStrategy,
+}
+
+fn text(v: Value) -> Option<String> {
+    match v {
+        Value::Null => None,
+        Value::String(s) => Some(s),
+        other => Some(other.to_string()),
+    }
+}
+
+fn headers_of(v: Value) -> Vec<(String, String)> {
+    let pair = |k: String, v: Value| (k, text(v).unwrap_or_default());
+    match v {
+        Value::Object(map) => map.into_iter().map(|(k, v)| pair(k, v)).collect(),
+        Value::Array(items) => items
+            .into_iter()
+            .filter_map(|item| match item {
+                Value::Object(mut h) => Some(pair(text(h.remove("key")?)?, h.remove("value").unwrap_or_default())),
+                Value::Array(mut kv) if kv.len() == 2 => {
+                    let v = kv.pop()?;
+                    Some(pair(text(kv.pop()?)?, v))
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+impl ImportMapping {
+    /// Build the record to produce from one exported line. Lines an export marked `"verbatim": false`
+    /// hold decoded or masked text instead of the record bytes and are refused.
+    pub fn record(&self, line: &Value) -> Result<ProduceRecord, String> {
+        if line.get("verbatim") == Some(&Value::Bool(false)) {
+            return Err("the record was exported decoded or masked; export it with the Text message type, \
+                        string keys and no masking rules to import it"
+                .into());
+        }
+        let get = |path: &Option<String>, default: &str| json_path_get(line, path.as_deref().unwrap_or(default));
+        Ok(ProduceRecord {
+            key: get(&self.key, ".key").and_then(text),
+            value: get(&self.value, ".value").and_then(text),
+            headers: get(&self.headers, ".headers").map(headers_of).unwrap_or_default(),
+            partition: line.get("partition").and_then(Value::as_i64).map(|p| p as i32),
+        })
+    }
+}
+
+async fn produce_batch(config: &KafkaConfig, request: &mut ProduceRequest, summary: &mut ReplaySummary) -> Result<(), String> {
+    if request.records.is_empty() {
+        return Ok(());
+    }
+    let reports = Kafka::produce(config, request)
+        .await
+        .map_err(|e| format!("Failed to produce messages: {e}"))?;
+    for report in reports {
+        match report.error {
+            None => summary.produced += 1,
+            Some(e) => {
+                summary.failed += 1;
+                summary.error.get_or_insert(format!("{}: {}", e.code, e.message));
+            }
+        }
+    }
+    request.records.clear();
+    Ok(())
+}
+
+async fn import_file(config: &KafkaConfig, path: &str, topic: &str, mapping: &ImportMapping) -> Result<ReplaySummary, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open import file: {e}"))?;
+    let mut summary = ReplaySummary::default();
+    let mut request = ProduceRequest {
+        topic: topic.to_string(),
+        records: Vec::with_capacity(IMPORT_BATCH),
+        partitioning: mapping.partitioning,
+        partition: None,
+    };
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read import file: {e}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: Value = serde_json::from_str(&line).map_err(|e| format!("Invalid JSON on line {}: {e}", i + 1))?;
+        request.records.push(mapping.record(&value).map_err(|e| format!("Cannot import line {}: {e}", i + 1))?);
+        summary.read += 1;
+        if request.records.len() >= IMPORT_BATCH {
+            produce_batch(config, &mut request, &mut summary).await?;
+        }
+    }
+    produce_batch(config, &mut request, &mut summary).await?;
+    Ok(summary)
+}
+
+/// Produce every record of an NDJSON export (key, value, headers) into `topic`.
+#[tauri::command]
+pub async fn import_messages(
+    app: AppHandle,
+    config: KafkaConfig,
+    path: String,
+    topic: String,
+    mapping: Option<ImportMapping>,
+) -> CommandResult<ReplaySummary> {
+    ensure_writable("Importing messages")?;
+    let mapping = mapping.unwrap_or_default();
+    let result = import_file(&config, &path, &topic, &mapping).await;
+    let details = serde_json::json!({
+        "path": path,
+        "read": result.as_ref().map(|s| s.read).ok(),
+        "failed": result.as_ref().map(|s| s.failed).ok(),
+        "partitioning": mapping.partitioning,
+    });
+    audit::record(&app, "import", &config, &topic, details, &result);
+    result.map_err(|e| Envelope::failed("import_messages", e))
+}
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rdkafka::admin::{AdminClient, AdminOptions, AlterConfig, ConfigSource, NewPartitions, ResourceSpecifier};
+use rdkafka::config::ClientConfig;
+use rdkafka::topic_partition_list::TopicPartitionList;
+use rdkafka::Offset;
+use serde::Serialize;
+
+use super::oauth::{create_off_runtime, ClientCtx};
+use super::offsets::PartitionOffset;
+use super::service::Kafka;
+use super::types::KafkaConfig;
+use crate::utils::kafka::{apply_custom_properties, configure_security};
+
+/// Build an rdkafka AdminClient configured according to KafkaConfig.
+pub(crate) fn create_admin(config: &KafkaConfig) -> anyhow::Result<AdminClient<ClientCtx>> {
+    let context = ClientCtx::new(config)?;
+    let mut cc = ClientConfig::new();
+    cc.set("bootstrap.servers", context.bootstrap_servers(config));
+    cc.set("socket.timeout.ms", "10000");
+
+    configure_security(&mut cc, config)?;
+    apply_custom_properties(&mut cc, config);
+
+    let admin: AdminClient<ClientCtx> = cc.create_with_context(context)?;
+    // The admin client never polls its main queue, so this token is its only one
+    admin.inner().context().prime(admin.inner())?;
+    Ok(admin)
+}
+
+pub(crate) fn admin_options() -> AdminOptions {
+    AdminOptions::new().request_timeout(Some(Duration::from_secs(15)))
+}
+
+/// One topic configuration parameter as reported by the broker.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicConfigEntry {
+    pub name: String,
+    /// None for sensitive values or parameters without a value
+    pub value: Option<String>,
+    /// dynamic_topic | dynamic_broker | dynamic_default_broker | static_broker | default | unknown
+    pub source: String,
+    pub is_default: bool,
+    pub is_read_only: bool,
+    pub is_sensitive: bool,
+}
+
+/// All configuration parameters of a topic plus the subset overridden on the topic itself.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicConfigs {
+    pub topic: String,
+    pub entries: Vec<TopicConfigEntry>,
+    /// Non-default values set on the topic (what differs from the broker defaults)
+    pub overrides: Vec<TopicConfigEntry>,
+}
+
+impl TopicConfigs {
+    /// Value of a parameter, if the broker reported one.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.entries.iter().find(|e| e.name == name).and_then(|e| e.value.as_deref())
+    }
+}
+
+fn source_name(source: &ConfigSource) -> &'static str {
+    match source {
+        ConfigSource::DynamicTopic => "dynamic_topic",
+        ConfigSource::DynamicBroker => "dynamic_broker",
+        ConfigSource::DynamicDefaultBroker => "dynamic_default_broker",
+        ConfigSource::StaticBroker => "static_broker",
+        ConfigSource::Default => "default",
+        ConfigSource::Unknown => "unknown",
+    }
+}
+
+impl Kafka {
+    /// Describe a topic's configuration (retention.ms, cleanup.policy, max.message.bytes, ...).
+    pub async fn describe_topic_configs(config: &KafkaConfig, topic: &str) -> anyhow::Result<TopicConfigs> {
+        let admin = create_off_runtime(config, create_admin).await?;
+        let results = admin
+            .describe_configs(&[ResourceSpecifier::Topic(topic)], &admin_options())
+            .await?;
+        let resource = results
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Empty DescribeConfigs response"))?
+            .map_err(|code| anyhow::anyhow!("DescribeConfigs failed: {:?}", code))?;
+
+        let mut entries: Vec<TopicConfigEntry> = resource
+            .entries
+            .iter()
+            .map(|e| TopicConfigEntry {
+                name: e.name.clone(),
+                value: e.value.clone(),
+                source: source_name(&e.source).to_string(),
+                is_default: e.is_default,
+                is_read_only: e.is_read_only,
+                is_sensitive: e.is_sensitive,
+            })
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        let overrides = entries
+            .iter()
+            .filter(|e| e.source == "dynamic_topic")
+            .cloned()
+            .collect();
+        Ok(TopicConfigs { topic: topic.to_string(), entries, overrides })
+    }
+
+    /// Change topic configuration parameters. A None value removes the topic override (back to the broker default).
+    /// AlterConfigs replaces the whole set of topic overrides, so existing overrides are re-sent alongside the changes.
+    pub async fn alter_topic_config(
+        config: &KafkaConfig,
+        topic: &str,
+        changes: &HashMap<String, Option<String>>,
+    ) -> anyhow::Result<TopicConfigs> {
+        let current = Self::describe_topic_configs(config, topic).await?;
+        let mut desired: HashMap<String, String> = HashMap::new();
+        for e in &current.overrides {
+            match e.value.as_ref() {
+                Some(v) => {
+                    desired.insert(e.name.clone(), v.clone());
+                }
+                // Sensitive overrides cannot be read back, and re-sending them blank would wipe them
+                None if e.is_sensitive && !changes.contains_key(&e.name) => {
+                    return Err(anyhow::anyhow!(
+                        "Topic has a sensitive override '{}' that would be lost; set it explicitly",
+                        e.name
+                    ));
+                }
+                None => {}
+            }
+        }
+        for (name, value) in changes {
+            match value {
+                Some(v) => {
+                    desired.insert(name.clone(), v.clone());
+                }
+                None => {
+                    desired.remove(name);
+                }
+            }
+        }
+
+        let mut alter = AlterConfig::new(ResourceSpecifier::Topic(topic));
+        for (k, v) in &desired {
+            alter = alter.set(k, v);
+        }
+        let admin = create_off_runtime(config, create_admin).await?;
+        check_alter_results(admin.alter_configs(&[alter], &admin_options()).await?)?;
+        Self::describe_topic_configs(config, topic).await
+    }
+
+    /// Grow a topic to `new_count` partitions. Partitions can only be added, never removed;
+    /// note that key-hash placement changes for keys produced afterwards.
+    pub async fn add_partitions(config: &KafkaConfig, topic: &str, new_count: usize) -> anyhow::Result<Vec<i32>> {
+        let cfg = KafkaConfig { topic: topic.to_string(), ..config.clone() };
+        let current = Self::topic_partitions(&cfg)?.len();
+        if new_count <= current {
+            return Err(anyhow::anyhow!("Topic already has {} partitions; new count must be greater", current));
+        }
+        let admin = create_off_runtime(config, create_admin).await?;
+        let results = admin
+            .create_partitions(&[NewPartitions::new(topic, new_count)], &admin_options())
+            .await?;
+        for r in results {
+            if let Err((name, code)) = r {
+                return Err(anyhow::anyhow!("CreatePartitions failed for {}: {:?}", name, code));
+            }
+        }
+        let mut parts = Self::topic_partitions(&cfg)?;
+        parts.sort_unstable();
+        Ok(parts)
+    }
+
+    /// Delete a topic and all of its data.
+    pub async fn delete_topic(config: &KafkaConfig, topic: &str) -> anyhow::Result<()> {
+        let admin = create_off_runtime(config, create_admin).await?;
+        for r in admin.delete_topics(&[topic], &admin_options()).await? {
+            if let Err((name, code)) = r {
+                return Err(anyhow::anyhow!("DeleteTopics failed for {}: {:?}", name, code));
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete all records before the given offsets (-1 = up to the high watermark).
+    /// Returns the resulting low watermark per partition.
+    pub async fn delete_records(config: &KafkaConfig, topic: &str, offsets: &[PartitionOffset]) -> anyhow::Result<Vec<PartitionOffset>> {
+        if offsets.is_empty() {
+            return Err(anyhow::anyhow!("No offsets given"));
+        }
+        let mut tpl = TopicPartitionList::new();
+        for o in offsets {
+            let offset = if o.offset < 0 { Offset::End } else { Offset::Offset(o.offset) };
+            tpl.add_partition_offset(topic, o.partition, offset)?;
+        }
+        let admin = create_off_runtime(config, create_admin).await?;
+        // The raw request blocks on its result queue
+        let mut out = tokio::task::spawn_blocking(move || delete_records_raw(&admin, &tpl)).await??;
+        out.sort_by_key(|o| o.partition);
+        Ok(out)
+    }
+}
+
+/// DeleteRecords has no safe wrapper in rdkafka 0.36, so the request goes through librdkafka directly
+/// on a private result queue.
+fn delete_records_raw(
+    admin: &AdminClient<ClientCtx>,
+    offsets: &TopicPartitionList,
+) -> anyhow::Result<Vec<PartitionOffset>> {
+    use rdkafka::bindings as rd;
+    use std::ffi::CStr;
+
+    const TIMEOUT_MS: i32 = 30_000;
+    let rk = admin.inner().native_ptr();
+    // SAFETY: every handle created here is destroyed before returning; librdkafka copies the
+    // partition list into the request, and the result list is read before its event is destroyed.
+    unsafe {
+        let queue = rd::rd_kafka_queue_new(rk);
+        let options = rd::rd_kafka_AdminOptions_new(rk, rd::rd_kafka_admin_op_t::RD_KAFKA_ADMIN_OP_DELETERECORDS);
+        let mut errstr = [0 as std::os::raw::c_char; 256];
+        rd::rd_kafka_AdminOptions_set_operation_timeout(options, TIMEOUT_MS, errstr.as_mut_ptr(), errstr.len());
+        let mut request = rd::rd_kafka_DeleteRecords_new(offsets.ptr());
+        rd::rd_kafka_DeleteRecords(rk, &mut request, 1, options, queue);
+        rd::rd_kafka_DeleteRecords_destroy(request);
+        rd::rd_kafka_AdminOptions_destroy(options);
+
+        let event = rd::rd_kafka_queue_poll(queue, TIMEOUT_MS + 15_000);
+        let result = if event.is_null() {
+            Err(anyhow::anyhow!("Timed out waiting for DeleteRecords"))
+        } else if rd::rd_kafka_event_error(event) != rd::rd_kafka_resp_err_t::RD_KAFKA_RESP_ERR_NO_ERROR {
+            let msg = CStr::from_ptr(rd::rd_kafka_event_error_string(event)).to_string_lossy().into_owned();
+            Err(anyhow::anyhow!("DeleteRecords failed: {}", msg))
+        } else {
+            let res = rd::rd_kafka_event_DeleteRecords_result(event);
+            let list = &*rd::rd_kafka_DeleteRecords_result_offsets(res);
+            let elems = std::slice::from_raw_parts(list.elems, list.cnt.max(0) as usize);
+            elems
+                .iter()
+                .map(|e| {
+                    if e.err != rd::rd_kafka_resp_err_t::RD_KAFKA_RESP_ERR_NO_ERROR {
+                        let msg = CStr::from_ptr(rd::rd_kafka_err2str(e.err)).to_string_lossy().into_owned();
+                        return Err(anyhow::anyhow!("DeleteRecords failed for partition {}: {}", e.partition, msg));
+                    }
+                    Ok(PartitionOffset { partition: e.partition, offset: e.offset })
+                })
+                .collect()
+        };
+        if !event.is_null() {
+            rd::rd_kafka_event_destroy(event);
+        }
+        rd::rd_kafka_queue_destroy(queue);
+        result
+    }
+}
+
+fn check_alter_results(results: Vec<rdkafka::admin::AlterConfigsResult>) -> anyhow::Result<()> {
+    for r in results {
+        if let Err((spec, code)) = r {
+            return Err(anyhow::anyhow!("AlterConfigs failed for {:?}: {:?}", spec, code));
+        }
+    }
+    Ok(())
+}
@@ -4,19 +4,111 @@ use rdkafka::consumer::Consumer;
 use rdkafka::topic_partition_list::TopicPartitionList;
 use rdkafka::Offset;
 
+use rdkafka::error::RDKafkaErrorCode;
+use rdkafka::metadata::MetadataTopic;
+
+use super::consumer::{is_authorization_error, AccessDenied};
 use super::service::Kafka;
+use super::types::KafkaConfig;
+
+/// Fail fast on session start when metadata already reports an authorization error for the topic.
+fn check_topic_access(t: &MetadataTopic, topic: &str) -> anyhow::Result<()> {
+    match t.error().map(RDKafkaErrorCode::from) {
+        Some(code) if is_authorization_error(code) => Err(AccessDenied { topic: topic.to_string(), code }.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Partitions to read out of the topic's `available` ones: an explicit list wins over the single `partition`
+/// setting ("all" / empty / none reads everything). Listed ids are deduplicated and must exist.
+pub fn select_partitions(available: &[i32], partition: Option<&str>, list: Option<&[i32]>) -> anyhow::Result<Vec<i32>> {
+    if let Some(list) = list.filter(|l| !l.is_empty()) {
+        let mut out = list.to_vec();
+        out.sort_unstable();
+        out.dedup();
+        if let Some(p) = out.iter().find(|p| !available.contains(p)) {
+            return Err(anyhow::anyhow!("Partition {} does not exist (topic has {})", p, available.len()));
+        }
+        return Ok(out);
+    }
+    match partition {
+        Some(s) if s != "all" && !s.is_empty() => {
+            let p: i32 = s.parse().map_err(|e| anyhow::anyhow!("Invalid partition id '{}': {}", s, e))?;
+            Ok(vec![p])
+        }
+        _ => Ok(available.to_vec()),
+    }
+}
+
+/// Which partitions a connection reads and where it starts; changed by filter commands while the reader is shared.
+#[derive(Debug, Clone, Default)]
+pub struct ReadFilters {
+    pub partition: Option<String>,
+    pub partitions: Option<Vec<i32>>,
+    pub start_offset: Option<i64>,
+    pub start_from: Option<String>,
+}
+
+impl ReadFilters {
+    /// The filters a connection starts with.
+    pub fn from_config(config: &KafkaConfig) -> Self {
+        Self {
+            partition: config.partition.clone(),
+            partitions: config.partitions.clone(),
+            start_offset: config.start_offset,
+            start_from: config.start_from.clone(),
+        }
+    }
+
+    /// True when reading starts from the newest records.
+    pub fn newest(&self) -> bool {
+        self.start_from.as_deref().map(|s| s.eq_ignore_ascii_case("newest")).unwrap_or(false)
+    }
+}
 
 impl Kafka {
+    /// Current partition/offset filters.
+    pub fn filters(&self) -> anyhow::Result<ReadFilters> {
+        Ok(self
+            .filters
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (filters): {e}"))?
+            .clone())
+    }
+
+    /// True when applying these filters would read the same records as now (an omitted `start_from` keeps the current one).
+    pub fn filters_unchanged(
+        &self,
+        partition: Option<&str>,
+        partitions: Option<&[i32]>,
+        start_offset: Option<i64>,
+        start_from: Option<&str>,
+    ) -> bool {
+        let Ok(f) = self.filters() else { return false };
+        f.partition.as_deref() == partition
+            && f.partitions.as_deref() == partitions
+            && f.start_offset == start_offset
+            && start_from.is_none_or(|s| f.start_from.as_deref() == Some(s))
+    }
+
     /// Apply partition/offset filters and reset internal reading state.
-    pub fn apply_filters_mut(
-        &mut self,
+    pub fn apply_filters(
+        &self,
         partition: Option<String>,
+        partitions: Option<Vec<i32>>,
         start_offset: Option<i64>,
         start_from: Option<String>,
     ) -> anyhow::Result<()> {
-        self.config.partition = partition;
-        self.config.start_offset = start_offset;
-        self.config.start_from = start_from.or_else(|| self.config.start_from.clone());
+        {
+            let mut f = self
+                .filters
+                .lock()
+                .map_err(|e| anyhow::anyhow!("State lock poisoned (filters): {e}"))?;
+            f.start_from = start_from.or_else(|| f.start_from.take());
+            f.partition = partition;
+            f.partitions = partitions;
+            f.start_offset = start_offset;
+        }
         // Reset assignment state so next consume will reassign
         self.assigned.store(true, std::sync::atomic::Ordering::SeqCst);
         self.end_offsets
@@ -35,6 +127,19 @@ impl Kafka {
             .lock()
             .map_err(|e| anyhow::anyhow!("State lock poisoned (buffers): {e}"))?
             .clear();
+        self.start_offsets
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (start_offsets): {e}"))?
+            .clear();
+        self.delivered
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (delivered): {e}"))?
+            .clear();
+        *self
+            .partition_queues
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (partition_queues): {e}"))? = None;
+        self.queues_split.store(false, std::sync::atomic::Ordering::SeqCst);
         let empty = TopicPartitionList::new();
         self.consumer.assign(&empty)?;
         // Mark as not assigned so next consume will ensure assignment
@@ -49,26 +154,12 @@ impl Kafka {
             return Ok(()); // already assigned
         }
         let topic = &self.config.topic;
+        let filters = self.filters()?;
         // Determine partitions to consume
-        let partitions: Vec<i32> = if let Some(part_str) = &self.config.partition {
-            if part_str != "all" && !part_str.is_empty() {
-                let p: i32 = part_str
-                    .parse()
-                    .map_err(|e| anyhow::anyhow!("Invalid partition id '{}': {}", part_str, e))?;
-                vec![p]
-            } else {
-                // enumerate all partitions for topic
-                let md = self
-                    .consumer
-                    .client()
-                    .fetch_metadata(Some(topic), Duration::from_secs(5))?;
-                let t = md
-                    .topics()
-                    .iter()
-                    .find(|t| t.name() == topic)
-                    .ok_or_else(|| anyhow::anyhow!("Topic not found in metadata"))?;
-                t.partitions().iter().map(|p| p.id()).collect()
-            }
+        let list = filters.partitions.as_deref().filter(|l| !l.is_empty());
+        let single = filters.partition.as_deref().filter(|s| *s != "all" && !s.is_empty());
+        let partitions: Vec<i32> = if list.is_none() && single.is_some() {
+            select_partitions(&[], single, None)?
         } else {
             // enumerate all partitions for topic
             let md = self
@@ -80,7 +171,9 @@ impl Kafka {
                 .iter()
                 .find(|t| t.name() == topic)
                 .ok_or_else(|| anyhow::anyhow!("Topic not found in metadata"))?;
-            t.partitions().iter().map(|p| p.id()).collect()
+            check_topic_access(t, topic)?;
+            let available: Vec<i32> = t.partitions().iter().map(|p| p.id()).collect();
+            select_partitions(&available, None, list)?
         };
 
         // Snapshot low/high watermarks and pre-mark empty partitions as done
@@ -111,6 +204,12 @@ impl Kafka {
                 .map_err(|e| anyhow::anyhow!("State lock poisoned (partitions): {e}"))?;
             *parts = partitions.clone();
         }
+        // A new assignment deactivates split queues of the previous one
+        *self
+            .partition_queues
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (partition_queues): {e}"))? = None;
+        self.queues_split.store(false, Ordering::SeqCst);
         // init buffers for partitions
         self.buffers
             .lock()
@@ -119,18 +218,13 @@ impl Kafka {
 
         // Assign explicit starting offsets based on selected partition and requested start_offset
         let mut tpl = TopicPartitionList::new();
-        let is_all = self
-            .config
-            .partition
-            .as_deref()
-            .map(|s| s == "all")
-            .unwrap_or(true);
-        let newest = self
-            .config
-            .start_from
-            .as_deref()
-            .map(|s| s.eq_ignore_ascii_case("newest"))
-            .unwrap_or(false);
+        // start_offset only applies to a single selected partition
+        let is_all = match list {
+            Some(l) => l.len() > 1,
+            None => filters.partition.as_deref().map(|s| s == "all").unwrap_or(true),
+        };
+        let newest = filters.newest();
+        let mut starts = std::collections::HashMap::new();
         const BACK_WINDOW: i64 = 2000; // how many latest offsets to read back from end when starting from newest
         for p in partitions {
             let off = if newest {
@@ -139,9 +233,9 @@ impl Kafka {
                 let start = if high > BACK_WINDOW { high - BACK_WINDOW } else { low };
                 Offset::Offset(start)
             } else if is_all {
-                // When reading all partitions, ignore start_offset and begin from earliest for each
+                // When reading several partitions, ignore start_offset and begin from earliest for each
                 Offset::Beginning
-            } else if let Some(req) = self.config.start_offset {
+            } else if let Some(req) = filters.start_offset {
                 // Clamp to earliest available if requested offset is older than retention (deleted)
                 let (low, _high) = self
                     .consumer
@@ -151,9 +245,22 @@ impl Kafka {
             } else {
                 Offset::Beginning
             };
+            let start = match off {
+                Offset::Offset(o) => o,
+                _ => self.consumer.fetch_watermarks(topic, p, Duration::from_secs(5))?.0,
+            };
+            starts.insert(p, start);
             tpl.add_partition_offset(topic, p, off)?;
         }
-        self.consumer.assign(&tpl)?;
+        *self
+            .start_offsets
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (start_offsets): {e}"))? = starts;
+        self.delivered
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (delivered): {e}"))?
+            .clear();
+        self.assign(&tpl)?;
         Ok(())
     }
 }
@@ -0,0 +1,364 @@
+use std::collections::HashMap;
+
+use base64::Engine;
+use serde_json::Value;
+
+/// Records nested deeper than this are rejected (guards recursive schemas against hostile payloads).
+const MAX_DEPTH: usize = 128;
+
+/// Parsed Avro writer schema. Named types are resolved lazily through `AvroSchema::named`,
+/// which keeps recursive records representable.
+#[derive(Debug, Clone)]
+pub enum Schema {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    Record { name: String, fields: Vec<(String, Schema)> },
+    Enum { name: String, symbols: Vec<String> },
+    Fixed { name: String, size: usize },
+    Array(Box<Schema>),
+    Map(Box<Schema>),
+    Union(Vec<Schema>),
+    /// bytes/fixed carrying a big-endian two's complement unscaled value
+    Decimal { scale: u32, inner: Box<Schema> },
+    /// Reference to a named type (full name)
+    Ref(String),
+}
+
+/// A writer schema together with the named types it declares.
+#[derive(Debug, Clone)]
+pub struct AvroSchema {
+    pub root: Schema,
+    named: HashMap<String, Schema>,
+}
+
+impl AvroSchema {
+    /// Parse a schema from its JSON text (as stored in the schema registry).
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let json: Value = serde_json::from_str(text).map_err(|e| anyhow::anyhow!("Invalid Avro schema JSON: {e}"))?;
+        let mut named = HashMap::new();
+        let root = parse_schema(&json, None, &mut named)?;
+        Ok(Self { root, named })
+    }
+
+    /// Decode a single Avro binary datum into JSON text. Field order follows the schema.
+    /// Unions render as the bare branch value, bytes/fixed as base64, decimals as strings.
+    pub fn decode_to_json(&self, data: &[u8]) -> anyhow::Result<String> {
+        let mut r = Reader { data, pos: 0 };
+        let mut out = String::with_capacity(data.len() * 2);
+        self.write_value(&self.root, &mut r, &mut out, 0)?;
+        if r.pos != data.len() {
+            anyhow::bail!("{} trailing bytes after Avro datum (schema mismatch?)", data.len() - r.pos);
+        }
+        Ok(out)
+    }
+
+    fn write_value(&self, schema: &Schema, r: &mut Reader, out: &mut String, depth: usize) -> anyhow::Result<()> {
+        if depth > MAX_DEPTH {
+            anyhow::bail!("Avro datum nested deeper than {MAX_DEPTH} levels");
+        }
+        match schema {
+            Schema::Null => out.push_str("null"),
+            Schema::Boolean => out.push_str(if r.byte()? != 0 { "true" } else { "false" }),
+            Schema::Int | Schema::Long => out.push_str(&r.long()?.to_string()),
+            Schema::Float => push_float(out, f32::from_le_bytes(r.array()?) as f64),
+            Schema::Double => push_float(out, f64::from_le_bytes(r.array()?)),
+            Schema::Bytes => {
+                let len = r.len()?;
+                push_base64(out, r.take(len)?);
+            }
+            Schema::String => {
+                let len = r.len()?;
+                let s = std::str::from_utf8(r.take(len)?).map_err(|e| anyhow::anyhow!("Invalid UTF-8 in Avro string: {e}"))?;
+                push_str(out, s);
+            }
+            Schema::Record { fields, .. } => {
+                out.push('{');
+                for (i, (name, field)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    push_str(out, name);
+                    out.push(':');
+                    self.write_value(field, r, out, depth + 1)?;
+                }
+                out.push('}');
+            }
+            Schema::Enum { name, symbols } => {
+                let idx = r.long()?;
+                let symbol = usize::try_from(idx)
+                    .ok()
+                    .and_then(|i| symbols.get(i))
+                    .ok_or_else(|| anyhow::anyhow!("Enum index {idx} out of range for {name}"))?;
+                push_str(out, symbol);
+            }
+            Schema::Fixed { size, .. } => push_base64(out, r.take(*size)?),
+            Schema::Array(items) => {
+                out.push('[');
+                let mut first = true;
+                while let Some(count) = r.block_count()? {
+                    for _ in 0..count {
+                        if !first {
+                            out.push(',');
+                        }
+                        first = false;
+                        self.write_value(items, r, out, depth + 1)?;
+                    }
+                }
+                out.push(']');
+            }
+            Schema::Map(values) => {
+                out.push('{');
+                let mut first = true;
+                while let Some(count) = r.block_count()? {
+                    for _ in 0..count {
+                        if !first {
+                            out.push(',');
+                        }
+                        first = false;
+                        let len = r.len()?;
+                        let key = String::from_utf8_lossy(r.take(len)?).to_string();
+                        push_str(out, &key);
+                        out.push(':');
+                        self.write_value(values, r, out, depth + 1)?;
+                    }
+                }
+                out.push('}');
+            }
+            Schema::Union(branches) => {
+                let idx = r.long()?;
+                let branch = usize::try_from(idx)
+                    .ok()
+                    .and_then(|i| branches.get(i))
+                    .ok_or_else(|| anyhow::anyhow!("Union branch {idx} out of range"))?;
+                self.write_value(branch, r, out, depth + 1)?;
+            }
+            Schema::Decimal { scale, inner } => {
+                let bytes = match self.resolve(inner)? {
+                    Schema::Fixed { size, .. } => r.take(*size)?,
+                    _ => {
+                        let len = r.len()?;
+                        r.take(len)?
+                    }
+                };
+                match decimal_string(bytes, *scale) {
+                    Some(s) => push_str(out, &s),
+                    None => push_base64(out, bytes),
+                }
+            }
+            Schema::Ref(name) => {
+                let target = self.resolve(schema).map_err(|_| anyhow::anyhow!("Unknown Avro type {name}"))?;
+                self.write_value(target, r, out, depth + 1)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve<'a>(&'a self, schema: &'a Schema) -> anyhow::Result<&'a Schema> {
+        match schema {
+            Schema::Ref(name) => self.named.get(name).ok_or_else(|| anyhow::anyhow!("Unknown Avro type {name}")),
+            other => Ok(other),
+        }
+    }
+}
+
+fn full_name(name: &str, namespace: Option<&str>) -> String {
+    match namespace {
+        Some(ns) if !ns.is_empty() && !name.contains('.') => format!("{ns}.{name}"),
+        _ => name.to_string(),
+    }
+}
+
+fn parse_schema(json: &Value, namespace: Option<&str>, named: &mut HashMap<String, Schema>) -> anyhow::Result<Schema> {
+    match json {
+        Value::String(name) => Ok(match name.as_str() {
+            "null" => Schema::Null,
+            "boolean" => Schema::Boolean,
+            "int" => Schema::Int,
+            "long" => Schema::Long,
+            "float" => Schema::Float,
+            "double" => Schema::Double,
+            "bytes" => Schema::Bytes,
+            "string" => Schema::String,
+            other => {
+                let full = full_name(other, namespace);
+                // Unqualified names may also refer to types declared in the null namespace
+                if named.contains_key(&full) || !named.contains_key(other) {
+                    Schema::Ref(full)
+                } else {
+                    Schema::Ref(other.to_string())
+                }
+            }
+        }),
+        Value::Array(branches) => Ok(Schema::Union(
+            branches.iter().map(|b| parse_schema(b, namespace, named)).collect::<anyhow::Result<_>>()?,
+        )),
+        Value::Object(obj) => {
+            let ty = obj.get("type").ok_or_else(|| anyhow::anyhow!("Avro schema object without \"type\""))?;
+            let name = obj.get("name").and_then(Value::as_str);
+            let ns = obj.get("namespace").and_then(Value::as_str).or(namespace);
+            let parsed = match ty.as_str() {
+                Some("record") | Some("error") => {
+                    let full = full_name(name.ok_or_else(|| anyhow::anyhow!("Avro record without a name"))?, ns);
+                    // Names inside the record resolve against the record's own namespace
+                    let inner_ns = full.rsplit_once('.').map(|(ns, _)| ns.to_string());
+                    let raw_fields = obj
+                        .get("fields")
+                        .and_then(Value::as_array)
+                        .ok_or_else(|| anyhow::anyhow!("Avro record {full} without fields"))?;
+                    // Register a reference first so self-referencing fields can be parsed
+                    named.insert(full.clone(), Schema::Ref(full.clone()));
+                    let mut fields = Vec::with_capacity(raw_fields.len());
+                    for f in raw_fields {
+                        let fname = f
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .ok_or_else(|| anyhow::anyhow!("Avro field without a name in {full}"))?;
+                        let fty = f.get("type").ok_or_else(|| anyhow::anyhow!("Avro field {full}.{fname} without a type"))?;
+                        fields.push((fname.to_string(), parse_schema(fty, inner_ns.as_deref(), named)?));
+                    }
+                    let record = Schema::Record { name: full.clone(), fields };
+                    named.insert(full, record.clone());
+                    record
+                }
+                Some("enum") => {
+                    let full = full_name(name.ok_or_else(|| anyhow::anyhow!("Avro enum without a name"))?, ns);
+                    let symbols = obj
+                        .get("symbols")
+                        .and_then(Value::as_array)
+                        .map(|s| s.iter().filter_map(Value::as_str).map(str::to_string).collect())
+                        .unwrap_or_default();
+                    let schema = Schema::Enum { name: full.clone(), symbols };
+                    named.insert(full, schema.clone());
+                    schema
+                }
+                Some("fixed") => {
+                    let full = full_name(name.ok_or_else(|| anyhow::anyhow!("Avro fixed without a name"))?, ns);
+                    let size = obj
+                        .get("size")
+                        .and_then(Value::as_u64)
+                        .ok_or_else(|| anyhow::anyhow!("Avro fixed {full} without a size"))? as usize;
+                    let schema = Schema::Fixed { name: full.clone(), size };
+                    named.insert(full, schema.clone());
+                    schema
+                }
+                Some("array") => Schema::Array(Box::new(parse_schema(
+                    obj.get("items").ok_or_else(|| anyhow::anyhow!("Avro array without items"))?,
+                    ns,
+                    named,
+                )?)),
+                Some("map") => Schema::Map(Box::new(parse_schema(
+                    obj.get("values").ok_or_else(|| anyhow::anyhow!("Avro map without values"))?,
+                    ns,
+                    named,
+                )?)),
+                // Primitive wrapped in an object (e.g. with a logicalType), or a nested type definition
+                _ => parse_schema(ty, ns, named)?,
+            };
+            if obj.get("logicalType").and_then(Value::as_str) == Some("decimal") {
+                if let Schema::Bytes | Schema::Fixed { .. } = parsed {
+                    let scale = obj.get("scale").and_then(Value::as_u64).unwrap_or(0) as u32;
+                    return Ok(Schema::Decimal { scale, inner: Box::new(parsed) });
+                }
+            }
+            Ok(parsed)
+        }
+        other => anyhow::bail!("Unsupported Avro schema node: {other}"),
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> anyhow::Result<&'a [u8]> {
+        let end = self.pos.checked_add(n).filter(|e| *e <= self.data.len());
+        let end = end.ok_or_else(|| anyhow::anyhow!("Avro datum truncated at byte {}", self.pos))?;
+        let out = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn byte(&mut self) -> anyhow::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> anyhow::Result<[u8; N]> {
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(self.take(N)?);
+        Ok(buf)
+    }
+
+    /// Zigzag varint (int and long share the encoding).
+    fn long(&mut self) -> anyhow::Result<i64> {
+        let mut raw = 0u64;
+        for shift in (0..70).step_by(7) {
+            let b = self.byte()?;
+            raw |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(((raw >> 1) as i64) ^ -((raw & 1) as i64));
+            }
+        }
+        anyhow::bail!("Avro varint too long at byte {}", self.pos)
+    }
+
+    fn len(&mut self) -> anyhow::Result<usize> {
+        let n = self.long()?;
+        usize::try_from(n).map_err(|_| anyhow::anyhow!("Negative Avro length {n}"))
+    }
+
+    /// Item count of the next array/map block; None at the terminating empty block.
+    fn block_count(&mut self) -> anyhow::Result<Option<u64>> {
+        let count = self.long()?;
+        if count == 0 {
+            return Ok(None);
+        }
+        if count < 0 {
+            // Negative counts are followed by the block size in bytes
+            self.long()?;
+        }
+        Ok(Some(count.unsigned_abs()))
+    }
+}
+
+fn push_str(out: &mut String, s: &str) {
+    out.push_str(&serde_json::to_string(s).unwrap_or_default());
+}
+
+fn push_float(out: &mut String, v: f64) {
+    match serde_json::Number::from_f64(v) {
+        Some(n) => out.push_str(&n.to_string()),
+        // NaN and infinities have no JSON number form
+        None => push_str(out, &v.to_string()),
+    }
+}
+
+fn push_base64(out: &mut String, bytes: &[u8]) {
+    push_str(out, &base64::engine::general_purpose::STANDARD.encode(bytes));
+}
+
+/// Render a decimal's unscaled two's complement bytes with the given scale (None when wider than i128).
+fn decimal_string(bytes: &[u8], scale: u32) -> Option<String> {
+    if bytes.is_empty() || bytes.len() > 16 {
+        return None;
+    }
+    let fill = if bytes[0] & 0x80 != 0 { 0xff } else { 0 };
+    let mut buf = [fill; 16];
+    buf[16 - bytes.len()..].copy_from_slice(bytes);
+    let unscaled = i128::from_be_bytes(buf);
+    if scale == 0 {
+        return Some(unscaled.to_string());
+    }
+    let digits = unscaled.unsigned_abs().to_string();
+    let scale = scale as usize;
+    let padded = format!("{digits:0>width$}", width = scale + 1);
+    let (int, frac) = padded.split_at(padded.len() - scale);
+    Some(format!("{}{int}.{frac}", if unscaled < 0 { "-" } else { "" }))
+}
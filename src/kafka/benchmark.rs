@@ -0,0 +1,203 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use rdkafka::producer::{DeliveryFuture, FutureRecord, Producer};
+use serde::{Deserialize, Serialize};
+
+use super::oauth::create_off_runtime;
+use super::producer::create_producer;
+use super::service::Kafka;
+use super::types::{KafkaConfig, ProducerDefaults};
+
+const MAX_MESSAGES: usize = 1_000_000;
+const MAX_MESSAGE_SIZE: usize = 10 * 1024 * 1024;
+
+/// Producer settings and workload of a produce benchmark.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProduceBenchmark {
+    #[serde(default = "default_messages")]
+    pub messages: usize,
+    /// Payload size in bytes
+    #[serde(rename = "message_size", alias = "messageSize", default = "default_size")]
+    pub message_size: usize,
+    /// "0" | "1" | "all"; the connection's producer defaults when omitted
+    pub acks: Option<String>,
+    /// "none" | "gzip" | "snappy" | "lz4" | "zstd"
+    #[serde(rename = "compression_type", alias = "compressionType")]
+    pub compression_type: Option<String>,
+    #[serde(rename = "linger_ms", alias = "lingerMs")]
+    pub linger_ms: Option<u32>,
+    /// Records awaiting acknowledgement at once
+    #[serde(rename = "max_in_flight", alias = "maxInFlight", default = "default_in_flight")]
+    pub max_in_flight: usize,
+    /// Repetitive text payloads instead of random bytes, to see what compression gains
+    #[serde(default)]
+    pub compressible: bool,
+}
+
+fn default_messages() -> usize { 10_000 }
+fn default_size() -> usize { 1024 }
+fn default_in_flight() -> usize { 1000 }
+
+/// Produce latencies in milliseconds (enqueue to acknowledgement).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LatencySummary {
+    pub min: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl LatencySummary {
+    /// Nearest-rank percentiles of the given latencies.
+    pub fn from_latencies(mut latencies: Vec<f64>) -> Self {
+        if latencies.is_empty() {
+            return Self::default();
+        }
+        latencies.sort_by(f64::total_cmp);
+        let at = |p: f64| {
+            let rank = (p / 100.0 * latencies.len() as f64).ceil() as usize;
+            latencies[rank.clamp(1, latencies.len()) - 1]
+        };
+        Self {
+            min: latencies[0],
+            p50: at(50.0),
+            p95: at(95.0),
+            p99: at(99.0),
+            max: latencies[latencies.len() - 1],
+            mean: latencies.iter().sum::<f64>() / latencies.len() as f64,
+        }
+    }
+}
+
+/// Result of a produce benchmark.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProduceBenchmarkReport {
+    pub topic: String,
+    pub acks: Option<String>,
+    #[serde(rename = "compression_type")]
+    pub compression_type: Option<String>,
+    pub produced: usize,
+    pub failed: usize,
+    /// Payload bytes acknowledged
+    pub bytes: u64,
+    #[serde(rename = "elapsed_ms")]
+    pub elapsed_ms: u64,
+    #[serde(rename = "messages_per_second")]
+    pub messages_per_second: f64,
+    #[serde(rename = "megabytes_per_second")]
+    pub megabytes_per_second: f64,
+    /// Latencies of acknowledged records
+    #[serde(rename = "latency_ms")]
+    pub latency_ms: LatencySummary,
+    /// First delivery error, if any
+    pub error: Option<String>,
+}
+
+fn fill_payload(buf: &mut [u8], compressible: bool, seq: usize) -> anyhow::Result<()> {
+    if compressible {
+        let text = format!("{{\"seq\":{seq},\"status\":\"OK\",\"source\":\"rkui-benchmark\"}} ");
+        for (b, t) in buf.iter_mut().zip(text.bytes().cycle()) {
+            *b = t;
+        }
+        return Ok(());
+    }
+    openssl::rand::rand_bytes(buf).map_err(|e| anyhow::anyhow!("Failed to generate payload: {}", e))
+}
+
+type Delivery = tokio::task::JoinHandle<(<DeliveryFuture as std::future::Future>::Output, Duration)>;
+
+/// Wait for one delivery and account for it.
+async fn collect(handle: Delivery, latencies: &mut Vec<f64>, failed: &mut usize, error: &mut Option<String>) -> anyhow::Result<()> {
+    let (res, elapsed) = handle.await.map_err(|e| anyhow::anyhow!("Delivery task failed: {}", e))?;
+    match res {
+        Ok(Ok(_)) => latencies.push(elapsed.as_secs_f64() * 1000.0),
+        Ok(Err((e, _))) => {
+            *failed += 1;
+            error.get_or_insert(e.to_string());
+        }
+        Err(_) => {
+            *failed += 1;
+            error.get_or_insert("Producer dropped before delivery".into());
+        }
+    }
+    Ok(())
+}
+
+impl Kafka {
+    /// Produce `messages` test records of `message_size` bytes to `topic` and measure latency and throughput.
+    pub async fn benchmark_produce(
+        config: &KafkaConfig,
+        topic: &str,
+        bench: &ProduceBenchmark,
+    ) -> anyhow::Result<ProduceBenchmarkReport> {
+        if bench.messages == 0 || bench.messages > MAX_MESSAGES {
+            return Err(anyhow::anyhow!("Message count must be between 1 and {}", MAX_MESSAGES));
+        }
+        if bench.message_size > MAX_MESSAGE_SIZE {
+            return Err(anyhow::anyhow!("Message size must be at most {} bytes", MAX_MESSAGE_SIZE));
+        }
+        let defaults = config.producer_defaults.clone().unwrap_or_default();
+        let settings = ProducerDefaults {
+            acks: bench.acks.clone().or(defaults.acks),
+            compression_type: bench.compression_type.clone().or(defaults.compression_type),
+            linger_ms: bench.linger_ms.or(defaults.linger_ms),
+            default_headers: Vec::new(),
+        };
+        let cfg = KafkaConfig { producer_defaults: Some(settings.clone()), ..config.clone() };
+        let producer = create_off_runtime(&cfg, create_producer).await?;
+        producer.client().fetch_metadata(Some(topic), Duration::from_secs(5))?;
+
+        let max_in_flight = bench.max_in_flight.max(1);
+        let mut payload = vec![0u8; bench.message_size];
+        let mut in_flight = VecDeque::with_capacity(max_in_flight);
+        let mut latencies = Vec::with_capacity(bench.messages);
+        let mut failed = 0usize;
+        let mut error: Option<String> = None;
+
+        let started = Instant::now();
+        for seq in 0..bench.messages {
+            if in_flight.len() >= max_in_flight {
+                if let Some(handle) = in_flight.pop_front() {
+                    collect(handle, &mut latencies, &mut failed, &mut error).await?;
+                }
+            }
+            fill_payload(&mut payload, bench.compressible, seq)?;
+            let record: FutureRecord<'_, [u8], [u8]> = FutureRecord::to(topic).payload(&payload);
+            let sent = Instant::now();
+            match producer.send_result(record) {
+                Ok(fut) => in_flight.push_back(tokio::spawn(async move {
+                    let res = fut.await;
+                    (res, sent.elapsed())
+                })),
+                Err((e, _)) => {
+                    failed += 1;
+                    error.get_or_insert(e.to_string());
+                }
+            }
+        }
+        while let Some(handle) = in_flight.pop_front() {
+            collect(handle, &mut latencies, &mut failed, &mut error).await?;
+        }
+        let elapsed = started.elapsed();
+
+        let produced = latencies.len();
+        let bytes = (produced * bench.message_size) as u64;
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        Ok(ProduceBenchmarkReport {
+            topic: topic.to_string(),
+            acks: settings.acks,
+            compression_type: settings.compression_type,
+            produced,
+            failed,
+            bytes,
+            elapsed_ms: elapsed.as_millis() as u64,
+            messages_per_second: produced as f64 / secs,
+            megabytes_per_second: bytes as f64 / (1024.0 * 1024.0) / secs,
+            latency_ms: LatencySummary::from_latencies(latencies),
+            error,
+        })
+    }
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use rdkafka::consumer::Consumer;
+
+use super::admin::{admin_options, create_admin};
+use super::consumer::create_consumer;
+use super::oauth::create_off_runtime;
+use super::offsets::PartitionOffset;
+use super::service::Kafka;
+use super::types::KafkaConfig;
+
+/// Consumer groups holding shared read positions are named `rkui-bookmark-<name>`.
+pub const BOOKMARK_GROUP_PREFIX: &str = "rkui-bookmark-";
+
+/// Group id of a bookmark; names are limited to letters, digits, '.', '_' and '-'.
+pub fn bookmark_group(name: &str) -> anyhow::Result<String> {
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')) {
+        return Err(anyhow::anyhow!("Invalid bookmark name '{}': use letters, digits, '.', '_' or '-'", name));
+    }
+    Ok(format!("{BOOKMARK_GROUP_PREFIX}{name}"))
+}
+
+impl Kafka {
+    /// Next offset the reader would show per partition: after the last record handed to the UI,
+    /// or the assignment start for partitions nothing was read from yet.
+    pub fn read_position(&self) -> anyhow::Result<Vec<PartitionOffset>> {
+        let parts = self
+            .partitions
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (partitions): {e}"))?
+            .clone();
+        let starts = self
+            .start_offsets
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (start_offsets): {e}"))?
+            .clone();
+        let delivered = self
+            .delivered
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (delivered): {e}"))?
+            .clone();
+        let mut out: Vec<PartitionOffset> = parts
+            .into_iter()
+            .filter_map(|p| {
+                let offset = match delivered.get(&p) {
+                    Some(&(_, last)) => last + 1,
+                    None => *starts.get(&p)?,
+                };
+                Some(PartitionOffset { partition: p, offset })
+            })
+            .collect();
+        out.sort_by_key(|o| o.partition);
+        Ok(out)
+    }
+
+    /// Commit the reader's current position on its topic under the bookmark's group, replacing an older one.
+    pub fn save_bookmark(&self, name: &str) -> anyhow::Result<Vec<PartitionOffset>> {
+        let group = bookmark_group(name)?;
+        let offsets = self.read_position()?;
+        if offsets.is_empty() {
+            return Err(anyhow::anyhow!("Nothing to bookmark: no messages have been loaded yet"));
+        }
+        Self::prepare_group_offsets(&self.config, &group, &self.config.topic, &offsets, true)
+    }
+
+    /// Names of all bookmarks on the cluster.
+    pub fn list_bookmarks(config: &KafkaConfig) -> anyhow::Result<Vec<String>> {
+        let consumer = create_consumer(config)?;
+        let groups = consumer.client().fetch_group_list(None, Duration::from_secs(10))?;
+        let mut names: Vec<String> = groups
+            .groups()
+            .iter()
+            .filter_map(|g| g.name().strip_prefix(BOOKMARK_GROUP_PREFIX).map(str::to_string))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Remove a bookmark's group and its committed offsets.
+    pub async fn delete_bookmark(config: &KafkaConfig, name: &str) -> anyhow::Result<()> {
+        let group = bookmark_group(name)?;
+        let admin = create_off_runtime(config, create_admin).await?;
+        for r in admin.delete_groups(&[group.as_str()], &admin_options()).await? {
+            if let Err((name, code)) = r {
+                return Err(anyhow::anyhow!("DeleteGroups failed for {}: {:?}", name, code));
+            }
+        }
+        Ok(())
+    }
+}
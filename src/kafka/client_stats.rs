@@ -0,0 +1,105 @@
+use rdkafka::statistics::Statistics;
+use serde::Serialize;
+
+/// How often the reading session's consumer reports statistics (`statistics.interval.ms`).
+pub const STATS_INTERVAL_MS: u64 = 5000;
+
+/// Connection to one broker.
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokerStats {
+    pub name: String,
+    pub node_id: i32,
+    /// librdkafka connection state, e.g. "UP", "CONNECT", "DOWN"
+    pub state: String,
+    /// Request round trip over the last interval, in ms (mostly Fetch requests for a reading session)
+    pub rtt_avg_ms: Option<f64>,
+    pub rtt_p99_ms: Option<f64>,
+    /// Requests waiting to be sent
+    pub outbuf_cnt: i64,
+    /// Requests sent and awaiting a response
+    pub waitresp_cnt: i64,
+}
+
+/// Fetch queue of one assigned partition.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionStats {
+    pub topic: String,
+    pub partition: i32,
+    /// Records fetched and not yet handed to the app, and their bytes
+    pub fetchq_cnt: i64,
+    pub fetchq_size: u64,
+    /// e.g. "active", "offset-query", "none"
+    pub fetch_state: String,
+    /// Offsets between the read position and the high watermark; -1 while unknown
+    pub consumer_lag: i64,
+}
+
+/// Latest librdkafka statistics of a connection's consumer, reduced to what the UI shows.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientStats {
+    /// Unix milliseconds of the report
+    pub timestamp: i64,
+    /// Events waiting in the client's reply queue
+    pub replyq: i64,
+    /// Round trip of fetch requests averaged over all brokers, in ms
+    pub fetch_latency_ms: Option<f64>,
+    pub brokers: Vec<BrokerStats>,
+    pub partitions: Vec<PartitionStats>,
+}
+
+fn micros_to_ms(us: i64) -> f64 {
+    us as f64 / 1000.0
+}
+
+impl From<&Statistics> for ClientStats {
+    fn from(s: &Statistics) -> Self {
+        let mut brokers: Vec<BrokerStats> = s
+            .brokers
+            .values()
+            // The internal pseudo-broker (nodeid -1) only queues work for unknown leaders
+            .filter(|b| b.nodeid >= 0)
+            .map(|b| {
+                let rtt = b.rtt.as_ref().filter(|w| w.cnt > 0);
+                BrokerStats {
+                    name: b.nodename.clone(),
+                    node_id: b.nodeid,
+                    state: b.state.clone(),
+                    rtt_avg_ms: rtt.map(|w| micros_to_ms(w.avg)),
+                    rtt_p99_ms: rtt.map(|w| micros_to_ms(w.p99)),
+                    outbuf_cnt: b.outbuf_cnt,
+                    waitresp_cnt: b.waitresp_cnt,
+                }
+            })
+            .collect();
+        brokers.sort_by_key(|b| b.node_id);
+
+        let (sum, cnt) = s
+            .brokers
+            .values()
+            .filter_map(|b| b.rtt.as_ref().filter(|w| w.cnt > 0))
+            .fold((0i64, 0i64), |(sum, cnt), w| (sum + w.sum, cnt + w.cnt));
+        let fetch_latency_ms = (cnt > 0).then(|| micros_to_ms(sum) / cnt as f64);
+
+        let mut partitions: Vec<PartitionStats> = s
+            .topics
+            .values()
+            .flat_map(|t| {
+                t.partitions
+                    .values()
+                    // Partition -1 holds records not yet assigned to a partition (producers only)
+                    .filter(|p| p.partition >= 0)
+                    .map(|p| PartitionStats {
+                        topic: t.topic.clone(),
+                        partition: p.partition,
+                        fetchq_cnt: p.fetchq_cnt,
+                        fetchq_size: p.fetchq_size,
+                        fetch_state: p.fetch_state.clone(),
+                        consumer_lag: p.consumer_lag,
+                    })
+            })
+            .collect();
+        partitions.sort_by(|a, b| (&a.topic, a.partition).cmp(&(&b.topic, b.partition)));
+
+        ClientStats { timestamp: s.time * 1000, replyq: s.replyq, fetch_latency_ms, brokers, partitions }
+    }
+}
@@ -0,0 +1,435 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use rdkafka::message::{
+    BorrowedHeaders, BorrowedMessage, Headers, Message as RdMessage, OwnedHeaders, OwnedMessage, Timestamp,
+};
+
+use super::compression::{decompress, PayloadCompression};
+use super::decoder::{decode_simple_key, decoder_for, AvroDecoder, KeyType, MessageType};
+use super::masking::Masker;
+use super::plugin::PluginDecoder;
+use super::types::{ExtractColumn, MessageTypeRule, UiMessage};
+use crate::proto_decoder::{parse_confluent_header, ConfluentHeader, ProtoDecoder};
+use crate::utils::json::json_path_get;
+
+/// Decoded payload text with metadata about how it was produced.
+#[derive(Default)]
+pub struct DecodedPayload {
+    pub value: String,
+    pub error: Option<String>,
+    pub repaired: bool,
+    pub confluent: Option<ConfluentHeader>,
+    pub validation_error: Option<String>,
+    pub compression: Option<PayloadCompression>,
+    /// Format picked by MessageType::Auto
+    pub detected_format: Option<&'static str>,
+    /// Protobuf message type guessed when none was selected
+    pub guessed_message: Option<String>,
+}
+
+/// Decoding pipeline shared by the paging readers and the streaming filtered load.
+/// Cheap to clone: heavy parts (descriptors) are behind Arc.
+#[derive(Clone)]
+pub struct MessageCodec {
+    pub message_type: MessageType,
+    // Optional protobuf decoder initialized when proto schema path is provided
+    pub proto_decoder: Option<Arc<ProtoDecoder>>,
+    // Avro decoder backed by the schema registry when message type is Avro
+    pub avro_decoder: Option<Arc<AvroDecoder>>,
+    // WASM decoder plugin when message type is Plugin
+    pub plugin_decoder: Option<Arc<PluginDecoder>>,
+    // JSON Schema that Json payloads are checked against
+    pub json_validator: Option<Arc<jsonschema::Validator>>,
+    // Record-value compression stripped before any decoder runs
+    pub compression: PayloadCompression,
+    // Key rendering; protobuf/avro keys use the decoders above
+    pub key_type: KeyType,
+    pub key_message_full_name: Option<String>,
+    // PII redaction applied to decoded keys, payloads and header values
+    pub masker: Option<Arc<Masker>>,
+    // When set, list rows skip payload decoding (see `get_message_at` for lazy decode)
+    pub lazy_decode: bool,
+    // Expressions evaluated per record into UiMessage.extracted
+    pub extract_columns: Arc<Vec<ExtractColumn>>,
+    // Per-record protobuf message type selection for multi-schema topics
+    pub message_rules: Arc<Vec<MessageTypeRule>>,
+}
+
+impl MessageCodec {
+    /// Codec for `message_type` without schemas, plugins, masking or extraction; set those fields as needed.
+    pub fn new(message_type: MessageType) -> Self {
+        Self {
+            message_type,
+            proto_decoder: None,
+            avro_decoder: None,
+            plugin_decoder: None,
+            json_validator: None,
+            compression: PayloadCompression::default(),
+            key_type: KeyType::default(),
+            key_message_full_name: None,
+            masker: None,
+            lazy_decode: false,
+            extract_columns: Arc::default(),
+            message_rules: Arc::default(),
+        }
+    }
+
+    /// Decode key/value according to configured message type.
+    pub fn decode(&self, key: Option<&[u8]>, payload: Option<&[u8]>) -> (String, String, Option<String>) {
+        let d = self.decode_payload(payload, None);
+        let (key, key_error) = self.decode_key(key);
+        (key, d.value, join_errors(key_error, d.error))
+    }
+
+    /// Render a key according to `key_type`; undecodable keys fall back to UTF-8 text plus an error.
+    /// Masking rules apply to keys like to payloads.
+    pub fn decode_key(&self, key: Option<&[u8]>) -> (String, Option<String>) {
+        let Some(bytes) = key else {
+            return (String::new(), None);
+        };
+        let decoded = match self.key_type {
+            KeyType::Protobuf => match self.proto_decoder.as_ref() {
+                Some(pd) => pd.decode_detailed_as(bytes, self.key_message_full_name.as_deref()).map(|d| d.json),
+                None => Err("no protobuf descriptors loaded".to_string()),
+            },
+            KeyType::Avro => match self.avro_decoder.as_ref() {
+                Some(ad) => ad.decode_detailed(bytes).map(|(_, json)| json).map_err(|e| e.to_string()),
+                None => Err("no schema registry configured".to_string()),
+            },
+            ty => decode_simple_key(ty, bytes),
+        };
+        let (text, error) = match decoded {
+            Ok(text) => (text, None),
+            Err(e) => (String::from_utf8_lossy(bytes).to_string(), Some(format!("Key decode error: {e}"))),
+        };
+        (self.mask(text), error)
+    }
+
+    /// Record headers for the UI, with the masking rules applied to their values.
+    pub fn decode_headers(&self, headers: Option<&BorrowedHeaders>) -> Vec<(String, String)> {
+        header_pairs(headers).into_iter().map(|(name, value)| (name, self.mask(value))).collect()
+    }
+
+    fn mask(&self, text: String) -> String {
+        match self.masker.as_ref().map(|m| m.mask(&text)) {
+            Some(Cow::Owned(masked)) => masked,
+            _ => text,
+        }
+    }
+
+    /// Pick the protobuf message type for a record from the configured rules (None = default type).
+    fn select_message_type(&self, payload: &[u8], headers: Option<&BorrowedHeaders>) -> Option<&str> {
+        if self.message_rules.is_empty() {
+            return None;
+        }
+        let schema_id = parse_confluent_header(payload).map(|h| h.schema_id);
+        let header_value = |name: &str| -> Option<Option<String>> {
+            headers?
+                .iter()
+                .find(|h| h.key == name)
+                .map(|h| h.value.map(|v| String::from_utf8_lossy(v).to_string()))
+        };
+        self.message_rules
+            .iter()
+            .find(|rule| {
+                if rule.schema_id.is_none() && rule.header.is_none() {
+                    return false;
+                }
+                if let Some(id) = rule.schema_id {
+                    if schema_id != Some(id) {
+                        return false;
+                    }
+                }
+                if let Some(name) = rule.header.as_deref() {
+                    match (header_value(name), rule.header_value.as_deref()) {
+                        (None, _) => return false,
+                        (Some(actual), Some(expected)) if actual.as_deref() != Some(expected) => return false,
+                        _ => {}
+                    }
+                }
+                true
+            })
+            .map(|rule| rule.message_full_name.as_str())
+    }
+
+    /// Decode a payload into its UI text plus decode metadata.
+    /// Compressed values are inflated first, so every decoder sees the original bytes;
+    /// masking rules run last, so nothing downstream sees unmasked text.
+    /// Headers are only used to select the message type on multi-schema topics.
+    pub fn decode_payload(&self, payload: Option<&[u8]>, headers: Option<&BorrowedHeaders>) -> DecodedPayload {
+        let mut d = self.decode_inflated(payload, headers);
+        d.value = self.mask(d.value);
+        d
+    }
+
+    fn decode_inflated(&self, payload: Option<&[u8]>, headers: Option<&BorrowedHeaders>) -> DecodedPayload {
+        let Some(bytes) = payload else {
+            return self.decode_plain(None, headers);
+        };
+        match decompress(bytes, self.compression) {
+            Ok(Some((compression, inflated))) => {
+                let mut d = self.decode_plain(Some(&inflated), headers);
+                d.compression = Some(compression);
+                d
+            }
+            Ok(None) => self.decode_plain(Some(bytes), headers),
+            // Explicitly configured compression that does not apply: show raw text, keep reading
+            Err(e) => DecodedPayload {
+                value: String::from_utf8_lossy(bytes).to_string(),
+                error: Some(e),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn decode_plain(&self, payload: Option<&[u8]>, headers: Option<&BorrowedHeaders>) -> DecodedPayload {
+        if matches!(self.message_type, MessageType::Auto) {
+            if let Some(bytes) = payload {
+                return self.decode_auto(bytes, headers);
+            }
+        }
+        // If protobuf configured and decoder available, try to decode to JSON
+        if matches!(self.message_type, MessageType::Protobuf) {
+            if let (Some(pd), Some(bytes)) = (self.proto_decoder.as_ref(), payload) {
+                let decoded = match self.select_message_type(bytes, headers) {
+                    Some(name) => pd.decode_detailed_as(bytes, Some(name)),
+                    None => pd.decode_detailed(bytes),
+                };
+                return match decoded {
+                    Ok(d) => DecodedPayload {
+                        value: d.json,
+                        repaired: d.repaired,
+                        confluent: d.confluent,
+                        guessed_message: d.guessed_message,
+                        ..Default::default()
+                    },
+                    // Failed to decode: return raw text and attach error, but do not stop reading
+                    Err(e) => DecodedPayload {
+                        value: String::from_utf8_lossy(bytes).to_string(),
+                        error: Some(format!("Protobuf decode error: {}", e)),
+                        ..Default::default()
+                    },
+                };
+            }
+        }
+        if matches!(self.message_type, MessageType::Avro) {
+            if let (Some(ad), Some(bytes)) = (self.avro_decoder.as_ref(), payload) {
+                return match ad.decode_detailed(bytes) {
+                    Ok((schema_id, json)) => DecodedPayload {
+                        value: json,
+                        confluent: Some(ConfluentHeader { schema_id, message_indexes: Vec::new(), header_len: 5 }),
+                        ..Default::default()
+                    },
+                    Err(e) => DecodedPayload {
+                        value: String::from_utf8_lossy(bytes).to_string(),
+                        error: Some(format!("Avro decode error: {}", e)),
+                        ..Default::default()
+                    },
+                };
+            }
+        }
+        if matches!(self.message_type, MessageType::Plugin) {
+            if let (Some(pd), Some(bytes)) = (self.plugin_decoder.as_ref(), payload) {
+                return match pd.decode_detailed(bytes) {
+                    Ok(json) => DecodedPayload { value: json, ..Default::default() },
+                    Err(e) => DecodedPayload {
+                        value: String::from_utf8_lossy(bytes).to_string(),
+                        error: Some(format!("Plugin decode error: {}", e)),
+                        ..Default::default()
+                    },
+                };
+            }
+        }
+        // Fallback to existing decoders
+        let dec = decoder_for(&self.message_type);
+        let (_k, v) = dec.decode(None, payload);
+        let validation_error = match (&self.message_type, self.json_validator.as_ref(), payload) {
+            (MessageType::Json, Some(validator), Some(_)) => validate_json(validator, &v),
+            _ => None,
+        };
+        DecodedPayload { value: v, validation_error, ..Default::default() }
+    }
+
+    /// Try JSON, then the Confluent Avro envelope, then protobuf (when schemas are loaded),
+    /// then UTF-8 text; anything else is shown as hex.
+    fn decode_auto(&self, bytes: &[u8], headers: Option<&BorrowedHeaders>) -> DecodedPayload {
+        let detected = |value: String, format: &'static str| DecodedPayload {
+            value,
+            detected_format: Some(format),
+            ..Default::default()
+        };
+        if let Ok(text) = std::str::from_utf8(bytes) {
+            if serde_json::from_str::<serde_json::Value>(text).is_ok() {
+                let validation_error = self.json_validator.as_ref().and_then(|v| validate_json(v, text));
+                return DecodedPayload { validation_error, ..detected(text.to_string(), "json") };
+            }
+        }
+        if let Some(ad) = self.avro_decoder.as_ref() {
+            if let Ok((schema_id, json)) = ad.decode_detailed(bytes) {
+                return DecodedPayload {
+                    confluent: Some(ConfluentHeader { schema_id, message_indexes: Vec::new(), header_len: 5 }),
+                    ..detected(json, "avro")
+                };
+            }
+        }
+        if let Some(pd) = self.proto_decoder.as_ref() {
+            let decoded = match self.select_message_type(bytes, headers) {
+                Some(name) => pd.decode_detailed_as(bytes, Some(name)),
+                None => pd.decode_detailed(bytes),
+            };
+            if let Ok(d) = decoded {
+                return DecodedPayload {
+                    repaired: d.repaired,
+                    confluent: d.confluent,
+                    guessed_message: d.guessed_message,
+                    ..detected(d.json, "protobuf")
+                };
+            }
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(text) if !text.chars().any(|c| c.is_control() && !c.is_whitespace()) => detected(text.to_string(), "text"),
+            _ => detected(bytes.iter().map(|b| format!("{b:02x}")).collect(), "hex"),
+        }
+    }
+
+    /// Evaluate configured extraction columns against a decoded payload.
+    /// Non-JSON payloads and unresolved paths yield null values.
+    pub fn extract(&self, payload: &str) -> Option<HashMap<String, serde_json::Value>> {
+        if self.extract_columns.is_empty() {
+            return None;
+        }
+        let parsed = serde_json::from_str::<serde_json::Value>(payload).ok();
+        let mut out = HashMap::with_capacity(self.extract_columns.len());
+        for col in self.extract_columns.iter() {
+            let v = parsed
+                .as_ref()
+                .and_then(|root| json_path_get(root, &col.expr))
+                .unwrap_or(serde_json::Value::Null);
+            out.insert(col.name.clone(), v);
+        }
+        Some(out)
+    }
+
+    /// Build a UI row from a polled record. Returns the sort timestamp (ms) alongside the row;
+    /// records without a timestamp sort last (i64::MAX).
+    /// Honors `lazy_decode`: the payload is left empty and only metadata is filled in
+    /// (extraction columns are still computed so domain columns work without shipping payloads).
+    pub fn to_ui_message(&self, m: &BorrowedMessage<'_>) -> (i64, UiMessage) {
+        self.build(m, m.headers(), self.lazy_decode)
+    }
+
+    /// Same as `to_ui_message`, but always decodes the payload.
+    pub fn to_ui_message_full(&self, m: &BorrowedMessage<'_>) -> (i64, UiMessage) {
+        self.build(m, m.headers(), false)
+    }
+
+    /// Fully decode a detached record (e.g. one kept in the raw cache).
+    pub fn to_ui_message_owned(&self, m: &OwnedMessage) -> (i64, UiMessage) {
+        self.build(m, m.headers().map(OwnedHeaders::as_borrowed), false)
+    }
+
+    fn build<M: RdMessage>(&self, m: &M, headers: Option<&BorrowedHeaders>, skip_payload: bool) -> (i64, UiMessage) {
+        let partition = m.partition();
+        let offset = m.offset();
+        let (key, key_error) = self.decode_key(m.key());
+        let (d, extracted) = if skip_payload && self.extract_columns.is_empty() {
+            (DecodedPayload::default(), None)
+        } else {
+            let mut d = self.decode_payload(m.payload(), headers);
+            let extracted = self.extract(&d.value);
+            if skip_payload {
+                // Keep metadata only; the payload is fetched on demand
+                d.value.clear();
+                d.error = None;
+                d.validation_error = None;
+            }
+            (d, extracted)
+        };
+        let (schema_id, message_indexes) = match d.confluent {
+            // Message indexes only exist in the protobuf envelope
+            Some(h) => (Some(h.schema_id), (!h.message_indexes.is_empty()).then_some(h.message_indexes)),
+            None => (None, None),
+        };
+        let (ts_ms, ts_str) = timestamp_parts(m.timestamp());
+        let as_text = |bytes: Option<&[u8]>, text: &str| bytes.is_none_or(|b| b == text.as_bytes());
+        let verbatim = !skip_payload && as_text(m.key(), &key) && as_text(m.payload(), &d.value);
+        let ui = UiMessage {
+            id: format!("{}-{}", partition, offset),
+            partition,
+            key,
+            offset,
+            message: d.value,
+            timestamp: ts_str,
+            decoding_error: join_errors(key_error, d.error),
+            size: m.payload().map_or(0, <[u8]>::len),
+            decoded: !skip_payload,
+            extracted,
+            payload_repaired: d.repaired,
+            schema_id,
+            message_indexes,
+            headers: self.decode_headers(headers),
+            validation_error: d.validation_error,
+            payload_compression: d.compression.map(|c| c.as_str().to_string()),
+            detected_format: d.detected_format.map(str::to_string),
+            guessed_message_type: d.guessed_message,
+            is_tombstone: m.payload().is_none(),
+            verbatim,
+        };
+        (ts_ms, ui)
+    }
+}
+
+/// Check a payload against the JSON Schema; reports the first few violations with their paths.
+fn validate_json(validator: &jsonschema::Validator, text: &str) -> Option<String> {
+    const MAX_REPORTED: usize = 3;
+    let value: serde_json::Value = match serde_json::from_str(text) {
+        Ok(v) => v,
+        Err(e) => return Some(format!("Not valid JSON: {e}")),
+    };
+    let errors: Vec<String> = validator
+        .iter_errors(&value)
+        .take(MAX_REPORTED)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            if path.is_empty() { e.to_string() } else { format!("{path}: {e}") }
+        })
+        .collect();
+    if errors.is_empty() { None } else { Some(errors.join("; ")) }
+}
+
+fn join_errors(key_error: Option<String>, value_error: Option<String>) -> Option<String> {
+    match (key_error, value_error) {
+        (Some(k), Some(v)) => Some(format!("{k}; {v}")),
+        (k, v) => k.or(v),
+    }
+}
+
+/// Collect record headers for the UI.
+pub(crate) fn header_pairs(headers: Option<&BorrowedHeaders>) -> Vec<(String, String)> {
+    headers
+        .map(|hs| {
+            hs.iter()
+                .map(|h| {
+                    let value = h.value.map(|v| String::from_utf8_lossy(v).to_string()).unwrap_or_default();
+                    (h.key.to_string(), value)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Convert an rdkafka timestamp into (sort key ms, RFC3339 string).
+pub(crate) fn timestamp_parts(ts: Timestamp) -> (i64, String) {
+    match ts {
+        Timestamp::NotAvailable => (i64::MAX, String::new()),
+        Timestamp::CreateTime(ms) | Timestamp::LogAppendTime(ms) => {
+            if let Some(dt) = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(ms) {
+                (ms, dt.to_rfc3339())
+            } else {
+                (ms, String::new())
+            }
+        }
+    }
+}
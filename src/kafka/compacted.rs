@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::replay::ReplayRange;
+use super::service::Kafka;
+use super::types::UiMessage;
+
+/// Newest record per key of a topic, as a consumer materializing it into a table would see it.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeySnapshot {
+    /// Live records ordered by partition and offset
+    pub messages: Vec<UiMessage>,
+    pub scanned: usize,
+    /// Keys whose newest record is a tombstone (left out of `messages`)
+    pub deleted: usize,
+    /// True when the key limit was reached; keys seen afterwards were ignored
+    pub truncated: bool,
+    /// True when the scan was stopped early; the snapshot only covers what was read so far
+    pub cancelled: bool,
+}
+
+struct Entry {
+    partition: i32,
+    /// None when the record has no usable timestamp
+    timestamp_ms: Option<i64>,
+    /// None once the key was deleted
+    message: Option<UiMessage>,
+}
+
+/// Folds records into the newest value per key.
+pub struct LatestByKey {
+    entries: HashMap<String, Entry>,
+    max_keys: usize,
+    scanned: usize,
+    truncated: bool,
+}
+
+impl LatestByKey {
+    pub fn new(max_keys: usize) -> Self {
+        Self { entries: HashMap::new(), max_keys, scanned: 0, truncated: false }
+    }
+
+    /// Add the next record. Within a partition later records win; the same key on another
+    /// partition (a re-partitioned topic) wins when its timestamp is not older, or when either
+    /// record has no timestamp to compare.
+    pub fn push(&mut self, ui: UiMessage) {
+        self.scanned += 1;
+        let key = ui.key.clone();
+        let entry = Entry {
+            partition: ui.partition,
+            timestamp_ms: chrono::DateTime::parse_from_rfc3339(&ui.timestamp).map(|t| t.timestamp_millis()).ok(),
+            message: (!ui.is_tombstone).then_some(ui),
+        };
+        let full = self.entries.len() >= self.max_keys;
+        match self.entries.get_mut(&key) {
+            Some(prev) => {
+                let newer = match (entry.timestamp_ms, prev.timestamp_ms) {
+                    (Some(ts), Some(prev_ts)) => ts >= prev_ts,
+                    _ => true,
+                };
+                if prev.partition == entry.partition || newer {
+                    *prev = entry;
+                }
+            }
+            None if full => self.truncated = true,
+            None => {
+                self.entries.insert(key, entry);
+            }
+        }
+    }
+
+    pub fn scanned(&self) -> usize {
+        self.scanned
+    }
+
+    pub fn keys(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn finish(self) -> KeySnapshot {
+        let deleted = self.entries.values().filter(|e| e.message.is_none()).count();
+        let mut messages: Vec<UiMessage> = self.entries.into_values().filter_map(|e| e.message).collect();
+        messages.sort_by_key(|m| (m.partition, m.offset));
+        KeySnapshot { messages, scanned: self.scanned, deleted, truncated: self.truncated, cancelled: false }
+    }
+}
+
+impl Kafka {
+    /// Scan the configured topic up to the current high watermarks and keep the newest record per key,
+    /// dropping keys whose newest record is a tombstone. `progress` gets (scanned, keys) every
+    /// `progress_every` records and stops the scan by returning false.
+    pub fn latest_by_key(
+        &self,
+        max_keys: usize,
+        progress_every: usize,
+        mut progress: impl FnMut(usize, usize) -> bool,
+    ) -> anyhow::Result<KeySnapshot> {
+        let ranges: Vec<ReplayRange> = self
+            .watermarks(&self.config.topic)?
+            .into_iter()
+            .map(|w| ReplayRange { partition: w.partition, start_offset: w.low, end_offset: Some(w.high) })
+            .collect();
+        let mut table = LatestByKey::new(max_keys);
+        let mut stopped = false;
+        let result = self.read_ranges(&ranges, |ui| {
+            table.push(ui);
+            if table.scanned().is_multiple_of(progress_every.max(1)) && !progress(table.scanned(), table.keys()) {
+                stopped = true;
+                return Err(anyhow::anyhow!("Scan stopped"));
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            if !stopped {
+                return Err(e);
+            }
+        }
+        let mut snapshot = table.finish();
+        snapshot.cancelled = stopped;
+        Ok(snapshot)
+    }
+}
@@ -0,0 +1,122 @@
+use std::io::Read;
+
+use serde::{Deserialize, Serialize};
+
+/// Decompressed payloads larger than this are rejected (guards against compression bombs).
+const MAX_DECOMPRESSED: u64 = 64 * 1024 * 1024;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+/// Snappy framing format stream identifier chunk
+const SNAPPY_FRAMED_MAGIC: &[u8] = b"\xff\x06\x00\x00sNaPpY";
+/// snappy-java (xerial) stream header
+const SNAPPY_XERIAL_MAGIC: &[u8] = b"\x82SNAPPY\x00";
+
+/// Compression applied by the producer to the record value itself (not Kafka batch compression).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PayloadCompression {
+    /// Detect gzip, zstd and framed/xerial snappy by magic bytes; undetected payloads pass through
+    #[default]
+    #[serde(rename = "auto")] Auto,
+    #[serde(rename = "none")] None,
+    #[serde(rename = "gzip")] Gzip,
+    #[serde(rename = "zstd")] Zstd,
+    /// Framed, xerial or raw (block) snappy
+    #[serde(rename = "snappy")] Snappy,
+}
+
+impl PayloadCompression {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PayloadCompression::Auto => "auto",
+            PayloadCompression::None => "none",
+            PayloadCompression::Gzip => "gzip",
+            PayloadCompression::Zstd => "zstd",
+            PayloadCompression::Snappy => "snappy",
+        }
+    }
+}
+
+/// Compression recognized from the payload's magic bytes.
+pub fn detect(payload: &[u8]) -> Option<PayloadCompression> {
+    if payload.starts_with(GZIP_MAGIC) {
+        Some(PayloadCompression::Gzip)
+    } else if payload.starts_with(ZSTD_MAGIC) {
+        Some(PayloadCompression::Zstd)
+    } else if payload.starts_with(SNAPPY_FRAMED_MAGIC) || payload.starts_with(SNAPPY_XERIAL_MAGIC) {
+        Some(PayloadCompression::Snappy)
+    } else {
+        None
+    }
+}
+
+/// Decompress a payload according to `mode`. Returns None when the payload is left as is
+/// (mode none, or nothing detected / detection was a false positive in auto mode).
+pub fn decompress(payload: &[u8], mode: PayloadCompression) -> Result<Option<(PayloadCompression, Vec<u8>)>, String> {
+    let (codec, strict) = match mode {
+        PayloadCompression::None => return Ok(None),
+        PayloadCompression::Auto => match detect(payload) {
+            Some(c) => (c, false),
+            None => return Ok(None),
+        },
+        explicit => (explicit, true),
+    };
+    let result = match codec {
+        PayloadCompression::Gzip => read_limited(flate2::read::MultiGzDecoder::new(payload)),
+        PayloadCompression::Zstd => {
+            zstd::stream::read::Decoder::new(payload).map_err(|e| e.to_string()).and_then(read_limited)
+        }
+        PayloadCompression::Snappy => snappy(payload),
+        PayloadCompression::Auto | PayloadCompression::None => return Ok(None),
+    };
+    match result {
+        Ok(bytes) => Ok(Some((codec, bytes))),
+        Err(e) if strict => Err(format!("Failed to decompress {} payload: {e}", codec.as_str())),
+        Err(_) => Ok(None),
+    }
+}
+
+fn read_limited(reader: impl Read) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    reader
+        .take(MAX_DECOMPRESSED + 1)
+        .read_to_end(&mut out)
+        .map_err(|e| e.to_string())?;
+    if out.len() as u64 > MAX_DECOMPRESSED {
+        return Err(format!("decompressed payload exceeds {} MiB", MAX_DECOMPRESSED / (1024 * 1024)));
+    }
+    Ok(out)
+}
+
+fn snappy(payload: &[u8]) -> Result<Vec<u8>, String> {
+    if payload.starts_with(SNAPPY_FRAMED_MAGIC) {
+        return read_limited(snap::read::FrameDecoder::new(payload));
+    }
+    if let Some(rest) = payload.strip_prefix(SNAPPY_XERIAL_MAGIC) {
+        // 4-byte version + 4-byte compatible version, then (big-endian length, raw block) pairs
+        let mut blocks = rest.get(8..).ok_or("truncated xerial snappy header")?;
+        let mut out = Vec::new();
+        while !blocks.is_empty() {
+            let len = blocks
+                .get(..4)
+                .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+                .ok_or("truncated xerial snappy block")?;
+            let block = blocks.get(4..4 + len).ok_or("truncated xerial snappy block")?;
+            out.extend(raw_snappy(block)?);
+            if out.len() as u64 > MAX_DECOMPRESSED {
+                return Err(format!("decompressed payload exceeds {} MiB", MAX_DECOMPRESSED / (1024 * 1024)));
+            }
+            blocks = &blocks[4 + len..];
+        }
+        return Ok(out);
+    }
+    raw_snappy(payload)
+}
+
+fn raw_snappy(block: &[u8]) -> Result<Vec<u8>, String> {
+    let len = snap::raw::decompress_len(block).map_err(|e| e.to_string())?;
+    if len as u64 > MAX_DECOMPRESSED {
+        return Err(format!("decompressed payload exceeds {} MiB", MAX_DECOMPRESSED / (1024 * 1024)));
+    }
+    snap::raw::Decoder::new().decompress_vec(block).map_err(|e| e.to_string())
+}
@@ -1,15 +1,56 @@
+use std::fmt;
+use std::time::Duration;
+
 use rdkafka::config::ClientConfig;
-use rdkafka::consumer::BaseConsumer;
+use rdkafka::consumer::{BaseConsumer, Consumer, StreamConsumer};
+use rdkafka::error::{KafkaError, RDKafkaErrorCode, KafkaResult};
+use rdkafka::message::BorrowedMessage;
 
-use crate::utils::kafka::configure_security;
+use crate::utils::kafka::{apply_custom_properties, configure_security};
+use super::client_stats::STATS_INTERVAL_MS;
+use super::oauth::ClientCtx;
 use super::types::KafkaConfig;
 
 /// Build an rdkafka BaseConsumer configured according to KafkaConfig.
-pub(crate) fn create_consumer(config: &KafkaConfig) -> anyhow::Result<BaseConsumer> {
+pub(crate) fn create_consumer(config: &KafkaConfig) -> anyhow::Result<BaseConsumer<ClientCtx>> {
+    // A default group id; for UI reading anything is fine.
+    create_group_consumer(config, "rkui-consumer")
+}
+
+/// Same as `create_consumer`, bound to a specific group id (used to inspect a group's committed offsets).
+/// The consumer never subscribes, so it does not join or rebalance the group.
+pub(crate) fn create_group_consumer(config: &KafkaConfig, group_id: &str) -> anyhow::Result<BaseConsumer<ClientCtx>> {
+    let context = ClientCtx::new(config)?;
+    let consumer: BaseConsumer<ClientCtx> = consumer_client_config(config, group_id, &context)?.create_with_context(context)?;
+    consumer.context().prime(consumer.client())?;
+    Ok(consumer)
+}
+
+/// Async consumer of a reading session (paging and filtered loads). Must be created inside a Tokio
+/// runtime context: it spawns a small wake-up task there.
+pub(crate) fn create_stream_consumer(config: &KafkaConfig) -> anyhow::Result<StreamConsumer<ClientCtx>> {
+    let context = ClientCtx::new(config)?;
+    let mut cc = consumer_client_config(config, "rkui-consumer", &context)?;
+    // Statistics of the reading session for `get_client_stats`; a custom interval takes precedence
+    if cc.get("statistics.interval.ms").is_none() {
+        cc.set("statistics.interval.ms", STATS_INTERVAL_MS.to_string());
+    }
+    let consumer: StreamConsumer<ClientCtx> = cc.create_with_context(context)?;
+    consumer.context().prime(consumer.client())?;
+    Ok(consumer)
+}
+
+/// Next record of a stream consumer, or None when nothing arrived within `timeout`.
+/// Drop-in for `BaseConsumer::poll` in async readers.
+pub(crate) async fn recv_timeout(consumer: &StreamConsumer<ClientCtx>, timeout: Duration) -> Option<KafkaResult<BorrowedMessage<'_>>> {
+    tokio::time::timeout(timeout, consumer.recv()).await.ok()
+}
+
+/// Consumer ClientConfig (tuning + security) shared by all consumer flavours.
+pub(crate) fn consumer_client_config(config: &KafkaConfig, group_id: &str, context: &ClientCtx) -> anyhow::Result<ClientConfig> {
     let mut cc = ClientConfig::new();
-    cc.set("bootstrap.servers", &config.broker);
-    // A default group id; for UI reading anything is fine. Could be made configurable later.
-    cc.set("group.id", "rkui-consumer");
+    cc.set("bootstrap.servers", context.bootstrap_servers(config));
+    cc.set("group.id", group_id);
 
     // Оптимизации для быстрого переназначения партиций
     cc.set("socket.timeout.ms", "10000");             // Уменьшаем таймаут сокета
@@ -39,7 +80,38 @@ pub(crate) fn create_consumer(config: &KafkaConfig) -> anyhow::Result<BaseConsum
 
     // Полная настройка безопасности (PLAINTEXT/SSL/SASL*) вынесена в utils
     configure_security(&mut cc, config)?;
+    apply_custom_properties(&mut cc, config);
+    Ok(cc)
+}
 
-    let consumer: BaseConsumer = cc.create()?;
-    Ok(consumer)
+/// The broker rejected access to a topic (ACLs). Readers stop on it instead of treating it as an idle poll.
+#[derive(Debug, Clone)]
+pub struct AccessDenied {
+    pub topic: String,
+    pub code: RDKafkaErrorCode,
+}
+
+impl fmt::Display for AccessDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No read permission for topic '{}' ({:?})", self.topic, self.code)
+    }
+}
+
+impl std::error::Error for AccessDenied {}
+
+pub fn is_authorization_error(code: RDKafkaErrorCode) -> bool {
+    matches!(
+        code,
+        RDKafkaErrorCode::TopicAuthorizationFailed
+            | RDKafkaErrorCode::GroupAuthorizationFailed
+            | RDKafkaErrorCode::ClusterAuthorizationFailed
+    )
+}
+
+/// Turn authorization failures from poll into errors; everything else stays a transient (idle) poll.
+pub(crate) fn check_poll_error(e: &KafkaError, topic: &str) -> anyhow::Result<()> {
+    match e.rdkafka_error_code() {
+        Some(code) if is_authorization_error(code) => Err(AccessDenied { topic: topic.to_string(), code }.into()),
+        _ => Ok(()),
+    }
 }
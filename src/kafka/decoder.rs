@@ -1,5 +1,11 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
 use serde::{Deserialize, Serialize};
 
+use super::avro::AvroSchema;
+use super::schema_registry::{split_envelope, SchemaRegistry};
+
 /// MessageType lists supported payload formats.
 /// Keeping it here decouples decoding from the Kafka consumer logic
 /// and allows adding formats without touching reader/consumer code.
@@ -8,6 +14,67 @@ pub enum MessageType {
     #[serde(rename = "json")] Json,
     #[serde(rename = "text")] Text,
     #[serde(rename = "protobuf")] Protobuf,
+    #[serde(rename = "avro")] Avro,
+    /// Decoded by a WASM plugin (see `PluginDecoder`)
+    #[serde(rename = "plugin")] Plugin,
+    /// Detect per record: JSON, Confluent Avro, protobuf (when schemas are loaded), text, else hex
+    #[serde(rename = "auto")] Auto,
+}
+
+/// How record keys are rendered. Protobuf and Avro keys reuse the value decoders' schemas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum KeyType {
+    /// UTF-8 (lossy)
+    #[default]
+    #[serde(rename = "text")] Text,
+    #[serde(rename = "json")] Json,
+    #[serde(rename = "protobuf")] Protobuf,
+    /// Big-endian, as written by Kafka's IntegerSerializer / LongSerializer
+    #[serde(rename = "int32")] Int32,
+    #[serde(rename = "int64")] Int64,
+    /// 16 raw bytes (or already textual UUIDs)
+    #[serde(rename = "uuid")] Uuid,
+    #[serde(rename = "avro")] Avro,
+}
+
+/// Render a key of a schema-less key type; Err carries the reason for the UI.
+/// Protobuf and Avro keys are decoded by `MessageCodec`.
+pub fn decode_simple_key(ty: KeyType, key: &[u8]) -> Result<String, String> {
+    match ty {
+        KeyType::Int32 => <[u8; 4]>::try_from(key)
+            .map(|b| i32::from_be_bytes(b).to_string())
+            .map_err(|_| format!("Expected 4 bytes for an int32 key, got {}", key.len())),
+        KeyType::Int64 => <[u8; 8]>::try_from(key)
+            .map(|b| i64::from_be_bytes(b).to_string())
+            .map_err(|_| format!("Expected 8 bytes for an int64 key, got {}", key.len())),
+        KeyType::Uuid => match key.len() {
+            16 => {
+                let hex: String = key.iter().map(|b| format!("{b:02x}")).collect();
+                Ok(format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..]))
+            }
+            36 if key.iter().all(|b| b.is_ascii_hexdigit() || *b == b'-') => Ok(String::from_utf8_lossy(key).to_string()),
+            n => Err(format!("Expected 16 bytes for a UUID key, got {n}")),
+        },
+        KeyType::Json => {
+            let text = String::from_utf8_lossy(key).to_string();
+            match serde_json::from_str::<serde_json::Value>(&text) {
+                Ok(_) => Ok(text),
+                Err(e) => Err(format!("Key is not valid JSON: {e}")),
+            }
+        }
+        KeyType::Text | KeyType::Protobuf | KeyType::Avro => Ok(String::from_utf8_lossy(key).to_string()),
+    }
+}
+
+/// Bytes a rendered key of a schema-less key type was read from; None when the text does not determine them
+/// (schema-encoded keys, and UUIDs, which may be stored as 16 bytes or as text).
+pub fn encode_simple_key(ty: KeyType, key: &str) -> Option<Vec<u8>> {
+    match ty {
+        KeyType::Text | KeyType::Json => Some(key.as_bytes().to_vec()),
+        KeyType::Int32 => key.parse::<i32>().ok().map(|n| n.to_be_bytes().to_vec()),
+        KeyType::Int64 => key.parse::<i64>().ok().map(|n| n.to_be_bytes().to_vec()),
+        KeyType::Uuid | KeyType::Protobuf | KeyType::Avro => None,
+    }
 }
 
 /// Trait for decoding a raw Kafka payload into a UI-presentable string.
@@ -48,11 +115,66 @@ impl MessageDecoder for ProtobufDecoder {
     }
 }
 
+/// Avro decoder for Confluent-framed payloads: the writer schema is resolved from the
+/// schema id in the envelope via the schema registry and parsed once per id.
+pub struct AvroDecoder {
+    registry: SchemaRegistry,
+    parsed: Mutex<HashMap<u32, Arc<AvroSchema>>>,
+}
+
+impl AvroDecoder {
+    pub fn new(registry: SchemaRegistry) -> Self {
+        Self { registry, parsed: Mutex::new(HashMap::new()) }
+    }
+
+    /// Decode a payload into (schema id, JSON text).
+    pub fn decode_detailed(&self, payload: &[u8]) -> anyhow::Result<(u32, String)> {
+        let (id, body) = split_envelope(payload)
+            .ok_or_else(|| anyhow::anyhow!("Payload is not framed with the Confluent magic byte and schema id"))?;
+        let schema = self.schema(id)?;
+        Ok((id, schema.decode_to_json(body)?))
+    }
+
+    fn schema(&self, id: u32) -> anyhow::Result<Arc<AvroSchema>> {
+        if let Some(s) = self.parsed.lock().ok().and_then(|p| p.get(&id).cloned()) {
+            return Ok(s);
+        }
+        let registered = self.registry.schema_by_id(id)?;
+        if !registered.schema_type.eq_ignore_ascii_case("AVRO") {
+            anyhow::bail!("Schema id {id} is a {} schema, not Avro", registered.schema_type);
+        }
+        let schema = Arc::new(AvroSchema::parse(&registered.schema)?);
+        if let Ok(mut parsed) = self.parsed.lock() {
+            parsed.insert(id, schema.clone());
+        }
+        Ok(schema)
+    }
+}
+
+impl MessageDecoder for AvroDecoder {
+    fn decode(&self, key: Option<&[u8]>, payload: Option<&[u8]>) -> (String, String) {
+        let k = key.map(|k| String::from_utf8_lossy(k).to_string()).unwrap_or_default();
+        let v = payload
+            .map(|p| match self.decode_detailed(p) {
+                Ok((_, json)) => json,
+                Err(_) => String::from_utf8_lossy(p).to_string(),
+            })
+            .unwrap_or_default();
+        (k, v)
+    }
+}
+
 /// Factory for decoder instances. Light-weight and cheap to construct.
 pub fn decoder_for(ty: &MessageType) -> Box<dyn MessageDecoder> {
     match ty {
         MessageType::Json => Box::new(JsonDecoder),
         MessageType::Text => Box::new(TextDecoder),
         MessageType::Protobuf => Box::new(ProtobufDecoder),
+        // Avro needs a schema registry; MessageCodec uses AvroDecoder when one is configured
+        MessageType::Avro => Box::new(TextDecoder),
+        // Plugins are loaded from disk; MessageCodec uses PluginDecoder when one is configured
+        MessageType::Plugin => Box::new(TextDecoder),
+        // Detection happens in MessageCodec, which has access to all configured decoders
+        MessageType::Auto => Box::new(TextDecoder),
     }
 }
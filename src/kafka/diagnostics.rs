@@ -0,0 +1,129 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use std::error::Error;
+
+use rdkafka::client::{ClientContext, OAuthToken};
+use rdkafka::consumer::{BaseConsumer, Consumer, ConsumerContext};
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use serde::Serialize;
+
+use super::consumer::{consumer_client_config, is_authorization_error};
+use super::oauth::ClientCtx;
+use super::service::Kafka;
+use super::types::KafkaConfig;
+
+/// Client context that keeps the errors librdkafka reports through the error callback.
+/// Authentication and TLS failures only surface there; metadata calls just time out.
+struct CaptureContext {
+    inner: ClientCtx,
+    errors: Mutex<Vec<(Option<RDKafkaErrorCode>, String)>>,
+}
+
+impl ClientContext for CaptureContext {
+    const ENABLE_REFRESH_OAUTH_TOKEN: bool = true;
+
+    fn error(&self, error: KafkaError, reason: &str) {
+        if let Ok(mut errs) = self.errors.lock() {
+            errs.push((error.rdkafka_error_code(), format!("{}: {}", error, reason)));
+        }
+    }
+
+    fn generate_oauth_token(&self, oauthbearer_config: Option<&str>) -> Result<OAuthToken, Box<dyn Error>> {
+        self.inner.generate_oauth_token(oauthbearer_config)
+    }
+}
+
+impl ConsumerContext for CaptureContext {}
+
+/// Outcome of `test_connection`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionTest {
+    pub ok: bool,
+    /// Time to fetch cluster metadata (or to fail)
+    pub latency_ms: u64,
+    pub broker_count: usize,
+    pub topic_count: usize,
+    /// config | tunnel | auth | ssl | network | other; None when ok
+    pub error_kind: Option<String>,
+    pub error: Option<String>,
+}
+
+fn classify(code: Option<RDKafkaErrorCode>) -> &'static str {
+    match code {
+        Some(c) if is_authorization_error(c) => "auth",
+        Some(RDKafkaErrorCode::Authentication) | Some(RDKafkaErrorCode::SaslAuthenticationFailed) => "auth",
+        Some(RDKafkaErrorCode::SSL) => "ssl",
+        Some(
+            RDKafkaErrorCode::BrokerTransportFailure
+            | RDKafkaErrorCode::AllBrokersDown
+            | RDKafkaErrorCode::Resolve
+            | RDKafkaErrorCode::OperationTimedOut
+            | RDKafkaErrorCode::RequestTimedOut,
+        ) => "network",
+        _ => "other",
+    }
+}
+
+impl Kafka {
+    /// Build a consumer and fetch cluster metadata with a short timeout to validate connection settings.
+    pub fn test_connection(config: &KafkaConfig, timeout: Duration) -> ConnectionTest {
+        let failed = |kind: &str, error: String, latency_ms: u64| ConnectionTest {
+            ok: false,
+            latency_ms,
+            broker_count: 0,
+            topic_count: 0,
+            error_kind: Some(kind.to_string()),
+            error: Some(error),
+        };
+        let ctx = match ClientCtx::new(config) {
+            Ok(a) => a,
+            Err(e) => return failed("tunnel", e.to_string(), 0),
+        };
+        // Bad file paths, keystore passwords etc. fail before any network I/O
+        let consumer: BaseConsumer<CaptureContext> = match consumer_client_config(config, "rkui-connection-test", &ctx)
+            .and_then(|cc| {
+                let context = CaptureContext { inner: ctx.clone(), errors: Mutex::new(Vec::new()) };
+                cc.create_with_context(context).map_err(anyhow::Error::from)
+            }) {
+            Ok(c) => c,
+            Err(e) => return failed("config", e.to_string(), 0),
+        };
+        // A token endpoint that rejects the client credentials is an authentication failure
+        if let Err(e) = consumer.context().inner.prime(consumer.client()) {
+            return failed("auth", e.to_string(), 0);
+        }
+
+        let started = Instant::now();
+        let result = consumer.client().fetch_metadata(None, timeout);
+        let latency_ms = started.elapsed().as_millis() as u64;
+        match result {
+            Ok(md) => ConnectionTest {
+                ok: true,
+                latency_ms,
+                broker_count: md.brokers().len(),
+                topic_count: md.topics().len(),
+                error_kind: None,
+                error: None,
+            },
+            Err(e) => {
+                // Prefer the root cause from the error callback (e.g. SASL failure) over the generic timeout
+                let captured = consumer
+                    .context()
+                    .errors
+                    .lock()
+                    .ok()
+                    .and_then(|errs| {
+                        errs.iter()
+                            .find(|(c, _)| !matches!(classify(*c), "network" | "other"))
+                            .or_else(|| errs.first())
+                            .cloned()
+                    });
+                match captured {
+                    Some((code, msg)) => failed(classify(code), msg, latency_ms),
+                    None => failed(classify(e.rdkafka_error_code()), e.to_string(), latency_ms),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rdkafka::consumer::Consumer;
+use rdkafka::message::{Message as RdMessage, OwnedMessage};
+use rdkafka::topic_partition_list::TopicPartitionList;
+use rdkafka::Offset;
+
+use super::codec::MessageCodec;
+use super::consumer::create_consumer;
+use super::replay::ReplayRange;
+use super::service::Kafka;
+use super::types::UiMessage;
+
+impl Kafka {
+    /// Fetch a single record by partition/offset and decode it fully.
+    /// Served from the raw cache when possible, otherwise read with a short-lived consumer
+    /// so the paging position of the main consumer is untouched.
+    pub fn message_at(&self, partition: i32, offset: i64) -> anyhow::Result<UiMessage> {
+        self.message_at_with(&self.codec, partition, offset)
+    }
+
+    /// Like `message_at`, decoding with another codec (see `Kafka::build_codec`).
+    pub fn message_at_with(&self, codec: &MessageCodec, partition: i32, offset: i64) -> anyhow::Result<UiMessage> {
+        let m = self.raw_message_at(partition, offset)?;
+        Ok(codec.to_ui_message_owned(&m).1)
+    }
+
+    /// The original record at partition/offset, from the raw cache when it is there.
+    pub fn raw_message_at(&self, partition: i32, offset: i64) -> anyhow::Result<OwnedMessage> {
+        if let Some(m) = self.raw_cache.as_ref().and_then(|c| c.get(partition, offset)) {
+            return Ok(m);
+        }
+        let consumer = create_consumer(&self.config)?;
+        let mut tpl = TopicPartitionList::new();
+        tpl.add_partition_offset(&self.config.topic, partition, Offset::Offset(offset))?;
+        consumer.assign(&tpl)?;
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            match consumer.poll(Duration::from_millis(200)) {
+                Some(Ok(m)) => {
+                    if m.partition() != partition || m.offset() < offset {
+                        continue;
+                    }
+                    if m.offset() > offset {
+                        // Compacted or deleted: the broker skipped past the requested offset
+                        return Err(anyhow::anyhow!(
+                            "Offset {} is not available in partition {} (next is {})",
+                            offset, partition, m.offset()
+                        ));
+                    }
+                    return Ok(m.detach());
+                }
+                Some(Err(e)) => return Err(e.into()),
+                None => {}
+            }
+        }
+        Err(anyhow::anyhow!("Timed out fetching message {}-{}", partition, offset))
+    }
+
+    /// Read offset ranges of the configured topic with a short-lived consumer, fully decoding each record.
+    /// Stops early when `visit` fails; returns how many records were visited.
+    pub fn read_ranges(
+        &self,
+        ranges: &[ReplayRange],
+        mut visit: impl FnMut(UiMessage) -> anyhow::Result<()>,
+    ) -> anyhow::Result<usize> {
+        let consumer = create_consumer(&self.config)?;
+        let topic = self.config.topic.as_str();
+        let mut ends: HashMap<i32, i64> = HashMap::new();
+        let mut tpl = TopicPartitionList::new();
+        for r in ranges {
+            let end = match r.end_offset {
+                Some(e) => e,
+                None => consumer.fetch_watermarks(topic, r.partition, Duration::from_secs(5))?.1,
+            };
+            if r.start_offset < end {
+                ends.insert(r.partition, end);
+                tpl.add_partition_offset(topic, r.partition, Offset::Offset(r.start_offset))?;
+            }
+        }
+        if ends.is_empty() {
+            return Ok(0);
+        }
+        consumer.assign(&tpl)?;
+
+        let mut visited = 0;
+        let mut last_progress = Instant::now();
+        while !ends.is_empty() {
+            let m = match consumer.poll(Duration::from_millis(200)) {
+                Some(Ok(m)) => m,
+                Some(Err(e)) => return Err(e.into()),
+                None => {
+                    // Ranges past the retained log never reach their end; stop once the topic is quiet
+                    if last_progress.elapsed() > Duration::from_secs(10) {
+                        break;
+                    }
+                    continue;
+                }
+            };
+            last_progress = Instant::now();
+            let Some(&end) = ends.get(&m.partition()) else { continue; };
+            if m.offset() >= end - 1 {
+                ends.remove(&m.partition());
+            }
+            if m.offset() >= end {
+                continue;
+            }
+            let (_ts, ui) = self.codec.to_ui_message_full(&m);
+            visit(ui)?;
+            visited += 1;
+        }
+        Ok(visited)
+    }
+}
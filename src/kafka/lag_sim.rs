@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rdkafka::consumer::Consumer;
+use rdkafka::message::Message;
+use rdkafka::topic_partition_list::TopicPartitionList;
+use rdkafka::Offset;
+use serde::{Deserialize, Serialize};
+
+use super::consumer::{check_poll_error, create_consumer};
+use super::service::Kafka;
+use super::types::KafkaConfig;
+
+/// Upper bound for a simulation run.
+const MAX_DURATION_SECS: u64 = 600;
+
+/// How a simulated consumer reads: from a group's committed offsets at a fixed rate.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LagSimulation {
+    /// Group whose committed offsets are the starting point; nothing is committed for it
+    pub group: String,
+    /// Records the simulated consumer processes per second
+    #[serde(rename = "messages_per_second", alias = "messagesPerSecond")]
+    pub messages_per_second: f64,
+    #[serde(rename = "duration_secs", alias = "durationSecs", default = "default_duration")]
+    pub duration_secs: u64,
+    #[serde(rename = "sample_interval_ms", alias = "sampleIntervalMs", default = "default_interval")]
+    pub sample_interval_ms: u64,
+}
+
+fn default_duration() -> u64 { 60 }
+fn default_interval() -> u64 { 1000 }
+
+/// Lag observed at one point of the run.
+#[derive(Debug, Clone, Serialize)]
+pub struct LagSample {
+    #[serde(rename = "elapsed_ms")]
+    pub elapsed_ms: u64,
+    /// Records appended to the topic since the start
+    pub produced: i64,
+    /// Records read by the simulated consumer since the start
+    pub consumed: i64,
+    /// High watermarks minus the simulated consumer's position
+    pub lag: i64,
+}
+
+/// Outcome of a run: whether a consumer at the given rate keeps up with the topic.
+#[derive(Debug, Clone, Serialize)]
+pub struct LagSimulationReport {
+    pub group: String,
+    pub topic: String,
+    #[serde(rename = "target_rate")]
+    pub target_rate: f64,
+    /// Produce rate observed on the topic during the run (msg/s)
+    #[serde(rename = "produce_rate")]
+    pub produce_rate: f64,
+    /// Rate actually reached; below target when the backlog ran dry or the broker was slower
+    #[serde(rename = "consume_rate")]
+    pub consume_rate: f64,
+    #[serde(rename = "start_lag")]
+    pub start_lag: i64,
+    #[serde(rename = "end_lag")]
+    pub end_lag: i64,
+    /// Least-squares slope of the lag over time (msg/s); positive means falling behind
+    #[serde(rename = "lag_trend")]
+    pub lag_trend: f64,
+    #[serde(rename = "keeps_up")]
+    pub keeps_up: bool,
+    /// Projected time to reach zero lag at the observed trend, when it is shrinking
+    #[serde(rename = "drain_secs")]
+    pub drain_secs: Option<f64>,
+    pub samples: Vec<LagSample>,
+    pub cancelled: bool,
+}
+
+impl LagSimulationReport {
+    /// Derive rates, trend and verdict from the collected samples (the first one is the start).
+    pub fn from_samples(group: &str, topic: &str, target_rate: f64, samples: Vec<LagSample>, cancelled: bool) -> Self {
+        let (first, last) = (samples.first(), samples.last());
+        let start_lag = first.map_or(0, |s| s.lag);
+        let end_lag = last.map_or(0, |s| s.lag);
+        let secs = last.map_or(0.0, |s| s.elapsed_ms as f64 / 1000.0);
+        let per_sec = |n: i64| if secs > 0.0 { n as f64 / secs } else { 0.0 };
+
+        let n = samples.len() as f64;
+        let lag_trend = if samples.len() < 2 {
+            0.0
+        } else {
+            let mean_t = samples.iter().map(|s| s.elapsed_ms as f64 / 1000.0).sum::<f64>() / n;
+            let mean_l = samples.iter().map(|s| s.lag as f64).sum::<f64>() / n;
+            let (mut cov, mut var) = (0.0, 0.0);
+            for s in &samples {
+                let dt = s.elapsed_ms as f64 / 1000.0 - mean_t;
+                cov += dt * (s.lag as f64 - mean_l);
+                var += dt * dt;
+            }
+            if var > 0.0 { cov / var } else { 0.0 }
+        };
+        // A consumer that drained the backlog keeps up even if the trend is noisy
+        let keeps_up = end_lag == 0 || lag_trend <= 0.0;
+        let drain_secs = (lag_trend < 0.0 && end_lag > 0).then(|| end_lag as f64 / -lag_trend);
+
+        Self {
+            group: group.to_string(),
+            topic: topic.to_string(),
+            target_rate,
+            produce_rate: per_sec(last.map_or(0, |s| s.produced)),
+            consume_rate: per_sec(last.map_or(0, |s| s.consumed)),
+            start_lag,
+            end_lag,
+            lag_trend,
+            keeps_up,
+            drain_secs,
+            samples,
+            cancelled,
+        }
+    }
+}
+
+impl Kafka {
+    /// Read `config.topic` from the group's committed offsets at a fixed rate for a while, sampling how
+    /// the lag evolves against the produce rate. The group itself is never joined or committed to.
+    /// `progress` gets every sample and returns false to stop early.
+    pub fn simulate_lag(
+        config: &KafkaConfig,
+        sim: &LagSimulation,
+        mut progress: impl FnMut(&LagSample) -> bool,
+    ) -> anyhow::Result<LagSimulationReport> {
+        if !sim.messages_per_second.is_finite() || sim.messages_per_second <= 0.0 {
+            return Err(anyhow::anyhow!("Rate must be greater than zero"));
+        }
+        let topic = config.topic.as_str();
+        let duration = Duration::from_secs(sim.duration_secs.clamp(1, MAX_DURATION_SECS));
+        let interval = Duration::from_millis(sim.sample_interval_ms.max(100)).min(duration);
+        let timeout = Duration::from_secs(5);
+
+        let lag = Self::consumer_lag(config, &sim.group, topic)?;
+        // Next offset the simulated consumer reads, per partition
+        let mut position: HashMap<i32, i64> = HashMap::new();
+        let mut tpl = TopicPartitionList::new();
+        for p in &lag.partitions {
+            let start = p.committed.unwrap_or(p.low_watermark).max(p.low_watermark);
+            position.insert(p.partition, start);
+            tpl.add_partition_offset(topic, p.partition, Offset::Offset(start))?;
+        }
+        let start_high: i64 = lag.partitions.iter().map(|p| p.high_watermark).sum();
+
+        let consumer = create_consumer(config)?;
+        consumer.assign(&tpl)?;
+
+        let started = Instant::now();
+        let mut consumed = 0i64;
+        let mut samples = vec![LagSample { elapsed_ms: 0, produced: 0, consumed: 0, lag: lag.total_lag }];
+        let mut next_sample = started + interval;
+        let mut cancelled = false;
+        loop {
+            let now = Instant::now();
+            if now >= next_sample {
+                let mut high_total = 0i64;
+                let mut lag_total = 0i64;
+                for (p, pos) in &position {
+                    let (_, high) = consumer.fetch_watermarks(topic, *p, timeout)?;
+                    high_total += high;
+                    lag_total += (high - pos).max(0);
+                }
+                let sample = LagSample {
+                    elapsed_ms: started.elapsed().as_millis() as u64,
+                    produced: high_total - start_high,
+                    consumed,
+                    lag: lag_total,
+                };
+                let keep_going = progress(&sample);
+                samples.push(sample);
+                if !keep_going {
+                    cancelled = true;
+                    break;
+                }
+                // The last sample is the first one at or after the end of the run
+                if started.elapsed() >= duration {
+                    break;
+                }
+                next_sample += interval;
+                continue;
+            }
+
+            // Pace reads: record n is due n / rate seconds after the start
+            let due = started + Duration::from_secs_f64(consumed as f64 / sim.messages_per_second);
+            if due > now {
+                std::thread::sleep((due - now).min(next_sample - now));
+                continue;
+            }
+            match consumer.poll((next_sample - now).min(Duration::from_millis(100))) {
+                Some(Ok(m)) => {
+                    position.insert(m.partition(), m.offset() + 1);
+                    consumed += 1;
+                }
+                Some(Err(e)) => check_poll_error(&e, topic)?,
+                None => {}
+            }
+        }
+        Ok(LagSimulationReport::from_samples(&sim.group, topic, sim.messages_per_second, samples, cancelled))
+    }
+}
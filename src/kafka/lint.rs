@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use rdkafka::consumer::Consumer;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::consumer::create_consumer;
+use super::service::Kafka;
+use super::types::KafkaConfig;
+
+/// Naming and sizing conventions topics are audited against. Empty lists and unset minimums are not checked.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TopicLintRules {
+    /// Regexes of which a topic name must match at least one (e.g. `^[a-z0-9]+(\.[a-z0-9-]+)+$`)
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Regexes no topic name may match
+    #[serde(default, rename = "forbidden_patterns", alias = "forbiddenPatterns")]
+    pub forbidden_patterns: Vec<String>,
+    /// Prefixes of which a topic name must start with one (e.g. a team or domain)
+    #[serde(default, rename = "required_prefixes", alias = "requiredPrefixes")]
+    pub required_prefixes: Vec<String>,
+    #[serde(rename = "min_partitions", alias = "minPartitions")]
+    pub min_partitions: Option<usize>,
+    #[serde(rename = "min_replication", alias = "minReplication")]
+    pub min_replication: Option<usize>,
+    /// Also audit internal topics (names starting with '_', e.g. `__consumer_offsets`)
+    #[serde(default, rename = "include_internal", alias = "includeInternal")]
+    pub include_internal: bool,
+}
+
+/// What the audit knows about a topic.
+#[derive(Debug, Clone)]
+pub struct TopicFacts {
+    pub name: String,
+    pub partitions: usize,
+    /// Smallest replica count over the topic's partitions
+    pub replication: usize,
+}
+
+/// One broken rule.
+#[derive(Debug, Clone, Serialize)]
+pub struct LintViolation {
+    pub topic: String,
+    /// "pattern" | "forbidden_pattern" | "prefix" | "partitions" | "replication"
+    pub rule: String,
+    pub message: String,
+}
+
+/// Outcome of auditing a cluster's topics.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicLintReport {
+    pub checked: usize,
+    pub compliant: usize,
+    pub violations: Vec<LintViolation>,
+}
+
+fn compile(patterns: &[String]) -> anyhow::Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|p| Regex::new(p).with_context(|| format!("Invalid topic name pattern '{p}'")))
+        .collect()
+}
+
+/// Check topics against the rules; violations are listed per topic in name order.
+pub fn lint_topics(rules: &TopicLintRules, topics: &[TopicFacts]) -> anyhow::Result<TopicLintReport> {
+    let allowed = compile(&rules.patterns)?;
+    let forbidden = compile(&rules.forbidden_patterns)?;
+    let mut topics: Vec<&TopicFacts> = topics
+        .iter()
+        .filter(|t| rules.include_internal || !t.name.starts_with('_'))
+        .collect();
+    topics.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut violations = Vec::new();
+    let mut compliant = 0;
+    for t in &topics {
+        let before = violations.len();
+        let mut flag = |rule: &str, message: String| {
+            violations.push(LintViolation { topic: t.name.clone(), rule: rule.to_string(), message });
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|re| re.is_match(&t.name)) {
+            flag("pattern", format!("Name does not match any of: {}", rules.patterns.join(", ")));
+        }
+        for (re, src) in forbidden.iter().zip(&rules.forbidden_patterns) {
+            if re.is_match(&t.name) {
+                flag("forbidden_pattern", format!("Name matches forbidden pattern '{src}'"));
+            }
+        }
+        if !rules.required_prefixes.is_empty() && !rules.required_prefixes.iter().any(|p| t.name.starts_with(p.as_str())) {
+            flag("prefix", format!("Name does not start with any of: {}", rules.required_prefixes.join(", ")));
+        }
+        if let Some(min) = rules.min_partitions.filter(|m| t.partitions < *m) {
+            flag("partitions", format!("{} partitions, at least {} required", t.partitions, min));
+        }
+        if let Some(min) = rules.min_replication.filter(|m| t.replication < *m) {
+            flag("replication", format!("Replication factor {}, at least {} required", t.replication, min));
+        }
+        if violations.len() == before {
+            compliant += 1;
+        }
+    }
+    Ok(TopicLintReport { checked: topics.len(), compliant, violations })
+}
+
+impl Kafka {
+    /// Audit every topic of the cluster against the rules using metadata only.
+    pub fn lint_topics(config: &KafkaConfig, rules: &TopicLintRules) -> anyhow::Result<TopicLintReport> {
+        let consumer = create_consumer(config)?;
+        let md = consumer.client().fetch_metadata(None, Duration::from_secs(10))?;
+        let facts: Vec<TopicFacts> = md
+            .topics()
+            .iter()
+            // Topics the credentials may not describe have no partition data to judge
+            .filter(|t| t.error().is_none())
+            .map(|t| TopicFacts {
+                name: t.name().to_string(),
+                partitions: t.partitions().len(),
+                replication: t.partitions().iter().map(|p| p.replicas().len()).min().unwrap_or(0),
+            })
+            .collect();
+        lint_topics(rules, &facts)
+    }
+}
@@ -0,0 +1,80 @@
+use std::borrow::Cow;
+
+use anyhow::Context;
+use regex::Regex;
+
+use super::types::MaskingRule;
+use crate::utils::json::json_path_for_each_mut;
+
+const DEFAULT_REPLACEMENT: &str = "***";
+
+struct CompiledRule {
+    path: Option<String>,
+    pattern: Option<Regex>,
+    replacement: String,
+}
+
+/// Compiled masking rules; see `MaskingRule` for their semantics.
+pub struct Masker {
+    rules: Vec<CompiledRule>,
+}
+
+impl Masker {
+    pub fn new(rules: &[MaskingRule]) -> anyhow::Result<Self> {
+        let rules = rules
+            .iter()
+            .filter(|r| r.path.is_some() || r.pattern.is_some())
+            .map(|r| {
+                let pattern = r
+                    .pattern
+                    .as_deref()
+                    .map(|p| Regex::new(p).with_context(|| format!("Invalid masking pattern '{p}'")))
+                    .transpose()?;
+                Ok(CompiledRule {
+                    path: r.path.clone(),
+                    pattern,
+                    replacement: r.replacement.clone().unwrap_or_else(|| DEFAULT_REPLACEMENT.to_string()),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Redact a decoded payload. Path rules only apply to JSON payloads.
+    pub fn mask<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut out = Cow::Borrowed(text);
+        if self.rules.iter().any(|r| r.path.is_some()) {
+            if let Ok(mut value) = serde_json::from_str::<serde_json::Value>(text) {
+                let mut changed = false;
+                for rule in &self.rules {
+                    let Some(path) = rule.path.as_deref() else { continue };
+                    json_path_for_each_mut(&mut value, path, &mut |v| {
+                        let masked = match (&rule.pattern, v.as_str()) {
+                            (Some(re), Some(s)) => re.replace_all(s, rule.replacement.as_str()).into_owned(),
+                            // A pattern only masks strings; other values at the path are left alone
+                            (Some(_), None) => return,
+                            (None, _) => rule.replacement.clone(),
+                        };
+                        *v = serde_json::Value::String(masked);
+                        changed = true;
+                    });
+                }
+                if changed {
+                    out = Cow::Owned(value.to_string());
+                }
+            }
+        }
+        for rule in self.rules.iter().filter(|r| r.path.is_none()) {
+            if let Some(re) = &rule.pattern {
+                if let Cow::Owned(s) = re.replace_all(&out, rule.replacement.as_str()) {
+                    out = Cow::Owned(s);
+                }
+            }
+        }
+        out
+    }
+}
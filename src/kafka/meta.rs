@@ -2,8 +2,10 @@ use std::time::Duration;
 
 use rdkafka::consumer::Consumer;
 
-use super::consumer::create_consumer;
-use super::types::KafkaConfig;
+use rdkafka::error::RDKafkaErrorCode;
+
+use super::consumer::{create_consumer, is_authorization_error};
+use super::types::{KafkaConfig, TopicAccess, TopicInfo};
 
 impl super::service::Kafka {
     /// Discover all topics in the cluster.
@@ -31,4 +33,34 @@ impl super::service::Kafka {
             .ok_or_else(|| anyhow::anyhow!("Topic not found in metadata"))?;
         Ok(t.partitions().iter().map(|p| p.id()).collect())
     }
+
+    /// Discover all topics with partition counts and per-topic access state from metadata.
+    /// Topics the credentials may not describe are reported as unauthorized instead of failing the listing.
+    pub fn list_topics_detailed(config: &KafkaConfig) -> anyhow::Result<Vec<TopicInfo>> {
+        let consumer = create_consumer(config)?;
+        let md = consumer
+            .client()
+            .fetch_metadata(None, Duration::from_secs(5))?;
+        let mut out: Vec<TopicInfo> = md
+            .topics()
+            .iter()
+            .map(|t| {
+                let code = t.error().map(RDKafkaErrorCode::from);
+                let access = match code {
+                    None => TopicAccess::Ok,
+                    Some(c) if is_authorization_error(c) => TopicAccess::Unauthorized,
+                    Some(_) => TopicAccess::Error,
+                };
+                TopicInfo {
+                    name: t.name().to_string(),
+                    partitions: t.partitions().len(),
+                    access,
+                    error: code.map(|c| format!("{:?}", c)),
+                }
+            })
+            .collect();
+        out.sort_by(|a, b| a.name.cmp(&b.name));
+        out.dedup_by(|a, b| a.name == b.name);
+        Ok(out)
+    }
 }
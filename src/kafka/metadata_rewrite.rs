@@ -0,0 +1,193 @@
+const METADATA: i16 = 3;
+const FIND_COORDINATOR: i16 = 10;
+
+/// Requests whose responses name brokers by their advertised address.
+pub fn carries_brokers(api_key: i16) -> bool {
+    matches!(api_key, METADATA | FIND_COORDINATOR)
+}
+
+/// Copies a response while replacing the broker addresses in it.
+struct Rewriter<'a> {
+    src: &'a [u8],
+    pos: usize,
+    out: Vec<u8>,
+    /// Compact strings/arrays and tagged fields (KIP-482)
+    flexible: bool,
+}
+
+impl<'a> Rewriter<'a> {
+    fn take(&mut self, n: usize) -> anyhow::Result<&'a [u8]> {
+        let end = self.pos.checked_add(n).filter(|e| *e <= self.src.len());
+        let end = end.ok_or_else(|| anyhow::anyhow!("Truncated response at byte {}", self.pos))?;
+        let bytes = &self.src[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn copy(&mut self, n: usize) -> anyhow::Result<()> {
+        let bytes = self.take(n)?;
+        self.out.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn read_i16(&mut self) -> anyhow::Result<i16> {
+        Ok(i16::from_be_bytes(self.take(2)?.try_into()?))
+    }
+
+    fn read_i32(&mut self) -> anyhow::Result<i32> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn read_uvarint(&mut self) -> anyhow::Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.take(1)?[0];
+            value |= u64::from(b & 0x7f) << shift;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(anyhow::anyhow!("Malformed varint at byte {}", self.pos))
+    }
+
+    fn put_uvarint(&mut self, mut v: u64) {
+        while v >= 0x80 {
+            self.out.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        self.out.push(v as u8);
+    }
+
+    fn copy_uvarint(&mut self) -> anyhow::Result<u64> {
+        let v = self.read_uvarint()?;
+        self.put_uvarint(v);
+        Ok(v)
+    }
+
+    /// Element count of an array (null counts as empty).
+    fn copy_array_len(&mut self) -> anyhow::Result<usize> {
+        if self.flexible {
+            Ok(self.copy_uvarint()?.saturating_sub(1) as usize)
+        } else {
+            let n = self.read_i32()?;
+            self.out.extend_from_slice(&n.to_be_bytes());
+            Ok(n.max(0) as usize)
+        }
+    }
+
+    /// Length of a (nullable) string, None for null.
+    fn read_string_len(&mut self) -> anyhow::Result<Option<usize>> {
+        if self.flexible {
+            Ok(self.read_uvarint()?.checked_sub(1).map(|n| n as usize))
+        } else {
+            let n = self.read_i16()?;
+            Ok((n >= 0).then_some(n as usize))
+        }
+    }
+
+    fn copy_string(&mut self) -> anyhow::Result<()> {
+        let start = self.pos;
+        let len = self.read_string_len()?;
+        self.out.extend_from_slice(&self.src[start..self.pos]);
+        self.copy(len.unwrap_or(0))
+    }
+
+    fn read_string(&mut self) -> anyhow::Result<String> {
+        let len = self.read_string_len()?.unwrap_or(0);
+        Ok(String::from_utf8_lossy(self.take(len)?).into_owned())
+    }
+
+    fn put_string(&mut self, s: &str) {
+        if self.flexible {
+            self.put_uvarint(s.len() as u64 + 1);
+        } else {
+            self.out.extend_from_slice(&(s.len() as i16).to_be_bytes());
+        }
+        self.out.extend_from_slice(s.as_bytes());
+    }
+
+    fn copy_tagged_fields(&mut self) -> anyhow::Result<()> {
+        if !self.flexible {
+            return Ok(());
+        }
+        for _ in 0..self.copy_uvarint()? {
+            self.copy_uvarint()?;
+            let size = self.copy_uvarint()? as usize;
+            self.copy(size)?;
+        }
+        Ok(())
+    }
+
+    /// Host and port of a broker, passed through `map` unless the broker is unknown (empty host).
+    fn broker(&mut self, map: &mut impl FnMut(&str, i32) -> (String, i32)) -> anyhow::Result<()> {
+        let host = self.read_string()?;
+        let port = self.read_i32()?;
+        let (host, port) = if host.is_empty() || port <= 0 { (host, port) } else { map(&host, port) };
+        self.put_string(&host);
+        self.out.extend_from_slice(&port.to_be_bytes());
+        Ok(())
+    }
+
+    fn metadata(&mut self, version: i16, map: &mut impl FnMut(&str, i32) -> (String, i32)) -> anyhow::Result<()> {
+        if version >= 3 {
+            self.copy(4)?; // throttle_time_ms
+        }
+        for _ in 0..self.copy_array_len()? {
+            self.copy(4)?; // node_id
+            self.broker(map)?;
+            if version >= 1 {
+                self.copy_string()?; // rack
+            }
+            self.copy_tagged_fields()?;
+        }
+        Ok(())
+    }
+
+    fn find_coordinator(&mut self, version: i16, map: &mut impl FnMut(&str, i32) -> (String, i32)) -> anyhow::Result<()> {
+        if version >= 1 {
+            self.copy(4)?; // throttle_time_ms
+        }
+        if version < 4 {
+            self.copy(2)?; // error_code
+            if version >= 1 {
+                self.copy_string()?; // error_message
+            }
+            self.copy(4)?; // node_id
+            return self.broker(map);
+        }
+        for _ in 0..self.copy_array_len()? {
+            self.copy_string()?; // key
+            self.copy(4)?; // node_id
+            self.broker(map)?;
+            self.copy(2)?; // error_code
+            self.copy_string()?; // error_message
+            self.copy_tagged_fields()?;
+        }
+        Ok(())
+    }
+}
+
+/// Replace the advertised broker addresses in a Metadata or FindCoordinator response with `map(host, port)`.
+/// `response` is the frame without its size prefix (correlation id first); other responses come back unchanged.
+pub fn rewrite_response(
+    response: &[u8],
+    api_key: i16,
+    api_version: i16,
+    mut map: impl FnMut(&str, i32) -> (String, i32),
+) -> anyhow::Result<Vec<u8>> {
+    let flexible = match api_key {
+        METADATA => api_version >= 9,
+        FIND_COORDINATOR => api_version >= 3,
+        _ => return Ok(response.to_vec()),
+    };
+    let mut w = Rewriter { src: response, pos: 0, out: Vec::with_capacity(response.len() + 64), flexible };
+    w.copy(4)?; // correlation_id
+    w.copy_tagged_fields()?; // response header v1
+    match api_key {
+        METADATA => w.metadata(api_version, &mut map)?,
+        _ => w.find_coordinator(api_version, &mut map)?,
+    }
+    // Topics, cluster id etc. follow unchanged
+    w.out.extend_from_slice(&response[w.pos..]);
+    Ok(w.out)
+}
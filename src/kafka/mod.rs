@@ -1,3 +1,5 @@
+mod client_stats;
+mod codec;
 mod decoder;
 pub mod reader;
 pub mod types;
@@ -6,7 +8,66 @@ mod consumer;
 mod service;
 mod assignment;
 mod meta;
+mod fetch;
+mod benchmark;
+mod bookmarks;
+mod compacted;
+mod lag_sim;
+mod lint;
+mod raw_cache;
+mod profile;
+mod producer;
+mod oauth;
+mod metadata_rewrite;
+mod offsets;
+mod page_size;
+mod partition_queues;
+mod replay;
+mod timeline;
+mod admin;
+mod retention;
+mod reverse_scan;
+mod tunnel;
+mod diagnostics;
+pub mod partitioner;
+pub mod avro;
+pub mod schema_registry;
+pub mod compression;
+pub mod plugin;
+pub mod masking;
 
-pub use decoder::{MessageType, decoder_for};
+pub use admin::{TopicConfigEntry, TopicConfigs};
+pub use client_stats::{BrokerStats, ClientStats, PartitionStats, STATS_INTERVAL_MS};
+pub use codec::MessageCodec;
+pub use bookmarks::{bookmark_group, BOOKMARK_GROUP_PREFIX};
+pub use benchmark::{LatencySummary, ProduceBenchmark, ProduceBenchmarkReport};
+pub use assignment::select_partitions;
+pub use compacted::{KeySnapshot, LatestByKey};
+pub use lag_sim::{LagSample, LagSimulation, LagSimulationReport};
+pub use lint::{lint_topics, LintViolation, TopicFacts, TopicLintReport, TopicLintRules};
+pub use raw_cache::RawCache;
+pub use masking::Masker;
+pub use compression::PayloadCompression;
+pub use consumer::{is_authorization_error, AccessDenied};
+pub(crate) use consumer::recv_timeout;
+pub(crate) use reverse_scan::ReverseScan;
+pub use decoder::{decode_simple_key, encode_simple_key, AvroDecoder, KeyType, MessageType, decoder_for};
+pub use diagnostics::ConnectionTest;
+pub use metadata_rewrite::{carries_brokers, rewrite_response};
+pub use oauth::{ClientCtx, OAuthSettings};
+pub use page_size::{PageSizer, DEFAULT_PAGE_SIZE};
+pub use offsets::{ConsumerLag, OffsetBase, OffsetExpression, PartitionOffset, PartitionSize, PartitionWatermarks, ResolvedOffset, TimeOffset, TopicOffsets};
+pub use profile::TopicProfile;
+pub use schema_registry::SchemaRegistry;
+pub use security::{inspect_truststore, CertificateInfo};
+pub use retention::{PartitionRetention, RetentionEstimate};
+pub use replay::{window_ranges, ReplayRange, ReplayRequest, ReplaySummary, ReplayThrottle, TimestampMode};
 pub use service::Kafka;
-pub use types::{KafkaConfig, UiMessage};
+pub use tunnel::{SshTunnel, UnknownHostKey};
+pub use timeline::{keep_newest, KeyTimeline, TimelineEntry, TimelineWindow};
+pub use partitioner::PartitionStrategy;
+pub use plugin::PluginDecoder;
+pub use types::{
+    ConsumeBatch, ConsumeProgress, DecoderSettings, DeliveryReport, KafkaConfig, MaskingRule, PartitionProgress, ProduceError,
+    ProduceRecord, ProduceRequest, ProducerDefaults, SshTunnelConfig, TopicAccess, TopicInfo, UiMessage,
+};
@@ -0,0 +1,226 @@
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::Engine;
+use rdkafka::client::{Client, ClientContext, OAuthToken};
+use rdkafka::consumer::ConsumerContext;
+use rdkafka::statistics::Statistics;
+use serde::Deserialize;
+
+use super::client_stats::ClientStats;
+use super::tunnel::{self, SshTunnel};
+use super::types::KafkaConfig;
+
+const TOKEN_REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// Token lifetime assumed when the endpoint doesn't say.
+const DEFAULT_EXPIRES_IN_SECS: i64 = 3600;
+
+/// OIDC client credentials for SASL/OAUTHBEARER (Confluent Cloud, Keycloak, ...).
+#[derive(Debug, Clone)]
+pub struct OAuthSettings {
+    pub token_endpoint_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub scope: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<i64>,
+}
+
+impl OAuthSettings {
+    /// Settings of a SASL connection using OAUTHBEARER; None for every other kind of connection.
+    pub fn from_config(config: &KafkaConfig) -> Option<Self> {
+        let sasl = config
+            .security_type
+            .as_deref()
+            .is_some_and(|s| s.eq_ignore_ascii_case("sasl_plaintext") || s.eq_ignore_ascii_case("sasl_ssl"));
+        let oauth = config.sasl_mechanism.as_deref().is_some_and(|m| m.trim().eq_ignore_ascii_case("OAUTHBEARER"));
+        if !(sasl && oauth) {
+            return None;
+        }
+        let field = |v: &Option<String>| v.as_deref().map(str::trim).unwrap_or_default().to_string();
+        Some(Self {
+            token_endpoint_url: field(&config.sasl_oauthbearer_token_endpoint_url),
+            client_id: field(&config.sasl_oauthbearer_client_id),
+            client_secret: config.sasl_oauthbearer_client_secret.clone().unwrap_or_default(),
+            scope: config.sasl_oauthbearer_scope.clone().filter(|s| !s.trim().is_empty()),
+        })
+    }
+
+    /// Request an access token with the client credentials grant.
+    pub fn fetch_token(&self) -> anyhow::Result<OAuthToken> {
+        if self.token_endpoint_url.is_empty() || self.client_id.is_empty() {
+            return Err(anyhow::anyhow!("OAUTHBEARER needs a token endpoint URL and a client id"));
+        }
+        let mut form = vec![
+            ("grant_type", "client_credentials"),
+            ("client_id", self.client_id.as_str()),
+            ("client_secret", self.client_secret.as_str()),
+        ];
+        if let Some(scope) = self.scope.as_deref() {
+            form.push(("scope", scope));
+        }
+        let agent = ureq::AgentBuilder::new().timeout(TOKEN_REQUEST_TIMEOUT).build();
+        let resp = agent
+            .post(&self.token_endpoint_url)
+            .set("Accept", "application/json")
+            .send_form(&form)
+            .map_err(|e| match e {
+                ureq::Error::Status(code, resp) => {
+                    let body = resp.into_string().unwrap_or_default();
+                    anyhow::anyhow!("Token endpoint returned HTTP {code}: {}", body.trim())
+                }
+                other => anyhow::anyhow!("Failed to reach token endpoint {}: {other}", self.token_endpoint_url),
+            })?;
+        let token: TokenResponse = resp
+            .into_json()
+            .map_err(|e| anyhow::anyhow!("Failed to parse token endpoint response: {e}"))?;
+        let expires_in = token.expires_in.filter(|s| *s > 0).unwrap_or(DEFAULT_EXPIRES_IN_SECS);
+        Ok(OAuthToken {
+            principal_name: jwt_subject(&token.access_token).unwrap_or_else(|| self.client_id.clone()),
+            token: token.access_token,
+            lifetime_ms: chrono::Utc::now().timestamp_millis() + expires_in * 1000,
+        })
+    }
+}
+
+/// `sub` claim of a JWT access token; opaque tokens have none.
+fn jwt_subject(token: &str) -> Option<String> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    claims.get("sub")?.as_str().map(str::to_string)
+}
+
+struct CachedToken {
+    token: String,
+    principal: String,
+    expires_ms: i64,
+    issued_ms: i64,
+}
+
+/// Context of every client a connection creates: answers librdkafka's OAUTHBEARER token refreshes from the
+/// configured token endpoint, keeps the connection's SSH tunnel open while the client lives and holds the
+/// client's latest statistics (when enabled). Clones share the token, the tunnel and the statistics.
+#[derive(Clone)]
+pub struct ClientCtx {
+    oauth: Option<OAuthSettings>,
+    cached: Arc<Mutex<Option<CachedToken>>>,
+    tunnel: Option<Arc<SshTunnel>>,
+    stats: Arc<Mutex<Option<ClientStats>>>,
+}
+
+impl ClientCtx {
+    /// Opens the connection's SSH tunnel when it has one (or joins the one already open).
+    pub fn new(config: &KafkaConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            oauth: OAuthSettings::from_config(config),
+            cached: Arc::new(Mutex::new(None)),
+            tunnel: tunnel::open(config)?,
+            stats: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Statistics librdkafka reported last; None until `statistics.interval.ms` elapsed while the client was polled.
+    pub fn latest_stats(&self) -> Option<ClientStats> {
+        self.stats.lock().ok()?.clone()
+    }
+
+    /// `bootstrap.servers` of the connection's clients: the tunnel's local forwards, or the configured brokers.
+    pub fn bootstrap_servers<'a>(&'a self, config: &'a KafkaConfig) -> &'a str {
+        self.tunnel.as_deref().map_or(config.broker.as_str(), SshTunnel::bootstrap_servers)
+    }
+
+    /// Current token, fetching a new one once half of the cached one's lifetime is gone: librdkafka asks
+    /// again at 80% of it, and the token set up front answers its first request.
+    fn token(&self) -> anyhow::Result<OAuthToken> {
+        let Some(oauth) = &self.oauth else {
+            return Err(anyhow::anyhow!("No OAUTHBEARER token endpoint configured"));
+        };
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut cached = self.cached.lock().map_err(|e| anyhow::anyhow!("OAuth token lock poisoned: {e}"))?;
+        if let Some(c) = cached.as_ref().filter(|c| now < c.issued_ms + (c.expires_ms - c.issued_ms) / 2) {
+            return Ok(OAuthToken { token: c.token.clone(), principal_name: c.principal.clone(), lifetime_ms: c.expires_ms });
+        }
+        let fresh = oauth.fetch_token()?;
+        *cached = Some(CachedToken {
+            token: fresh.token.clone(),
+            principal: fresh.principal_name.clone(),
+            expires_ms: fresh.lifetime_ms,
+            issued_ms: now,
+        });
+        Ok(fresh)
+    }
+
+    /// Set the first token on a new client right away. Refreshes only run when a client is polled, and
+    /// metadata requests made before that would stall on authentication.
+    pub(crate) fn prime<C: ClientContext>(&self, client: &Client<C>) -> anyhow::Result<()> {
+        if self.oauth.is_none() {
+            return Ok(());
+        }
+        let token = self.token()?;
+        set_token(client, &token)
+    }
+}
+
+/// Create a client from an async command. Creation opens the SSH tunnel and fetches the first OAuth token,
+/// both blocking, so it runs on the blocking pool.
+pub(crate) async fn create_off_runtime<T: Send + 'static>(
+    config: &KafkaConfig,
+    create: fn(&KafkaConfig) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let config = config.clone();
+    tokio::task::spawn_blocking(move || create(&config)).await?
+}
+
+impl ClientContext for ClientCtx {
+    const ENABLE_REFRESH_OAUTH_TOKEN: bool = true;
+
+    fn generate_oauth_token(&self, _oauthbearer_config: Option<&str>) -> Result<OAuthToken, Box<dyn Error>> {
+        self.token().map_err(|e| e.to_string().into())
+    }
+
+    fn stats(&self, statistics: Statistics) {
+        if let Ok(mut stats) = self.stats.lock() {
+            *stats = Some(ClientStats::from(&statistics));
+        }
+    }
+}
+
+impl ConsumerContext for ClientCtx {}
+
+/// rdkafka 0.36 only sets tokens from its refresh events, so the first one goes through librdkafka directly.
+fn set_token<C: ClientContext>(client: &Client<C>, token: &OAuthToken) -> anyhow::Result<()> {
+    use rdkafka::bindings as rd;
+    use std::ffi::{CStr, CString};
+
+    let value = CString::new(token.token.as_str()).map_err(|_| anyhow::anyhow!("OAuth token contains a NUL byte"))?;
+    let principal =
+        CString::new(token.principal_name.as_str()).map_err(|_| anyhow::anyhow!("OAuth principal contains a NUL byte"))?;
+    let mut errstr = [0 as std::os::raw::c_char; 512];
+    // SAFETY: librdkafka copies the strings; no extensions are passed.
+    let err = unsafe {
+        rd::rd_kafka_oauthbearer_set_token(
+            client.native_ptr(),
+            value.as_ptr(),
+            token.lifetime_ms,
+            principal.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            errstr.as_mut_ptr(),
+            errstr.len(),
+        )
+    };
+    if err != rd::rd_kafka_resp_err_t::RD_KAFKA_RESP_ERR_NO_ERROR {
+        // SAFETY: librdkafka NUL-terminates the error string within the buffer
+        let msg = unsafe { CStr::from_ptr(errstr.as_ptr()) }.to_string_lossy().into_owned();
+        return Err(anyhow::anyhow!("Failed to set OAUTHBEARER token: {msg}"));
+    }
+    Ok(())
+}
@@ -0,0 +1,414 @@
+use std::time::{Duration, Instant};
+
+use rdkafka::consumer::{BaseConsumer, CommitMode, Consumer};
+use rdkafka::message::Message;
+use rdkafka::topic_partition_list::TopicPartitionList;
+use rdkafka::Offset;
+use serde::{Deserialize, Serialize};
+
+use super::consumer::{create_consumer, create_group_consumer};
+use super::oauth::ClientCtx;
+use super::service::Kafka;
+use super::types::KafkaConfig;
+
+/// Committed position of a group on one partition compared to the partition's watermarks.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionLag {
+    pub partition: i32,
+    /// None when the group has no committed offset for this partition
+    pub committed: Option<i64>,
+    pub low_watermark: i64,
+    pub high_watermark: i64,
+    /// high - committed; without a commit, everything still retained counts as lag
+    pub lag: i64,
+}
+
+/// Lag of a consumer group on a topic.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsumerLag {
+    pub group: String,
+    pub topic: String,
+    pub partitions: Vec<PartitionLag>,
+    pub total_lag: i64,
+}
+
+/// Position to commit for one partition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartitionOffset {
+    pub partition: i32,
+    pub offset: i64,
+}
+
+/// First offset at or after a timestamp for one partition.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeOffset {
+    pub partition: i32,
+    /// None when no record is that recent (the next record will be written at the high watermark)
+    pub offset: Option<i64>,
+}
+
+/// Earliest (low) and next-to-be-written (high) offsets of a partition.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionWatermarks {
+    pub partition: i32,
+    pub low: i64,
+    pub high: i64,
+}
+
+/// Watermarks of one partition and the number of offsets between them.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionSize {
+    pub partition: i32,
+    pub low: i64,
+    pub high: i64,
+    /// high - low; approximate as compaction and transaction markers leave gaps
+    pub count: i64,
+}
+
+/// Size of a topic per partition, shown before a scan starts.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicOffsets {
+    pub topic: String,
+    pub partitions: Vec<PartitionSize>,
+    /// Sum of the partition counts
+    pub total: i64,
+}
+
+impl TopicOffsets {
+    pub fn from_watermarks(topic: &str, watermarks: &[PartitionWatermarks]) -> Self {
+        let partitions: Vec<PartitionSize> = watermarks
+            .iter()
+            .map(|w| PartitionSize { partition: w.partition, low: w.low, high: w.high, count: (w.high - w.low).max(0) })
+            .collect();
+        let total = partitions.iter().map(|p| p.count).sum();
+        TopicOffsets { topic: topic.to_string(), partitions, total }
+    }
+}
+
+/// First record at or after `offset` and before `end`, as (offset, timestamp ms), read after re-assigning.
+fn sample_at(
+    consumer: &BaseConsumer<ClientCtx>,
+    topic: &str,
+    partition: i32,
+    offset: i64,
+    end: i64,
+) -> anyhow::Result<Option<(i64, Option<i64>)>> {
+    let mut tpl = TopicPartitionList::new();
+    tpl.add_partition_offset(topic, partition, Offset::Offset(offset))?;
+    consumer.assign(&tpl)?;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        match consumer.poll(Duration::from_millis(200)) {
+            Some(Ok(m)) if m.partition() == partition && m.offset() >= offset => {
+                return Ok((m.offset() < end).then(|| (m.offset(), m.timestamp().to_millis())));
+            }
+            Some(Err(e)) => return Err(e.into()),
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Offset of the first record in [low, high) whose timestamp is >= `target_ms`, found by seeking and sampling
+/// (about log2(high - low) fetches). Use it when offsetsForTimes is off, e.g. CreateTime records on a
+/// LogAppendTime topic. Assumes timestamps mostly grow with offsets; None when no record is that recent.
+pub(crate) fn search_timestamp(
+    consumer: &BaseConsumer<ClientCtx>,
+    topic: &str,
+    partition: i32,
+    target_ms: i64,
+    low: i64,
+    high: i64,
+) -> anyhow::Result<Option<i64>> {
+    let (mut lo, mut hi) = (low, high);
+    // Earliest record seen that is recent enough; `lo` itself may be a gap left by compaction
+    let mut found = None;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        match sample_at(consumer, topic, partition, mid, hi)? {
+            Some((offset, ts)) if ts.is_none_or(|ts| ts < target_ms) => lo = offset + 1,
+            Some((offset, _)) => {
+                found = Some(offset);
+                hi = mid;
+            }
+            // Nothing left in [mid, hi) (compacted or deleted)
+            None => hi = mid,
+        }
+    }
+    Ok(found)
+}
+
+fn watermarks_with(consumer: &impl Consumer<ClientCtx>, topic: &str) -> anyhow::Result<Vec<PartitionWatermarks>> {
+    let timeout = Duration::from_secs(5);
+    let md = consumer.client().fetch_metadata(Some(topic), timeout)?;
+    let t = md
+        .topics()
+        .iter()
+        .find(|t| t.name() == topic)
+        .ok_or_else(|| anyhow::anyhow!("Topic not found in metadata"))?;
+    let mut out = Vec::with_capacity(t.partitions().len());
+    for p in t.partitions() {
+        let (low, high) = consumer.fetch_watermarks(topic, p.id(), timeout)?;
+        out.push(PartitionWatermarks { partition: p.id(), low, high });
+    }
+    out.sort_by_key(|w| w.partition);
+    Ok(out)
+}
+
+impl Kafka {
+    /// Watermarks of any topic on the configured cluster, reusing this reader's client connection.
+    pub fn watermarks(&self, topic: &str) -> anyhow::Result<Vec<PartitionWatermarks>> {
+        watermarks_with(self.consumer.as_ref(), topic)
+    }
+
+    /// Watermarks and approximate record counts per partition of any topic on the configured cluster.
+    pub fn topic_offsets(&self, topic: &str) -> anyhow::Result<TopicOffsets> {
+        Ok(TopicOffsets::from_watermarks(topic, &self.watermarks(topic)?))
+    }
+
+    /// Watermarks using a short-lived client (no configured reader needed).
+    pub fn watermarks_for(config: &KafkaConfig, topic: &str) -> anyhow::Result<Vec<PartitionWatermarks>> {
+        watermarks_with(&create_consumer(config)?, topic)
+    }
+
+    /// Compare a group's committed offsets with the high watermarks of every partition of a topic.
+    pub fn consumer_lag(config: &KafkaConfig, group: &str, topic: &str) -> anyhow::Result<ConsumerLag> {
+        let consumer = create_group_consumer(config, group)?;
+        let timeout = Duration::from_secs(10);
+        let md = consumer.client().fetch_metadata(Some(topic), timeout)?;
+        let t = md
+            .topics()
+            .iter()
+            .find(|t| t.name() == topic)
+            .ok_or_else(|| anyhow::anyhow!("Topic not found in metadata"))?;
+        let mut ids: Vec<i32> = t.partitions().iter().map(|p| p.id()).collect();
+        ids.sort_unstable();
+
+        let mut tpl = TopicPartitionList::new();
+        for p in &ids {
+            tpl.add_partition(topic, *p);
+        }
+        let committed = consumer.committed_offsets(tpl, timeout)?;
+
+        let mut partitions = Vec::with_capacity(ids.len());
+        for p in ids {
+            let (low, high) = consumer.fetch_watermarks(topic, p, timeout)?;
+            let committed = committed
+                .find_partition(topic, p)
+                .and_then(|e| match e.offset() {
+                    Offset::Offset(o) => Some(o),
+                    _ => None,
+                });
+            // A commit below the log start (data already deleted) cannot lag more than what is retained
+            let lag = (high - committed.unwrap_or(low).max(low)).max(0);
+            partitions.push(PartitionLag { partition: p, committed, low_watermark: low, high_watermark: high, lag });
+        }
+        let total_lag = partitions.iter().map(|p| p.lag).sum();
+        Ok(ConsumerLag { group: group.to_string(), topic: topic.to_string(), partitions, total_lag })
+    }
+
+    /// Commit offsets for a new consumer group so an application can start exactly at them.
+    /// Refuses groups with active members, and groups that already have commits on the topic unless `overwrite`.
+    pub fn prepare_group_offsets(
+        config: &KafkaConfig,
+        group: &str,
+        topic: &str,
+        offsets: &[PartitionOffset],
+        overwrite: bool,
+    ) -> anyhow::Result<Vec<PartitionOffset>> {
+        if group.trim().is_empty() {
+            return Err(anyhow::anyhow!("Group id must not be empty"));
+        }
+        if offsets.is_empty() {
+            return Err(anyhow::anyhow!("No offsets given"));
+        }
+        let consumer = create_group_consumer(config, group)?;
+        let timeout = Duration::from_secs(10);
+
+        let groups = consumer.client().fetch_group_list(Some(group), timeout)?;
+        if let Some(g) = groups.groups().iter().find(|g| g.name() == group) {
+            if !g.members().is_empty() {
+                return Err(anyhow::anyhow!("Group '{}' has {} active member(s); stop them first", group, g.members().len()));
+            }
+        }
+
+        let mut tpl = TopicPartitionList::new();
+        for o in offsets {
+            tpl.add_partition_offset(topic, o.partition, Offset::Offset(o.offset))?;
+        }
+        if !overwrite {
+            let existing = consumer.committed_offsets(tpl.clone(), timeout)?;
+            if existing.elements().iter().any(|e| matches!(e.offset(), Offset::Offset(_))) {
+                return Err(anyhow::anyhow!("Group '{}' already has committed offsets on '{}'", group, topic));
+            }
+        }
+        consumer.commit(&tpl, CommitMode::Sync)?;
+
+        // Read back what the broker stored
+        let committed = consumer.committed_offsets(tpl, timeout)?;
+        Ok(committed
+            .elements()
+            .iter()
+            .filter_map(|e| match e.offset() {
+                Offset::Offset(o) => Some(PartitionOffset { partition: e.partition(), offset: o }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Earliest offset whose timestamp is >= `timestamp_ms`, for every partition of a topic.
+    /// `precise` searches the record timestamps instead of trusting the broker's time index.
+    pub fn offsets_for_time(
+        config: &KafkaConfig,
+        topic: &str,
+        timestamp_ms: i64,
+        precise: bool,
+    ) -> anyhow::Result<Vec<TimeOffset>> {
+        let consumer = create_consumer(config)?;
+        if precise {
+            return watermarks_with(&consumer, topic)?
+                .into_iter()
+                .map(|w| {
+                    let offset = search_timestamp(&consumer, topic, w.partition, timestamp_ms, w.low, w.high)?;
+                    Ok(TimeOffset { partition: w.partition, offset })
+                })
+                .collect();
+        }
+        let timeout = Duration::from_secs(10);
+        let md = consumer.client().fetch_metadata(Some(topic), timeout)?;
+        let t = md
+            .topics()
+            .iter()
+            .find(|t| t.name() == topic)
+            .ok_or_else(|| anyhow::anyhow!("Topic not found in metadata"))?;
+        let mut tpl = TopicPartitionList::new();
+        for p in t.partitions() {
+            // offsets_for_times takes the timestamp in the offset field
+            tpl.add_partition_offset(topic, p.id(), Offset::Offset(timestamp_ms))?;
+        }
+        let resolved = consumer.offsets_for_times(tpl, timeout)?;
+        let mut out: Vec<TimeOffset> = resolved
+            .elements()
+            .iter()
+            .map(|e| TimeOffset {
+                partition: e.partition(),
+                offset: match e.offset() {
+                    Offset::Offset(o) if o >= 0 => Some(o),
+                    _ => None,
+                },
+            })
+            .collect();
+        out.sort_by_key(|o| o.partition);
+        Ok(out)
+    }
+}
+
+/// Position an offset expression starts from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetBase {
+    Earliest,
+    Latest,
+    Absolute(i64),
+    /// First record at or after this time (epoch ms)
+    Time(i64),
+}
+
+/// Offset input such as `latest-1000`, `earliest+5`, `42` or `@2024-05-01T10:00:00Z`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffsetExpression {
+    pub base: OffsetBase,
+    pub delta: i64,
+}
+
+impl OffsetExpression {
+    /// Parse `<base>[(+|-)<n>]` where base is `earliest`/`beginning`, `latest`/`end`, an offset,
+    /// or `@` followed by an RFC 3339 time or epoch milliseconds.
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr: String = expr.chars().filter(|c| !c.is_whitespace()).collect();
+        if expr.is_empty() {
+            return Err("Empty offset expression".into());
+        }
+        // Times contain '-' themselves, so a time base only takes a trailing delta after a complete time
+        let split = match expr.strip_prefix('@') {
+            Some(_) => expr.rfind(['+', '-']).filter(|&i| {
+                let (head, tail) = expr.split_at(i);
+                tail[1..].chars().all(|c| c.is_ascii_digit())
+                    && !tail[1..].is_empty()
+                    && parse_time(&head[1..]).is_ok()
+            }),
+            None => expr.find(['+', '-']).filter(|&i| i > 0),
+        };
+        let (head, delta) = match split {
+            Some(i) => {
+                let (head, tail) = expr.split_at(i);
+                let n: i64 = tail[1..].parse().map_err(|_| format!("Invalid offset delta '{tail}'"))?;
+                (head, if tail.starts_with('-') { -n } else { n })
+            }
+            None => (expr.as_str(), 0),
+        };
+        let base = match head.to_ascii_lowercase().as_str() {
+            "earliest" | "beginning" | "oldest" => OffsetBase::Earliest,
+            "latest" | "end" | "newest" => OffsetBase::Latest,
+            h if h.starts_with('@') => OffsetBase::Time(parse_time(&head[1..])?),
+            h => match h.parse::<i64>() {
+                Ok(o) if o >= 0 => OffsetBase::Absolute(o),
+                _ => return Err(format!("Invalid offset expression '{expr}'")),
+            },
+        };
+        Ok(Self { base, delta })
+    }
+}
+
+fn parse_time(s: &str) -> Result<i64, String> {
+    if let Ok(ms) = s.parse::<i64>() {
+        return Ok(ms);
+    }
+    chrono::DateTime::parse_from_rfc3339(s)
+        .map(|t| t.timestamp_millis())
+        .map_err(|_| format!("Invalid time '{s}' (expected RFC 3339 or epoch milliseconds)"))
+}
+
+/// Concrete offset of an expression on one partition, clamped to the retained range.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedOffset {
+    pub partition: i32,
+    pub offset: i64,
+    pub low: i64,
+    pub high: i64,
+    /// True when the expression pointed outside [low, high] and was clamped
+    pub clamped: bool,
+}
+
+impl Kafka {
+    /// Resolve an offset expression against a partition's watermarks (and timestamps for `@` bases).
+    pub fn resolve_offset(
+        config: &KafkaConfig,
+        topic: &str,
+        partition: i32,
+        expr: &OffsetExpression,
+        precise: bool,
+    ) -> anyhow::Result<ResolvedOffset> {
+        let consumer = create_consumer(config)?;
+        let timeout = Duration::from_secs(10);
+        let (low, high) = consumer.fetch_watermarks(topic, partition, timeout)?;
+        let base = match expr.base {
+            OffsetBase::Earliest => low,
+            OffsetBase::Latest => high,
+            OffsetBase::Absolute(o) => o,
+            OffsetBase::Time(ms) if precise => search_timestamp(&consumer, topic, partition, ms, low, high)?.unwrap_or(high),
+            OffsetBase::Time(ms) => {
+                let mut tpl = TopicPartitionList::new();
+                tpl.add_partition_offset(topic, partition, Offset::Offset(ms))?;
+                let resolved = consumer.offsets_for_times(tpl, timeout)?;
+                // No record that recent: the next one will be written at the high watermark
+                match resolved.find_partition(topic, partition).map(|e| e.offset()) {
+                    Some(Offset::Offset(o)) if o >= 0 => o,
+                    _ => high,
+                }
+            }
+        };
+        let target = base.saturating_add(expr.delta);
+        let offset = target.clamp(low, high);
+        Ok(ResolvedOffset { partition, offset, low, high, clamped: offset != target })
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use super::types::UiMessage;
+
+/// Page size before anything was measured.
+pub const DEFAULT_PAGE_SIZE: usize = 200;
+const MIN_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 5000;
+/// Time a page may take to read and decode.
+const PAGE_TIME_BUDGET: Duration = Duration::from_millis(300);
+/// Rendered (decoded key + payload) bytes a page may carry to the UI.
+const PAGE_BYTES_BUDGET: f64 = 8.0 * 1024.0 * 1024.0;
+/// Weight of the newest page in the running averages.
+const SMOOTHING: f64 = 0.3;
+
+/// Tunes the page size of a reading session to a fixed time and size budget: many tiny records per page,
+/// few huge ones. Only full pages are measured; short ones mostly waited for the broker.
+#[derive(Debug, Clone, Default)]
+pub struct PageSizer {
+    /// Seconds to read and decode one record
+    secs_per_message: Option<f64>,
+    /// Rendered bytes per record
+    bytes_per_message: Option<f64>,
+}
+
+fn smooth(avg: Option<f64>, sample: f64) -> f64 {
+    match avg {
+        Some(a) => a + SMOOTHING * (sample - a),
+        None => sample,
+    }
+}
+
+impl PageSizer {
+    /// Size of the next page.
+    pub fn next_size(&self) -> usize {
+        let by_time = self.secs_per_message.map(|s| PAGE_TIME_BUDGET.as_secs_f64() / s.max(1e-7));
+        let by_bytes = self.bytes_per_message.map(|b| PAGE_BYTES_BUDGET / b.max(1.0));
+        match (by_time, by_bytes) {
+            (None, None) => DEFAULT_PAGE_SIZE,
+            (t, b) => (t.unwrap_or(f64::MAX).min(b.unwrap_or(f64::MAX)) as usize).clamp(MIN_PAGE_SIZE, MAX_PAGE_SIZE),
+        }
+    }
+
+    /// Account for a page of `requested` records that took `elapsed`.
+    pub fn record(&mut self, requested: usize, messages: &[UiMessage], elapsed: Duration) {
+        if messages.is_empty() || messages.len() < requested {
+            return;
+        }
+        let n = messages.len() as f64;
+        let bytes: usize = messages.iter().map(|m| m.key.len() + m.message.len()).sum();
+        self.secs_per_message = Some(smooth(self.secs_per_message, elapsed.as_secs_f64() / n));
+        self.bytes_per_message = Some(smooth(self.bytes_per_message, bytes as f64 / n));
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::{HashMap, VecDeque};
+use std::ffi::CString;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rdkafka::bindings as rd;
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::error::{KafkaError, KafkaResult};
+use rdkafka::message::{BorrowedMessage, Message as RdMessage};
+use rdkafka::TopicPartitionList;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::codec::MessageCodec;
+use super::consumer::check_poll_error;
+use super::oauth::ClientCtx;
+use super::raw_cache::RawCache;
+use super::service::Kafka;
+use super::types::UiMessage;
+
+/// Decoded records a partition may queue ahead of the merge readers.
+const QUEUE_DEPTH: usize = 1000;
+
+/// What a partition task hands to the merge readers.
+pub(crate) enum Queued {
+    Row(i64, Box<UiMessage>),
+    Error(KafkaError),
+    /// The partition reached its snapshot end; nothing follows
+    End,
+}
+
+/// Partitions of a session split off the consumer's main queue, each fetched and decoded by its own task,
+/// so the merge readers combine records that are already buffered instead of polling one shared queue.
+pub(crate) struct PartitionQueues {
+    receivers: HashMap<i32, mpsc::Receiver<Queued>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Drop for PartitionQueues {
+    fn drop(&mut self) {
+        for t in &self.tasks {
+            t.abort();
+        }
+    }
+}
+
+/// Queue a polled record (unless it is past the snapshot end) and the end marker once the end is reached.
+/// Returns false when nothing more should be queued.
+async fn deliver(
+    tx: &mpsc::Sender<Queued>,
+    m: &BorrowedMessage<'_>,
+    end: i64,
+    codec: &MessageCodec,
+    raw_cache: Option<&RawCache>,
+) -> bool {
+    if m.offset() < end {
+        if let Some(cache) = raw_cache {
+            cache.insert(m);
+        }
+        let (ts_ms, ui) = codec.to_ui_message(m);
+        if tx.send(Queued::Row(ts_ms, Box::new(ui))).await.is_err() {
+            return false;
+        }
+    }
+    if m.offset() >= end - 1 {
+        let _ = tx.send(Queued::End).await;
+        return false;
+    }
+    true
+}
+
+impl PartitionQueues {
+    /// Split `parts` off the consumer and start draining each up to its snapshot end. Call right after an
+    /// assignment; records the main queue fetched before the split are forwarded to their partition.
+    fn split(kafka: &Kafka, parts: &[i32], ends: &HashMap<i32, i64>) -> anyhow::Result<Self> {
+        let topic = kafka.config.topic.as_str();
+        let mut receivers = HashMap::new();
+        let mut senders = HashMap::new();
+        let mut tasks = Vec::new();
+        for &p in parts {
+            let end = ends.get(&p).copied().unwrap_or(i64::MAX);
+            let queue = kafka
+                .consumer
+                .split_partition_queue(topic, p)
+                .ok_or_else(|| anyhow::anyhow!("Cannot split partition {} of topic {}", p, topic))?;
+            let (tx, rx) = mpsc::channel(QUEUE_DEPTH);
+            receivers.insert(p, rx);
+            senders.insert(p, (tx.clone(), end));
+            let codec = kafka.codec.clone();
+            let raw_cache = kafka.raw_cache.clone();
+            tasks.push(tokio::spawn(async move {
+                loop {
+                    let more = match queue.recv().await {
+                        Ok(m) => deliver(&tx, &m, end, &codec, raw_cache.as_deref()).await,
+                        Err(e) => tx.send(Queued::Error(e)).await.is_ok(),
+                    };
+                    if !more {
+                        break;
+                    }
+                }
+            }));
+        }
+        // The main queue must keep being served for events even though no records are expected on it
+        let consumer: Arc<StreamConsumer<ClientCtx>> = kafka.consumer.clone();
+        let codec = kafka.codec.clone();
+        let raw_cache = kafka.raw_cache.clone();
+        tasks.push(tokio::spawn(async move {
+            loop {
+                // Errors of split partitions arrive on their own queues
+                let Ok(m) = consumer.recv().await else { continue };
+                if let Some((tx, end)) = senders.get(&m.partition()) {
+                    deliver(tx, &m, *end, &codec, raw_cache.as_deref()).await;
+                }
+            }
+        }));
+        Ok(Self { receivers, tasks })
+    }
+
+    /// Move what `partition` has queued into the session buffers, waiting up to `wait` when nothing is queued yet.
+    /// Returns how many records (and end markers) arrived.
+    pub(crate) async fn pull(&mut self, kafka: &Kafka, partition: i32, wait: Duration) -> anyhow::Result<usize> {
+        let Some(rx) = self.receivers.get_mut(&partition) else { return Ok(0) };
+        let mut items = Vec::new();
+        while let Ok(item) = rx.try_recv() {
+            items.push(item);
+        }
+        if items.is_empty() {
+            if let Ok(Some(item)) = tokio::time::timeout(wait, rx.recv()).await {
+                items.push(item);
+                while let Ok(item) = rx.try_recv() {
+                    items.push(item);
+                }
+            }
+        }
+
+        let mut received = 0;
+        let mut ended = false;
+        {
+            let mut bufs = kafka
+                .buffers
+                .lock()
+                .map_err(|e| anyhow::anyhow!("State lock poisoned (buffers): {e}"))?;
+            let q = bufs.entry(partition).or_insert_with(VecDeque::new);
+            for item in items {
+                match item {
+                    Queued::Row(ts_ms, ui) => {
+                        received += 1;
+                        // Records forwarded from the main queue may interleave with the partition's own
+                        let at = if q.back().is_some_and(|(_, last)| last.offset > ui.offset) {
+                            q.partition_point(|(_, r)| r.offset < ui.offset)
+                        } else {
+                            q.len()
+                        };
+                        q.insert(at, (ts_ms, *ui));
+                    }
+                    Queued::Error(e) => check_poll_error(&e, &kafka.config.topic)?,
+                    Queued::End => {
+                        received += 1;
+                        ended = true;
+                    }
+                }
+            }
+        }
+        if ended {
+            self.receivers.remove(&partition);
+            kafka
+                .done_partitions
+                .lock()
+                .map_err(|e| anyhow::anyhow!("State lock poisoned (done_partitions): {e}"))?
+                .insert(partition);
+        }
+        Ok(received)
+    }
+}
+
+impl Kafka {
+    /// Partition queues for a merge read, split on the first read after an assignment.
+    /// Hand them back with `return_partition_queues` when the read is over.
+    pub(crate) fn take_partition_queues(&self, ends: &HashMap<i32, i64>, parts: &[i32]) -> anyhow::Result<PartitionQueues> {
+        let taken = self
+            .partition_queues
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (partition_queues): {e}"))?
+            .take();
+        // Until the queues come back the next read reassigns: a read dropped midway (aborted or panicked)
+        // takes the records its partition tasks fetched with it
+        self.assigned.store(false, std::sync::atomic::Ordering::SeqCst);
+        if let Some(queues) = taken {
+            return Ok(queues);
+        }
+        let active: Vec<i32> = {
+            let done = self
+                .done_partitions
+                .lock()
+                .map_err(|e| anyhow::anyhow!("State lock poisoned (done_partitions): {e}"))?;
+            parts.iter().copied().filter(|p| !done.contains(p)).collect()
+        };
+        let queues = PartitionQueues::split(self, &active, ends)?;
+        self.queues_split.store(true, std::sync::atomic::Ordering::SeqCst);
+        let mut split = self
+            .split_partitions
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (split_partitions): {e}"))?;
+        for p in active {
+            if !split.contains(&p) {
+                split.push(p);
+            }
+        }
+        Ok(queues)
+    }
+
+    pub(crate) fn return_partition_queues(&self, queues: PartitionQueues) -> anyhow::Result<()> {
+        *self
+            .partition_queues
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (partition_queues): {e}"))? = Some(queues);
+        self.assigned.store(true, std::sync::atomic::Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Stop the partition tasks so the main queue gets every partition again, also when the read holding them
+    /// was aborted and dropped them. The next read reassigns from the configured start.
+    pub(crate) fn release_partition_queues(&self) -> anyhow::Result<()> {
+        self.partition_queues
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (partition_queues): {e}"))?
+            .take();
+        if self.queues_split.swap(false, std::sync::atomic::Ordering::SeqCst) {
+            self.assigned.store(false, std::sync::atomic::Ordering::SeqCst);
+        }
+        Ok(())
+    }
+
+    /// Assign the consumer, routing partitions split off by earlier merge reads back to the main queue.
+    pub(crate) fn assign(&self, tpl: &TopicPartitionList) -> anyhow::Result<()> {
+        self.consumer.assign(tpl)?;
+        let split = self
+            .split_partitions
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (split_partitions): {e}"))?
+            .clone();
+        rejoin_main_queue(&self.consumer, &self.config.topic, &split)?;
+        Ok(())
+    }
+
+    /// Partitions ever split off the main queue (see `rejoin_main_queue`).
+    pub(crate) fn detached_partitions(&self) -> anyhow::Result<Vec<i32>> {
+        Ok(self
+            .split_partitions
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (split_partitions): {e}"))?
+            .clone())
+    }
+}
+
+/// Forward the fetch queues of `parts` to the consumer's main queue again. Call after every assign: stopping a
+/// partition drops its forwarding, and librdkafka never restores one the application changed by splitting.
+pub(crate) fn rejoin_main_queue(consumer: &StreamConsumer<ClientCtx>, topic: &str, parts: &[i32]) -> KafkaResult<()> {
+    if parts.is_empty() {
+        return Ok(());
+    }
+    // The assign queued the fetch stops on the main thread; this request is served after them
+    consumer.assignment()?;
+    let topic = CString::new(topic).map_err(KafkaError::Nul)?;
+    let rk = consumer.client().native_ptr();
+    // SAFETY: the queue handles come from the live client and are destroyed right after use; a forwarding
+    // keeps its own reference to the main queue.
+    unsafe {
+        let main = rd::rd_kafka_queue_get_consumer(rk);
+        if main.is_null() {
+            return Ok(());
+        }
+        for &p in parts {
+            let queue = rd::rd_kafka_queue_get_partition(rk, topic.as_ptr(), p);
+            if !queue.is_null() {
+                rd::rd_kafka_queue_forward(queue, main);
+                rd::rd_kafka_queue_destroy(queue);
+            }
+        }
+        rd::rd_kafka_queue_destroy(main);
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// How produced records are assigned to partitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PartitionStrategy {
+    /// Leave it to librdkafka's partitioner (consistent_random)
+    #[default]
+    #[serde(rename = "default")] Default,
+    /// Use the record's (or request's) explicit partition
+    #[serde(rename = "explicit")] Explicit,
+    /// murmur2(key) like the Java client, so records land where Java producers put them
+    #[serde(rename = "key_hash", alias = "murmur2")] KeyHash,
+    /// Cycle through partitions
+    #[serde(rename = "round_robin")] RoundRobin,
+}
+
+// Shared across requests so consecutive produce calls keep spreading evenly
+static ROUND_ROBIN: AtomicUsize = AtomicUsize::new(0);
+
+/// Kafka's Java `Utils.murmur2` (seed 0x9747b28c).
+pub fn murmur2(data: &[u8]) -> i32 {
+    const SEED: u32 = 0x9747_b28c;
+    const M: u32 = 0x5bd1_e995;
+    const R: u32 = 24;
+
+    let length = data.len();
+    let mut h: u32 = SEED ^ (length as u32);
+    let chunks = data.chunks_exact(4);
+    let tail = chunks.remainder();
+    for c in chunks {
+        let mut k = u32::from_le_bytes([c[0], c[1], c[2], c[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M);
+        h ^= k;
+    }
+    match tail.len() {
+        3 => {
+            h ^= (tail[2] as u32) << 16;
+            h ^= (tail[1] as u32) << 8;
+            h ^= tail[0] as u32;
+            h = h.wrapping_mul(M);
+        }
+        2 => {
+            h ^= (tail[1] as u32) << 8;
+            h ^= tail[0] as u32;
+            h = h.wrapping_mul(M);
+        }
+        1 => {
+            h ^= tail[0] as u32;
+            h = h.wrapping_mul(M);
+        }
+        _ => {}
+    }
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^= h >> 15;
+    h as i32
+}
+
+/// Partition chosen by the Java DefaultPartitioner for a non-null key.
+pub fn java_partition_for_key(key: &[u8], partition_count: i32) -> i32 {
+    // Utils.toPositive: clear the sign bit rather than abs()
+    (murmur2(key) & 0x7fff_ffff) % partition_count.max(1)
+}
+
+/// Resolve the target partition for a record. None = let librdkafka decide.
+pub fn choose_partition(
+    strategy: PartitionStrategy,
+    explicit: Option<i32>,
+    key: Option<&[u8]>,
+    partition_count: i32,
+) -> anyhow::Result<Option<i32>> {
+    let next_round_robin = || (ROUND_ROBIN.fetch_add(1, Ordering::Relaxed) % partition_count.max(1) as usize) as i32;
+    match strategy {
+        PartitionStrategy::Default => Ok(explicit),
+        PartitionStrategy::Explicit => {
+            let p = explicit.ok_or_else(|| anyhow::anyhow!("Explicit partitioning requires a partition"))?;
+            if p < 0 || p >= partition_count {
+                return Err(anyhow::anyhow!("Partition {} is out of range (topic has {})", p, partition_count));
+            }
+            Ok(Some(p))
+        }
+        PartitionStrategy::KeyHash => Ok(Some(match key {
+            Some(k) => java_partition_for_key(k, partition_count),
+            // Null keys have no hash; spread them like the Java client does
+            None => next_round_robin(),
+        })),
+        PartitionStrategy::RoundRobin => Ok(Some(next_round_robin())),
+    }
+}
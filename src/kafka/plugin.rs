@@ -0,0 +1,147 @@
+use std::sync::Mutex;
+
+use anyhow::{anyhow, bail, Context};
+use wasmi::{Config, Engine, Instance, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc};
+
+use super::decoder::MessageDecoder;
+
+/// Instructions a plugin may execute per record before it is stopped.
+const FUEL_PER_DECODE: u64 = 50_000_000;
+/// Upper bound for a plugin's linear memory.
+const MAX_PLUGIN_MEMORY: usize = 256 * 1024 * 1024;
+
+/// Decoder backed by a WebAssembly module, for proprietary binary formats.
+///
+/// A plugin exports:
+/// - `memory`
+/// - `alloc(len: i32) -> i32`: a buffer the host copies the payload into
+/// - `decode(ptr: i32, len: i32) -> i64`: `(out_ptr << 32) | out_len` of the UTF-8 JSON result;
+///   a negative value `-((err_ptr << 32) | err_len)` points to an error message instead
+/// - optionally `dealloc(ptr: i32, len: i32)`, called for the input and output buffers
+///
+/// No host functions are imported; plugins are sandboxed and metered.
+pub struct PluginDecoder {
+    engine: Engine,
+    module: Module,
+    // Reused between records; replaced after a trap since the plugin state may be corrupt
+    instance: Mutex<Option<PluginInstance>>,
+}
+
+struct PluginInstance {
+    store: Store<StoreLimits>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    decode: TypedFunc<(i32, i32), i64>,
+    dealloc: Option<TypedFunc<(i32, i32), ()>>,
+}
+
+impl PluginDecoder {
+    /// Load and validate a `.wasm` module from disk.
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path).with_context(|| format!("Failed to read decoder plugin {path}"))?;
+        Self::from_bytes(&bytes).with_context(|| format!("Invalid decoder plugin {path}"))
+    }
+
+    pub fn from_bytes(wasm: &[u8]) -> anyhow::Result<Self> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, wasm).map_err(|e| anyhow!("{e}"))?;
+        let plugin = Self { engine, module, instance: Mutex::new(None) };
+        // Instantiate eagerly so a broken plugin fails at configuration time
+        let instance = plugin.instantiate()?;
+        *plugin.instance.lock().map_err(|e| anyhow!("Plugin state poisoned: {e}"))? = Some(instance);
+        Ok(plugin)
+    }
+
+    fn instantiate(&self) -> anyhow::Result<PluginInstance> {
+        let limits = StoreLimitsBuilder::new().memory_size(MAX_PLUGIN_MEMORY).instances(1).build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(FUEL_PER_DECODE).map_err(|e| anyhow!("{e}"))?;
+        let linker = Linker::<StoreLimits>::new(&self.engine);
+        let instance: Instance = linker
+            .instantiate(&mut store, &self.module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|e| anyhow!("Failed to instantiate plugin: {e}"))?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| anyhow!("Plugin does not export 'memory'"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|e| anyhow!("Plugin export 'alloc(i32) -> i32' missing: {e}"))?;
+        let decode = instance
+            .get_typed_func::<(i32, i32), i64>(&store, "decode")
+            .map_err(|e| anyhow!("Plugin export 'decode(i32, i32) -> i64' missing: {e}"))?;
+        let dealloc = instance.get_typed_func::<(i32, i32), ()>(&store, "dealloc").ok();
+        Ok(PluginInstance { store, memory, alloc, decode, dealloc })
+    }
+
+    /// Run the plugin on a payload; the result is checked to be JSON.
+    pub fn decode_detailed(&self, payload: &[u8]) -> anyhow::Result<String> {
+        let mut guard = self.instance.lock().map_err(|e| anyhow!("Plugin state poisoned: {e}"))?;
+        if guard.is_none() {
+            *guard = Some(self.instantiate()?);
+        }
+        let instance = guard.as_mut().expect("instance initialized above");
+        let result = instance.call(payload);
+        if result.is_err() {
+            // Traps can leave allocator state half-updated; start fresh for the next record
+            *guard = None;
+        }
+        let text = result?;
+        serde_json::from_str::<serde_json::Value>(&text).map_err(|e| anyhow!("Plugin returned invalid JSON: {e}"))?;
+        Ok(text)
+    }
+}
+
+impl PluginInstance {
+    fn call(&mut self, payload: &[u8]) -> anyhow::Result<String> {
+        self.store.set_fuel(FUEL_PER_DECODE).map_err(|e| anyhow!("{e}"))?;
+        let len = i32::try_from(payload.len()).map_err(|_| anyhow!("Payload too large for plugin"))?;
+        let ptr = self.alloc.call(&mut self.store, len).map_err(|e| anyhow!("Plugin alloc failed: {e}"))?;
+        self.memory
+            .write(&mut self.store, ptr as u32 as usize, payload)
+            .map_err(|e| anyhow!("Plugin alloc returned an invalid buffer: {e}"))?;
+        let packed = self.decode.call(&mut self.store, (ptr, len)).map_err(|e| anyhow!("Plugin trapped: {e}"))?;
+        self.free(ptr, len);
+
+        let (failed, packed) = if packed < 0 { (true, packed.wrapping_neg()) } else { (false, packed) };
+        let (out_ptr, out_len) = ((packed >> 32) as u32, packed as u32);
+        // Checked before allocating: a bogus length would otherwise reserve up to 4 GiB on the host
+        let size = self.memory.data(&self.store).len();
+        let in_bounds = (out_ptr as usize).checked_add(out_len as usize).is_some_and(|end| end <= size);
+        if !in_bounds {
+            bail!("Plugin returned a buffer outside its memory ({out_len} bytes at {out_ptr})");
+        }
+        let mut out = vec![0u8; out_len as usize];
+        self.memory
+            .read(&self.store, out_ptr as usize, &mut out)
+            .map_err(|e| anyhow!("Plugin returned an invalid buffer: {e}"))?;
+        self.free(out_ptr as i32, out_len as i32);
+        let text = String::from_utf8(out).map_err(|e| anyhow!("Plugin returned non UTF-8 output: {e}"))?;
+        if failed {
+            bail!("{text}");
+        }
+        Ok(text)
+    }
+
+    fn free(&mut self, ptr: i32, len: i32) {
+        if let Some(dealloc) = &self.dealloc {
+            let _ = dealloc.call(&mut self.store, (ptr, len));
+        }
+    }
+}
+
+impl MessageDecoder for PluginDecoder {
+    fn decode(&self, key: Option<&[u8]>, payload: Option<&[u8]>) -> (String, String) {
+        let k = key.map(|k| String::from_utf8_lossy(k).to_string()).unwrap_or_default();
+        let v = payload
+            .map(|p| match self.decode_detailed(p) {
+                Ok(json) => json,
+                Err(_) => String::from_utf8_lossy(p).to_string(),
+            })
+            .unwrap_or_default();
+        (k, v)
+    }
+}
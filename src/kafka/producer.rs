@@ -0,0 +1,147 @@
+use std::time::{Duration, Instant};
+
+use rdkafka::config::ClientConfig;
+use rdkafka::error::KafkaError;
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
+
+use super::oauth::{create_off_runtime, ClientCtx};
+use super::partitioner::choose_partition;
+use super::types::{DeliveryReport, KafkaConfig, ProduceError, ProduceRequest};
+use crate::utils::kafka::{apply_custom_properties, configure_security};
+
+/// Build an rdkafka FutureProducer configured according to KafkaConfig.
+pub(crate) fn create_producer(config: &KafkaConfig) -> anyhow::Result<FutureProducer<ClientCtx>> {
+    let context = ClientCtx::new(config)?;
+    let mut cc = ClientConfig::new();
+    cc.set("bootstrap.servers", context.bootstrap_servers(config));
+    cc.set("socket.timeout.ms", "10000");
+    cc.set("message.timeout.ms", "30000");
+    cc.set("allow.auto.create.topics", "false");
+    if let Some(d) = config.producer_defaults.as_ref() {
+        if let Some(c) = d.compression_type.as_deref() {
+            cc.set("compression.type", c);
+        }
+        if let Some(a) = d.acks.as_deref() {
+            cc.set("acks", a);
+        }
+        if let Some(l) = d.linger_ms {
+            cc.set("linger.ms", l.to_string());
+        }
+    }
+
+    configure_security(&mut cc, config)?;
+    apply_custom_properties(&mut cc, config);
+
+    let producer: FutureProducer<ClientCtx> = cc.create_with_context(context.clone())?;
+    context.prime(producer.client())?;
+    Ok(producer)
+}
+
+fn produce_error(e: &KafkaError) -> ProduceError {
+    ProduceError {
+        code: e
+            .rdkafka_error_code()
+            .map(|c| format!("{:?}", c))
+            .unwrap_or_else(|| "Unknown".to_string()),
+        message: e.to_string(),
+    }
+}
+
+fn failed(index: usize, partition: Option<i32>, error: ProduceError) -> DeliveryReport {
+    DeliveryReport { index, partition, offset: None, timestamp: None, latency_ms: 0, error: Some(error) }
+}
+
+impl super::service::Kafka {
+    /// Produce records to a topic, resolving partitions per the request's strategy.
+    /// All records are enqueued up front and reported individually in request order.
+    pub async fn produce(config: &KafkaConfig, req: &ProduceRequest) -> anyhow::Result<Vec<DeliveryReport>> {
+        let producer = create_off_runtime(config, create_producer).await?;
+        let md = producer
+            .client()
+            .fetch_metadata(Some(&req.topic), Duration::from_secs(5))?;
+        let partition_count = md
+            .topics()
+            .iter()
+            .find(|t| t.name() == req.topic)
+            .map(|t| t.partitions().len() as i32)
+            .filter(|n| *n > 0)
+            .ok_or_else(|| anyhow::anyhow!("Topic not found in metadata"))?;
+
+        let default_headers = config
+            .producer_defaults
+            .as_ref()
+            .map(|d| d.default_headers.as_slice())
+            .unwrap_or_default();
+
+        // Each delivery is awaited in its own task so latency reflects the actual ack time
+        let mut pending = Vec::with_capacity(req.records.len());
+        for (i, rec) in req.records.iter().enumerate() {
+            let key = rec.key.as_deref().map(str::as_bytes);
+            let partition = match choose_partition(req.partitioning, rec.partition.or(req.partition), key, partition_count) {
+                Ok(p) => p,
+                Err(e) => {
+                    pending.push(Err(failed(i, None, ProduceError { code: "InvalidPartition".into(), message: e.to_string() })));
+                    continue;
+                }
+            };
+
+            let mut headers = OwnedHeaders::new();
+            for (k, v) in &rec.headers {
+                headers = headers.insert(Header { key: k, value: Some(v.as_bytes()) });
+            }
+            for (k, v) in default_headers {
+                if !rec.headers.iter().any(|(rk, _)| rk == k) {
+                    headers = headers.insert(Header { key: k, value: Some(v.as_bytes()) });
+                }
+            }
+            let timestamp = chrono::Utc::now().timestamp_millis();
+            let mut record: FutureRecord<'_, [u8], [u8]> = FutureRecord::to(&req.topic).headers(headers).timestamp(timestamp);
+            if let Some(k) = key {
+                record = record.key(k);
+            }
+            if let Some(v) = rec.value.as_deref() {
+                record = record.payload(v.as_bytes());
+            }
+            if let Some(p) = partition {
+                record = record.partition(p);
+            }
+            let started = Instant::now();
+            match producer.send_result(record) {
+                Ok(fut) => pending.push(Ok((timestamp, tokio::spawn(async move {
+                    let res = fut.await;
+                    (res, started.elapsed())
+                })))),
+                // Rejected locally before reaching the broker (queue full, message too large, ...)
+                Err((e, _)) => pending.push(Err(failed(i, partition, produce_error(&e)))),
+            }
+        }
+
+        let mut out = Vec::with_capacity(pending.len());
+        for (i, p) in pending.into_iter().enumerate() {
+            let (timestamp, handle) = match p {
+                Ok(v) => v,
+                Err(report) => {
+                    out.push(report);
+                    continue;
+                }
+            };
+            let (res, elapsed) = handle.await.map_err(|e| anyhow::anyhow!("Delivery task failed: {}", e))?;
+            let latency_ms = elapsed.as_millis() as u64;
+            let report = match res {
+                Ok(Ok((partition, offset))) => DeliveryReport {
+                    index: i,
+                    partition: Some(partition),
+                    offset: Some(offset),
+                    timestamp: Some(timestamp),
+                    latency_ms,
+                    error: None,
+                },
+                Ok(Err((e, _))) => DeliveryReport { latency_ms, ..failed(i, None, produce_error(&e)) },
+                Err(_) => failed(i, None, ProduceError { code: "Canceled".into(), message: "Producer dropped before delivery".into() }),
+            };
+            out.push(report);
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rdkafka::consumer::Consumer;
+use rdkafka::message::Message as RdMessage;
+use rdkafka::topic_partition_list::TopicPartitionList;
+use rdkafka::Offset;
+use serde::Serialize;
+
+use super::consumer::create_consumer;
+use super::decoder::{KeyType, MessageType};
+use super::types::KafkaConfig;
+use crate::proto_decoder::{looks_like_protobuf, parse_confluent_header, PayloadEnvelope};
+
+/// Decode settings suggested by profiling; the UI can merge them into KafkaConfig as-is.
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodeRecommendation {
+    pub message_type: MessageType,
+    pub payload_envelope: Option<PayloadEnvelope>,
+    /// Suggested key decoding when keys are not plain text
+    pub key_type: Option<KeyType>,
+    pub reason: String,
+}
+
+/// Summary of a sample of records from a topic.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicProfile {
+    pub topic: String,
+    pub sampled: usize,
+    /// Detected value format -> count: json | text | protobuf | confluent_protobuf | confluent_avro | binary | tombstone
+    pub formats: HashMap<String, usize>,
+    /// Detected key type -> count: null | json | uuid | int32 | int64 | text | binary
+    pub key_types: HashMap<String, usize>,
+    pub avg_value_size: f64,
+    pub max_value_size: usize,
+    pub avg_key_size: f64,
+    pub recommendation: DecodeRecommendation,
+}
+
+fn classify_value(payload: Option<&[u8]>) -> &'static str {
+    let Some(bytes) = payload.filter(|b| !b.is_empty()) else { return "tombstone"; };
+    if let Some(h) = parse_confluent_header(bytes) {
+        // Protobuf envelopes carry message indexes before a well-formed message; Avro has no indexes
+        return if looks_like_protobuf(&bytes[h.header_len..]) { "confluent_protobuf" } else { "confluent_avro" };
+    }
+    if bytes.len() > 5 && bytes[0] == 0 {
+        return "confluent_avro";
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        if serde_json::from_str::<serde_json::Value>(text).is_ok() {
+            return "json";
+        }
+        let printable = text.chars().filter(|c| !c.is_control() || c.is_whitespace()).count();
+        if printable * 100 >= text.chars().count() * 95 {
+            return "text";
+        }
+    }
+    if looks_like_protobuf(bytes) {
+        return "protobuf";
+    }
+    "binary"
+}
+
+fn classify_key(key: Option<&[u8]>) -> &'static str {
+    let Some(bytes) = key.filter(|b| !b.is_empty()) else { return "null"; };
+    match bytes.len() {
+        4 if std::str::from_utf8(bytes).is_err() => return "int32",
+        8 if std::str::from_utf8(bytes).is_err() => return "int64",
+        16 if std::str::from_utf8(bytes).is_err() => return "uuid",
+        _ => {}
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => {
+            let is_uuid = text.len() == 36
+                && text.chars().enumerate().all(|(i, c)| {
+                    if matches!(i, 8 | 13 | 18 | 23) { c == '-' } else { c.is_ascii_hexdigit() }
+                });
+            if is_uuid {
+                "uuid"
+            } else if (text.starts_with('{') || text.starts_with('['))
+                && serde_json::from_str::<serde_json::Value>(text).is_ok()
+            {
+                "json"
+            } else {
+                "text"
+            }
+        }
+        Err(_) => "binary",
+    }
+}
+
+fn recommend(formats: &HashMap<String, usize>, key_types: &HashMap<String, usize>) -> DecodeRecommendation {
+    let top = formats
+        .iter()
+        .filter(|(f, _)| f.as_str() != "tombstone")
+        .max_by_key(|(_, n)| **n)
+        .map(|(f, _)| f.as_str());
+    let (message_type, payload_envelope, reason) = match top {
+        Some("json") => (MessageType::Json, None, "Values are JSON documents"),
+        Some("protobuf") => (MessageType::Protobuf, Some(PayloadEnvelope::Raw), "Values look like bare protobuf messages; select a message type"),
+        Some("confluent_protobuf") => (
+            MessageType::Protobuf,
+            Some(PayloadEnvelope::Confluent),
+            "Values use the Confluent protobuf envelope; select a message type",
+        ),
+        Some("confluent_avro") => (
+            MessageType::Avro,
+            None,
+            "Values use the Confluent envelope without protobuf indexes (likely Avro); set a schema registry URL",
+        ),
+        Some("binary") => (MessageType::Text, None, "Values are binary in an unknown format"),
+        Some(_) => (MessageType::Text, None, "Values are plain text"),
+        None => (MessageType::Text, None, "No non-empty values sampled"),
+    };
+    let key_type = match key_types.iter().filter(|(k, _)| k.as_str() != "null").max_by_key(|(_, n)| **n) {
+        Some((k, _)) if k == "int32" => Some(KeyType::Int32),
+        Some((k, _)) if k == "int64" => Some(KeyType::Int64),
+        Some((k, _)) if k == "uuid" => Some(KeyType::Uuid),
+        Some((k, _)) if k == "json" => Some(KeyType::Json),
+        _ => None,
+    };
+    DecodeRecommendation { message_type, payload_envelope, key_type, reason: reason.to_string() }
+}
+
+impl super::service::Kafka {
+    /// Sample up to `sample` recent records of `config.topic` (spread across partitions) and profile them.
+    pub fn profile_topic(config: &KafkaConfig, sample: usize) -> anyhow::Result<TopicProfile> {
+        let consumer = create_consumer(config)?;
+        let topic = config.topic.as_str();
+        let md = consumer
+            .client()
+            .fetch_metadata(Some(topic), Duration::from_secs(5))?;
+        let t = md
+            .topics()
+            .iter()
+            .find(|t| t.name() == topic)
+            .ok_or_else(|| anyhow::anyhow!("Topic not found in metadata"))?;
+        let partitions: Vec<i32> = t.partitions().iter().map(|p| p.id()).collect();
+
+        // Read the most recent records of every partition: recent data reflects current producers
+        let per_partition = (sample / partitions.len().max(1)).max(1) as i64;
+        let mut tpl = TopicPartitionList::new();
+        let mut ends: HashMap<i32, i64> = HashMap::new();
+        for p in &partitions {
+            let (low, high) = consumer.fetch_watermarks(topic, *p, Duration::from_secs(5))?;
+            if high > low {
+                tpl.add_partition_offset(topic, *p, Offset::Offset((high - per_partition).max(low)))?;
+                ends.insert(*p, high);
+            }
+        }
+        consumer.assign(&tpl)?;
+
+        let mut formats: HashMap<String, usize> = HashMap::new();
+        let mut key_types: HashMap<String, usize> = HashMap::new();
+        let (mut sampled, mut value_total, mut key_total, mut max_value_size) = (0usize, 0usize, 0usize, 0usize);
+        let deadline = Instant::now() + Duration::from_secs(15);
+        let mut idle_loops = 0;
+        while sampled < sample && !ends.is_empty() && idle_loops < 20 && Instant::now() < deadline {
+            match consumer.poll(Duration::from_millis(200)) {
+                Some(Ok(m)) => {
+                    let end = ends.get(&m.partition()).copied().unwrap_or(i64::MAX);
+                    if m.offset() >= end - 1 {
+                        ends.remove(&m.partition());
+                    }
+                    if m.offset() >= end {
+                        continue;
+                    }
+                    *formats.entry(classify_value(m.payload()).to_string()).or_default() += 1;
+                    *key_types.entry(classify_key(m.key()).to_string()).or_default() += 1;
+                    value_total += m.payload_len();
+                    key_total += m.key_len();
+                    max_value_size = max_value_size.max(m.payload_len());
+                    sampled += 1;
+                }
+                Some(Err(_)) | None => idle_loops += 1,
+            }
+        }
+
+        let avg = |total: usize| if sampled == 0 { 0.0 } else { total as f64 / sampled as f64 };
+        let recommendation = recommend(&formats, &key_types);
+        Ok(TopicProfile {
+            topic: topic.to_string(),
+            sampled,
+            formats,
+            key_types,
+            avg_value_size: avg(value_total),
+            max_value_size,
+            avg_key_size: avg(key_total),
+            recommendation,
+        })
+    }
+}
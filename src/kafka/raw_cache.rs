@@ -0,0 +1,80 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use rdkafka::message::{BorrowedMessage, Headers, Message as RdMessage, OwnedMessage};
+
+/// Original records of recently loaded rows, bounded by total size; the oldest entries are evicted first.
+/// Lets redecode, hex views, raw export and re-produce work without fetching from the broker again.
+pub struct RawCache {
+    max_bytes: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    records: HashMap<(i32, i64), (OwnedMessage, usize)>,
+    order: VecDeque<(i32, i64)>,
+    bytes: usize,
+}
+
+fn record_size<M: RdMessage>(m: &M) -> usize {
+    let headers = m.headers().map_or(0, |hs| {
+        hs.iter().map(|h| h.key.len() + h.value.map_or(0, <[u8]>::len)).sum()
+    });
+    m.key().map_or(0, <[u8]>::len) + m.payload().map_or(0, <[u8]>::len) + headers
+}
+
+impl RawCache {
+    pub fn new(max_bytes: usize) -> Self {
+        Self { max_bytes, inner: Mutex::new(Inner::default()) }
+    }
+
+    /// Cache of `mb` megabytes; None when disabled (0 or unset).
+    pub fn from_megabytes(mb: Option<usize>) -> Option<Self> {
+        mb.filter(|mb| *mb > 0).map(|mb| Self::new(mb * 1024 * 1024))
+    }
+
+    pub fn insert(&self, m: &BorrowedMessage<'_>) {
+        if record_size(m) <= self.max_bytes {
+            self.insert_owned(m.detach());
+        }
+    }
+
+    /// Cache a detached record; records larger than the whole cache are skipped.
+    pub fn insert_owned(&self, m: OwnedMessage) {
+        let size = record_size(&m);
+        if size > self.max_bytes {
+            return;
+        }
+        let Ok(mut inner) = self.inner.lock() else { return };
+        let id = (m.partition(), m.offset());
+        if let Some((_, old)) = inner.records.insert(id, (m, size)) {
+            inner.bytes -= old;
+        } else {
+            inner.order.push_back(id);
+        }
+        inner.bytes += size;
+        while inner.bytes > self.max_bytes {
+            let Some(oldest) = inner.order.pop_front() else { break };
+            if let Some((_, freed)) = inner.records.remove(&oldest) {
+                inner.bytes -= freed;
+            }
+        }
+    }
+
+    pub fn get(&self, partition: i32, offset: i64) -> Option<OwnedMessage> {
+        let inner = self.inner.lock().ok()?;
+        inner.records.get(&(partition, offset)).map(|(m, _)| m.clone())
+    }
+
+    /// Number of cached records and their total size in bytes.
+    pub fn usage(&self) -> (usize, usize) {
+        self.inner.lock().map(|i| (i.records.len(), i.bytes)).unwrap_or((0, 0))
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut inner) = self.inner.lock() {
+            *inner = Inner::default();
+        }
+    }
+}
@@ -1,65 +1,30 @@
-use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::collections::BinaryHeap;
 use std::time::Duration;
 
-use rdkafka::message::Message as RdMessage;
-
+use crate::kafka::partition_queues::PartitionQueues;
 use crate::kafka::{Kafka, UiMessage};
 
+/// How long a partition may stay silent before its tail is taken as complete.
+const IDLE_WAIT: Duration = Duration::from_secs(20);
+
 /// Newest-first merge across multiple partitions using buffered tails and a max-heap.
-pub fn consume_merge_newest(
+pub(crate) async fn consume_merge_newest(
     kafka: &Kafka,
-    ends: &HashMap<i32, i64>,
-    parts: &Vec<i32>,
+    queues: &mut PartitionQueues,
+    parts: &[i32],
     limit: usize,
 ) -> anyhow::Result<Vec<UiMessage>> {
-    // Prefill buffers with all available messages up to the snapshot end for each partition
-    let mut idle_loops = 0;
-    loop {
-        let done_all = {
+    // Collect each partition's tail up to the snapshot end; the others keep draining in the meantime
+    for &p in parts {
+        loop {
             let done = kafka
                 .done_partitions
                 .lock()
-                .map_err(|e| anyhow::anyhow!("State lock poisoned (done_partitions): {e}"))?;
-            parts.iter().all(|p| done.contains(p))
-        };
-        if done_all { break; }
-        if idle_loops >= 40 { break; }
-        match kafka.consumer.as_ref().poll(Duration::from_millis(20000)) {
-            Some(Ok(m)) => {
-                let partition = m.partition();
-                let offset = m.offset();
-                let end = ends.get(&partition).cloned().unwrap_or(i64::MAX);
-                if offset >= end {
-                    let mut done = kafka
-                        .done_partitions
-                        .lock()
-                        .map_err(|e| anyhow::anyhow!("State lock poisoned (done_partitions): {e}"))?;
-                    done.insert(partition);
-                } else {
-                    let (key, payload, decoding_error) = kafka.decode(m.key(), m.payload());
-                    let (ts_ms, ts_str) = match m.timestamp() {
-                        rdkafka::message::Timestamp::NotAvailable => (i64::MAX, String::from("")),
-                        rdkafka::message::Timestamp::CreateTime(ms)
-                        | rdkafka::message::Timestamp::LogAppendTime(ms) => {
-                            if let Some(dt) = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(ms) { (ms, dt.to_rfc3339()) } else { (ms, String::new()) }
-                        }
-                    };
-                    let ui = UiMessage { id: format!("{}-{}", partition, offset), partition, key, offset, message: payload, timestamp: ts_str, decoding_error };
-                    let mut bufs = kafka
-                        .buffers
-                        .lock()
-                        .map_err(|e| anyhow::anyhow!("State lock poisoned (buffers): {e}"))?;
-                    bufs.entry(partition).or_insert_with(VecDeque::new).push_back((ts_ms, ui));
-                    if offset >= end - 1 {
-                        let mut done = kafka
-                            .done_partitions
-                            .lock()
-                            .map_err(|e| anyhow::anyhow!("State lock poisoned (done_partitions): {e}"))?;
-                        done.insert(partition);
-                    }
-                }
+                .map_err(|e| anyhow::anyhow!("State lock poisoned (done_partitions): {e}"))?
+                .contains(&p);
+            if done || queues.pull(kafka, p, IDLE_WAIT).await? == 0 {
+                break;
             }
-            Some(Err(_)) | None => { idle_loops += 1; }
         }
     }
 
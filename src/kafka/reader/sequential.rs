@@ -3,21 +3,17 @@ use std::time::Duration;
 
 use rdkafka::message::Message as RdMessage;
 
+use crate::kafka::consumer::{check_poll_error, recv_timeout};
 use crate::kafka::{Kafka, UiMessage};
 
 /// Strategy: simple sequential consumption for a single partition.
-pub fn consume_sequential(
+pub async fn consume_sequential(
     kafka: &Kafka,
     ends: &HashMap<i32, i64>,
     parts: &Vec<i32>,
     limit: usize,
 ) -> anyhow::Result<Vec<UiMessage>> {
-    let newest = kafka
-        .config
-        .start_from
-        .as_deref()
-        .map(|s| s.eq_ignore_ascii_case("newest"))
-        .unwrap_or(false);
+    let newest = kafka.filters()?.newest();
 
     // Newest-first requires global ordering across the whole snapshot window.
     if newest {
@@ -33,7 +29,7 @@ pub fn consume_sequential(
             };
             if active_parts.is_empty() { break; }
             if idle_loops >= 20 { break; }
-            match kafka.consumer.as_ref().poll(Duration::from_millis(200)) {
+            match recv_timeout(&kafka.consumer, Duration::from_millis(200)).await {
                 Some(Ok(m)) => {
                     let partition = m.partition();
                     let offset = m.offset();
@@ -46,19 +42,7 @@ pub fn consume_sequential(
                         done.insert(partition);
                         continue;
                     }
-                    let (key, payload, decoding_error) = kafka.decode(m.key(), m.payload());
-                    let (ts_ms, ts_str) = match m.timestamp() {
-                        rdkafka::message::Timestamp::NotAvailable => (i64::MAX, String::from("")),
-                        rdkafka::message::Timestamp::CreateTime(ms)
-                        | rdkafka::message::Timestamp::LogAppendTime(ms) => {
-                            if let Some(dt) = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(ms) {
-                                (ms, dt.to_rfc3339())
-                            } else {
-                                (ms, String::new())
-                            }
-                        }
-                    };
-                    let ui = UiMessage { id: format!("{}-{}", partition, offset), partition, key, offset, message: payload, timestamp: ts_str, decoding_error };
+                    let (ts_ms, ui) = kafka.to_row(&m);
                     let mut bufs = kafka
                         .buffers
                         .lock()
@@ -72,7 +56,11 @@ pub fn consume_sequential(
                         done.insert(partition);
                     }
                 }
-                Some(Err(_)) | None => idle_loops += 1,
+                Some(Err(e)) => {
+                    check_poll_error(&e, &kafka.config.topic)?;
+                    idle_loops += 1;
+                }
+                None => idle_loops += 1,
             }
         }
 
@@ -116,7 +104,7 @@ pub fn consume_sequential(
     let mut collected: Vec<(i64, UiMessage)> = Vec::with_capacity(limit);
     let mut idle_loops = 0;
     while collected.len() < limit && idle_loops < 20 {
-        match kafka.consumer.as_ref().poll(Duration::from_millis(200)) {
+        match recv_timeout(&kafka.consumer, Duration::from_millis(200)).await {
             Some(Ok(m)) => {
                 let partition = m.partition();
                 let offset = m.offset();
@@ -130,15 +118,7 @@ pub fn consume_sequential(
                     if parts.iter().all(|p| done.contains(p)) { break; }
                     continue;
                 }
-                let (key, payload, decoding_error) = kafka.decode(m.key(), m.payload());
-                let (ts_ms, ts_str) = match m.timestamp() {
-                    rdkafka::message::Timestamp::NotAvailable => (i64::MAX, String::from("")),
-                    rdkafka::message::Timestamp::CreateTime(ms)
-                    | rdkafka::message::Timestamp::LogAppendTime(ms) => {
-                        if let Some(dt) = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(ms) { (ms, dt.to_rfc3339()) } else { (ms, String::new()) }
-                    }
-                };
-                let ui = UiMessage { id: format!("{}-{}", partition, offset), partition, key, offset, message: payload, timestamp: ts_str, decoding_error };
+                let (ts_ms, ui) = kafka.to_row(&m);
                 collected.push((ts_ms, ui));
                 if offset >= end - 1 {
                     let mut done = kafka
@@ -149,7 +129,11 @@ pub fn consume_sequential(
                     if parts.iter().all(|p| done.contains(p)) { break; }
                 }
             }
-            Some(Err(_)) | None => idle_loops += 1,
+            Some(Err(e)) => {
+                check_poll_error(&e, &kafka.config.topic)?;
+                idle_loops += 1;
+            }
+            None => idle_loops += 1,
         }
     }
     collected.sort_by(|a, b| a.0.cmp(&b.0));
@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rdkafka::consumer::Consumer;
+use rdkafka::message::{Header, Headers, Message as RdMessage, OwnedHeaders};
+use rdkafka::producer::{DeliveryFuture, FutureRecord};
+use rdkafka::topic_partition_list::TopicPartitionList;
+use rdkafka::Offset;
+use serde::{Deserialize, Serialize};
+
+use super::consumer::create_consumer;
+use super::oauth::create_off_runtime;
+use super::offsets::PartitionWatermarks;
+use super::producer::create_producer;
+use super::service::Kafka;
+use super::types::KafkaConfig;
+
+// Deliveries awaited in batches so a long replay doesn't hold every future in memory
+const MAX_IN_FLIGHT: usize = 1000;
+
+/// How replayed records are timestamped.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[serde(tag = "mode")]
+pub enum TimestampMode {
+    /// Keep the source timestamp
+    #[default]
+    #[serde(rename = "original")] Original,
+    /// Re-stamp every record with the replay time
+    #[serde(rename = "now")] Now,
+    /// Add a fixed delta (ms, may be negative) to the source timestamp
+    #[serde(rename = "shift")] Shift {
+        #[serde(rename = "shift_ms", alias = "shiftMs")]
+        shift_ms: i64,
+    },
+}
+
+/// Pacing for replays so reprocessing doesn't flood downstream consumers.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "mode")]
+pub enum ReplayThrottle {
+    /// At most this many records per second
+    #[serde(rename = "rate")] Rate {
+        #[serde(rename = "messages_per_second", alias = "messagesPerSecond")]
+        messages_per_second: f64,
+    },
+    /// Reproduce the gaps between source timestamps, sped up by `speed` (1.0 = real time)
+    #[serde(rename = "original_spacing", alias = "originalSpacing")] OriginalSpacing {
+        #[serde(default = "default_speed")]
+        speed: f64,
+        /// Upper bound for a single pause so quiet periods in the source don't stall the replay
+        #[serde(rename = "max_gap_ms", alias = "maxGapMs")]
+        max_gap_ms: Option<u64>,
+    },
+}
+
+fn default_speed() -> f64 {
+    1.0
+}
+
+/// Slowest pacing accepted; anything slower would overflow the send deadlines.
+const MIN_RATE: f64 = 0.001;
+
+impl ReplayThrottle {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        match *self {
+            ReplayThrottle::Rate { messages_per_second } if !(messages_per_second.is_finite() && messages_per_second >= MIN_RATE) => {
+                Err(anyhow::anyhow!("Rate must be at least {MIN_RATE} messages per second"))
+            }
+            ReplayThrottle::OriginalSpacing { speed, .. } if !(speed.is_finite() && speed > 0.0) => {
+                Err(anyhow::anyhow!("Speed must be greater than zero"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Tracks when the next record may be sent.
+struct Pacer {
+    throttle: ReplayThrottle,
+    started: Instant,
+    sent: u64,
+    // (previous source timestamp, wall-clock instant it was sent at)
+    last: Option<(i64, Instant)>,
+}
+
+impl Pacer {
+    fn new(throttle: ReplayThrottle) -> Self {
+        Self { throttle, started: Instant::now(), sent: 0, last: None }
+    }
+
+    /// Delay before sending a record with the given source timestamp.
+    fn delay(&mut self, source_ts: Option<i64>) -> Duration {
+        let now = Instant::now();
+        let due = match self.throttle {
+            // Checked by `validate`
+            ReplayThrottle::Rate { messages_per_second } => {
+                self.started + Duration::from_secs_f64(self.sent as f64 / messages_per_second)
+            }
+            ReplayThrottle::OriginalSpacing { speed, max_gap_ms } => match (self.last, source_ts) {
+                // Records are interleaved across partitions, so gaps going backwards in time are ignored
+                (Some((prev_ts, prev_at)), Some(ts)) if ts > prev_ts => {
+                    let mut gap = Duration::from_millis((ts - prev_ts) as u64).div_f64(speed.max(0.001));
+                    if let Some(max) = max_gap_ms {
+                        gap = gap.min(Duration::from_millis(max));
+                    }
+                    prev_at + gap
+                }
+                _ => now,
+            },
+        };
+        self.sent += 1;
+        let at = due.max(now);
+        if let Some(ts) = source_ts {
+            if self.last.is_none_or(|(prev_ts, _)| ts >= prev_ts) {
+                self.last = Some((ts, at));
+            }
+        }
+        at - now
+    }
+}
+
+/// Offsets [start_offset, end_offset) of one source partition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayRange {
+    pub partition: i32,
+    #[serde(rename = "start_offset", alias = "startOffset")]
+    pub start_offset: i64,
+    /// Exclusive; defaults to the high watermark at replay start
+    #[serde(rename = "end_offset", alias = "endOffset")]
+    pub end_offset: Option<i64>,
+}
+
+/// Copy a range of the configured topic to another topic on the same cluster.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayRequest {
+    #[serde(rename = "target_topic", alias = "targetTopic")]
+    pub target_topic: String,
+    pub ranges: Vec<ReplayRange>,
+    /// Write to the source partition number instead of letting the producer partition by key
+    #[serde(default, rename = "preserve_partition", alias = "preservePartition")]
+    pub preserve_partition: bool,
+    #[serde(default)]
+    pub timestamps: TimestampMode,
+    /// Header receiving the source timestamp (ms) when timestamps are rewritten
+    #[serde(rename = "original_timestamp_header", alias = "originalTimestampHeader")]
+    pub original_timestamp_header: Option<String>,
+    /// Optional pacing; unthrottled when omitted
+    pub throttle: Option<ReplayThrottle>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReplaySummary {
+    pub read: usize,
+    pub produced: usize,
+    pub failed: usize,
+    /// First delivery error, if any
+    pub error: Option<String>,
+}
+
+impl ReplaySummary {
+    async fn settle(&mut self, pending: &mut Vec<DeliveryFuture>) {
+        for fut in pending.drain(..) {
+            match fut.await {
+                Ok(Ok(_)) => self.produced += 1,
+                Ok(Err((e, _))) => {
+                    self.failed += 1;
+                    self.error.get_or_insert_with(|| e.to_string());
+                }
+                Err(_) => {
+                    self.failed += 1;
+                    self.error.get_or_insert_with(|| "Producer dropped before delivery".to_string());
+                }
+            }
+        }
+    }
+}
+
+impl Kafka {
+    /// Replay offset ranges of `config.topic` into `req.target_topic`, copying keys, payloads and headers as raw bytes.
+    pub async fn replay(config: &KafkaConfig, req: &ReplayRequest) -> anyhow::Result<ReplaySummary> {
+        Self::replay_to(config, config, req, |_| true).await
+    }
+
+    /// Like `replay`, producing to the cluster of `target` (which may be `config`'s own).
+    /// `progress` sees the running summary after every source record and stops the copy by returning false.
+    pub async fn replay_to(
+        config: &KafkaConfig,
+        target: &KafkaConfig,
+        req: &ReplayRequest,
+        mut progress: impl FnMut(&ReplaySummary) -> bool,
+    ) -> anyhow::Result<ReplaySummary> {
+        if let Some(throttle) = &req.throttle {
+            throttle.validate()?;
+        }
+        let consumer = create_off_runtime(config, create_consumer).await?;
+        let producer = create_off_runtime(target, create_producer).await?;
+        let header_name = req
+            .original_timestamp_header
+            .clone()
+            .unwrap_or_else(|| "x-rkui-original-timestamp".to_string());
+
+        // Resolve open-ended ranges against the current high watermark
+        let mut ends: HashMap<i32, i64> = HashMap::new();
+        let mut tpl = TopicPartitionList::new();
+        for r in &req.ranges {
+            let end = match r.end_offset {
+                Some(e) => e,
+                None => consumer.fetch_watermarks(&config.topic, r.partition, Duration::from_secs(5))?.1,
+            };
+            if r.start_offset < end {
+                ends.insert(r.partition, end);
+                tpl.add_partition_offset(&config.topic, r.partition, Offset::Offset(r.start_offset))?;
+            }
+        }
+        let mut summary = ReplaySummary::default();
+        if ends.is_empty() {
+            return Ok(summary);
+        }
+        consumer.assign(&tpl)?;
+
+        let mut pending: Vec<DeliveryFuture> = Vec::new();
+        let mut pacer = req.throttle.map(Pacer::new);
+        let mut last_progress = Instant::now();
+        while !ends.is_empty() {
+            let m = match consumer.poll(Duration::from_millis(200)) {
+                Some(Ok(m)) => m,
+                Some(Err(e)) => return Err(e.into()),
+                None => {
+                    // Ranges past the retained log never reach their end; stop once the source is quiet
+                    if last_progress.elapsed() > Duration::from_secs(10) {
+                        break;
+                    }
+                    continue;
+                }
+            };
+            last_progress = Instant::now();
+            let partition = m.partition();
+            let Some(&end) = ends.get(&partition) else { continue; };
+            if m.offset() >= end {
+                ends.remove(&partition);
+                continue;
+            }
+            summary.read += 1;
+
+            let original_ts = m.timestamp().to_millis();
+            if let Some(p) = pacer.as_mut() {
+                let wait = p.delay(original_ts);
+                if !wait.is_zero() {
+                    tokio::time::sleep(wait).await;
+                }
+            }
+            let timestamp = match (req.timestamps, original_ts) {
+                (TimestampMode::Original, ts) => ts,
+                (TimestampMode::Now, _) => Some(chrono::Utc::now().timestamp_millis()),
+                (TimestampMode::Shift { shift_ms }, ts) => ts.map(|t| t.saturating_add(shift_ms)),
+            };
+
+            let mut headers = OwnedHeaders::new();
+            if let Some(hs) = m.headers() {
+                for h in hs.iter() {
+                    headers = headers.insert(Header { key: h.key, value: h.value });
+                }
+            }
+            let original_ts_text = original_ts.map(|t| t.to_string());
+            if !matches!(req.timestamps, TimestampMode::Original) {
+                if let Some(t) = original_ts_text.as_deref() {
+                    headers = headers.insert(Header { key: &header_name, value: Some(t.as_bytes()) });
+                }
+            }
+
+            let mut record: FutureRecord<'_, [u8], [u8]> = FutureRecord::to(&req.target_topic).headers(headers);
+            if let Some(k) = m.key() {
+                record = record.key(k);
+            }
+            if let Some(v) = m.payload() {
+                record = record.payload(v);
+            }
+            if let Some(ts) = timestamp {
+                record = record.timestamp(ts);
+            }
+            if req.preserve_partition {
+                record = record.partition(partition);
+            }
+            match producer.send_result(record) {
+                Ok(fut) => pending.push(fut),
+                Err((e, _)) => {
+                    summary.failed += 1;
+                    summary.error.get_or_insert_with(|| e.to_string());
+                }
+            }
+            if pending.len() >= MAX_IN_FLIGHT {
+                summary.settle(&mut pending).await;
+            }
+            if m.offset() >= end - 1 {
+                ends.remove(&partition);
+            }
+            if !progress(&summary) {
+                break;
+            }
+        }
+        summary.settle(&mut pending).await;
+        Ok(summary)
+    }
+
+    /// Ranges covering a time window of `config.topic`: from the first record at or after `from_ms`
+    /// (the log start when None) up to the first at or after `to_ms` (the high watermark when None).
+    /// `partitions` limits the ranges; every partition when None.
+    pub fn ranges_for_window(
+        config: &KafkaConfig,
+        partitions: Option<&[i32]>,
+        from_ms: Option<i64>,
+        to_ms: Option<i64>,
+        precise: bool,
+    ) -> anyhow::Result<Vec<ReplayRange>> {
+        let topic = config.topic.as_str();
+        let at = |ms: Option<i64>| -> anyhow::Result<HashMap<i32, Option<i64>>> {
+            Ok(match ms {
+                Some(ms) => Self::offsets_for_time(config, topic, ms, precise)?
+                    .into_iter()
+                    .map(|t| (t.partition, t.offset))
+                    .collect(),
+                None => HashMap::new(),
+            })
+        };
+        let (starts, ends) = (at(from_ms)?, at(to_ms)?);
+        Ok(window_ranges(Self::watermarks_for(config, topic)?, partitions, &starts, &ends))
+    }
+}
+
+/// Ranges between the offsets resolved for a window's start and end (`offsets_for_time` results by partition;
+/// None past the newest record). Partitions without a resolved bound span from the log start or to the
+/// high watermark.
+pub fn window_ranges(
+    watermarks: Vec<PartitionWatermarks>,
+    partitions: Option<&[i32]>,
+    starts: &HashMap<i32, Option<i64>>,
+    ends: &HashMap<i32, Option<i64>>,
+) -> Vec<ReplayRange> {
+    watermarks
+        .into_iter()
+        .filter(|w| partitions.is_none_or(|ps| ps.contains(&w.partition)))
+        .map(|w| {
+            // A bound past the newest record resolves to the high watermark
+            let bound = |m: &HashMap<i32, Option<i64>>, default: i64| match m.get(&w.partition) {
+                Some(o) => o.unwrap_or(w.high),
+                None => default,
+            };
+            ReplayRange {
+                partition: w.partition,
+                start_offset: bound(starts, w.low),
+                end_offset: Some(bound(ends, w.high)),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rdkafka::consumer::Consumer;
+use rdkafka::message::Message as RdMessage;
+use rdkafka::topic_partition_list::TopicPartitionList;
+use rdkafka::Offset;
+use serde::Serialize;
+
+use super::consumer::create_consumer;
+use super::service::Kafka;
+use super::types::KafkaConfig;
+
+/// Oldest retained data of one partition.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionRetention {
+    pub partition: i32,
+    pub low: i64,
+    pub high: i64,
+    /// Timestamp (ms) of the oldest retained record; None for empty partitions
+    pub oldest_timestamp: Option<i64>,
+    /// When the oldest retained record becomes eligible for deletion (oldest + retention.ms).
+    /// Brokers delete whole segments, so the data usually lives somewhat longer.
+    pub expires_at: Option<i64>,
+}
+
+/// "Do we still have last Tuesday?" — retention settings combined with the oldest retained records.
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionEstimate {
+    pub topic: String,
+    /// None when retention.ms is -1 (unlimited)
+    pub retention_ms: Option<i64>,
+    pub retention_bytes: Option<i64>,
+    pub cleanup_policy: String,
+    /// Data older than this (ms) is gone or due for deletion; None when retention is unlimited
+    pub horizon: Option<i64>,
+    /// Oldest timestamp still available on every partition (max of per-partition oldest timestamps)
+    pub fully_available_since: Option<i64>,
+    pub partitions: Vec<PartitionRetention>,
+}
+
+impl Kafka {
+    /// Estimate the retention horizon of a topic per partition.
+    pub async fn retention_estimate(config: &KafkaConfig, topic: &str) -> anyhow::Result<RetentionEstimate> {
+        let configs = Self::describe_topic_configs(config, topic).await?;
+        let parse = |name: &str| configs.value(name).and_then(|v| v.parse::<i64>().ok());
+        let retention_ms = parse("retention.ms").filter(|v| *v >= 0);
+        let retention_bytes = parse("retention.bytes").filter(|v| *v >= 0);
+        let cleanup_policy = configs.value("cleanup.policy").unwrap_or("delete").to_string();
+        // Compacted-only topics keep the latest record per key regardless of time
+        let time_based = cleanup_policy.split(',').any(|p| p.trim() == "delete");
+        let retention_ms = retention_ms.filter(|_| time_based);
+
+        let mut partitions = oldest_records(config, topic)?;
+        for p in partitions.iter_mut() {
+            p.expires_at = match (p.oldest_timestamp, retention_ms) {
+                (Some(t), Some(r)) => Some(t.saturating_add(r)),
+                _ => None,
+            };
+        }
+        let fully_available_since = partitions.iter().filter_map(|p| p.oldest_timestamp).max();
+        let horizon = retention_ms.map(|r| chrono::Utc::now().timestamp_millis() - r);
+        Ok(RetentionEstimate {
+            topic: topic.to_string(),
+            retention_ms,
+            retention_bytes,
+            cleanup_policy,
+            horizon,
+            fully_available_since,
+            partitions,
+        })
+    }
+}
+
+/// Watermarks and the timestamp of the record at the low watermark for every partition (expires_at left empty).
+fn oldest_records(config: &KafkaConfig, topic: &str) -> anyhow::Result<Vec<PartitionRetention>> {
+    let consumer = create_consumer(config)?;
+    let timeout = Duration::from_secs(5);
+    let md = consumer.client().fetch_metadata(Some(topic), timeout)?;
+    let t = md
+        .topics()
+        .iter()
+        .find(|t| t.name() == topic)
+        .ok_or_else(|| anyhow::anyhow!("Topic not found in metadata"))?;
+
+    let mut marks: Vec<(i32, i64, i64)> = Vec::new();
+    let mut tpl = TopicPartitionList::new();
+    for p in t.partitions() {
+        let (low, high) = consumer.fetch_watermarks(topic, p.id(), timeout)?;
+        marks.push((p.id(), low, high));
+        if high > low {
+            tpl.add_partition_offset(topic, p.id(), Offset::Offset(low))?;
+        }
+    }
+    marks.sort_by_key(|m| m.0);
+
+    let mut found: HashMap<i32, i64> = HashMap::new();
+    let wanted = tpl.count();
+    if wanted > 0 {
+        consumer.assign(&tpl)?;
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while found.len() < wanted && Instant::now() < deadline {
+            match consumer.poll(Duration::from_millis(200)) {
+                Some(Ok(m)) => {
+                    if let Some(ts) = m.timestamp().to_millis() {
+                        found.entry(m.partition()).or_insert(ts);
+                    }
+                }
+                Some(Err(e)) => return Err(e.into()),
+                None => {}
+            }
+        }
+    }
+    Ok(marks
+        .into_iter()
+        .map(|(partition, low, high)| PartitionRetention {
+            partition,
+            low,
+            high,
+            oldest_timestamp: found.get(&partition).copied(),
+            expires_at: None,
+        })
+        .collect())
+}
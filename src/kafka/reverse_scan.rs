@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::error::KafkaResult;
+use rdkafka::message::{BorrowedMessage, Message as RdMessage};
+use rdkafka::{Offset, TopicPartitionList};
+
+use super::consumer::recv_timeout;
+use super::oauth::ClientCtx;
+use super::partition_queues::rejoin_main_queue;
+
+/// Offsets read per partition and round.
+pub const REVERSE_WINDOW: i64 = 500;
+/// Silence after which the partitions still missing from a round are taken as read
+/// (transaction markers or compaction gaps at a window's end).
+const ROUND_IDLE: Duration = Duration::from_secs(2);
+
+/// Reads partitions from their snapshot end backwards: each round assigns every unfinished partition to its
+/// next older window of offsets, reads the windows fully and hands the records out newest first.
+pub(crate) struct ReverseScan<'a> {
+    consumer: &'a StreamConsumer<ClientCtx>,
+    topic: String,
+    /// Partitions split off the main queue by earlier merge reads, routed back after every round's assign
+    split: Vec<i32>,
+    /// Per partition: (first offset to read, end of the next window); the partition is finished once they meet
+    bounds: HashMap<i32, (i64, i64)>,
+    /// Windows of the round being read: partition -> (start, stop)
+    round: HashMap<i32, (i64, i64)>,
+    /// Records of the round being read
+    pending: Vec<BorrowedMessage<'a>>,
+    /// Records of the last finished round, oldest first (taken from the back)
+    ready: Vec<BorrowedMessage<'a>>,
+    /// When the round last received a record
+    last_record: Instant,
+}
+
+/// Ordering key: timestamp (records without one last), then partition and offset.
+fn sort_key(m: &BorrowedMessage<'_>) -> (i64, i32, i64) {
+    (m.timestamp().to_millis().unwrap_or(i64::MIN), m.partition(), m.offset())
+}
+
+impl<'a> ReverseScan<'a> {
+    /// Scan `[starts[p], ends[p])` of every partition that has both.
+    pub(crate) fn new(
+        consumer: &'a StreamConsumer<ClientCtx>,
+        topic: &str,
+        split: Vec<i32>,
+        starts: &HashMap<i32, i64>,
+        ends: &HashMap<i32, i64>,
+    ) -> Self {
+        let bounds = starts
+            .iter()
+            .filter_map(|(&p, &start)| Some((p, (start, *ends.get(&p)?))))
+            .filter(|(_, (start, stop))| start < stop)
+            .collect();
+        Self {
+            consumer,
+            topic: topic.to_string(),
+            split,
+            bounds,
+            round: HashMap::new(),
+            pending: Vec::new(),
+            ready: Vec::new(),
+            last_record: Instant::now(),
+        }
+    }
+
+    /// True once every window was read and handed out.
+    pub(crate) fn finished(&self) -> bool {
+        self.bounds.is_empty() && self.round.is_empty() && self.ready.is_empty()
+    }
+
+    /// Assign the next older window of every unfinished partition.
+    fn start_round(&mut self) -> KafkaResult<()> {
+        let mut tpl = TopicPartitionList::new();
+        for (&p, (floor, stop)) in self.bounds.iter_mut() {
+            let start = (*stop - REVERSE_WINDOW).max(*floor);
+            self.round.insert(p, (start, *stop));
+            tpl.add_partition_offset(&self.topic, p, Offset::Offset(start))?;
+            *stop = start;
+        }
+        self.bounds.retain(|_, (floor, stop)| stop > floor);
+        self.last_record = Instant::now();
+        self.consumer.assign(&tpl)?;
+        rejoin_main_queue(self.consumer, &self.topic, &self.split)
+    }
+
+    fn finish_round(&mut self) {
+        self.pending.sort_by_key(sort_key);
+        self.ready = std::mem::take(&mut self.pending);
+    }
+
+    /// Next record, newest first; None when nothing arrived within `timeout` or the scan is finished.
+    /// Drop-in for `recv_timeout` in the filtered load.
+    pub(crate) async fn next(&mut self, timeout: Duration) -> Option<KafkaResult<BorrowedMessage<'a>>> {
+        if let Some(m) = self.ready.pop() {
+            return Some(Ok(m));
+        }
+        if self.round.is_empty() {
+            if self.bounds.is_empty() {
+                return None;
+            }
+            if let Err(e) = self.start_round() {
+                return Some(Err(e));
+            }
+        }
+        match recv_timeout(self.consumer, timeout).await {
+            Some(Ok(m)) => {
+                let p = m.partition();
+                if let Some(&(start, stop)) = self.round.get(&p) {
+                    if m.offset() >= stop - 1 {
+                        self.round.remove(&p);
+                    }
+                    if (start..stop).contains(&m.offset()) {
+                        self.pending.push(m);
+                    }
+                }
+                self.last_record = Instant::now();
+            }
+            Some(Err(e)) => return Some(Err(e)),
+            None if self.last_record.elapsed() >= ROUND_IDLE => self.round.clear(),
+            None => {}
+        }
+        if self.round.is_empty() {
+            self.finish_round();
+        }
+        self.ready.pop().map(Ok)
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use super::types::KafkaConfig;
+
+/// Registry URL + schema id.
+type SchemaKey = (String, u32);
+
+/// Schemas are immutable per id, so they are cached for the process lifetime.
+static SCHEMA_CACHE: Lazy<Mutex<HashMap<SchemaKey, Arc<RegisteredSchema>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+/// Recent lookup failures, so an unreachable registry is not hit once per record.
+static FAILURES: Lazy<Mutex<HashMap<SchemaKey, (Instant, String)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const FAILURE_TTL: Duration = Duration::from_secs(30);
+
+/// Schema as returned by `GET /schemas/ids/{id}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisteredSchema {
+    #[serde(default)]
+    pub id: u32,
+    /// "AVRO" (the registry omits the field for Avro), "PROTOBUF" or "JSON"
+    #[serde(rename = "schemaType", default = "default_schema_type")]
+    pub schema_type: String,
+    pub schema: String,
+    /// Schemas imported by this one (protobuf imports, Avro named types)
+    #[serde(default)]
+    pub references: Vec<SchemaReference>,
+}
+
+/// Reference to another registered schema; `name` is the import path for protobuf.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaReference {
+    pub name: String,
+    pub subject: String,
+    pub version: i64,
+}
+
+fn default_schema_type() -> String {
+    "AVRO".to_string()
+}
+
+/// Minimal Confluent Schema Registry client (schema lookup by id).
+#[derive(Debug, Clone)]
+pub struct SchemaRegistry {
+    url: String,
+    authorization: Option<String>,
+}
+
+impl SchemaRegistry {
+    /// Build a client from the connection config; None when no registry URL is configured.
+    pub fn from_config(config: &KafkaConfig) -> Option<Self> {
+        let url = config.schema_registry_url.as_deref().map(str::trim).filter(|u| !u.is_empty())?;
+        let authorization = config.schema_registry_username.as_deref().filter(|u| !u.is_empty()).map(|user| {
+            let pass = config.schema_registry_password.as_deref().unwrap_or_default();
+            let token = base64::engine::general_purpose::STANDARD.encode(format!("{user}:{pass}"));
+            format!("Basic {token}")
+        });
+        Some(Self { url: url.trim_end_matches('/').to_string(), authorization })
+    }
+
+    /// Resolve a schema by id, consulting the process-wide cache first.
+    pub fn schema_by_id(&self, id: u32) -> anyhow::Result<Arc<RegisteredSchema>> {
+        let key = (self.url.clone(), id);
+        if let Some(s) = SCHEMA_CACHE.lock().ok().and_then(|c| c.get(&key).cloned()) {
+            return Ok(s);
+        }
+        if let Some((at, err)) = FAILURES.lock().ok().and_then(|f| f.get(&key).cloned()) {
+            if at.elapsed() < FAILURE_TTL {
+                anyhow::bail!(err);
+            }
+        }
+        match self.fetch(id) {
+            Ok(schema) => {
+                let schema = Arc::new(schema);
+                if let Ok(mut cache) = SCHEMA_CACHE.lock() {
+                    cache.insert(key, schema.clone());
+                }
+                Ok(schema)
+            }
+            Err(e) => {
+                if let Ok(mut failures) = FAILURES.lock() {
+                    failures.insert(key, (Instant::now(), e.to_string()));
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Resolve a reference to a concrete schema (subject versions map to immutable ids).
+    pub fn schema_by_reference(&self, reference: &SchemaReference) -> anyhow::Result<Arc<RegisteredSchema>> {
+        #[derive(Deserialize)]
+        struct SubjectVersion {
+            id: u32,
+        }
+        let path = format!("/subjects/{}/versions/{}", encode_path(&reference.subject), reference.version);
+        let version: SubjectVersion = self.get_json(&path, &format!("subject {} v{}", reference.subject, reference.version))?;
+        self.schema_by_id(version.id)
+    }
+
+    fn fetch(&self, id: u32) -> anyhow::Result<RegisteredSchema> {
+        let mut schema: RegisteredSchema = self.get_json(&format!("/schemas/ids/{id}"), &format!("schema id {id}"))?;
+        schema.id = id;
+        Ok(schema)
+    }
+
+    fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str, what: &str) -> anyhow::Result<T> {
+        let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+        let mut req = agent
+            .get(&format!("{}{path}", self.url))
+            .set("Accept", "application/vnd.schemaregistry.v1+json");
+        if let Some(auth) = self.authorization.as_deref() {
+            req = req.set("Authorization", auth);
+        }
+        let resp = req.call().map_err(|e| match e {
+            ureq::Error::Status(404, _) => anyhow::anyhow!("Schema registry {} has no {what}", self.url),
+            ureq::Error::Status(code, _) => anyhow::anyhow!("Schema registry returned HTTP {code} for {what}"),
+            other => anyhow::anyhow!("Failed to reach schema registry {}: {other}", self.url),
+        })?;
+        resp.into_json()
+            .map_err(|e| anyhow::anyhow!("Failed to parse schema registry response for {what}: {e}"))
+    }
+}
+
+/// Percent-encode a subject name for use as a URL path segment.
+fn encode_path(segment: &str) -> String {
+    let mut out = String::with_capacity(segment.len());
+    for b in segment.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+/// Split the Confluent envelope (magic byte 0 + big-endian schema id) off a payload.
+pub fn split_envelope(payload: &[u8]) -> Option<(u32, &[u8])> {
+    if payload.len() < 5 || payload[0] != 0 {
+        return None;
+    }
+    let id = u32::from_be_bytes([payload[1], payload[2], payload[3], payload[4]]);
+    Some((id, &payload[5..]))
+}
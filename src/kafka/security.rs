@@ -100,7 +100,7 @@ pub(crate) fn jks_truststore_to_pem_native(jks_path: &str, _storepass: Option<&s
         return Err(anyhow::anyhow!("Truststore file not found: {}", jks_path));
     }
     let bytes = std::fs::read(jks_path)?;
-    let certs = parse_jks_trusted_certs(&bytes)?;
+    let certs = parse_jks(&bytes)?.trusted;
     if certs.is_empty() {
         return Err(anyhow::anyhow!("No certificates found in truststore"));
     }
@@ -126,7 +126,7 @@ pub(crate) fn jks_truststore_to_pem(jks_path: &str, storepass: Option<&str>) ->
     match jks_truststore_to_pem_via_minijks(jks_path, storepass) {
         Ok(p) => return Ok(p),
         Err(e1) => {
-            eprintln!("[rkui] minijks parse failed: {}. Falling back to native JKS parser...", e1);
+            log::warn!("minijks parse failed: {}. Falling back to native JKS parser...", e1);
         }
     }
     // Fallback to native minimal parser
@@ -180,10 +180,21 @@ pub(crate) fn pkcs12_to_pem(p12_path: &str, password: Option<&str>) -> anyhow::R
     Ok(path_str)
 }
 
+/// Private key entry of a JKS keystore: the protected PKCS#8 key and its DER certificate chain.
+struct JksKeyEntry {
+    protected_key: Vec<u8>,
+    chain: Vec<Vec<u8>>,
+}
+
+/// Contents of a JKS keystore: DER certificates of trusted cert entries and the private key entries.
+struct JksEntries {
+    trusted: Vec<Vec<u8>>,
+    keys: Vec<JksKeyEntry>,
+}
 
-/// Minimal JKS reader: extracts DER certificates from trusted cert entries (type = 1).
-/// It does not validate the keystore SHA-1 integrity checksum and ignores private key entries.
-fn parse_jks_trusted_certs(data: &[u8]) -> anyhow::Result<Vec<Vec<u8>>> {
+/// Minimal JKS reader: extracts DER certificates from trusted cert entries (type = 2) and private key entries (type = 1).
+/// It does not validate the keystore SHA-1 integrity checksum.
+fn parse_jks(data: &[u8]) -> anyhow::Result<JksEntries> {
     let mut rd = Cursor::new(data);
 
     fn read_u32(rd: &mut Cursor<&[u8]>) -> anyhow::Result<u32> {
@@ -220,35 +231,24 @@ fn parse_jks_trusted_certs(data: &[u8]) -> anyhow::Result<Vec<Vec<u8>>> {
         }
         Ok(())
     }
-    fn read_attributes_if_any(version: u32, rd: &mut Cursor<&[u8]>) -> anyhow::Result<()> {
-        // In JKS v2, entries may be followed by attributes: count (u32), then key/value UTF strings
-        if version >= 2 {
-            let attr_count = read_u32(rd)? as usize;
-            for _ in 0..attr_count {
-                read_java_utf_skip(rd)?; // key
-                read_java_utf_skip(rd)?; // value
-            }
-        }
-        Ok(())
-    }
-
     use std::io::{Cursor, Read};
     let magic = read_u32(&mut rd)?;
     if magic != 0xFEED_FEED {
         return Err(anyhow::anyhow!("Not a JKS file (bad magic)"));
     }
-    let version = read_u32(&mut rd)?; // 1 or 2
+    let _version = read_u32(&mut rd)?; // 1 or 2; entries are laid out the same
     let count = read_u32(&mut rd)? as usize;
 
     let mut certs = Vec::new();
+    let mut keys = Vec::new();
     for _ in 0..count {
-        let tag = read_u32(&mut rd)?; // 1 = trusted cert, 2 = private key
+        let tag = read_u32(&mut rd)?; // 1 = private key, 2 = trusted cert
         // alias
         read_java_utf_skip(&mut rd)?;
         // timestamp
         let _ts = read_u64(&mut rd)?;
         match tag {
-            1 => {
+            2 => {
                 // cert type and bytes
                 read_java_utf_skip(&mut rd)?; // type (e.g., "X.509")
                 let len = read_u32(&mut rd)? as usize;
@@ -261,11 +261,9 @@ fn parse_jks_trusted_certs(data: &[u8]) -> anyhow::Result<Vec<Vec<u8>>> {
                 let mut buf = vec![0u8; len];
                 rd.read_exact(&mut buf)?;
                 certs.push(buf);
-                // optional attributes (v2)
-                read_attributes_if_any(version, &mut rd)?;
             }
-            2 => {
-                // private key entry: skip
+            1 => {
+                // private key entry: protected key, then the certificate chain
                 let key_len = read_u32(&mut rd)? as usize;
                 // Bounds check
                 let posk = rd.position() as usize;
@@ -273,9 +271,10 @@ fn parse_jks_trusted_certs(data: &[u8]) -> anyhow::Result<Vec<Vec<u8>>> {
                 if posk + key_len > totalk {
                     return Err(anyhow::anyhow!("Malformed JKS: private key length {} exceeds remaining bytes {}", key_len, totalk.saturating_sub(posk)));
                 }
-                let mut skip = vec![0u8; key_len];
-                rd.read_exact(&mut skip)?;
+                let mut protected_key = vec![0u8; key_len];
+                rd.read_exact(&mut protected_key)?;
                 let chain_len = read_u32(&mut rd)? as usize;
+                let mut chain = Vec::new();
                 for _ in 0..chain_len {
                     read_java_utf_skip(&mut rd)?; // cert type
                     let clen = read_u32(&mut rd)? as usize;
@@ -286,18 +285,292 @@ fn parse_jks_trusted_certs(data: &[u8]) -> anyhow::Result<Vec<Vec<u8>>> {
                     }
                     let mut s = vec![0u8; clen];
                     rd.read_exact(&mut s)?;
+                    chain.push(s);
                 }
-                // optional attributes (v2)
-                read_attributes_if_any(version, &mut rd)?;
+                keys.push(JksKeyEntry { protected_key, chain });
             }
             _ => return Err(anyhow::anyhow!("Unsupported JKS entry tag: {}", tag)),
         }
     }
     // trailing 20-byte SHA-1 checksum is ignored
-    Ok(certs)
+    Ok(JksEntries { trusted: certs, keys })
+}
+
+/// One DER element at the start of `data`: (tag, contents, rest).
+fn der_element(data: &[u8]) -> anyhow::Result<(u8, &[u8], &[u8])> {
+    let malformed = || anyhow::anyhow!("Malformed JKS private key");
+    let (&tag, rest) = data.split_first().ok_or_else(malformed)?;
+    let (&first, mut rest) = rest.split_first().ok_or_else(malformed)?;
+    let len = if first < 0x80 {
+        first as usize
+    } else {
+        let n = (first & 0x7f) as usize;
+        if n == 0 || n > 4 || rest.len() < n {
+            return Err(malformed());
+        }
+        let len = rest[..n].iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        rest = &rest[n..];
+        len
+    };
+    if rest.len() < len {
+        return Err(malformed());
+    }
+    Ok((tag, &rest[..len], &rest[len..]))
+}
+
+/// Sun's proprietary JKS key protection (OID 1.3.6.1.4.1.42.2.17.1.1)
+const JKS_KEY_PROTECTOR_OID: [u8; 10] = [0x2b, 0x06, 0x01, 0x04, 0x01, 0x2a, 0x02, 0x11, 0x01, 0x01];
+
+/// Unprotect a JKS private key entry into PKCS#8 DER. The key is XORed with a SHA-1 keystream seeded by
+/// a salt and the password (UTF-16BE); a trailing SHA-1 digest verifies the password.
+fn jks_unprotect_key(protected: &[u8], password: &str) -> anyhow::Result<Vec<u8>> {
+    use openssl::sha::Sha1;
+
+    let (_, info, _) = der_element(protected)?;
+    let (_, algorithm, rest) = der_element(info)?;
+    let (_, oid, _) = der_element(algorithm)?;
+    if oid != JKS_KEY_PROTECTOR_OID {
+        return Err(anyhow::anyhow!(
+            "Unsupported keystore key protection (JCEKS?); convert the keystore to PKCS#12: \
+             keytool -importkeystore -srckeystore <file> -destkeystore <file>.p12 -deststoretype pkcs12"
+        ));
+    }
+    let (_, data, _) = der_element(rest)?;
+    if data.len() < 40 {
+        return Err(anyhow::anyhow!("Malformed JKS private key"));
+    }
+    let (salt, rest) = data.split_at(20);
+    let (encrypted, check) = rest.split_at(rest.len() - 20);
+    let password: Vec<u8> = password.encode_utf16().flat_map(|c| c.to_be_bytes()).collect();
+
+    let mut plain = Vec::with_capacity(encrypted.len());
+    let mut digest = [0u8; 20];
+    digest.copy_from_slice(salt);
+    for chunk in encrypted.chunks(20) {
+        let mut h = Sha1::new();
+        h.update(&password);
+        h.update(&digest);
+        digest = h.finish();
+        plain.extend(chunk.iter().zip(digest.iter()).map(|(c, k)| c ^ k));
+    }
+    let mut h = Sha1::new();
+    h.update(&password);
+    h.update(&plain);
+    if h.finish() != check {
+        return Err(anyhow::anyhow!("Wrong key password for the JKS keystore"));
+    }
+    Ok(plain)
+}
+
+/// Extract the client identity of a Java keystore (.p12/.pfx or JKS) for mutual TLS. Returns the PEM text of
+/// (certificate chain, private key) in memory, meant for `ssl.certificate.pem` / `ssl.key.pem`, so the
+/// unencrypted key is never written to disk. `key_password` defaults to the store password, as with Java's `ssl.key.password`.
+pub(crate) fn keystore_to_pem(path: &str, store_password: Option<&str>, key_password: Option<&str>) -> anyhow::Result<(String, String)> {
+    if !Path::new(path).exists() {
+        return Err(anyhow::anyhow!("Keystore file not found: {}", path));
+    }
+    let store_password = store_password.unwrap_or("");
+    let key_password = key_password.filter(|p| !p.is_empty()).unwrap_or(store_password);
+    let (cert_pem, key_pem) = match detect_keystore_kind(path) {
+        KeyStoreKind::Pkcs12 => {
+            let bytes = std::fs::read(path)?;
+            let parsed = Pkcs12::from_der(&bytes)
+                .map_err(|e| anyhow::anyhow!("Failed to read PKCS#12: {}", e))?
+                .parse2(store_password)
+                .map_err(|e| anyhow::anyhow!("Failed to parse PKCS#12 keystore (wrong password?): {}", e))?;
+            let pkey = parsed.pkey.ok_or_else(|| anyhow::anyhow!("PKCS#12 keystore has no private key"))?;
+            let cert = parsed.cert.ok_or_else(|| anyhow::anyhow!("PKCS#12 keystore has no client certificate"))?;
+            let mut certs = cert.to_pem()?;
+            if let Some(chain) = parsed.ca {
+                for c in &chain {
+                    certs.extend(c.to_pem()?);
+                }
+            }
+            (certs, pkey.private_key_to_pem_pkcs8()?)
+        }
+        KeyStoreKind::JksOrJceks => {
+            let entries = parse_jks(&std::fs::read(path)?)?;
+            let entry = entries
+                .keys
+                .first()
+                .ok_or_else(|| anyhow::anyhow!("JKS keystore has no private key entry"))?;
+            if entry.chain.is_empty() {
+                return Err(anyhow::anyhow!("JKS keystore has no client certificate"));
+            }
+            let pkcs8 = jks_unprotect_key(&entry.protected_key, key_password)?;
+            let pkey = openssl::pkey::PKey::private_key_from_pkcs8(&pkcs8)
+                .map_err(|e| anyhow::anyhow!("Failed to read JKS private key: {}", e))?;
+            let mut certs = Vec::new();
+            for der in &entry.chain {
+                certs.extend(openssl::x509::X509::from_der(der)?.to_pem()?);
+            }
+            (certs, pkey.private_key_to_pem_pkcs8()?)
+        }
+        // A PEM holding both the certificate and the key serves as both
+        KeyStoreKind::PemOrDir => {
+            let pem = std::fs::read_to_string(path)?;
+            return Ok((pem.clone(), pem));
+        }
+        KeyStoreKind::Unknown => {
+            return Err(anyhow::anyhow!("Unsupported keystore format: {} (expected .p12, .pfx, .jks or .pem)", path))
+        }
+    };
+    Ok((String::from_utf8(cert_pem)?, String::from_utf8(key_pem)?))
+}
+
+/// Certificates expiring within this many days are flagged.
+const EXPIRY_WARNING_DAYS: i64 = 30;
+
+/// One certificate of a truststore or keystore, as shown by `inspect_truststore`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CertificateInfo {
+    /// Distinguished name, e.g. "CN=broker-1, O=Acme"
+    pub subject: String,
+    pub issuer: String,
+    /// DNS names, IP addresses, e-mail addresses and URIs of the subjectAltName extension
+    pub sans: Vec<String>,
+    /// RFC 3339
+    pub not_before: String,
+    pub not_after: String,
+    /// Whole days until expiry; negative once expired
+    pub days_left: i64,
+    pub expired: bool,
+    /// Valid now but expiring within 30 days
+    pub expires_soon: bool,
+    /// SHA-256 fingerprint, colon-separated hex
+    pub sha256: String,
+}
+
+fn distinguished_name(name: &openssl::x509::X509NameRef) -> String {
+    name.entries()
+        .map(|e| {
+            let key = e.object().nid().short_name().unwrap_or("?");
+            let value = String::from_utf8_lossy(e.data().as_slice());
+            format!("{key}={value}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn subject_alt_names(cert: &openssl::x509::X509Ref) -> Vec<String> {
+    let Some(names) = cert.subject_alt_names() else { return Vec::new() };
+    names
+        .iter()
+        .filter_map(|n| {
+            if let Some(dns) = n.dnsname() {
+                return Some(format!("DNS:{dns}"));
+            }
+            if let Some(ip) = n.ipaddress() {
+                let addr = match ip.len() {
+                    4 => std::net::IpAddr::from(<[u8; 4]>::try_from(ip).ok()?),
+                    16 => std::net::IpAddr::from(<[u8; 16]>::try_from(ip).ok()?),
+                    _ => return None,
+                };
+                return Some(format!("IP:{addr}"));
+            }
+            n.email().map(|e| format!("email:{e}")).or_else(|| n.uri().map(|u| format!("URI:{u}")))
+        })
+        .collect()
 }
 
+/// Seconds since the Unix epoch of an ASN.1 time.
+fn asn1_unix(time: &openssl::asn1::Asn1TimeRef) -> anyhow::Result<i64> {
+    let diff = openssl::asn1::Asn1Time::from_unix(0)?.diff(time)?;
+    Ok(i64::from(diff.days) * 86_400 + i64::from(diff.secs))
+}
 
+fn certificate_info(cert: &openssl::x509::X509Ref, now: i64) -> anyhow::Result<CertificateInfo> {
+    let rfc3339 = |secs: i64| {
+        chrono::DateTime::from_timestamp(secs, 0).map(|t| t.to_rfc3339()).unwrap_or_default()
+    };
+    let not_before = asn1_unix(cert.not_before())?;
+    let not_after = asn1_unix(cert.not_after())?;
+    let days_left = (not_after - now).div_euclid(86_400);
+    let expired = not_after <= now;
+    let sha256 = cert
+        .digest(openssl::hash::MessageDigest::sha256())?
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(":");
+    Ok(CertificateInfo {
+        subject: distinguished_name(cert.subject_name()),
+        issuer: distinguished_name(cert.issuer_name()),
+        sans: subject_alt_names(cert),
+        not_before: rfc3339(not_before),
+        not_after: rfc3339(not_after),
+        days_left,
+        expired,
+        expires_soon: !expired && days_left < EXPIRY_WARNING_DAYS,
+        sha256,
+    })
+}
+
+/// PEM certificates of a file, or of every .pem/.crt/.cer file in a directory.
+fn pem_certificates(path: &str) -> anyhow::Result<Vec<openssl::x509::X509>> {
+    let p = Path::new(path);
+    let files = if p.is_dir() {
+        let mut files: Vec<_> = std::fs::read_dir(p)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|f| {
+                let ext = f.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+                matches!(ext.as_str(), "pem" | "crt" | "cer")
+            })
+            .collect();
+        files.sort();
+        files
+    } else {
+        vec![p.to_path_buf()]
+    };
+    let mut certs = Vec::new();
+    for f in files {
+        let bytes = std::fs::read(&f)?;
+        match openssl::x509::X509::stack_from_pem(&bytes) {
+            Ok(found) if !found.is_empty() => certs.extend(found),
+            // A single DER certificate (.cer files often are)
+            _ => certs.push(
+                openssl::x509::X509::from_der(&bytes)
+                    .map_err(|_| anyhow::anyhow!("No certificates found in {}", f.display()))?,
+            ),
+        }
+    }
+    Ok(certs)
+}
+
+/// Certificates of a truststore or keystore (PEM file or directory, JKS, PKCS#12) with their validity,
+/// flagging those that expired or expire within 30 days. Key entries contribute their certificate chain.
+pub fn inspect_truststore(path: &str, password: Option<&str>) -> anyhow::Result<Vec<CertificateInfo>> {
+    if !Path::new(path).exists() {
+        return Err(anyhow::anyhow!("File not found: {}", path));
+    }
+    let certs = match detect_keystore_kind(path) {
+        KeyStoreKind::Pkcs12 => {
+            let parsed = Pkcs12::from_der(&std::fs::read(path)?)
+                .map_err(|e| anyhow::anyhow!("Failed to read PKCS#12: {}", e))?
+                .parse2(password.unwrap_or(""))
+                .map_err(|e| anyhow::anyhow!("Failed to parse PKCS#12 (wrong password?): {}", e))?;
+            let mut certs: Vec<_> = parsed.cert.into_iter().collect();
+            certs.extend(parsed.ca.into_iter().flatten());
+            certs
+        }
+        KeyStoreKind::JksOrJceks => {
+            let entries = parse_jks(&std::fs::read(path)?)?;
+            let chains = entries.keys.into_iter().flat_map(|k| k.chain);
+            entries
+                .trusted
+                .into_iter()
+                .chain(chains)
+                .map(|der| openssl::x509::X509::from_der(&der).map_err(anyhow::Error::from))
+                .collect::<anyhow::Result<Vec<_>>>()?
+        }
+        KeyStoreKind::PemOrDir | KeyStoreKind::Unknown => pem_certificates(path)?,
+    };
+    if certs.is_empty() {
+        return Err(anyhow::anyhow!("No certificates found in {}", path));
+    }
+    let now = chrono::Utc::now().timestamp();
+    certs.iter().map(|c| certificate_info(c, now)).collect()
+}
 
 /// Try to extract username and password from a JAAS-like config string.
 /// Accepts common variants like:
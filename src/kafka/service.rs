@@ -2,14 +2,31 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
 
-use super::decoder::{decoder_for, MessageType};
+use rdkafka::consumer::Consumer;
+use rdkafka::message::BorrowedMessage;
+
+use super::client_stats::ClientStats;
+use super::assignment::ReadFilters;
+use super::codec::MessageCodec;
+use super::decoder::{AvroDecoder, KeyType, MessageType};
+use super::masking::Masker;
+use super::oauth::ClientCtx;
+use super::page_size::PageSizer;
+use super::partition_queues::PartitionQueues;
+use super::plugin::PluginDecoder;
+use super::raw_cache::RawCache;
 use super::reader;
-use super::types::{KafkaConfig, UiMessage};
+use super::consumer::AccessDenied;
+use super::schema_registry::SchemaRegistry;
+use super::types::{ConsumeBatch, ConsumeProgress, KafkaConfig, PartitionProgress, TopicAccess, UiMessage};
+use crate::proto_decoder::{expand_proto_paths, proto_include_paths, DescriptorRegistry, ProtoDecodeOptions, ProtoDecoder};
 
 /// High-level Kafka reader object. Encapsulates consumer and reading state.
 pub struct Kafka {
     pub config: KafkaConfig,
-    pub consumer: Arc<rdkafka::consumer::BaseConsumer>,
+    // Partition/offset filters, starting from the config's; filter commands change them on the shared reader
+    pub filters: Mutex<ReadFilters>,
+    pub consumer: Arc<rdkafka::consumer::StreamConsumer<ClientCtx>>,
     pub assigned: AtomicBool,
     // Snapshot of end offsets (high watermarks) per partition at configuration time
     pub end_offsets: Mutex<HashMap<i32, i64>>,
@@ -17,100 +34,313 @@ pub struct Kafka {
     pub partitions: Mutex<Vec<i32>>,
     // Partitions that reached their end (as of the snapshot)
     pub done_partitions: Mutex<HashSet<i32>>,
+    // First offset read per partition (resolved at assignment), for progress reporting
+    pub start_offsets: Mutex<HashMap<i32, i64>>,
+    // Records handed to the UI per partition: (count, last offset)
+    pub delivered: Mutex<HashMap<i32, (u64, i64)>>,
     // Per-partition buffered messages to support global timestamp ordering and pagination
     pub buffers: Mutex<HashMap<i32, VecDeque<(i64, UiMessage)>>>,
-    // Optional protobuf decoder initialized when proto schema path is provided
-    pub proto_decoder: Option<Arc<crate::proto_decoder::ProtoDecoder>>,
+    // Payload decoding pipeline (message type, optional protobuf decoder, lazy decode)
+    pub codec: MessageCodec,
+    // Original bytes of loaded records (when raw_cache_mb is set)
+    pub raw_cache: Option<Arc<RawCache>>,
+    // Partitions drained by their own tasks for the merge readers (split on the first merge read)
+    pub(crate) partition_queues: Mutex<Option<PartitionQueues>>,
+    // Set from splitting the partition queues until they are dropped, also while a read holds them
+    pub(crate) queues_split: AtomicBool,
+    // Partitions ever split off the main queue: every assignment has to route them back to it
+    pub(crate) split_partitions: Mutex<Vec<i32>>,
+    // Page size tuned from the pages read so far (used when the UI asks for no particular limit)
+    pub page_sizer: Mutex<PageSizer>,
+    // Held by a page read from before it takes the reader until it is done, and by filter changes:
+    // concurrent pages would split records between them and a reset can't happen mid-read
+    pub reads: Arc<tokio::sync::Mutex<()>>,
 }
 
 impl Kafka {
-    /// Construct a Kafka object with empty state.
+    /// Construct a Kafka object with empty state. Needs a Tokio runtime context (see `create_stream_consumer`).
     pub fn new(config: KafkaConfig) -> anyhow::Result<Self> {
-        let consumer = super::consumer::create_consumer(&config)?;
-        // Initialize proto decoder if requested
-        let proto_decoder = if matches!(config.message_type, MessageType::Protobuf) {
-            // Prefer using cached descriptors (by key) if provided by UI
-            if let Some(key) = config.proto_descriptor_key.as_ref() {
-                if let Some(dec) = crate::proto_decoder::decoder_from_cache(key, config.proto_message_full_name.clone()) {
-                    Some(dec)
-                } else {
-                    // Fall back to proto files path if cache miss
-                    let path = config
-                        .proto_schema_path
-                        .as_ref()
-                        .ok_or_else(|| anyhow::anyhow!(
-                            "Protobuf message_type selected but neither valid proto_descriptor_key nor proto_schema_path provided"
-                        ))?;
-                    match crate::proto_decoder::ProtoDecoder::from_proto_files(
-                        vec![path.clone()],
-                        config.proto_message_full_name.clone(),
-                    ) {
-                        Ok(dec) => Some(dec),
-                        Err(e) => {
-                            return Err(anyhow::anyhow!("Failed to initialize proto decoder: {}", e));
-                        }
-                    }
-                }
-            } else {
-                // No cache key: require proto path and build
-                let path = config
-                    .proto_schema_path
-                    .as_ref()
-                    .ok_or_else(|| anyhow::anyhow!(
-                        "Protobuf message_type selected but no proto_schema_path provided"
-                    ))?;
-                match crate::proto_decoder::ProtoDecoder::from_proto_files(
-                    vec![path.clone()],
-                    config.proto_message_full_name.clone(),
-                ) {
-                    Ok(dec) => Some(dec),
-                    Err(e) => {
-                        return Err(anyhow::anyhow!("Failed to initialize proto decoder: {}", e));
-                    }
-                }
-            }
-        } else {
-            None
-        };
+        Self::create(config, None)
+    }
+
+    /// Like `new`, reusing protobuf descriptors other connections already linked from the same .proto sources.
+    pub fn with_descriptors(config: KafkaConfig, descriptors: &DescriptorRegistry) -> anyhow::Result<Self> {
+        Self::create(config, Some(descriptors))
+    }
+
+    fn create(config: KafkaConfig, descriptors: Option<&DescriptorRegistry>) -> anyhow::Result<Self> {
+        let consumer = super::consumer::create_stream_consumer(&config)?;
+        let codec = Self::build_codec_with(&config, descriptors)?;
+        let raw_cache = RawCache::from_megabytes(config.raw_cache_mb).map(Arc::new);
         Ok(Self {
+            filters: Mutex::new(ReadFilters::from_config(&config)),
             config,
             consumer: Arc::new(consumer),
             assigned: AtomicBool::new(false),
             end_offsets: Mutex::new(HashMap::new()),
             partitions: Mutex::new(Vec::new()),
             done_partitions: Mutex::new(HashSet::new()),
+            start_offsets: Mutex::new(HashMap::new()),
+            delivered: Mutex::new(HashMap::new()),
             buffers: Mutex::new(HashMap::new()),
+            codec,
+            raw_cache,
+            partition_queues: Mutex::new(None),
+            queues_split: AtomicBool::new(false),
+            split_partitions: Mutex::new(Vec::new()),
+            page_sizer: Mutex::new(PageSizer::default()),
+            reads: Arc::new(tokio::sync::Mutex::new(())),
+        })
+    }
+
+    /// Latest librdkafka statistics of the reading consumer (reported every `STATS_INTERVAL_MS` while reading).
+    pub fn client_stats(&self) -> Option<ClientStats> {
+        self.consumer.context().latest_stats()
+    }
+
+    /// UI row for a polled record; its original bytes are kept when the raw cache is enabled.
+    pub fn to_row(&self, m: &BorrowedMessage<'_>) -> (i64, UiMessage) {
+        if let Some(cache) = &self.raw_cache {
+            cache.insert(m);
+        }
+        self.codec.to_ui_message(m)
+    }
+
+    /// Decoding pipeline for a configuration (message and key types, schema sources, masking).
+    pub fn build_codec(config: &KafkaConfig) -> anyhow::Result<MessageCodec> {
+        Self::build_codec_with(config, None)
+    }
+
+    /// `build_codec` taking .proto descriptors from a shared registry when one is given.
+    pub fn build_codec_with(config: &KafkaConfig, descriptors: Option<&DescriptorRegistry>) -> anyhow::Result<MessageCodec> {
+        // Initialize proto decoder if requested
+        let key_type = config.key_type.unwrap_or_default();
+        // Auto detection only tries protobuf when some schema source is configured
+        let auto_proto = matches!(config.message_type, MessageType::Auto)
+            && (config.proto_schema_path.is_some()
+                || config.proto_descriptor_key.is_some()
+                || SchemaRegistry::from_config(config).is_some());
+        let proto_decoder =
+            if matches!(config.message_type, MessageType::Protobuf) || key_type == KeyType::Protobuf || auto_proto {
+                Some(Self::build_proto_decoder(config, descriptors)?)
+            } else {
+                None
+            };
+        let avro_decoder = if matches!(config.message_type, MessageType::Avro) || key_type == KeyType::Avro {
+            let registry = SchemaRegistry::from_config(config)
+                .ok_or_else(|| anyhow::anyhow!("Avro message or key type selected but schema_registry_url is not set"))?;
+            Some(Arc::new(AvroDecoder::new(registry)))
+        } else if matches!(config.message_type, MessageType::Auto) {
+            SchemaRegistry::from_config(config).map(|registry| Arc::new(AvroDecoder::new(registry)))
+        } else {
+            None
+        };
+        let plugin_decoder = if matches!(config.message_type, MessageType::Plugin) {
+            let path = config
+                .decoder_plugin_path
+                .as_deref()
+                .filter(|p| !p.is_empty())
+                .ok_or_else(|| anyhow::anyhow!("Plugin message_type selected but decoder_plugin_path is not set"))?;
+            Some(Arc::new(PluginDecoder::load(path)?))
+        } else {
+            None
+        };
+        let json_validator = match (&config.message_type, config.json_schema_path.as_deref()) {
+            (MessageType::Json | MessageType::Auto, Some(path)) if !path.is_empty() => Some(Self::build_json_validator(path)?),
+            _ => None,
+        };
+        let masker = match config.masking_rules.as_deref() {
+            Some(rules) => Some(Arc::new(Masker::new(rules)?)).filter(|m| !m.is_empty()),
+            None => None,
+        };
+        Ok(MessageCodec {
+            message_type: config.message_type.clone(),
             proto_decoder,
+            avro_decoder,
+            plugin_decoder,
+            json_validator,
+            compression: config.payload_compression.unwrap_or_default(),
+            key_type,
+            key_message_full_name: config.key_proto_message_full_name.clone(),
+            masker,
+            lazy_decode: config.lazy_decode.unwrap_or(false),
+            extract_columns: Arc::new(config.extract_columns.clone().unwrap_or_default()),
+            message_rules: Arc::new(config.proto_message_rules.clone().unwrap_or_default()),
         })
     }
 
+    /// Build a protobuf decoder from cached descriptors (by key) or from the proto schema path;
+    /// with a schema registry and neither of them, decoding relies on registry schemas alone.
+    fn build_proto_decoder(config: &KafkaConfig, descriptors: Option<&DescriptorRegistry>) -> anyhow::Result<Arc<ProtoDecoder>> {
+        let options = ProtoDecodeOptions {
+            envelope: config.payload_envelope.unwrap_or_default(),
+            enable_repair: config.enable_payload_repair.unwrap_or(false),
+            registry: SchemaRegistry::from_config(config),
+            include_paths: config.proto_include_paths.clone().unwrap_or_default(),
+        };
+        let has_registry = options.registry.is_some();
+        // Prefer the descriptors `parse_proto_metadata` registered under the key the UI passed along
+        if let Some(files) = config.proto_descriptor_key.as_deref().zip(descriptors).and_then(|(key, r)| r.by_key(key)) {
+            return Ok(ProtoDecoder::from_shared_files(files, config.proto_message_full_name.clone(), options));
+        }
+        // With a schema registry, Confluent-framed records decode without local descriptors
+        if has_registry && config.proto_schema_path.is_none() {
+            return Ok(ProtoDecoder::from_linked_files(Vec::new(), config.proto_message_full_name.clone(), options));
+        }
+        // Fall back to proto files path (no cache key or cache miss)
+        let path = config
+            .proto_schema_path
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!(
+                "Protobuf message_type selected but neither valid proto_descriptor_key, proto_schema_path nor schema_registry_url provided"
+            ))?;
+        let selected = config.proto_message_full_name.clone();
+        let decoder = match descriptors {
+            Some(registry) => {
+                let paths = std::slice::from_ref(path);
+                let includes = proto_include_paths(paths, &options.include_paths);
+                expand_proto_paths(paths)
+                    .and_then(|files| registry.register_with(&files, &includes))
+                    .map(|(_, files)| ProtoDecoder::from_shared_files(files, selected, options))
+            }
+            None => ProtoDecoder::from_proto_files_with_options(vec![path.clone()], selected, options),
+        };
+        decoder.map_err(|e| anyhow::anyhow!("Failed to initialize proto decoder: {}", e))
+    }
+
+    /// Load a JSON Schema file for payload validation.
+    fn build_json_validator(path: &str) -> anyhow::Result<Arc<jsonschema::Validator>> {
+        let text = std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("Failed to read JSON Schema '{}': {}", path, e))?;
+        let schema: serde_json::Value =
+            serde_json::from_str(&text).map_err(|e| anyhow::anyhow!("Failed to parse JSON Schema '{}': {}", path, e))?;
+        let validator =
+            jsonschema::validator_for(&schema).map_err(|e| anyhow::anyhow!("Invalid JSON Schema '{}': {}", path, e))?;
+        Ok(Arc::new(validator))
+    }
+
     /// Lightweight helper that decodes key/value according to configured message type.
     pub fn decode(&self, key: Option<&[u8]>, payload: Option<&[u8]>) -> (String, String, Option<String>) {
-        // Key as UTF-8 lossy
-        let key_s = key
-            .map(|k| String::from_utf8_lossy(k).to_string())
-            .unwrap_or_default();
-        // If protobuf configured and decoder available, try to decode to JSON
-        if matches!(self.config.message_type, MessageType::Protobuf) {
-            if let (Some(pd), Some(bytes)) = (self.proto_decoder.as_ref(), payload) {
-                match pd.decode(bytes) {
-                    Ok(json) => return (key_s, json, None),
-                    Err(e) => {
-                        // Failed to decode: return raw text and attach error, but do not stop reading
-                        let raw = String::from_utf8_lossy(bytes).to_string();
-                        return (key_s, raw, Some(format!("Protobuf decode error: {}", e)));
-                    }
-                }
+        self.codec.decode(key, payload)
+    }
+
+    /// Records per page: the requested limit, or one tuned to the time and size of the pages read so far.
+    pub fn page_size(&self, requested: Option<usize>) -> anyhow::Result<usize> {
+        match requested {
+            Some(limit) => Ok(limit),
+            None => Ok(self
+                .page_sizer
+                .lock()
+                .map_err(|e| anyhow::anyhow!("State lock poisoned (page_sizer): {e}"))?
+                .next_size()),
+        }
+    }
+
+    /// Read next batch of messages along with how far into the snapshot we are.
+    pub async fn consume_next(&self, limit: usize) -> anyhow::Result<ConsumeBatch> {
+        let started = std::time::Instant::now();
+        let messages = match self.next_messages(limit).await {
+            Ok(m) => m,
+            // Missing ACLs are a state of the topic, not a transient failure: report it and stop paging
+            Err(e) if e.downcast_ref::<AccessDenied>().is_some() => {
+                return Ok(ConsumeBatch {
+                    messages: Vec::new(),
+                    progress: self.progress()?,
+                    end_of_snapshot: true,
+                    access: TopicAccess::Unauthorized,
+                });
+            }
+            Err(e) => return Err(e),
+        };
+        self.page_sizer
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (page_sizer): {e}"))?
+            .record(limit, &messages, started.elapsed());
+        {
+            let mut delivered = self
+                .delivered
+                .lock()
+                .map_err(|e| anyhow::anyhow!("State lock poisoned (delivered): {e}"))?;
+            for m in &messages {
+                let entry = delivered.entry(m.partition).or_insert((0, m.offset));
+                entry.0 += 1;
+                entry.1 = m.offset;
             }
         }
-        // Fallback to existing decoders
-        let dec = decoder_for(&self.config.message_type);
-        let (_k, v) = dec.decode(None, payload);
-        (key_s, v, None)
+        let mut progress = self.progress()?;
+        let parts = self
+            .partitions
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (partitions): {e}"))?
+            .clone();
+        let end_of_snapshot = self.snapshot_exhausted(&parts)?;
+        if end_of_snapshot {
+            // Offset gaps (compaction, transaction markers) keep counts below the window size
+            progress.percent = 100.0;
+        }
+        Ok(ConsumeBatch { messages, progress, end_of_snapshot, access: TopicAccess::Ok })
+    }
+
+    /// True when every partition reached its snapshot end and nothing is left in the buffers.
+    /// An empty batch without this flag only means the readers were idle for a while.
+    fn snapshot_exhausted(&self, parts: &[i32]) -> anyhow::Result<bool> {
+        let all_done = {
+            let done = self
+                .done_partitions
+                .lock()
+                .map_err(|e| anyhow::anyhow!("State lock poisoned (done_partitions): {e}"))?;
+            parts.iter().all(|p| done.contains(p))
+        };
+        if !all_done {
+            return Ok(false);
+        }
+        let bufs = self
+            .buffers
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (buffers): {e}"))?;
+        Ok(bufs.values().all(|q| q.is_empty()))
+    }
+
+    /// Per-partition delivered counts vs the snapshot window [start, end).
+    pub fn progress(&self) -> anyhow::Result<ConsumeProgress> {
+        let parts = self
+            .partitions
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (partitions): {e}"))?
+            .clone();
+        let ends = self
+            .end_offsets
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (end_offsets): {e}"))?
+            .clone();
+        let starts = self
+            .start_offsets
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (start_offsets): {e}"))?
+            .clone();
+        let delivered = self
+            .delivered
+            .lock()
+            .map_err(|e| anyhow::anyhow!("State lock poisoned (delivered): {e}"))?
+            .clone();
+
+        let mut partitions = Vec::with_capacity(parts.len());
+        let (mut total, mut done_total) = (0u64, 0u64);
+        for p in parts {
+            let end = ends.get(&p).copied().unwrap_or(0);
+            let start = starts.get(&p).copied().unwrap_or(end).min(end);
+            let (count, last) = delivered.get(&p).copied().map_or((0, None), |(c, o)| (c, Some(o)));
+            let window = (end - start).max(0) as u64;
+            total += window;
+            // Compacted topics have offset gaps, so counts are capped by the window
+            done_total += count.min(window);
+            partitions.push(PartitionProgress { partition: p, start, end, last_offset: last, delivered: count });
+        }
+        let percent = if total == 0 { 100.0 } else { done_total as f64 * 100.0 / total as f64 };
+        Ok(ConsumeProgress { partitions, delivered: done_total, total, percent })
     }
 
     /// Read next batch of messages according to the selected strategy.
-    pub fn consume_next(&self, limit: usize) -> anyhow::Result<Vec<UiMessage>> {
+    async fn next_messages(&self, limit: usize) -> anyhow::Result<Vec<UiMessage>> {
         self.ensure_assigned()?;
         let ends = self
             .end_offsets
@@ -123,37 +353,17 @@ impl Kafka {
             .map_err(|e| anyhow::anyhow!("State lock poisoned (partitions): {e}"))?
             .clone();
         // If all partitions are marked done, return only when internal buffers are fully drained.
-        {
-            let all_done = {
-                let done = self
-                    .done_partitions
-                    .lock()
-                    .map_err(|e| anyhow::anyhow!("State lock poisoned (done_partitions): {e}"))?;
-                parts.iter().all(|p| done.contains(p))
-            };
-            if all_done {
-                let has_buffered = {
-                    let bufs = self
-                        .buffers
-                        .lock()
-                        .map_err(|e| anyhow::anyhow!("State lock poisoned (buffers): {e}"))?;
-                    bufs.values().any(|q| !q.is_empty())
-                };
-                if !has_buffered {
-                    return Ok(Vec::new());
-                }
-            }
+        if self.snapshot_exhausted(&parts)? {
+            return Ok(Vec::new());
         }
 
-        let partitions_all = self
-            .config
-            .partition
-            .as_deref()
-            .map(|s| s == "all")
-            .unwrap_or(true);
+        // A listed subset is merged like "all"; only a single selected partition reads sequentially
+        let filters = self.filters()?;
+        let partitions_all = filters.partitions.as_ref().is_some_and(|l| !l.is_empty())
+            || filters.partition.as_deref().map(|s| s == "all").unwrap_or(true);
         if !partitions_all || parts.len() <= 1 {
-            return reader::consume_sequential(self, &ends, &parts, limit);
+            return reader::consume_sequential(self, &ends, &parts, limit).await;
         }
-        reader::consume_merge(self, &ends, &parts, limit)
+        reader::consume_merge(self, &ends, &parts, limit).await
     }
 }
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use super::decoder::encode_simple_key;
+use super::partitioner::java_partition_for_key;
+use super::service::Kafka;
+use super::types::UiMessage;
+use crate::utils::json::json_path_get;
+
+/// Payload fields tried for the operation when no path is configured (Debezium, CloudEvents, common event shapes).
+const OP_FIELDS: &[&str] = &[".op", ".payload.op", ".operation", ".action", ".eventType", ".event_type", ".type"];
+/// Payload fields tried for the status when no path is configured.
+const STATUS_FIELDS: &[&str] = &[".status", ".state", ".after.status", ".payload.after.status", ".after.state"];
+
+/// Which part of a topic to search for a key's records.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TimelineWindow {
+    /// Epoch ms; from the log start when omitted
+    #[serde(rename = "from_time", alias = "fromTime")]
+    pub from_time: Option<i64>,
+    /// Epoch ms (exclusive); up to the current end when omitted
+    #[serde(rename = "to_time", alias = "toTime")]
+    pub to_time: Option<i64>,
+    /// Scan every partition instead of only the one the Java default partitioner picks for the key's bytes;
+    /// needed when producers used a custom partitioner. Keys whose bytes their text does not determine
+    /// (protobuf, avro and uuid key types) always scan every partition
+    #[serde(default, rename = "all_partitions", alias = "allPartitions")]
+    pub all_partitions: bool,
+    /// jq-style path of the operation field; well-known field names are tried when omitted
+    #[serde(rename = "op_path", alias = "opPath")]
+    pub op_path: Option<String>,
+    /// jq-style path of the status field; well-known field names are tried when omitted
+    #[serde(rename = "status_path", alias = "statusPath")]
+    pub status_path: Option<String>,
+    /// Records returned at most, the newest ones (default 1000)
+    pub limit: Option<usize>,
+}
+
+/// One record of a key's history.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEntry {
+    /// Epoch ms; None when the record carries no timestamp
+    #[serde(rename = "timestamp_ms")]
+    pub timestamp_ms: Option<i64>,
+    pub op: Option<String>,
+    pub status: Option<String>,
+    pub message: UiMessage,
+}
+
+/// All records of one key, oldest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyTimeline {
+    pub key: String,
+    pub partitions: Vec<i32>,
+    /// Records read to find the key's records
+    pub scanned: usize,
+    pub entries: Vec<TimelineEntry>,
+    /// True when older records of the key were dropped to stay within the limit
+    pub truncated: bool,
+}
+
+fn field_text(payload: &serde_json::Value, custom: Option<&str>, defaults: &[&str]) -> Option<String> {
+    let found = match custom {
+        Some(path) => json_path_get(payload, path),
+        None => defaults.iter().find_map(|p| json_path_get(payload, p).filter(|v| !v.is_null())),
+    }?;
+    match found {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s),
+        other => Some(other.to_string()),
+    }
+}
+
+impl TimelineEntry {
+    /// Wrap a record, pulling the operation and status out of a JSON payload.
+    pub fn new(message: UiMessage, window: &TimelineWindow) -> Self {
+        let timestamp_ms = chrono::DateTime::parse_from_rfc3339(&message.timestamp)
+            .map(|d| d.timestamp_millis())
+            .ok();
+        let payload = serde_json::from_str::<serde_json::Value>(&message.message).ok();
+        let (op, status) = match &payload {
+            Some(p) => (
+                field_text(p, window.op_path.as_deref(), OP_FIELDS),
+                field_text(p, window.status_path.as_deref(), STATUS_FIELDS),
+            ),
+            // A tombstone ends the entity
+            None if message.is_tombstone => (Some("delete".into()), None),
+            None => (None, None),
+        };
+        Self { timestamp_ms, op, status, message }
+    }
+}
+
+impl Kafka {
+    /// The newest records (up to the limit) with the given (decoded) key in a time window of the configured
+    /// topic, ordered by time. Only the partition the Java default partitioner picks for the key's bytes is
+    /// read unless `all_partitions` is set or the key type does not determine them.
+    pub fn key_timeline(&self, key: &str, window: &TimelineWindow) -> anyhow::Result<KeyTimeline> {
+        let limit = window.limit.unwrap_or(1000).max(1);
+        let all: Vec<i32> = self.watermarks(&self.config.topic)?.into_iter().map(|w| w.partition).collect();
+        if all.is_empty() {
+            return Err(anyhow::anyhow!("Topic has no partitions"));
+        }
+        let key_bytes = encode_simple_key(self.config.key_type.unwrap_or_default(), key);
+        let partitions = match key_bytes {
+            Some(bytes) if !window.all_partitions => vec![java_partition_for_key(&bytes, all.len() as i32)],
+            _ => all,
+        };
+        let ranges = Self::ranges_for_window(&self.config, Some(&partitions), window.from_time, window.to_time, false)?;
+
+        // The whole window is read to find the newest records; at most twice the limit is held at a time
+        let mut entries = Vec::new();
+        let mut truncated = false;
+        let mut scanned = 0;
+        self.read_ranges(&ranges, |ui| {
+            scanned += 1;
+            if ui.key == key {
+                entries.push(TimelineEntry::new(ui, window));
+                if entries.len() >= limit.saturating_mul(2) {
+                    truncated |= keep_newest(&mut entries, limit);
+                }
+            }
+            Ok(())
+        })?;
+        truncated |= keep_newest(&mut entries, limit);
+        Ok(KeyTimeline { key: key.to_string(), partitions, scanned, entries, truncated })
+    }
+}
+
+/// Sort entries by time and drop all but the newest `limit`; true when any were dropped.
+pub fn keep_newest(entries: &mut Vec<TimelineEntry>, limit: usize) -> bool {
+    entries.sort_by_key(|e| (e.timestamp_ms.unwrap_or(i64::MAX), e.message.partition, e.message.offset));
+    let excess = entries.len().saturating_sub(limit);
+    entries.drain(..excess);
+    excess > 0
+}
@@ -0,0 +1,597 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use mio::net::TcpListener;
+use mio::{Events, Interest, Poll, Registry, Token, Waker};
+use once_cell::sync::Lazy;
+use ssh2::{CheckResult, HashType, KnownHostFileKind, Session};
+
+use super::metadata_rewrite::{carries_brokers, rewrite_response};
+use super::types::{KafkaConfig, SshTunnelConfig};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const KEEPALIVE_SECS: u32 = 30;
+/// Longest wait of the forwarding thread for a ready socket; bounds how late a keepalive goes out.
+const IDLE_WAIT: Duration = Duration::from_secs(1);
+/// Token of the waker that stops the forwarding thread
+const WAKE: Token = Token(0);
+/// Token of every socket: any readiness triggers a pass over all of them
+const IO: Token = Token(1);
+/// LIBSSH2_ERROR_EAGAIN: a non-blocking call has to be repeated once the socket is ready
+const SSH_EAGAIN: i32 = -37;
+/// Bytes a connection may hold for a slow reader before it stops reading the other side.
+const MAX_PENDING: usize = 4 * 1024 * 1024;
+/// Largest broker-address response buffered for rewriting.
+const MAX_REWRITTEN_FRAME: usize = 64 * 1024 * 1024;
+
+/// Open tunnels by bastion and bootstrap servers; every client of a connection holds its tunnel.
+static TUNNELS: Lazy<Mutex<HashMap<String, Weak<SshTunnel>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// SSH local port forwards to the brokers of one connection. Bootstrap servers get a forward up front; brokers
+/// learned from metadata get theirs when first advertised, with the advertised address rewritten to it.
+/// Closed once the last client holding it is dropped.
+pub struct SshTunnel {
+    /// Local addresses of the bootstrap servers, comma separated
+    bootstrap: String,
+    /// Set by the forwarding thread once the SSH session is unusable
+    broken: Arc<AtomicBool>,
+    shutdown: Arc<AtomicBool>,
+    waker: Waker,
+    pump: Option<JoinHandle<()>>,
+}
+
+impl SshTunnel {
+    /// `bootstrap.servers` for clients that connect through the tunnel.
+    pub fn bootstrap_servers(&self) -> &str {
+        &self.bootstrap
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        let _ = self.waker.wake();
+        if let Some(pump) = self.pump.take() {
+            let _ = pump.join();
+        }
+    }
+}
+
+/// The connection's tunnel, opened on first use; None when the connection is direct.
+pub(crate) fn open(config: &KafkaConfig) -> anyhow::Result<Option<Arc<SshTunnel>>> {
+    let Some(ssh) = config.ssh_tunnel.as_ref().filter(|t| !t.host.trim().is_empty()) else {
+        return Ok(None);
+    };
+    let tls = config.ssl_enabled
+        || config
+            .security_type
+            .as_deref()
+            .is_some_and(|s| s.eq_ignore_ascii_case("ssl") || s.eq_ignore_ascii_case("sasl_ssl"));
+    if tls {
+        return Err(anyhow::anyhow!(
+            "SSH tunnels support PLAINTEXT and SASL_PLAINTEXT connections only: broker addresses inside TLS traffic can't be rewritten"
+        ));
+    }
+    let key = format!("{}@{}:{}/{}", ssh.username.trim(), ssh.host.trim(), ssh.port.unwrap_or(22), config.broker);
+    {
+        let mut tunnels = TUNNELS.lock().map_err(|e| anyhow::anyhow!("Tunnel registry lock poisoned: {e}"))?;
+        tunnels.retain(|_, t| t.strong_count() > 0);
+        if let Some(t) = usable(&tunnels, &key) {
+            return Ok(Some(t));
+        }
+    }
+    // Connecting takes a while: the registry stays available to other connections meanwhile
+    let tunnel = Arc::new(start(ssh, &config.broker)?);
+    let existing = {
+        let mut tunnels = TUNNELS.lock().map_err(|e| anyhow::anyhow!("Tunnel registry lock poisoned: {e}"))?;
+        // Another client of the connection may have opened it in the meantime
+        let existing = usable(&tunnels, &key);
+        if existing.is_none() {
+            tunnels.insert(key, Arc::downgrade(&tunnel));
+        }
+        existing
+    };
+    // An unused new tunnel stops its pump on drop, outside the lock
+    Ok(Some(existing.unwrap_or(tunnel)))
+}
+
+/// The registered tunnel for `key` while it is open and working.
+fn usable(tunnels: &HashMap<String, Weak<SshTunnel>>, key: &str) -> Option<Arc<SshTunnel>> {
+    tunnels.get(key).and_then(Weak::upgrade).filter(|t| !t.broken.load(Ordering::SeqCst))
+}
+
+fn start(ssh: &SshTunnelConfig, brokers: &str) -> anyhow::Result<SshTunnel> {
+    let (session, socket) = connect(ssh)?;
+    let poll = Poll::new()?;
+    let waker = Waker::new(poll.registry(), WAKE)?;
+    let mut forwards = Forwards::new(poll.registry().try_clone()?);
+    let mut bootstrap = Vec::new();
+    for server in brokers.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        // librdkafka also accepts "PLAINTEXT://host:port"
+        let address = server.rsplit("://").next().unwrap_or(server);
+        let (host, port) = match address.rsplit_once(':') {
+            Some((h, p)) => (h.trim_matches(['[', ']']), p.parse().map_err(|_| anyhow::anyhow!("Invalid broker port in '{server}'"))?),
+            None => (address, 9092),
+        };
+        bootstrap.push(format!("127.0.0.1:{}", forwards.local_port(host, port)?));
+    }
+    if bootstrap.is_empty() {
+        return Err(anyhow::anyhow!("No bootstrap servers to tunnel"));
+    }
+    // Woken by the SSH socket; the session itself reads and writes through its own handle of it
+    let mut socket = mio::net::TcpStream::from_std(socket);
+    poll.registry().register(&mut socket, IO, Interest::READABLE | Interest::WRITABLE)?;
+    session.set_blocking(false);
+
+    let broken = Arc::new(AtomicBool::new(false));
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let mut pump = Pump {
+        session,
+        _socket: socket,
+        poll,
+        forwards,
+        opening: VecDeque::new(),
+        conns: Vec::new(),
+        closing: Vec::new(),
+        broken: broken.clone(),
+    };
+    let stop = shutdown.clone();
+    let handle = std::thread::Builder::new()
+        .name("rkui-ssh-tunnel".into())
+        .spawn(move || pump.run(&stop))?;
+    Ok(SshTunnel { bootstrap: bootstrap.join(","), broken, shutdown, waker, pump: Some(handle) })
+}
+
+/// The authenticated session and a second handle of its socket to wait on.
+fn connect(ssh: &SshTunnelConfig) -> anyhow::Result<(Session, TcpStream)> {
+    let host = ssh.host.trim();
+    let port = ssh.port.unwrap_or(22);
+    let addr = (host, port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Cannot resolve SSH host {host}"))?;
+    let tcp = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+        .map_err(|e| anyhow::anyhow!("Cannot reach SSH host {host}:{port}: {e}"))?;
+    let socket = tcp.try_clone()?;
+    let mut session = Session::new()?;
+    session.set_tcp_stream(tcp);
+    // Bounds the handshake and authentication; the forwarding thread never blocks
+    session.set_timeout(CONNECT_TIMEOUT.as_millis() as u32);
+    session.handshake().map_err(|e| anyhow::anyhow!("SSH handshake with {host}:{port} failed: {e}"))?;
+    check_host_key(&session, ssh, host, port)?;
+    authenticate(&session, ssh)?;
+    session.set_keepalive(true, KEEPALIVE_SECS);
+    Ok((session, socket))
+}
+
+fn known_hosts_file() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(Path::new(&home).join(".ssh").join("known_hosts"))
+}
+
+/// SSH host that is neither in ~/.ssh/known_hosts nor confirmed in the tunnel settings. The user checks
+/// `fingerprint` and, when it is the bastion's, saves it as the tunnel's `host_key_fingerprint`.
+#[derive(Debug, Clone)]
+pub struct UnknownHostKey {
+    pub host: String,
+    pub port: u16,
+    /// OpenSSH style, e.g. "SHA256:nThbg6kXUpJWGl7E1IGOCspRomTxdCARLviKw6E5SY8"
+    pub fingerprint: String,
+}
+
+impl fmt::Display for UnknownHostKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Host key of {}:{} is unknown ({}); confirm it or add the host to ~/.ssh/known_hosts",
+            self.host, self.port, self.fingerprint
+        )
+    }
+}
+
+impl std::error::Error for UnknownHostKey {}
+
+/// Accept a host whose key matches ~/.ssh/known_hosts, or the fingerprint the user confirmed when it isn't listed.
+fn check_host_key(session: &Session, ssh: &SshTunnelConfig, host: &str, port: u16) -> anyhow::Result<()> {
+    if let Some(file) = known_hosts_file().filter(|f| f.exists()) {
+        let mut known = session.known_hosts()?;
+        known
+            .read_file(&file, KnownHostFileKind::OpenSSH)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", file.display()))?;
+        let (key, _) = session.host_key().ok_or_else(|| anyhow::anyhow!("SSH host {host} sent no host key"))?;
+        match known.check_port(host, port, key) {
+            CheckResult::Match => return Ok(()),
+            CheckResult::Mismatch => {
+                return Err(anyhow::anyhow!(
+                    "Host key of {host}:{port} does not match {}; refusing to connect",
+                    file.display()
+                ))
+            }
+            CheckResult::Failure => return Err(anyhow::anyhow!("Failed to check the host key of {host}:{port}")),
+            CheckResult::NotFound => {}
+        }
+    }
+    let hash = session
+        .host_key_hash(HashType::Sha256)
+        .ok_or_else(|| anyhow::anyhow!("SSH host {host} sent no host key"))?;
+    let fingerprint = format!("SHA256:{}", base64::engine::general_purpose::STANDARD_NO_PAD.encode(hash));
+    if ssh.host_key_fingerprint.as_deref().map(str::trim) == Some(fingerprint.as_str()) {
+        return Ok(());
+    }
+    Err(UnknownHostKey { host: host.to_string(), port, fingerprint }.into())
+}
+
+/// Private key, then password, then the SSH agent.
+fn authenticate(session: &Session, ssh: &SshTunnelConfig) -> anyhow::Result<()> {
+    let user = ssh.username.trim();
+    if user.is_empty() {
+        return Err(anyhow::anyhow!("SSH tunnel needs a username"));
+    }
+    let key = ssh.private_key_path.as_deref().map(str::trim).filter(|k| !k.is_empty());
+    let result = if let Some(key) = key {
+        if !Path::new(key).exists() {
+            return Err(anyhow::anyhow!("SSH private key not found: {key}"));
+        }
+        let passphrase = ssh.private_key_passphrase.as_deref().filter(|p| !p.is_empty());
+        session.userauth_pubkey_file(user, None, Path::new(key), passphrase)
+    } else if let Some(password) = ssh.password.as_deref() {
+        session.userauth_password(user, password)
+    } else {
+        session.userauth_agent(user)
+    };
+    result.map_err(|e| anyhow::anyhow!("SSH authentication as {user}@{} failed: {e}", ssh.host.trim()))?;
+    if !session.authenticated() {
+        return Err(anyhow::anyhow!("SSH authentication as {user}@{} failed", ssh.host.trim()));
+    }
+    Ok(())
+}
+
+/// Local listeners and the remote address each one forwards to.
+struct Forwards {
+    /// Where the listeners and their accepted connections are registered for readiness
+    registry: Registry,
+    listeners: Vec<(TcpListener, String, u16)>,
+    by_remote: HashMap<(String, u16), u16>,
+}
+
+impl Forwards {
+    fn new(registry: Registry) -> Self {
+        Self { registry, listeners: Vec::new(), by_remote: HashMap::new() }
+    }
+
+    /// Local port forwarding to `host:port`, listening on a new one if there is none yet.
+    fn local_port(&mut self, host: &str, port: u16) -> anyhow::Result<u16> {
+        if let Some(local) = self.by_remote.get(&(host.to_string(), port)) {
+            return Ok(*local);
+        }
+        let mut listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))?;
+        self.registry.register(&mut listener, IO, Interest::READABLE)?;
+        let local = listener.local_addr()?.port();
+        self.listeners.push((listener, host.to_string(), port));
+        self.by_remote.insert((host.to_string(), port), local);
+        Ok(local)
+    }
+}
+
+/// Moves the bytes of every forwarded connection; owns the SSH session, which isn't safe to share.
+/// Nothing here blocks: channel opens and closes are resumed on later passes, and the thread sleeps in
+/// `poll` once a pass moved nothing.
+struct Pump {
+    session: Session,
+    /// Registered handle of the session's socket; kept so the registration lives as long as the session
+    _socket: mio::net::TcpStream,
+    poll: Poll,
+    forwards: Forwards,
+    /// Accepted connections waiting for their SSH channel, oldest first
+    opening: VecDeque<Opening>,
+    conns: Vec<Conn>,
+    /// Channels of finished connections whose close hasn't gone out yet
+    closing: Vec<ssh2::Channel>,
+    broken: Arc<AtomicBool>,
+}
+
+/// A local connection whose channel to `host:port` is being opened.
+struct Opening {
+    stream: mio::net::TcpStream,
+    host: String,
+    port: u16,
+    since: Instant,
+}
+
+fn would_block(e: &ssh2::Error) -> bool {
+    e.code() == ssh2::ErrorCode::Session(SSH_EAGAIN)
+}
+
+impl Pump {
+    fn run(&mut self, shutdown: &AtomicBool) {
+        let mut events = Events::with_capacity(64);
+        let mut keepalive = Instant::now();
+        while !shutdown.load(Ordering::SeqCst) {
+            let mut busy = self.accept();
+            busy |= self.open_channels();
+            for conn in &mut self.conns {
+                busy |= conn.pump(&mut self.forwards);
+            }
+            let (closed, open) = std::mem::take(&mut self.conns).into_iter().partition(|c| c.closed);
+            self.conns = open;
+            self.closing.extend(closed.into_iter().map(|c: Conn| c.channel));
+            busy |= self.close_channels();
+            if keepalive.elapsed() >= Duration::from_secs(u64::from(KEEPALIVE_SECS)) {
+                keepalive = Instant::now();
+                if let Err(e) = self.session.keepalive_send() {
+                    self.lost(&e);
+                }
+            }
+            if !busy {
+                // Until a socket is ready, the tunnel is dropped or the keepalive may be due
+                match self.poll.poll(&mut events, Some(IDLE_WAIT)) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                    Err(e) => {
+                        log::warn!("SSH tunnel: waiting for sockets failed: {e}");
+                        std::thread::sleep(IDLE_WAIT);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Take new local connections; each gets its own SSH channel to the forward's remote address.
+    fn accept(&mut self) -> bool {
+        let mut busy = false;
+        for (listener, host, port) in &self.forwards.listeners {
+            while let Ok((mut stream, _)) = listener.accept() {
+                busy = true;
+                let _ = stream.set_nodelay(true);
+                match self.forwards.registry.register(&mut stream, IO, Interest::READABLE | Interest::WRITABLE) {
+                    Ok(()) => self.opening.push_back(Opening { stream, host: host.clone(), port: *port, since: Instant::now() }),
+                    Err(e) => log::warn!("SSH tunnel: cannot watch a connection to {host}:{port}: {e}"),
+                }
+            }
+        }
+        busy
+    }
+
+    /// Open the channels of accepted connections, oldest first. libssh2 opens one channel at a time and
+    /// resumes a pending open when called again, so a slow open holds up the opens behind it only.
+    fn open_channels(&mut self) -> bool {
+        let mut busy = false;
+        while let Some(pending) = self.opening.pop_front() {
+            match self.session.channel_direct_tcpip(&pending.host, pending.port, None) {
+                Ok(channel) => self.conns.push(Conn::new(pending.stream, channel)),
+                Err(e) if would_block(&e) => {
+                    if pending.since.elapsed() <= CONNECT_TIMEOUT {
+                        self.opening.push_front(pending);
+                        return busy;
+                    }
+                    // Give up on the connection. libssh2 would resume the abandoned open for the next one in line,
+                    // so drop those too; their clients reconnect, and new clients get a new tunnel
+                    if !self.broken.swap(true, Ordering::SeqCst) {
+                        log::warn!("SSH tunnel: no answer to forwarding {}:{}; new clients get a new tunnel", pending.host, pending.port);
+                    }
+                    self.opening.clear();
+                    return true;
+                }
+                Err(e) => {
+                    log::warn!("SSH tunnel: cannot forward to {}:{}: {e}", pending.host, pending.port);
+                    self.lost(&e);
+                }
+            }
+            busy = true;
+        }
+        busy
+    }
+
+    /// Close the channels of finished connections; a close the session can't send yet is retried next pass.
+    fn close_channels(&mut self) -> bool {
+        let before = self.closing.len();
+        self.closing.retain_mut(|channel| matches!(channel.close(), Err(e) if would_block(&e)));
+        self.closing.len() < before
+    }
+
+    /// Mark the tunnel broken when `e` means the SSH connection is gone; new clients then open a fresh one.
+    fn lost(&self, e: &ssh2::Error) {
+        // LIBSSH2_ERROR_SOCKET_SEND, _SOCKET_DISCONNECT, _TIMEOUT, _SOCKET_TIMEOUT, _SOCKET_RECV
+        if matches!(e.code(), ssh2::ErrorCode::Session(-7 | -13 | -9 | -30 | -43)) {
+            self.broken.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+/// One Kafka connection through the tunnel. Requests are passed on as they are; responses naming brokers
+/// are buffered whole and rewritten, everything else streams through.
+struct Conn {
+    local: mio::net::TcpStream,
+    channel: ssh2::Channel,
+    /// Requests whose responses name brokers: correlation id -> (api key, api version)
+    inflight: HashMap<i32, (i16, i16)>,
+    /// Received from the client, not yet scanned for request headers
+    requests: Vec<u8>,
+    /// Bytes of the current request left to pass without scanning
+    request_rest: usize,
+    /// Received from the broker, not yet scanned for response headers
+    responses: Vec<u8>,
+    response_rest: usize,
+    to_broker: Vec<u8>,
+    to_client: Vec<u8>,
+    /// The broker side is done; the connection closes once `to_client` is flushed
+    broker_closed: bool,
+    closed: bool,
+}
+
+/// Append what `from` has to `buf`: true when anything arrived, Err once it is closed or failed.
+fn read_some(from: &mut impl Read, buf: &mut Vec<u8>) -> Result<bool, ()> {
+    let mut chunk = [0u8; 32 * 1024];
+    match from.read(&mut chunk) {
+        Ok(0) => Err(()),
+        Ok(n) => {
+            buf.extend_from_slice(&chunk[..n]);
+            Ok(true)
+        }
+        Err(e) if e.kind() == ErrorKind::WouldBlock => Ok(false),
+        Err(_) => Err(()),
+    }
+}
+
+/// Write as much of `buf` as `to` takes: true when anything was written, Err once it is closed or failed.
+fn write_some(to: &mut impl Write, buf: &mut Vec<u8>) -> Result<bool, ()> {
+    if buf.is_empty() {
+        return Ok(false);
+    }
+    match to.write(buf) {
+        Ok(0) => Err(()),
+        Ok(n) => {
+            buf.drain(..n);
+            Ok(true)
+        }
+        Err(e) if e.kind() == ErrorKind::WouldBlock => Ok(false),
+        Err(_) => Err(()),
+    }
+}
+
+impl Conn {
+    fn new(local: mio::net::TcpStream, channel: ssh2::Channel) -> Self {
+        Self {
+            local,
+            channel,
+            inflight: HashMap::new(),
+            requests: Vec::new(),
+            request_rest: 0,
+            responses: Vec::new(),
+            response_rest: 0,
+            to_broker: Vec::new(),
+            to_client: Vec::new(),
+            broker_closed: false,
+            closed: false,
+        }
+    }
+
+    /// Move what is ready in both directions; true when anything moved.
+    fn pump(&mut self, forwards: &mut Forwards) -> bool {
+        let moved = self.exchange(forwards);
+        self.closed = moved.is_err();
+        moved.unwrap_or(true)
+    }
+
+    fn exchange(&mut self, forwards: &mut Forwards) -> Result<bool, ()> {
+        let mut busy = false;
+        if !self.broker_closed {
+            if self.to_broker.len() < MAX_PENDING && read_some(&mut self.local, &mut self.requests)? {
+                busy = true;
+                self.scan_requests()?;
+            }
+            busy |= write_some(&mut self.channel, &mut self.to_broker)?;
+            if self.to_client.len() < MAX_PENDING {
+                match read_some(&mut self.channel, &mut self.responses) {
+                    Ok(true) => {
+                        busy = true;
+                        self.scan_responses(forwards)?;
+                    }
+                    Ok(false) => {}
+                    // Hand the client what the broker sent before closing
+                    Err(()) => {
+                        busy = true;
+                        self.broker_closed = true;
+                    }
+                }
+            }
+        }
+        busy |= write_some(&mut self.local, &mut self.to_client)?;
+        if self.broker_closed && self.to_client.is_empty() {
+            return Err(());
+        }
+        Ok(busy)
+    }
+
+    /// Note the requests whose responses name brokers and queue everything for the broker.
+    fn scan_requests(&mut self) -> Result<(), ()> {
+        loop {
+            if self.request_rest > 0 {
+                let n = self.request_rest.min(self.requests.len());
+                self.to_broker.extend(self.requests.drain(..n));
+                self.request_rest -= n;
+                if self.requests.is_empty() {
+                    return Ok(());
+                }
+                continue;
+            }
+            // size, api key, api version, correlation id
+            let Some(header) = self.requests.get(..12) else { return Ok(()) };
+            let size = i32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+            let api_key = i16::from_be_bytes([header[4], header[5]]);
+            let api_version = i16::from_be_bytes([header[6], header[7]]);
+            let correlation_id = i32::from_be_bytes([header[8], header[9], header[10], header[11]]);
+            if size < 8 {
+                return Err(());
+            }
+            if carries_brokers(api_key) {
+                self.inflight.insert(correlation_id, (api_key, api_version));
+            }
+            self.request_rest = 4 + size as usize;
+        }
+    }
+
+    /// Queue responses for the client, rewriting the broker addresses of those noted in `scan_requests`.
+    fn scan_responses(&mut self, forwards: &mut Forwards) -> Result<(), ()> {
+        loop {
+            if self.response_rest > 0 {
+                let n = self.response_rest.min(self.responses.len());
+                self.to_client.extend(self.responses.drain(..n));
+                self.response_rest -= n;
+                if self.responses.is_empty() {
+                    return Ok(());
+                }
+                continue;
+            }
+            // size, correlation id
+            let Some(header) = self.responses.get(..8) else { return Ok(()) };
+            let size = i32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+            let correlation_id = i32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+            if size < 4 {
+                return Err(());
+            }
+            let size = size as usize;
+            let Some(&(api_key, api_version)) = self.inflight.get(&correlation_id) else {
+                self.response_rest = 4 + size;
+                continue;
+            };
+            if size > MAX_REWRITTEN_FRAME {
+                self.inflight.remove(&correlation_id);
+                self.response_rest = 4 + size;
+                continue;
+            }
+            if self.responses.len() < 4 + size {
+                return Ok(());
+            }
+            self.inflight.remove(&correlation_id);
+            let frame: Vec<u8> = self.responses.drain(..4 + size).collect();
+            let rewritten = rewrite_response(&frame[4..], api_key, api_version, |host, port| {
+                match u16::try_from(port).map_err(anyhow::Error::from).and_then(|p| forwards.local_port(host, p)) {
+                    Ok(local) => ("127.0.0.1".to_string(), i32::from(local)),
+                    Err(e) => {
+                        log::warn!("SSH tunnel: cannot forward broker {host}:{port}: {e}");
+                        (host.to_string(), port)
+                    }
+                }
+            });
+            match rewritten {
+                Ok(body) => {
+                    self.to_client.extend_from_slice(&(body.len() as i32).to_be_bytes());
+                    self.to_client.extend_from_slice(&body);
+                }
+                Err(e) => {
+                    log::warn!("SSH tunnel: broker addresses left as advertised: {e}");
+                    self.to_client.extend_from_slice(&frame);
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,14 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use super::decoder::MessageType;
+use super::compression::PayloadCompression;
+use super::decoder::{KeyType, MessageType};
+use super::partitioner::PartitionStrategy;
+use crate::proto_decoder::PayloadEnvelope;
 
 /// UI-facing message representation. Keep it small and serializable.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UiMessage {
     pub id: String,
     pub partition: i32,
@@ -12,6 +17,164 @@ pub struct UiMessage {
     pub message: String,
     pub timestamp: String,
     pub decoding_error: Option<String>,
+    /// Payload size in bytes (0 for tombstones)
+    pub size: usize,
+    /// False when the payload was skipped by lazy decode; fetch it via `get_message_at`
+    pub decoded: bool,
+    /// Values of configured extraction columns (name -> value); None when no columns are configured
+    pub extracted: Option<HashMap<String, serde_json::Value>>,
+    /// True when the payload bytes were modified (0x0A repair) to make them decode
+    pub payload_repaired: bool,
+    /// Schema Registry id from the Confluent envelope (when detected)
+    pub schema_id: Option<u32>,
+    /// Protobuf message index path from the Confluent envelope (when detected)
+    pub message_indexes: Option<Vec<i64>>,
+    /// Record headers as (name, UTF-8 lossy value); null values become empty strings
+    pub headers: Vec<(String, String)>,
+    /// Why the JSON payload does not conform to the configured JSON Schema (None when valid or not checked)
+    #[serde(default)]
+    pub validation_error: Option<String>,
+    /// Compression stripped from the value before decoding ("gzip" | "zstd" | "snappy")
+    #[serde(default)]
+    pub payload_compression: Option<String>,
+    /// Decoder that succeeded under the "auto" message type: json | avro | protobuf | text | hex
+    #[serde(default)]
+    pub detected_format: Option<String>,
+    /// Protobuf message type picked by scoring the schema because none was selected
+    #[serde(default)]
+    pub guessed_message_type: Option<String>,
+    /// The record has a null payload (a delete marker on compacted topics), as opposed to an empty one
+    #[serde(default)]
+    pub is_tombstone: bool,
+    /// Key and value are exactly their record bytes as UTF-8 (not decoded, inflated or masked),
+    /// so an export of them produces the same record again (see `import_messages`)
+    #[serde(default)]
+    pub verbatim: bool,
+}
+
+/// Reading position of one partition within the session snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartitionProgress {
+    pub partition: i32,
+    /// First offset of the snapshot window
+    pub start: i64,
+    /// Snapshot end (high watermark at assignment, exclusive)
+    pub end: i64,
+    /// Offset of the last record returned to the UI
+    pub last_offset: Option<i64>,
+    pub delivered: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsumeProgress {
+    pub partitions: Vec<PartitionProgress>,
+    pub delivered: u64,
+    /// Number of offsets in the snapshot window across partitions
+    pub total: u64,
+    pub percent: f64,
+}
+
+/// Whether the current credentials can read a topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum TopicAccess {
+    #[default]
+    #[serde(rename = "ok")] Ok,
+    /// The broker answered with an authorization error (ACLs)
+    #[serde(rename = "unauthorized")] Unauthorized,
+    /// Metadata reported another error for the topic (e.g. leader not available)
+    #[serde(rename = "error")] Error,
+}
+
+/// Topic listing entry with its access state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicInfo {
+    pub name: String,
+    pub partitions: usize,
+    pub access: TopicAccess,
+    pub error: Option<String>,
+}
+
+/// Result of `consume_next_messages`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsumeBatch {
+    pub messages: Vec<UiMessage>,
+    pub progress: ConsumeProgress,
+    /// Snapshot fully read; further calls will return nothing (an empty batch without it just means idle)
+    pub end_of_snapshot: bool,
+    /// Unauthorized when the broker refused reads; messages are empty and end_of_snapshot is set
+    pub access: TopicAccess,
+}
+
+/// Named jq/JSONPath expression evaluated against the decoded payload, e.g. `orderId` = `.order.id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractColumn {
+    pub name: String,
+    #[serde(alias = "path")]
+    pub expr: String,
+}
+
+/// Redaction applied to decoded payloads before they leave the decode pipeline.
+/// With only `path`, the addressed JSON value is replaced; with only `pattern`, every regex match
+/// in the payload text is replaced; with both, the pattern is applied to string values at `path`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaskingRule {
+    /// jq-style path (`.user.email`, `.items[*].card`)
+    pub path: Option<String>,
+    /// Regular expression; `$1`-style groups may be used in the replacement
+    pub pattern: Option<String>,
+    /// Defaults to "***"
+    pub replacement: Option<String>,
+}
+
+/// Selects the protobuf message type for records on multi-schema topics (RecordNameStrategy).
+/// A rule matches when all of its provided conditions match; the first matching rule wins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageTypeRule {
+    /// Schema Registry id from the Confluent envelope
+    #[serde(rename = "schema_id", alias = "schemaId")]
+    pub schema_id: Option<u32>,
+    /// Record header name to inspect, e.g. "type" or "__TypeId__"
+    pub header: Option<String>,
+    /// Expected header value (UTF-8); when omitted, presence of the header is enough
+    #[serde(rename = "header_value", alias = "headerValue")]
+    pub header_value: Option<String>,
+    #[serde(rename = "message_full_name", alias = "messageFullName")]
+    pub message_full_name: String,
+}
+
+/// Per-connection producer defaults. Unset fields keep librdkafka defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProducerDefaults {
+    /// "none" | "gzip" | "snappy" | "lz4" | "zstd"
+    #[serde(rename = "compression_type", alias = "compressionType")]
+    pub compression_type: Option<String>,
+    /// "0" | "1" | "all" (or "-1")
+    pub acks: Option<String>,
+    #[serde(rename = "linger_ms", alias = "lingerMs")]
+    pub linger_ms: Option<u32>,
+    /// Headers attached to every produced record, e.g. ("x-source", "rkui");
+    /// a record header with the same name takes precedence
+    #[serde(default, rename = "default_headers", alias = "defaultHeaders")]
+    pub default_headers: Vec<(String, String)>,
+}
+
+/// SSH bastion a connection reaches its brokers through.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SshTunnelConfig {
+    pub host: String,
+    /// Defaults to 22
+    pub port: Option<u16>,
+    pub username: String,
+    /// Used when no private key is given; with neither, the SSH agent is asked
+    pub password: Option<String>,
+    /// OpenSSH or PEM private key file
+    #[serde(rename = "private_key_path", alias = "privateKeyPath")]
+    pub private_key_path: Option<String>,
+    #[serde(rename = "private_key_passphrase", alias = "privateKeyPassphrase")]
+    pub private_key_passphrase: Option<String>,
+    /// Host key the user confirmed for a bastion missing from ~/.ssh/known_hosts, e.g. "SHA256:nThbg6kX..."
+    #[serde(default, rename = "host_key_fingerprint", alias = "hostKeyFingerprint")]
+    pub host_key_fingerprint: Option<String>,
 }
 
 /// Kafka connection and reading configuration coming from the UI.
@@ -26,6 +189,21 @@ pub struct KafkaConfig {
     pub truststore_location: Option<String>,
     #[serde(rename = "truststore_password", alias = "truststorePassword")]
     pub truststore_password: Option<String>,
+    /// Keystore (.p12/.pfx/.jks) holding the client certificate and private key for mutual TLS;
+    /// `ssl_key_password` unlocks the key when it differs from the store password
+    #[serde(rename = "keystore_location", alias = "keystoreLocation")]
+    pub keystore_location: Option<String>,
+    #[serde(rename = "keystore_password", alias = "keystorePassword")]
+    pub keystore_password: Option<String>,
+    /// Verify that the broker certificate matches its hostname (librdkafka default: on)
+    #[serde(rename = "ssl_endpoint_identification", alias = "sslEndpointIdentification")]
+    pub ssl_endpoint_identification: Option<bool>,
+    /// Raw librdkafka properties set last on every client, overriding rkui's own settings
+    #[serde(rename = "custom_properties", alias = "customProperties")]
+    pub custom_properties: Option<HashMap<String, String>>,
+    /// Reach the brokers through SSH local port forwards (PLAINTEXT and SASL_PLAINTEXT only)
+    #[serde(rename = "ssh_tunnel", alias = "sshTunnel")]
+    pub ssh_tunnel: Option<SshTunnelConfig>,
     /// Optional selection of SSL mode when using SSL/SASL_SSL: "java_like" | "classic"
     #[serde(rename = "ssl_mode", alias = "sslMode")]
     pub ssl_mode: Option<String>,
@@ -43,22 +221,46 @@ pub struct KafkaConfig {
     /// Optional security type sent by the UI: "plaintext" | "ssl" | "sasl_plaintext" | "sasl_ssl"
     #[serde(rename = "security_type", alias = "securityType")]
     pub security_type: Option<String>,
-    /// Optional SASL mechanism (e.g., PLAIN, SCRAM-SHA-256, SCRAM-SHA-512)
+    /// Optional SASL mechanism (e.g., PLAIN, SCRAM-SHA-256, SCRAM-SHA-512, OAUTHBEARER, GSSAPI)
     #[serde(rename = "sasl_mechanism", alias = "saslMechanism")]
     pub sasl_mechanism: Option<String>,
     /// JAAS-like config string; we will parse username/password out of it
     #[serde(rename = "sasl_jaas_config", alias = "saslJaasConfig")]
     pub sasl_jaas_config: Option<String>,
+    /// OAUTHBEARER: OIDC token endpoint, queried with the client credentials grant
+    #[serde(rename = "sasl_oauthbearer_token_endpoint_url", alias = "saslOauthbearerTokenEndpointUrl")]
+    pub sasl_oauthbearer_token_endpoint_url: Option<String>,
+    #[serde(rename = "sasl_oauthbearer_client_id", alias = "saslOauthbearerClientId")]
+    pub sasl_oauthbearer_client_id: Option<String>,
+    #[serde(rename = "sasl_oauthbearer_client_secret", alias = "saslOauthbearerClientSecret")]
+    pub sasl_oauthbearer_client_secret: Option<String>,
+    /// Space-separated scopes requested with the token
+    #[serde(rename = "sasl_oauthbearer_scope", alias = "saslOauthbearerScope")]
+    pub sasl_oauthbearer_scope: Option<String>,
+    /// GSSAPI: Kerberos principal name of the brokers (default "kafka")
+    #[serde(rename = "sasl_kerberos_service_name", alias = "saslKerberosServiceName")]
+    pub sasl_kerberos_service_name: Option<String>,
+    /// GSSAPI: keytab and client principal used to obtain tickets; the ticket cache is used without them
+    #[serde(rename = "sasl_kerberos_keytab", alias = "saslKerberosKeytab")]
+    pub sasl_kerberos_keytab: Option<String>,
+    #[serde(rename = "sasl_kerberos_principal", alias = "saslKerberosPrincipal")]
+    pub sasl_kerberos_principal: Option<String>,
     pub message_type: MessageType,
     /// "all" or a specific partition id as string
     pub partition: Option<String>,
-    /// Starting offset for a specific partition (ignored when partition == "all")
+    /// Explicit subset of partitions to read; takes precedence over `partition` when non-empty
+    #[serde(default)]
+    pub partitions: Option<Vec<i32>>,
+    /// Starting offset for a specific partition (ignored when partition == "all" or several are listed)
     pub start_offset: Option<i64>,
     /// Start position preference: "oldest" (default) or "newest"
     #[serde(rename = "start_from", alias = "startFrom")]
     pub start_from: Option<String>,
     /// Optional path to proto schema (fallback if no cached descriptors provided)
     pub proto_schema_path: Option<String>,
+    /// Import roots for .proto sources, e.g. the repo root for `import "common/types.proto"`
+    #[serde(rename = "proto_include_paths", alias = "protoIncludePaths")]
+    pub proto_include_paths: Option<Vec<String>>,
     /// Optional fully qualified proto message name selected in UI
     #[serde(
         rename = "proto_message_full_name",
@@ -67,9 +269,56 @@ pub struct KafkaConfig {
         alias = "messageFullName"
     )]
     pub proto_message_full_name: Option<String>,
-    /// Optional cache key to reuse previously loaded descriptors (preferred over proto_schema_path)
+    /// `cache_key` from parse_proto_metadata: reuse those descriptors (preferred over proto_schema_path, its fallback)
     #[serde(rename = "proto_descriptor_key", alias = "protoDescriptorKey")]
     pub proto_descriptor_key: Option<String>,
+    /// Emit list rows without decoding payloads (key/offset/timestamp/size only)
+    #[serde(rename = "lazy_decode", alias = "lazyDecode")]
+    pub lazy_decode: Option<bool>,
+    /// Domain columns extracted from each decoded payload and attached to UiMessage.extracted
+    #[serde(rename = "extract_columns", alias = "extractColumns")]
+    pub extract_columns: Option<Vec<ExtractColumn>>,
+    /// Protobuf payload envelope: "auto" (default) | "none" | "confluent" | "grpc" | "varint"
+    #[serde(rename = "payload_envelope", alias = "payloadEnvelope")]
+    pub payload_envelope: Option<PayloadEnvelope>,
+    /// Allow the last-resort 0x0A tag repair when no envelope decodes (off by default)
+    #[serde(rename = "enable_payload_repair", alias = "enablePayloadRepair")]
+    pub enable_payload_repair: Option<bool>,
+    /// Per-record message type selection; falls back to proto_message_full_name when nothing matches
+    #[serde(rename = "proto_message_rules", alias = "protoMessageRules")]
+    pub proto_message_rules: Option<Vec<MessageTypeRule>>,
+    /// Producer settings applied to every produce request on this connection
+    #[serde(rename = "producer_defaults", alias = "producerDefaults")]
+    pub producer_defaults: Option<ProducerDefaults>,
+    /// Confluent Schema Registry base URL used to resolve schema ids (Avro)
+    #[serde(rename = "schema_registry_url", alias = "schemaRegistryUrl")]
+    pub schema_registry_url: Option<String>,
+    /// Optional basic auth credentials for the schema registry
+    #[serde(rename = "schema_registry_username", alias = "schemaRegistryUsername")]
+    pub schema_registry_username: Option<String>,
+    #[serde(rename = "schema_registry_password", alias = "schemaRegistryPassword")]
+    pub schema_registry_password: Option<String>,
+    /// JSON Schema file that JSON payloads are validated against (Json message type)
+    #[serde(rename = "json_schema_path", alias = "jsonSchemaPath")]
+    pub json_schema_path: Option<String>,
+    /// Compression of the record value itself: "auto" (default, by magic bytes) | "none" | "gzip" | "zstd" | "snappy"
+    #[serde(rename = "payload_compression", alias = "payloadCompression")]
+    pub payload_compression: Option<PayloadCompression>,
+    /// WASM module decoding values when message_type is "plugin"
+    #[serde(rename = "decoder_plugin_path", alias = "decoderPluginPath")]
+    pub decoder_plugin_path: Option<String>,
+    /// How keys are decoded: "text" (default) | "json" | "protobuf" | "int32" | "int64" | "uuid" | "avro"
+    #[serde(rename = "key_type", alias = "keyType")]
+    pub key_type: Option<KeyType>,
+    /// Message type of protobuf keys (not needed for Confluent-framed keys with a schema registry)
+    #[serde(rename = "key_proto_message_full_name", alias = "keyProtoMessageFullName")]
+    pub key_proto_message_full_name: Option<String>,
+    /// PII redaction rules applied to every decoded payload
+    #[serde(rename = "masking_rules", alias = "maskingRules")]
+    pub masking_rules: Option<Vec<MaskingRule>>,
+    /// Keep the original bytes of loaded records, up to this many megabytes (off when unset or 0)
+    #[serde(rename = "raw_cache_mb", alias = "rawCacheMb")]
+    pub raw_cache_mb: Option<usize>,
 }
 
 impl Default for KafkaConfig {
@@ -80,6 +329,11 @@ impl Default for KafkaConfig {
             ssl_enabled: false,
             truststore_location: None,
             truststore_password: None,
+            keystore_location: None,
+            keystore_password: None,
+            ssl_endpoint_identification: None,
+            custom_properties: None,
+            ssh_tunnel: None,
             ssl_mode: None,
             ssl_ca_root: None,
             ssl_ca_sub: None,
@@ -89,13 +343,138 @@ impl Default for KafkaConfig {
             security_type: None,
             sasl_mechanism: None,
             sasl_jaas_config: None,
+            sasl_oauthbearer_token_endpoint_url: None,
+            sasl_oauthbearer_client_id: None,
+            sasl_oauthbearer_client_secret: None,
+            sasl_oauthbearer_scope: None,
+            sasl_kerberos_service_name: None,
+            sasl_kerberos_keytab: None,
+            sasl_kerberos_principal: None,
             message_type: MessageType::Json,
             partition: None,
+            partitions: None,
             start_offset: None,
             start_from: Some("oldest".into()),
             proto_schema_path: None,
+            proto_include_paths: None,
             proto_message_full_name: None,
-            proto_descriptor_key: None, 
+            proto_descriptor_key: None,
+            lazy_decode: None,
+            extract_columns: None,
+            payload_envelope: None,
+            enable_payload_repair: None,
+            proto_message_rules: None,
+            producer_defaults: None,
+            schema_registry_url: None,
+            schema_registry_username: None,
+            schema_registry_password: None,
+            json_schema_path: None,
+            payload_compression: None,
+            decoder_plugin_path: None,
+            key_type: None,
+            key_proto_message_full_name: None,
+            masking_rules: None,
+            raw_cache_mb: None,
+        }
+    }
+}
+
+/// Decoder overrides for re-decoding records; unset fields keep the session's settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecoderSettings {
+    #[serde(rename = "message_type", alias = "messageType")]
+    pub message_type: Option<MessageType>,
+    #[serde(rename = "proto_schema_path", alias = "protoSchemaPath")]
+    pub proto_schema_path: Option<String>,
+    #[serde(rename = "proto_include_paths", alias = "protoIncludePaths")]
+    pub proto_include_paths: Option<Vec<String>>,
+    #[serde(rename = "proto_descriptor_key", alias = "protoDescriptorKey")]
+    pub proto_descriptor_key: Option<String>,
+    #[serde(rename = "proto_message_full_name", alias = "protoMessageFullName")]
+    pub proto_message_full_name: Option<String>,
+    #[serde(rename = "payload_envelope", alias = "payloadEnvelope")]
+    pub payload_envelope: Option<PayloadEnvelope>,
+    #[serde(rename = "payload_compression", alias = "payloadCompression")]
+    pub payload_compression: Option<PayloadCompression>,
+    #[serde(rename = "key_type", alias = "keyType")]
+    pub key_type: Option<KeyType>,
+    #[serde(rename = "key_proto_message_full_name", alias = "keyProtoMessageFullName")]
+    pub key_proto_message_full_name: Option<String>,
+}
+
+impl DecoderSettings {
+    /// The session configuration with these overrides applied.
+    pub fn apply(&self, config: &KafkaConfig) -> KafkaConfig {
+        let mut out = config.clone();
+        if let Some(t) = &self.message_type {
+            out.message_type = t.clone();
+            // A different message type replaces per-record proto rules chosen for the old one
+            out.proto_message_rules = None;
+        }
+        if self.proto_message_full_name.is_some() {
+            out.proto_message_rules = None;
+        }
+        let set = |dst: &mut Option<String>, src: &Option<String>| {
+            if src.is_some() {
+                dst.clone_from(src);
+            }
+        };
+        set(&mut out.proto_schema_path, &self.proto_schema_path);
+        set(&mut out.proto_descriptor_key, &self.proto_descriptor_key);
+        if self.proto_include_paths.is_some() {
+            out.proto_include_paths.clone_from(&self.proto_include_paths);
         }
+        set(&mut out.proto_message_full_name, &self.proto_message_full_name);
+        set(&mut out.key_proto_message_full_name, &self.key_proto_message_full_name);
+        out.payload_envelope = self.payload_envelope.or(out.payload_envelope);
+        out.payload_compression = self.payload_compression.or(out.payload_compression);
+        out.key_type = self.key_type.or(out.key_type);
+        // Re-decoding is for inspecting payloads, never skip them
+        out.lazy_decode = Some(false);
+        out
     }
 }
+
+/// Single record to produce from the UI. Values are sent as UTF-8 text.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProduceRecord {
+    pub key: Option<String>,
+    pub value: Option<String>,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    /// Target partition; overrides the request-level partition for explicit partitioning
+    pub partition: Option<i32>,
+}
+
+/// Produce request: records plus how to pick their partitions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProduceRequest {
+    pub topic: String,
+    pub records: Vec<ProduceRecord>,
+    #[serde(default)]
+    pub partitioning: PartitionStrategy,
+    /// Default partition for explicit partitioning
+    pub partition: Option<i32>,
+}
+
+/// Broker-side failure for a single produced record, e.g. `MessageSizeTooLarge` or `NotLeaderForPartition`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProduceError {
+    /// librdkafka error code name; "Unknown" when the error carries no code
+    pub code: String,
+    pub message: String,
+}
+
+/// Delivery report for one record of a produce request (same order as the request).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryReport {
+    pub index: usize,
+    pub partition: Option<i32>,
+    pub offset: Option<i64>,
+    /// CreateTime (ms) stamped on the record; topics using LogAppendTime overwrite it on the broker
+    pub timestamp: Option<i64>,
+    /// Time from enqueue to delivery acknowledgement
+    #[serde(rename = "latency_ms", alias = "latencyMs")]
+    pub latency_ms: u64,
+    pub error: Option<ProduceError>,
+}
@@ -2,50 +2,425 @@ use tauri::{State, Window, Emitter, AppHandle};
 use serde::{Deserialize, Serialize};
 use rdkafka::consumer::Consumer;
 
-use crate::app::{AppState, LoadSession};
-use crate::kafka::{Kafka, KafkaConfig, UiMessage};
+use crate::app::{ensure_writable, read_only, AppState, ConnectionInfo, LoadSession, Prewarm};
+use crate::kafka::{
+    bookmark_group, is_authorization_error, recv_timeout, CertificateInfo, ClientStats, ConnectionTest, ConsumeBatch, ConsumerLag, DecoderSettings, DeliveryReport, Kafka,
+    KafkaConfig, KeySnapshot, KeyTimeline, LagSimulation, LagSimulationReport, OffsetExpression, PartitionOffset,
+    PartitionWatermarks, ProduceBenchmark, ProduceBenchmarkReport, ProduceRequest, ReplayRange, ReplayRequest,
+    ReplaySummary, ReplayThrottle, ResolvedOffset, RetentionEstimate, ReverseScan, TimeOffset, TimelineWindow, TimestampMode,
+    TopicConfigs, TopicInfo, TopicLintReport, TopicLintRules, TopicOffsets, TopicProfile, UiMessage, UnknownHostKey,
+};
+use crate::audit;
+use crate::load_report::{LoadReportBuilder, ReportFilters};
+use crate::response::{CommandResult, Envelope, ErrorKind};
+use crate::{topic_decoders, topic_prefs};
+use crate::transform::TransformScript;
+use crate::utils::jq::JqFilter;
 
 /// Arguments for applying simple filters from the UI.
 /// - partition: "all" or specific partition as string
+/// - partitions: subset of partitions (e.g. [0, 3, 7]); overrides `partition`
 /// - start_offset: starting offset when a specific partition is selected
 #[derive(Debug, Deserialize)]
 pub struct ApplyFiltersArgs {
     pub partition: Option<String>,
+    #[serde(default)]
+    pub partitions: Option<Vec<i32>>,
     #[serde(rename = "start_offset", alias = "startOffset")]
     pub start_offset: Option<i64>,
     #[serde(rename = "start_from", alias = "startFrom")]
     pub start_from: Option<String>,
 }
 
-/// Configure Kafka connection (invoked from UI). This (re)creates a consumer.
+/// `configure_kafka_failed`, or `ssh_host_key_unknown` with the bastion's fingerprint for the user to confirm.
+pub(crate) fn connect_failed(e: anyhow::Error) -> Envelope {
+    match e.chain().find_map(|c| c.downcast_ref::<UnknownHostKey>()) {
+        Some(unknown) => Envelope::error("ssh_host_key_unknown", unknown.to_string())
+            .kind(ErrorKind::Config)
+            .with("host", &unknown.host)
+            .with("port", unknown.port)
+            .with("fingerprint", &unknown.fingerprint),
+        None => Envelope::failed("configure_kafka", e),
+    }
+}
+
+/// Configure Kafka connection (invoked from UI). This (re)creates the consumer of the active connection.
+#[tauri::command]
+pub async fn set_kafka_config(app: AppHandle, state: State<'_, AppState>, config: KafkaConfig) -> CommandResult<Envelope> {
+    let (broker, topic) = (config.broker.clone(), config.topic.clone());
+    state.reconfigure_kafka(topic_decoders::apply_for_session(&app, config)).await.map_err(connect_failed)?;
+    // Recent-topic history is best effort; never fail the connection over it
+    if !topic.is_empty() {
+        if let Err(e) = topic_prefs::prefs_dir(&app).and_then(|dir| topic_prefs::touch_recent(&dir, &broker, &topic)) {
+            log::warn!("{e}");
+            let message = format!("Connected, but the recent topic list was not updated: {e}");
+            return Ok(Envelope::warn("recent_topics_not_saved", message).with("error", e));
+        }
+    }
+    Ok(Envelope::success("connected", format!("Connected to {broker}")).with("broker", broker).with("topic", topic))
+}
+
+/// Open (or replace) a named connection and make it active; other connections stay open.
+#[tauri::command]
+pub async fn add_connection(app: AppHandle, state: State<'_, AppState>, name: String, config: KafkaConfig) -> CommandResult<()> {
+    state.add_connection(&name, topic_decoders::apply_for_session(&app, config)).await.map_err(connect_failed)
+}
+
+/// Make another open connection active.
 #[tauri::command]
-pub async fn set_kafka_config(state: State<'_, AppState>, config: KafkaConfig) -> Result<(), String> {
-    state
-        .reconfigure_kafka(config)
-        .map_err(|e| format!("Failed to configure Kafka: {e}"))
+pub fn switch_connection(state: State<AppState>, name: String) -> CommandResult<()> {
+    state.switch_connection(&name).map_err(|e| Envelope::failed("switch_connection", e))
+}
+
+/// Close a named connection.
+#[tauri::command]
+pub fn close_connection(state: State<AppState>, name: String) -> CommandResult<()> {
+    state.close_connection(&name).map_err(|e| Envelope::failed("close_connection", e))
+}
+
+/// Open connections with their broker/topic.
+#[tauri::command]
+pub fn list_connections(state: State<AppState>) -> CommandResult<Vec<ConnectionInfo>> {
+    let guard = state.kafka.lock().map_err(Envelope::state)?;
+    Ok(guard.list())
 }
 
 /// Read-only status for the UI header.
 #[tauri::command]
-pub fn get_kafka_status(state: State<AppState>) -> Result<String, String> {
-    let guard = state.kafka.lock().map_err(|e| format!("Failed to access state: {e}"))?;
-    if let Some(k) = &*guard {
+pub fn get_kafka_status(state: State<AppState>, connection: Option<String>) -> CommandResult<String> {
+    let guard = state.kafka.lock().map_err(Envelope::state)?;
+    if let Some(k) = guard.get(connection.as_deref()) {
         Ok(format!("connected to {} topic {}", k.config.broker, k.config.topic))
     } else {
-        Err("Kafka is not configured".to_string())
+        Err(Envelope::not_configured())
     }
 }
 
+/// Latest consumer statistics of the given (or active) connection: broker round trips, fetch latency and
+/// queue depths. None until the first report arrives, a few seconds into reading.
+#[tauri::command]
+pub fn get_client_stats(state: State<AppState>, connection: Option<String>) -> CommandResult<Option<ClientStats>> {
+    let guard = state.kafka.lock().map_err(Envelope::state)?;
+    let k = guard.get(connection.as_deref()).ok_or_else(Envelope::not_configured)?;
+    Ok(k.client_stats())
+}
+
+/// Emit `kafka:stats` ({connection, stats}) whenever a connection's consumer reported new statistics.
+pub fn start_stats_events(app: AppHandle) {
+    use tauri::Manager;
+    tauri::async_runtime::spawn(async move {
+        let mut emitted: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
+        loop {
+            tokio::time::sleep(std::time::Duration::from_millis(crate::kafka::STATS_INTERVAL_MS)).await;
+            let fresh: Vec<(String, ClientStats)> = {
+                let state = app.state::<AppState>();
+                let Ok(guard) = state.kafka.lock() else { continue };
+                emitted.retain(|name, _| guard.map.contains_key(name));
+                guard
+                    .map
+                    .iter()
+                    .filter_map(|(name, k)| k.client_stats().map(|s| (name.clone(), s)))
+                    .filter(|(name, s)| emitted.get(name) != Some(&s.timestamp))
+                    .collect()
+            };
+            for (connection, stats) in fresh {
+                emitted.insert(connection.clone(), stats.timestamp);
+                let _ = app.emit("kafka:stats", serde_json::json!({ "connection": connection, "stats": stats }));
+            }
+        }
+    });
+}
+
+/// Application mode, so the UI can hide actions the backend would refuse.
+#[tauri::command]
+pub async fn get_app_mode() -> CommandResult<serde_json::Value> {
+    Ok(serde_json::json!({ "read_only": read_only() }))
+}
+
+/// Validate connection settings (SSL/SASL, reachability) without configuring a reader.
+#[tauri::command]
+pub async fn test_connection(config: KafkaConfig, timeout_ms: Option<u64>) -> CommandResult<ConnectionTest> {
+    let timeout = std::time::Duration::from_millis(timeout_ms.unwrap_or(5000).clamp(500, 30000));
+    Ok(Kafka::test_connection(&config, timeout))
+}
+
+/// Certificates of a truststore or keystore (PEM, JKS, PKCS#12) with subject, SANs and expiry,
+/// flagging those that expire within 30 days.
+#[tauri::command]
+pub async fn inspect_truststore(path: String, password: Option<String>) -> CommandResult<Vec<CertificateInfo>> {
+    crate::kafka::inspect_truststore(&path, password.as_deref()).map_err(|e| Envelope::failed("inspect_truststore", e))
+}
+
 /// List topics for a given broker.
 #[tauri::command]
-pub async fn get_topics(config: KafkaConfig) -> Result<Vec<String>, String> {
-    Kafka::list_topics(&config).map_err(|e| format!("Failed to get topics: {e}"))
+pub async fn get_topics(config: KafkaConfig) -> CommandResult<Vec<String>> {
+    Kafka::list_topics(&config).map_err(|e| Envelope::failed("get_topics", e))
+}
+
+/// List topics with partition counts and access state (unauthorized topics are flagged, not dropped).
+#[tauri::command]
+pub async fn get_topics_detailed(config: KafkaConfig) -> CommandResult<Vec<TopicInfo>> {
+    Kafka::list_topics_detailed(&config).map_err(|e| Envelope::failed("get_topics", e))
+}
+
+/// Audit all topics against naming and sizing rules.
+#[tauri::command]
+pub async fn lint_topics(config: KafkaConfig, rules: TopicLintRules) -> CommandResult<TopicLintReport> {
+    Kafka::lint_topics(&config, &rules).map_err(|e| Envelope::failed("lint_topics", e))
 }
 
 /// List partitions for the selected topic.
 #[tauri::command]
-pub async fn get_topic_partitions(config: KafkaConfig) -> Result<Vec<i32>, String> {
-    Kafka::topic_partitions(&config).map_err(|e| format!("Failed to get partitions: {e}"))
+pub async fn get_topic_partitions(config: KafkaConfig) -> CommandResult<Vec<i32>> {
+    Kafka::topic_partitions(&config).map_err(|e| Envelope::failed("get_partitions", e))
+}
+
+/// Sample recent records of a topic and suggest decode settings.
+#[tauri::command]
+pub async fn profile_topic(config: KafkaConfig, topic: String, sample: Option<usize>) -> CommandResult<TopicProfile> {
+    let cfg = KafkaConfig { topic, ..config };
+    let sample = sample.unwrap_or(200).clamp(1, 5000);
+    Kafka::profile_topic(&cfg, sample).map_err(|e| Envelope::failed("profile_topic", e))
+}
+
+/// Committed offsets vs high watermarks for a consumer group on a topic.
+#[tauri::command]
+pub async fn get_consumer_lag(config: KafkaConfig, group: String, topic: String) -> CommandResult<ConsumerLag> {
+    Kafka::consumer_lag(&config, &group, &topic).map_err(|e| Envelope::failed("get_consumer_lag", e))
+}
+
+/// Share the reader's current position: commit it under the `rkui-bookmark-<name>` group (replacing an older bookmark).
+#[tauri::command]
+pub async fn save_bookmark(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+    connection: Option<String>,
+) -> CommandResult<Vec<PartitionOffset>> {
+    ensure_writable("Saving bookmarks")?;
+    let k = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        guard.get_shared(connection.as_deref()).ok_or_else(Envelope::not_configured)?
+    };
+    // The bookmark is a synchronous offset commit
+    let result = {
+        let (k, name) = (k.clone(), name.clone());
+        tokio::task::spawn_blocking(move || k.save_bookmark(&name))
+            .await
+            .map_err(|e| Envelope::failed("save_bookmark", e))
+            .and_then(|r| r.map_err(|e| Envelope::failed("save_bookmark", e)))
+    };
+    let details = serde_json::json!({ "name": name, "offsets": result.as_ref().ok() });
+    audit::record(&app, "save_bookmark", &k.config, &k.config.topic, details, &result);
+    result
+}
+
+/// Names of the bookmarks stored on the cluster.
+#[tauri::command]
+pub async fn list_bookmarks(config: KafkaConfig) -> CommandResult<Vec<String>> {
+    Kafka::list_bookmarks(&config).map_err(|e| Envelope::failed("list_bookmarks", e))
+}
+
+/// Positions of a bookmark on a topic, with the records written since (as the group's lag).
+#[tauri::command]
+pub async fn get_bookmark(config: KafkaConfig, name: String, topic: String) -> CommandResult<ConsumerLag> {
+    let group = bookmark_group(&name).map_err(|e| Envelope::invalid(e.to_string()).with("name", &name))?;
+    Kafka::consumer_lag(&config, &group, &topic).map_err(|e| Envelope::failed("read_bookmark", e))
+}
+
+#[tauri::command]
+pub async fn delete_bookmark(app: AppHandle, config: KafkaConfig, name: String) -> CommandResult<()> {
+    ensure_writable("Deleting bookmarks")?;
+    let result = Kafka::delete_bookmark(&config, &name)
+        .await
+        .map_err(|e| Envelope::failed("delete_bookmark", e));
+    audit::record(&app, "delete_bookmark", &config, &config.topic, serde_json::json!({ "name": name }), &result);
+    result
+}
+
+/// Read `topic` from a group's committed offsets at a fixed rate and report whether such a consumer keeps up.
+/// Emits `kafka:lag_sim_progress` (LagSample) per sample; `cancel_lag_simulation` ends the run early.
+#[tauri::command]
+pub async fn simulate_consumer_lag(
+    window: Window,
+    state: State<'_, AppState>,
+    config: KafkaConfig,
+    topic: String,
+    simulation: LagSimulation,
+) -> CommandResult<LagSimulationReport> {
+    let mut rx = {
+        let mut sess_guard = state.lag_sim_session.lock().map_err(Envelope::state)?;
+        if let Some(prev) = sess_guard.take() {
+            let _ = prev.cancel_tx.send(());
+        }
+        let (tx, rx) = broadcast::channel::<()>(1);
+        *sess_guard = Some(LoadSession::new(tx));
+        rx
+    };
+    let cfg = KafkaConfig { topic, ..config };
+    tokio::task::spawn_blocking(move || {
+        use tokio::sync::broadcast::error::TryRecvError;
+
+        Kafka::simulate_lag(&cfg, &simulation, |sample| {
+            let _ = window.emit("kafka:lag_sim_progress", sample);
+            !matches!(rx.try_recv(), Ok(_) | Err(TryRecvError::Closed))
+        })
+    })
+    .await
+    .map_err(|e| Envelope::failed("run_lag_simulation", e))?
+    .map_err(|e| Envelope::failed("simulate_consumer_lag", e))
+}
+
+#[tauri::command]
+pub async fn cancel_lag_simulation(state: State<'_, AppState>) -> CommandResult<()> {
+    let mut sess_guard = state.lag_sim_session.lock().map_err(Envelope::state)?;
+    if let Some(s) = sess_guard.take() {
+        let _ = s.cancel_tx.send(());
+    }
+    Ok(())
+}
+
+/// Low/high watermarks per partition. Reuses the configured reader's client when one exists
+/// on the same broker; otherwise `config` is required to open a short-lived one.
+#[tauri::command]
+pub async fn get_watermarks(
+    state: State<'_, AppState>,
+    config: Option<KafkaConfig>,
+    topic: String,
+) -> CommandResult<Vec<PartitionWatermarks>> {
+    let reader = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        guard.get_shared(None).filter(|k| config.as_ref().is_none_or(|c| c.broker == k.config.broker))
+    };
+    let watermarks = match reader {
+        Some(k) => tokio::task::spawn_blocking(move || k.watermarks(&topic)).await,
+        None => {
+            let config = config.ok_or_else(Envelope::not_configured)?;
+            tokio::task::spawn_blocking(move || Kafka::watermarks_for(&config, &topic)).await
+        }
+    };
+    watermarks
+        .map_err(|e| Envelope::failed("get_watermarks", e))?
+        .map_err(|e| Envelope::failed("get_watermarks", e))
+}
+
+/// Low/high watermark and approximate record count per partition of `topic`, read through the given (or active)
+/// connection, so the UI can show a topic's size before scanning it.
+#[tauri::command]
+pub async fn get_topic_offsets(
+    state: State<'_, AppState>,
+    topic: String,
+    connection: Option<String>,
+) -> CommandResult<TopicOffsets> {
+    let k = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        guard.get_shared(connection.as_deref()).ok_or_else(Envelope::not_configured)?
+    };
+    tokio::task::spawn_blocking(move || k.topic_offsets(&topic))
+        .await
+        .map_err(|e| Envelope::failed("get_topic_offsets", e))?
+        .map_err(|e| Envelope::failed("get_topic_offsets", e))
+}
+
+/// Per-partition offsets of the first records at or after `timestamp` (epoch ms).
+/// `precise` binary-searches record timestamps instead of using the broker's time index.
+#[tauri::command]
+pub async fn get_offsets_for_time(
+    config: KafkaConfig,
+    topic: String,
+    timestamp: i64,
+    precise: Option<bool>,
+) -> CommandResult<Vec<TimeOffset>> {
+    tokio::task::spawn_blocking(move || Kafka::offsets_for_time(&config, &topic, timestamp, precise.unwrap_or(false)))
+        .await
+        .map_err(|e| Envelope::failed("get_offsets_for_time", e))?
+        .map_err(|e| Envelope::failed("get_offsets_for_time", e))
+}
+
+/// Resolve an offset input (`latest-1000`, `earliest`, `@2024-05-01T10:00:00Z`, ...) to a concrete offset.
+/// Uses the active connection unless `config` is given; `precise` as in `get_offsets_for_time`.
+#[tauri::command]
+pub async fn resolve_offset_expression(
+    state: State<'_, AppState>,
+    config: Option<KafkaConfig>,
+    topic: String,
+    partition: i32,
+    expr: String,
+    precise: Option<bool>,
+) -> CommandResult<ResolvedOffset> {
+    let expr = OffsetExpression::parse(&expr)?;
+    let config = match config {
+        Some(c) => c,
+        None => {
+            let guard = state.kafka.lock().map_err(Envelope::state)?;
+            guard.active().map(|k| k.config.clone()).ok_or_else(Envelope::not_configured)?
+        }
+    };
+    tokio::task::spawn_blocking(move || Kafka::resolve_offset(&config, &topic, partition, &expr, precise.unwrap_or(false)))
+        .await
+        .map_err(|e| Envelope::failed("resolve_offset", e))?
+        .map_err(|e| Envelope::failed("resolve_offset", e))
+}
+
+/// Topic configuration with the non-default (topic-level) overrides split out.
+#[tauri::command]
+pub async fn describe_topic_configs(config: KafkaConfig, topic: String) -> CommandResult<TopicConfigs> {
+    Kafka::describe_topic_configs(&config, &topic)
+        .await
+        .map_err(|e| Envelope::failed("describe_topic_configs", e))
+}
+
+/// Set (or with null, reset to default) topic configuration parameters; returns the updated configuration.
+#[tauri::command]
+pub async fn alter_topic_config(
+    app: AppHandle,
+    config: KafkaConfig,
+    topic: String,
+    changes: std::collections::HashMap<String, Option<String>>,
+) -> CommandResult<TopicConfigs> {
+    ensure_writable("Altering topic configuration")?;
+    let result = Kafka::alter_topic_config(&config, &topic, &changes)
+        .await
+        .map_err(|e| Envelope::failed("alter_topic_config", e));
+    audit::record(&app, "alter_topic_config", &config, &topic, serde_json::json!({ "changes": changes }), &result);
+    result
+}
+
+/// Estimate how far back each partition of a topic still has data.
+#[tauri::command]
+pub async fn estimate_retention(config: KafkaConfig, topic: String) -> CommandResult<RetentionEstimate> {
+    Kafka::retention_estimate(&config, &topic)
+        .await
+        .map_err(|e| Envelope::failed("estimate_retention", e))
+}
+
+/// Increase the partition count of a topic; returns the resulting partition ids.
+#[tauri::command]
+pub async fn add_partitions(app: AppHandle, config: KafkaConfig, topic: String, new_count: usize) -> CommandResult<Vec<i32>> {
+    ensure_writable("Adding partitions")?;
+    let result = Kafka::add_partitions(&config, &topic, new_count)
+        .await
+        .map_err(|e| Envelope::failed("add_partitions", e));
+    audit::record(&app, "add_partitions", &config, &topic, serde_json::json!({ "new_count": new_count }), &result);
+    result
+}
+
+/// Create a consumer group positioned at the given offsets (handoff to an application team).
+/// Moving existing commits goes through `confirm::prepare_reset_offsets` instead.
+#[tauri::command]
+pub async fn prepare_consumer_group(
+    app: AppHandle,
+    config: KafkaConfig,
+    group: String,
+    topic: String,
+    offsets: Vec<PartitionOffset>,
+) -> CommandResult<Vec<PartitionOffset>> {
+    ensure_writable("Committing consumer group offsets")?;
+    let result = Kafka::prepare_group_offsets(&config, &group, &topic, &offsets, false)
+        .map_err(|e| Envelope::failed("prepare_consumer_group", e));
+    let details = serde_json::json!({ "group": group, "offsets": offsets, "overwrite": false });
+    audit::record(&app, "reset_offsets", &config, &topic, details, &result);
+    result
 }
 
 /// Apply filters (partition/offset). Resets internal reading state.
@@ -53,31 +428,520 @@ pub async fn get_topic_partitions(config: KafkaConfig) -> Result<Vec<i32>, Strin
 pub async fn apply_filters(
     state: State<'_, AppState>,
     args: ApplyFiltersArgs,
-) -> Result<(), String> {
-    let mut guard = state.kafka.lock().map_err(|e| format!("Failed to access state: {e}"))?;
-    if let Some(k) = guard.as_mut() {
-        k.apply_filters_mut(args.partition, args.start_offset, args.start_from)
-            .map_err(|e| format!("Failed to apply filters: {e}"))
+    connection: Option<String>,
+) -> CommandResult<()> {
+    let (name, reads) = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        let name = guard.resolve(connection.as_deref()).ok_or_else(Envelope::not_configured)?;
+        let reads = guard.get(Some(&name)).ok_or_else(Envelope::not_configured)?.reads.clone();
+        (name, reads)
+    };
+    if let Some(prewarm) = state.take_prewarm(&name).map_err(Envelope::state)? {
+        let unchanged = {
+            let guard = state.kafka.lock().map_err(Envelope::state)?;
+            guard.get(Some(&name)).is_some_and(|k| {
+                k.filters_unchanged(
+                    args.partition.as_deref(),
+                    args.partitions.as_deref(),
+                    args.start_offset,
+                    args.start_from.as_deref(),
+                )
+            })
+        };
+        if unchanged {
+            // The read-ahead already follows these filters; keep it for the next page request
+            state.prewarm.lock().map_err(Envelope::state)?.insert(name, prewarm);
+            return Ok(());
+        }
+        // Wait for the aborted read to release the reader before resetting it
+        prewarm.stop().await;
+    }
+    // Wait for a page being read instead of failing on the reader it holds
+    let _reading = reads.lock_owned().await;
+    let guard = state.kafka.lock().map_err(Envelope::state)?;
+    if let Some(k) = guard.get(Some(&name)) {
+        k.apply_filters(args.partition, args.partitions, args.start_offset, args.start_from)
+            .map_err(|e| Envelope::failed("apply_filters", e))
     } else {
-        Err("Kafka is not configured".into())
+        Err(Envelope::not_configured())
+    }
+}
+
+/// Start reading the first page of the connection's topic in the background (metadata, watermarks and records)
+/// so that the next consume_next_messages returns it at once. Does nothing once paging has started.
+#[tauri::command]
+pub async fn prewarm_topic(
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+    connection: Option<String>,
+) -> CommandResult<()> {
+    let (name, k) = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        let name = guard.resolve(connection.as_deref()).ok_or_else(Envelope::not_configured)?;
+        let k = guard.get_shared(Some(&name)).ok_or_else(Envelope::not_configured)?;
+        (name, k)
+    };
+    let mut prewarm = state.prewarm.lock().map_err(Envelope::state)?;
+    // A merge page in flight holds the split queues with the reader marked unassigned
+    let paging = k.assigned.load(std::sync::atomic::Ordering::SeqCst) || k.queues_split.load(std::sync::atomic::Ordering::SeqCst);
+    if paging || prewarm.contains_key(&name) {
+        return Ok(());
     }
+    let limit = k.page_size(limit).map_err(Envelope::state)?;
+    let reads = k.reads.clone();
+    let task = tokio::spawn(async move {
+        let _reading = reads.lock_owned().await;
+        k.consume_next(limit).await
+    });
+    prewarm.insert(name, Prewarm { limit, task });
+    Ok(())
 }
 
 /// Consume the next batch of messages using the currently selected strategy.
+/// Returns `{ messages, progress }` so paging UIs can show how deep into the snapshot they are.
+/// Without a `limit` the page size adapts to how long records take to read and how large they are.
+/// The first page comes from prewarm_topic when one was started with the same page size; with another
+/// size the read-ahead is dropped and the reader starts over.
 #[tauri::command]
-pub async fn consume_next_messages(state: State<'_, AppState>, limit: Option<usize>) -> Result<Vec<UiMessage>, String> {
-    let guard = state.kafka.lock().map_err(|e| format!("Failed to access state: {e}"))?;
-    if let Some(k) = &*guard {
-        let lim = limit.unwrap_or(200);
-        k.consume_next(lim).map_err(|e| format!("Failed to consume messages: {e}"))
-    } else {
-        Err("Kafka is not configured".into())
+pub async fn consume_next_messages(
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+    connection: Option<String>,
+) -> CommandResult<ConsumeBatch> {
+    let (name, reads, limit) = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        let name = guard.resolve(connection.as_deref()).ok_or_else(Envelope::not_configured)?;
+        let k = guard.get(Some(&name)).ok_or_else(Envelope::not_configured)?;
+        (name, k.reads.clone(), k.page_size(limit).map_err(Envelope::state)?)
+    };
+    let rewind = match state.take_prewarm(&name).map_err(Envelope::state)? {
+        Some(prewarm) if prewarm.limit == limit => {
+            return prewarm
+                .task
+                .await
+                .map_err(|e| Envelope::failed("consume_messages", e))?
+                .map_err(|e| Envelope::failed("consume_messages", e));
+        }
+        Some(prewarm) => {
+            prewarm.stop().await;
+            true
+        }
+        None => false,
+    };
+    // One page at a time per connection; taken before the reader so filter changes can wait for it
+    let _reading = reads.lock_owned().await;
+    // The reader is shared out of the lock so the state stays available while records are awaited
+    let k = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        if rewind {
+            // The read-ahead may have taken records past this page: start the reader over
+            let reader = guard.get(Some(&name)).ok_or_else(Envelope::not_configured)?;
+            let f = reader.filters().map_err(|e| Envelope::failed("consume_messages", e))?;
+            reader
+                .apply_filters(f.partition, f.partitions, f.start_offset, f.start_from)
+                .map_err(|e| Envelope::failed("consume_messages", e))?;
+        }
+        guard.get_shared(Some(&name)).ok_or_else(Envelope::not_configured)?
+    };
+    k.consume_next(limit)
+        .await
+        .map_err(|e| Envelope::failed("consume_messages", e))
+}
+
+/// Fetch and fully decode a single record (used by the UI when lazy_decode is enabled).
+#[tauri::command]
+pub async fn get_message_at(
+    state: State<'_, AppState>,
+    partition: i32,
+    offset: i64,
+    connection: Option<String>,
+) -> CommandResult<UiMessage> {
+    let k = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        guard.get_shared(connection.as_deref()).ok_or_else(Envelope::not_configured)?
+    };
+    tokio::task::spawn_blocking(move || k.message_at(partition, offset))
+        .await
+        .map_err(|e| Envelope::failed("fetch_message", e))?
+        .map_err(|e| Envelope::failed("fetch_message", e))
+}
+
+/// Original bytes of a record as hex, for hex views, raw export and re-producing.
+#[derive(Debug, Serialize)]
+pub struct RawMessage {
+    pub partition: i32,
+    pub offset: i64,
+    /// CreateTime/LogAppendTime in epoch ms
+    pub timestamp: Option<i64>,
+    pub key: Option<String>,
+    pub payload: Option<String>,
+    pub headers: Vec<(String, Option<String>)>,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Original bytes of a loaded record; served from the raw cache (see `raw_cache_mb`) when possible.
+#[tauri::command]
+pub async fn get_raw_message(
+    state: State<'_, AppState>,
+    partition: i32,
+    offset: i64,
+    connection: Option<String>,
+) -> CommandResult<RawMessage> {
+    use rdkafka::message::{Headers, Message as RdMessage};
+
+    let k = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        guard.get_shared(connection.as_deref()).ok_or_else(Envelope::not_configured)?
+    };
+    // Original bytes would show exactly what the masking rules hide
+    if k.config.masking_rules.as_ref().is_some_and(|rules| !rules.is_empty()) {
+        return Err(Envelope::error("raw_message_masked", "Original bytes are not available while masking rules are configured")
+            .kind(ErrorKind::State));
+    }
+    let m = tokio::task::spawn_blocking(move || k.raw_message_at(partition, offset))
+        .await
+        .map_err(|e| Envelope::failed("fetch_message", e))?
+        .map_err(|e| Envelope::failed("fetch_message", e))?;
+    Ok(RawMessage {
+        partition,
+        offset,
+        timestamp: m.timestamp().to_millis(),
+        key: m.key().map(to_hex),
+        payload: m.payload().map(to_hex),
+        headers: m
+            .headers()
+            .map(|hs| hs.iter().map(|h| (h.key.to_string(), h.value.map(to_hex))).collect())
+            .unwrap_or_default(),
+    })
+}
+
+/// Re-fetch records by id ("partition-offset") and decode them with other decoder settings
+/// (message type, proto message, envelope, ...), leaving the session's own decoding untouched.
+#[tauri::command]
+pub async fn redecode_messages(
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+    decoder_settings: DecoderSettings,
+    connection: Option<String>,
+) -> CommandResult<Vec<UiMessage>> {
+    let positions = ids
+        .iter()
+        .map(|id| {
+            id.split_once('-')
+                .and_then(|(p, o)| Some((p.parse::<i32>().ok()?, o.parse::<i64>().ok()?)))
+                .ok_or_else(|| Envelope::invalid(format!("Invalid message id '{id}' (expected partition-offset)")).with("id", id))
+        })
+        .collect::<CommandResult<Vec<_>>>()?;
+    let k = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        guard.get_shared(connection.as_deref()).ok_or_else(Envelope::not_configured)?
+    };
+    let descriptors = state.descriptors.clone();
+    tokio::task::spawn_blocking(move || {
+        let codec = Kafka::build_codec_with(&decoder_settings.apply(&k.config), Some(&descriptors))
+            .map_err(|e| Envelope::failed("set_up_decoder", e).kind(ErrorKind::Decode))?;
+        positions
+            .into_iter()
+            .map(|(p, o)| k.message_at_with(&codec, p, o).map_err(|e| Envelope::failed("fetch_message", e).with("partition", p).with("offset", o)))
+            .collect()
+    })
+    .await
+    .map_err(|e| Envelope::failed("redecode_messages", e))?
+}
+
+/// Keys tracked by `load_latest_by_key` when the caller sets no limit.
+const DEFAULT_MAX_KEYS: usize = 100_000;
+
+/// Compaction-aware read: the newest record per key of the whole topic, without deleted keys.
+/// Emits `kafka:latest_progress` ({scanned, keys}) while scanning; shares the load session,
+/// so `cancel_filtered_load` stops it and returns what was read so far.
+#[tauri::command]
+pub async fn load_latest_by_key(
+    window: Window,
+    state: State<'_, AppState>,
+    connection: Option<String>,
+    max_keys: Option<usize>,
+) -> CommandResult<KeySnapshot> {
+    let config = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        let Some(k) = guard.get(connection.as_deref()) else { return Err(Envelope::not_configured()); };
+        k.config.clone()
+    };
+    let mut rx = {
+        let mut sess_guard = state.load_session.lock().map_err(Envelope::state)?;
+        if let Some(prev) = sess_guard.take() {
+            let _ = prev.cancel_tx.send(());
+        }
+        let (tx, rx) = broadcast::channel::<()>(1);
+        *sess_guard = Some(LoadSession::new(tx));
+        rx
+    };
+    tokio::task::spawn_blocking(move || {
+        use tokio::sync::broadcast::error::TryRecvError;
+
+        // A dedicated reader keeps the UI session's consumer position untouched
+        let kafka = Kafka::new(config).map_err(|e| Envelope::failed("create_consumer", e))?;
+        kafka
+            .latest_by_key(max_keys.unwrap_or(DEFAULT_MAX_KEYS), 1000, |scanned, keys| {
+                let _ = window.emit("kafka:latest_progress", &serde_json::json!({ "scanned": scanned, "keys": keys }));
+                !matches!(rx.try_recv(), Ok(_) | Err(TryRecvError::Closed))
+            })
+            .map_err(|e| Envelope::failed("load_latest_values", e))
+    })
+    .await
+    .map_err(|e| Envelope::failed("run_load_task", e))?
+}
+
+/// History of one key: its records in a time window, oldest first, with operation/status pulled from the payload.
+/// Reads only the key's default-partitioner partition unless `window.all_partitions` is set.
+#[tauri::command]
+pub async fn get_key_timeline(
+    state: State<'_, AppState>,
+    connection: Option<String>,
+    topic: Option<String>,
+    key: String,
+    window: Option<TimelineWindow>,
+) -> CommandResult<KeyTimeline> {
+    let mut config = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        let Some(k) = guard.get(connection.as_deref()) else { return Err(Envelope::not_configured()); };
+        k.config.clone()
+    };
+    if let Some(t) = topic {
+        config.topic = t;
+    }
+    let window = window.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        let kafka = Kafka::new(config).map_err(|e| Envelope::failed("create_consumer", e))?;
+        kafka.key_timeline(&key, &window).map_err(|e| Envelope::failed("load_key_timeline", e))
+    })
+    .await
+    .map_err(|e| Envelope::failed("run_timeline_task", e))?
+}
+
+/// Produce records to a topic using the given connection settings.
+/// Returns one delivery report per record; per-record broker errors do not fail the call.
+/// Partitioning is chosen per request: default, explicit, key_hash (Java murmur2) or round_robin.
+#[tauri::command]
+pub async fn produce_messages(
+    app: AppHandle,
+    config: KafkaConfig,
+    request: ProduceRequest,
+) -> CommandResult<Vec<DeliveryReport>> {
+    ensure_writable("Producing messages")?;
+    let result = Kafka::produce(&config, &request)
+        .await
+        .map_err(|e| Envelope::failed("produce_messages", e));
+    let failed = result.as_ref().map(|r| r.iter().filter(|d| d.error.is_some()).count()).unwrap_or(0);
+    let details = serde_json::json!({
+        "records": request.records.len(),
+        "failed": failed,
+        "partitioning": request.partitioning,
+        "partition": request.partition,
+    });
+    audit::record(&app, "produce", &config, &request.topic, details, &result);
+    result
+}
+
+/// Produce synthetic records with the given producer settings and report latency percentiles and throughput.
+#[tauri::command]
+pub async fn benchmark_produce(
+    app: AppHandle,
+    config: KafkaConfig,
+    topic: String,
+    benchmark: ProduceBenchmark,
+) -> CommandResult<ProduceBenchmarkReport> {
+    ensure_writable("Producing benchmark messages")?;
+    let result = Kafka::benchmark_produce(&config, &topic, &benchmark)
+        .await
+        .map_err(|e| Envelope::failed("run_produce_benchmark", e));
+    let details = serde_json::json!({
+        "messages": benchmark.messages,
+        "message_size": benchmark.message_size,
+        "acks": benchmark.acks,
+        "compression_type": benchmark.compression_type,
+        "produced": result.as_ref().ok().map(|r| r.produced),
+    });
+    audit::record(&app, "benchmark", &config, &topic, details, &result);
+    result
+}
+
+/// Replay offset ranges of the configured topic into another topic, optionally re-stamping timestamps.
+#[tauri::command]
+pub async fn replay_messages(app: AppHandle, config: KafkaConfig, request: ReplayRequest) -> CommandResult<ReplaySummary> {
+    ensure_writable("Replaying messages")?;
+    let result = Kafka::replay(&config, &request)
+        .await
+        .map_err(|e| Envelope::failed("replay_messages", e));
+    let details = serde_json::json!({
+        "source_topic": config.topic,
+        "ranges": request.ranges,
+        "produced": result.as_ref().ok().map(|s| s.produced),
+    });
+    audit::record(&app, "replay", &config, &request.target_topic, details, &result);
+    result
+}
+
+/// Source window and destination of a copy session.
+#[derive(Debug, Deserialize)]
+pub struct StartCopyArgs {
+    /// Source cluster and topic
+    pub config: KafkaConfig,
+    /// Destination cluster; the source cluster when omitted
+    pub target: Option<KafkaConfig>,
+    #[serde(rename = "target_topic", alias = "targetTopic")]
+    pub target_topic: String,
+    /// Explicit offset ranges; otherwise derived from `partitions` and the time window
+    pub ranges: Option<Vec<ReplayRange>>,
+    pub partitions: Option<Vec<i32>>,
+    /// Window start/end (epoch ms)
+    #[serde(rename = "from_time", alias = "fromTime")]
+    pub from_time: Option<i64>,
+    #[serde(rename = "to_time", alias = "toTime")]
+    pub to_time: Option<i64>,
+    /// Resolve the window by searching record timestamps (see `get_offsets_for_time`)
+    #[serde(default)]
+    pub precise: bool,
+    #[serde(default, rename = "preserve_partition", alias = "preservePartition")]
+    pub preserve_partition: bool,
+    #[serde(default)]
+    pub timestamps: TimestampMode,
+    pub throttle: Option<ReplayThrottle>,
+}
+
+/// How often a copy reports `kafka:copy_progress`.
+const COPY_PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Cancellation and progress pacing of a running copy.
+pub struct CopyWatch {
+    cancel: broadcast::Receiver<()>,
+    interval: std::time::Duration,
+    last_emit: std::time::Instant,
+    cancelled: bool,
+}
+
+impl CopyWatch {
+    pub fn new(cancel: broadcast::Receiver<()>, interval: std::time::Duration, now: std::time::Instant) -> Self {
+        Self { cancel, interval, last_emit: now, cancelled: false }
+    }
+
+    /// False once the copy was cancelled or its session replaced or dropped.
+    pub fn keep_going(&mut self) -> bool {
+        use tokio::sync::broadcast::error::TryRecvError;
+
+        if !self.cancelled && matches!(self.cancel.try_recv(), Ok(_) | Err(TryRecvError::Closed)) {
+            self.cancelled = true;
+        }
+        !self.cancelled
     }
+
+    pub fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// Whether a progress event is due at `now`; the interval restarts when it is.
+    pub fn emit_due(&mut self, now: std::time::Instant) -> bool {
+        if now.saturating_duration_since(self.last_emit) < self.interval {
+            return false;
+        }
+        self.last_emit = now;
+        true
+    }
+}
+
+/// Copy a source range into a destination topic in the background. Emits `kafka:copy_started` (ranges),
+/// `kafka:copy_progress` (ReplaySummary, at most twice a second), then `kafka:copy_done`,
+/// `kafka:copy_cancelled` (both with the summary) or `kafka:copy_error`.
+#[tauri::command]
+pub async fn start_copy(app: AppHandle, window: Window, state: State<'_, AppState>, args: StartCopyArgs) -> CommandResult<()> {
+    ensure_writable("Copying messages")?;
+    let ranges = match args.ranges {
+        Some(r) => r,
+        None => {
+            // Resolving the window fetches offsets (per probe when precise), so it runs off the async runtime
+            let (config, partitions) = (args.config.clone(), args.partitions.clone());
+            let (from, to, precise) = (args.from_time, args.to_time, args.precise);
+            tokio::task::spawn_blocking(move || Kafka::ranges_for_window(&config, partitions.as_deref(), from, to, precise))
+                .await
+                .map_err(|e| Envelope::failed("resolve_copy_window", e))?
+                .map_err(|e| Envelope::failed("resolve_copy_window", e))?
+        }
+    };
+    let request = ReplayRequest {
+        target_topic: args.target_topic,
+        ranges,
+        preserve_partition: args.preserve_partition,
+        timestamps: args.timestamps,
+        original_timestamp_header: None,
+        throttle: args.throttle,
+    };
+    let source = args.config;
+    let target = args.target.unwrap_or_else(|| source.clone());
+
+    // Cancel previous copy if exists, then install a new one
+    let rx = {
+        let mut sess_guard = state.copy_session.lock().map_err(Envelope::state)?;
+        if let Some(prev) = sess_guard.take() {
+            let _ = prev.cancel_tx.send(());
+        }
+        let (tx, rx) = broadcast::channel::<()>(1);
+        *sess_guard = Some(LoadSession::new(tx));
+        rx
+    };
+    let _ = window.emit("kafka:copy_started", &serde_json::json!({ "ranges": request.ranges }));
+
+    tokio::spawn(async move {
+        let mut watch = CopyWatch::new(rx, COPY_PROGRESS_INTERVAL, std::time::Instant::now());
+        let result = Kafka::replay_to(&source, &target, &request, |summary| {
+            if !watch.keep_going() {
+                return false;
+            }
+            if watch.emit_due(std::time::Instant::now()) {
+                let _ = window.emit("kafka:copy_progress", summary);
+            }
+            true
+        })
+        .await
+        .map_err(|e| Envelope::failed("copy_messages", e));
+        let cancelled = watch.cancelled();
+        match &result {
+            Ok(summary) if cancelled => {
+                let _ = window.emit("kafka:copy_cancelled", summary);
+            }
+            Ok(summary) => {
+                let _ = window.emit("kafka:copy_done", summary);
+            }
+            Err(e) => {
+                let _ = window.emit("kafka:copy_error", &serde_json::json!({ "code": e.code, "error": e.message }));
+            }
+        }
+        let details = serde_json::json!({
+            "source_topic": source.topic,
+            "target_broker": target.broker,
+            "ranges": request.ranges,
+            "produced": result.as_ref().ok().map(|s| s.produced),
+            "cancelled": cancelled,
+        });
+        audit::record(&app, "copy", &source, &request.target_topic, details, &result);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn cancel_copy(state: State<'_, AppState>) -> CommandResult<()> {
+    let mut sess_guard = state.copy_session.lock().map_err(Envelope::state)?;
+    if let Some(s) = sess_guard.take() {
+        let _ = s.cancel_tx.send(());
+    }
+    Ok(())
 }
 
 use tokio::sync::broadcast;
 
-// jq/jaq support via jq-rs
+// Message filters: plain text or jq (jaq)
 
 #[derive(Debug, Deserialize, Serialize, Clone, Copy)]
 pub enum FilterMode {
@@ -89,6 +953,28 @@ impl Default for FilterMode {
     fn default() -> Self { FilterMode::Plain }
 }
 
+/// Options of the plain (substring) filters; jq filters ignore them.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlainFilterOptions {
+    /// Match key and message filters with exact case
+    #[serde(default, rename = "case_sensitive", alias = "caseSensitive")]
+    pub case_sensitive: bool,
+    /// Keep the records whose message does NOT contain the message filter
+    #[serde(default, rename = "invert_match", alias = "invertMatch")]
+    pub invert_match: bool,
+}
+
+impl PlainFilterOptions {
+    /// Whether `haystack` contains `needle` under the case setting (inversion is left to the caller).
+    pub fn contains(self, haystack: &str, needle: &str) -> bool {
+        if self.case_sensitive {
+            haystack.contains(needle)
+        } else {
+            haystack.to_lowercase().contains(&needle.to_lowercase())
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct StartFilteredLoadArgs {
     pub limit: Option<usize>,
@@ -98,141 +984,422 @@ pub struct StartFilteredLoadArgs {
     pub message_filter: Option<String>,
     #[serde(rename = "message_filter_mode", alias = "messageFilterMode")]
     pub message_filter_mode: Option<FilterMode>,
+    #[serde(flatten)]
+    pub plain: PlainFilterOptions,
+    /// Connection to read from; the active one when omitted
+    pub connection: Option<String>,
+    /// Rhai script run on each record before filtering (see `TransformScript`)
+    #[serde(rename = "transform_script", alias = "transformScript")]
+    pub transform_script: Option<String>,
+    /// Show only or hide tombstones; all records by default
+    #[serde(default)]
+    pub tombstones: TombstoneFilter,
+    /// Epoch ms; partitions are sought to the first record at or after it
+    #[serde(rename = "from_timestamp", alias = "fromTimestamp")]
+    pub from_timestamp: Option<i64>,
+    /// Find the first record of the time range from record timestamps instead of the broker's time index,
+    /// as `get_offsets_for_time` does with `precise`
+    #[serde(default, rename = "precise_time", alias = "preciseTime")]
+    pub precise_time: bool,
+    /// Epoch ms (exclusive); a partition stops once its records reach it
+    #[serde(rename = "to_timestamp", alias = "toTimestamp")]
+    pub to_timestamp: Option<i64>,
+    /// Partition -> (start_offset, end_offset), both inclusive like `first_offset`/`last_offset` of a
+    /// load report; only these partitions are read
+    #[serde(rename = "offset_ranges", alias = "offsetRanges")]
+    pub offset_ranges: Option<std::collections::HashMap<i32, (i64, i64)>>,
+    /// Matches per `kafka:messages` event (default 100)
+    #[serde(rename = "emit_batch_size", alias = "emitBatchSize")]
+    pub emit_batch_size: Option<usize>,
+    /// Longest a match waits for its batch to fill before it is emitted anyway, in ms (default 100)
+    #[serde(rename = "emit_interval_ms", alias = "emitIntervalMs")]
+    pub emit_interval_ms: Option<u64>,
+    /// Stop after reading this many records, matched or not
+    #[serde(rename = "max_scan_messages", alias = "maxScanMessages")]
+    pub max_scan_messages: Option<u64>,
+    /// Stop after scanning this long, in ms
+    #[serde(rename = "max_scan_duration_ms", alias = "maxScanDurationMs")]
+    pub max_scan_duration_ms: Option<u64>,
+    /// Read from the oldest offsets forward (default) or from the newest backwards
+    #[serde(default)]
+    pub direction: ScanDirection,
+    /// Finish as soon as this many records matched ("find the first occurrence" is 1), regardless of `limit`
+    #[serde(rename = "stop_after_matches", alias = "stopAfterMatches")]
+    pub stop_after_matches: Option<usize>,
 }
 
+/// How often a filtered load reports `kafka:load_progress`.
+const LOAD_PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
 
-fn eval_jq_bool(program: &str, value: &serde_json::Value) -> Result<bool, String> {
-    // Minimal jq-like evaluator without external/system dependencies.
-    // Supported forms:
-    // - ".a.b" -> true if value at path is boolean true
-    // - ".a.b == <literal>" -> true if equal (literal parsed as JSON if possible, or as string)
-    // - "true" -> true
-    let src = program.trim();
-    if src == "true" { return Ok(true); }
-
-    // Split by '==' if present (simple, not handling complex jq syntax)
-    if let Some(idx) = src.find("==") {
-        let (left, right) = src.split_at(idx);
-        let left = left.trim();
-        let right = right.trim_start_matches("==").trim();
-        let lv = json_path_get(value, left).ok_or_else(|| "jq path not found".to_string())?;
-        // Try parse right as JSON literal
-        let rv: serde_json::Value = match serde_json::from_str(right) {
-            Ok(v) => v,
-            Err(_) => {
-                // strip optional surrounding quotes
-                let r = right.trim();
-                let r = r.strip_prefix('"').and_then(|s| s.strip_suffix('"')).unwrap_or(r);
-                serde_json::Value::String(r.to_string())
-            }
-        };
-        return Ok(lv == rv);
+/// Matches of a filtered load waiting to go out as one `kafka:messages` event; one event per record floods
+/// the IPC bridge on hot topics.
+pub struct MessageBatch {
+    messages: Vec<UiMessage>,
+    size: usize,
+    interval: std::time::Duration,
+    last_flush: std::time::Instant,
+}
+
+impl MessageBatch {
+    pub fn new(size: usize, interval: std::time::Duration) -> Self {
+        let size = size.max(1);
+        Self { messages: Vec::with_capacity(size), size, interval, last_flush: std::time::Instant::now() }
+    }
+
+    pub fn push(&mut self, ui: UiMessage) {
+        if self.messages.is_empty() {
+            // The interval counts from the oldest waiting match
+            self.last_flush = std::time::Instant::now();
+        }
+        self.messages.push(ui);
+    }
+
+    /// Time until the pending matches must be emitted; None when nothing is pending.
+    pub fn until_due(&self) -> Option<std::time::Duration> {
+        if self.messages.is_empty() {
+            return None;
+        }
+        if self.messages.len() >= self.size {
+            return Some(std::time::Duration::ZERO);
+        }
+        Some(self.interval.saturating_sub(self.last_flush.elapsed()))
     }
 
-    // Path-only form
-    if src.starts_with('.') {
-        if let Some(v) = json_path_get(value, src) {
-            return Ok(v == serde_json::Value::Bool(true));
+    /// The pending matches, leaving the batch empty.
+    pub fn take(&mut self) -> Vec<UiMessage> {
+        std::mem::replace(&mut self.messages, Vec::with_capacity(self.size))
+    }
+
+    fn flush(&mut self, window: &Window) {
+        if !self.messages.is_empty() {
+            let _ = window.emit("kafka:messages", &self.take());
+        }
+    }
+}
+
+/// Scan budget of a filtered load (`max_scan_messages`, `max_scan_duration_ms`).
+pub struct ScanBudget {
+    pub max_messages: Option<u64>,
+    pub max_duration: Option<std::time::Duration>,
+}
+
+impl ScanBudget {
+    /// The budget used up ("messages" or "duration") after reading `scanned` records for `active` time.
+    pub fn exhausted(&self, scanned: u64, active: std::time::Duration) -> Option<&'static str> {
+        if self.max_messages.is_some_and(|max| scanned >= max) {
+            Some("messages")
+        } else if self.max_duration.is_some_and(|max| active >= max) {
+            Some("duration")
         } else {
-            return Ok(false);
+            None
+        }
+    }
+}
+
+/// Why a filtered load is done after `emitted` matches: "matches_found" once `stop_after_matches` is reached
+/// (checked first, it is the narrower ask) or "limit_reached".
+pub fn matches_done(emitted: usize, stop_after_matches: Option<usize>, limit: usize) -> Option<&'static str> {
+    if stop_after_matches.is_some_and(|n| emitted >= n) {
+        Some("matches_found")
+    } else if emitted >= limit {
+        Some("limit_reached")
+    } else {
+        None
+    }
+}
+
+/// Scanning time of a filtered load. Time spent paused is left out, so it doesn't count against the duration budget.
+pub struct ActiveClock {
+    started: std::time::Instant,
+    paused_for: std::time::Duration,
+    paused_at: Option<std::time::Instant>,
+}
+
+impl ActiveClock {
+    pub fn start(now: std::time::Instant) -> Self {
+        Self { started: now, paused_for: std::time::Duration::ZERO, paused_at: None }
+    }
+
+    pub fn pause(&mut self, now: std::time::Instant) {
+        self.paused_at.get_or_insert(now);
+    }
+
+    pub fn resume(&mut self, now: std::time::Instant) {
+        if let Some(at) = self.paused_at.take() {
+            self.paused_for += now.saturating_duration_since(at);
         }
     }
 
-    // Unsupported expression -> treat as non-match
-    Err("unsupported jq expression".into())
+    pub fn elapsed(&self, now: std::time::Instant) -> std::time::Duration {
+        self.paused_at.unwrap_or(now).saturating_duration_since(self.started).saturating_sub(self.paused_for)
+    }
+}
+
+/// Wait while a load is paused: true once it is resumed (or its pause switch is gone), false when it is cancelled.
+pub async fn hold_while_paused(
+    cancel: &mut broadcast::Receiver<()>,
+    paused: &mut tokio::sync::watch::Receiver<bool>,
+) -> bool {
+    while *paused.borrow_and_update() {
+        tokio::select! {
+            biased;
+            _ = cancel.recv() => return false,
+            changed = paused.changed() => {
+                if changed.is_err() {
+                    return true;
+                }
+            }
+        }
+    }
+    true
+}
+
+/// `kafka:load_done` with why the load finished: "completed" (snapshot read), "limit_reached", "matches_found"
+/// (`stop_after_matches`) or "budget_exhausted" (sent with the budget's details instead).
+fn emit_load_done(window: &Window, reason: &str, emitted: usize, report: &LoadReportBuilder) {
+    let _ = window.emit("kafka:load_done", &serde_json::json!({
+        "reason": reason,
+        "emitted": emitted,
+        "decode_failures": report.decode_failures(),
+    }));
+}
+
+/// Message filter compiled once per load: plain text (contains, optionally case-sensitive or inverted) or a jq program.
+pub(crate) enum MessageFilter {
+    Any,
+    Plain(String, PlainFilterOptions),
+    Jq(JqFilter),
 }
 
-fn json_path_get<'a>(root: &'a serde_json::Value, path: &str) -> Option<serde_json::Value> {
-    // path like .a.b[0].c
-    if !path.starts_with('.') { return None; }
-    let mut cur = root;
-    let mut idx = 1usize; // skip leading '.'
-    while idx < path.len() {
-        // parse key up to next '.' or '['
-        let bytes = path.as_bytes();
-        let mut j = idx;
-        while j < bytes.len() && bytes[j] != b'.' && bytes[j] != b'[' { j += 1; }
-        if j > idx {
-            let key = &path[idx..j];
-            cur = cur.get(key)?;
+impl MessageFilter {
+    pub(crate) fn new(filter: Option<&str>, mode: FilterMode, plain: PlainFilterOptions) -> CommandResult<Self> {
+        let Some(f) = filter.filter(|s| !s.trim().is_empty()) else { return Ok(MessageFilter::Any) };
+        match mode {
+            FilterMode::Plain => Ok(MessageFilter::Plain(f.to_string(), plain)),
+            FilterMode::Jq => JqFilter::compile(f)
+                .map(MessageFilter::Jq)
+                .map_err(|e| Envelope::invalid(format!("Invalid jq filter: {e}")).with("error", e.to_string())),
         }
-        idx = j;
-        if idx >= bytes.len() { break; }
-        if bytes[idx] == b'.' { idx += 1; continue; }
-        // handle [n]
-        if bytes[idx] == b'[' {
-            idx += 1;
-            // read number
-            let mut k = idx;
-            while k < bytes.len() && bytes[k].is_ascii_digit() { k += 1; }
-            if k == idx { return None; }
-            let n: usize = path[idx..k].parse().ok()?;
-            if k >= bytes.len() || bytes[k] != b']' { return None; }
-            cur = cur.get(n)?;
-            idx = k + 1;
-            if idx < bytes.len() && bytes[idx] == b'.' { idx += 1; }
-            continue;
+    }
+
+    fn matches(&self, message: &str) -> bool {
+        match self {
+            MessageFilter::Any => true,
+            MessageFilter::Plain(needle, opts) => opts.contains(message, needle) != opts.invert_match,
+            // Non-JSON messages never match a jq filter
+            MessageFilter::Jq(jq) => serde_json::from_str::<serde_json::Value>(message).is_ok_and(|v| jq.matches(&v)),
         }
     }
-    Some(cur.clone())
 }
 
-#[tauri::command]
-pub async fn start_filtered_load(window: Window, state: State<'_, AppState>, args: StartFilteredLoadArgs) -> Result<(), String> {
-    let limit = args.limit.unwrap_or(200);
+/// Which records to keep by tombstone state.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TombstoneFilter {
+    #[default]
+    #[serde(rename = "all")] All,
+    #[serde(rename = "only")] Only,
+    #[serde(rename = "exclude")] Exclude,
+}
 
-    // Prepare Kafka access and snapshot necessary pieces
-    let (consumer, message_type, proto_decoder, topic, parts, ends) = {
-        let guard = state.kafka.lock().map_err(|e| format!("Failed to access state: {e}"))?;
-        let Some(k) = &*guard else { return Err("Kafka is not configured".into()); };
-        // Ensure assignment to requested partitions/offsets without consuming any messages
-        if let Err(e) = k.ensure_assigned() {
-            return Err(format!("Failed to assign consumer: {e}"));
-        }
-        let parts = k
-            .partitions
-            .lock()
-            .map_err(|e| format!("State lock poisoned (partitions): {e}"))?
-            .clone();
-        let ends = k
-            .end_offsets
-            .lock()
-            .map_err(|e| format!("State lock poisoned (end_offsets): {e}"))?
-            .clone();
-        (
-            k.consumer.clone(),
-            k.config.message_type.clone(),
-            k.proto_decoder.clone(),
-            k.config.topic.clone(),
-            parts,
-            ends,
-        )
-    };
+impl TombstoneFilter {
+    pub fn matches(self, ui: &UiMessage) -> bool {
+        match self {
+            TombstoneFilter::All => true,
+            TombstoneFilter::Only => ui.is_tombstone,
+            TombstoneFilter::Exclude => !ui.is_tombstone,
+        }
+    }
+}
+
+/// Order in which a filtered load reads each partition.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScanDirection {
+    #[default]
+    #[serde(rename = "forward")] Forward,
+    /// Newest first, in windows of `REVERSE_WINDOW` offsets per partition: recent matches show up without
+    /// waiting for the whole topic
+    #[serde(rename = "backward")] Backward,
+}
+
+/// Key filter (plain contains, case per `plain`) and message filter; empty filters match everything.
+pub(crate) fn message_matches(
+    ui: &UiMessage,
+    key_filter: Option<&str>,
+    plain: PlainFilterOptions,
+    msg_filter: &MessageFilter,
+) -> bool {
+    if let Some(kf) = key_filter.filter(|s| !s.is_empty()) {
+        if !plain.contains(&ui.key, kf) {
+            return false;
+        }
+    }
+    msg_filter.matches(&ui.message)
+}
+
+/// Where a filtered load reads each partition.
+pub struct LoadAssignment {
+    pub parts: Vec<i32>,
+    /// Snapshot end (exclusive) per partition
+    pub ends: std::collections::HashMap<i32, i64>,
+    /// Partitions with nothing to read
+    pub done: std::collections::HashSet<i32>,
+    /// First offset to read per partition; a backward scan stops there
+    pub starts: std::collections::HashMap<i32, i64>,
+}
+
+/// Assign the connection's consumer for a filtered load: exact `offset_ranges`, the first offsets of a time
+/// range, or the reader's own assignment. Fetches metadata and watermarks, so it runs off the async runtime.
+pub fn assign_load(
+    k: &Kafka,
+    offset_ranges: Option<&std::collections::HashMap<i32, (i64, i64)>>,
+    first_offsets: Option<&[TimeOffset]>,
+) -> CommandResult<LoadAssignment> {
+    // The load polls the main queue, which doesn't see partitions split off for paging
+    k.release_partition_queues().map_err(|e| Envelope::failed("assign_consumer", e))?;
+    // Ensure assignment to requested partitions/offsets without consuming any messages
+    k.ensure_assigned().map_err(|e| Envelope::failed("assign_consumer", e))?;
+    let mut parts = k.partitions.lock().map_err(Envelope::state)?.clone();
+    let mut ends = k.end_offsets.lock().map_err(Envelope::state)?.clone();
+    let mut done = std::collections::HashSet::new();
+    let mut starts = std::collections::HashMap::new();
+    let topic = k.config.topic.as_str();
+    if let Some(ranges) = offset_ranges {
+        // Re-scan exact windows, clamped to what is still retained and already written
+        let mut tpl = rdkafka::TopicPartitionList::new();
+        parts = ranges.keys().copied().collect();
+        parts.sort_unstable();
+        ends.clear();
+        for (&p, &(start, end)) in ranges {
+            let (low, high) = k
+                .consumer
+                .fetch_watermarks(topic, p, std::time::Duration::from_secs(5))
+                .map_err(|e| Envelope::failed("fetch_watermarks", e).with("partition", p))?;
+            let (start, stop) = (start.max(low), end.saturating_add(1).min(high));
+            ends.insert(p, stop);
+            if start >= stop {
+                done.insert(p);
+                continue;
+            }
+            starts.insert(p, start);
+            tpl.add_partition_offset(topic, p, rdkafka::Offset::Offset(start))
+                .map_err(|e| Envelope::failed("seek_partition", e).with("partition", p))?;
+        }
+        k.assign(&tpl).map_err(|e| Envelope::failed("assign_consumer", e))?;
+    } else if let Some(first) = first_offsets {
+        // Seek every partition to the first record of the time range; those with none are done up front
+        let mut tpl = rdkafka::TopicPartitionList::new();
+        for p in &parts {
+            match first.iter().find(|s| s.partition == *p).and_then(|s| s.offset) {
+                Some(o) => {
+                    starts.insert(*p, o);
+                    tpl.add_partition_offset(topic, *p, rdkafka::Offset::Offset(o))
+                        .map_err(|e| Envelope::failed("seek_partition", e).with("partition", p))?
+                }
+                None => {
+                    done.insert(*p);
+                }
+            }
+        }
+        k.assign(&tpl).map_err(|e| Envelope::failed("assign_consumer", e))?;
+    }
 
-    // Compute initial done set for empty partitions (low == end)
-    let mut done_parts: std::collections::HashSet<i32> = std::collections::HashSet::new();
+    // Add empty partitions (low == end) to the done set
     for p in &parts {
-        if let Ok((low, _high)) = consumer.fetch_watermarks(&topic, *p, std::time::Duration::from_secs(5)) {
+        if let Ok((low, _high)) = k.consumer.fetch_watermarks(topic, *p, std::time::Duration::from_secs(5)) {
             if low >= *ends.get(p).unwrap_or(&i64::MAX) {
-                done_parts.insert(*p);
+                done.insert(*p);
             }
+            // Without an explicit start a backward scan runs down to the oldest retained record
+            starts.entry(*p).or_insert(low);
         }
     }
+    starts.retain(|p, _| !done.contains(p));
+    Ok(LoadAssignment { parts, ends, done, starts })
+}
+
+#[tauri::command]
+pub async fn start_filtered_load(window: Window, state: State<'_, AppState>, args: StartFilteredLoadArgs) -> CommandResult<()> {
+    let limit = args.limit.unwrap_or(200);
+    let transform = TransformScript::from_option(args.transform_script.as_deref())?;
+    let filter_mode = args.message_filter_mode.unwrap_or(FilterMode::Plain);
+    let compiled_filter = MessageFilter::new(args.message_filter.as_deref(), filter_mode, args.plain)?;
+
+    let (from_ts, to_ts) = (args.from_timestamp, args.to_timestamp);
+    if let (Some(from), Some(to)) = (from_ts, to_ts) {
+        if from >= to {
+            return Err(Envelope::invalid("from_timestamp must be before to_timestamp"));
+        }
+    }
+    if args.stop_after_matches == Some(0) {
+        return Err(Envelope::invalid("stop_after_matches must be at least 1"));
+    }
+    if let Some(ranges) = &args.offset_ranges {
+        if from_ts.is_some() {
+            return Err(Envelope::invalid("offset_ranges and from_timestamp cannot be combined"));
+        }
+        if ranges.is_empty() {
+            return Err(Envelope::invalid("offset_ranges is empty"));
+        }
+        if let Some((p, _)) = ranges.iter().find(|(_, (start, end))| start > end) {
+            return Err(Envelope::invalid(format!("Invalid offset range for partition {p}: start is after end")).with("partition", p));
+        }
+    }
+
+    // The load reads through the same consumer: stop the connection's read-ahead first
+    let (name, config) = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        let name = guard.resolve(args.connection.as_deref()).ok_or_else(Envelope::not_configured)?;
+        let config = guard.get(Some(&name)).ok_or_else(Envelope::not_configured)?.config.clone();
+        (name, config)
+    };
+    if let Some(prewarm) = state.take_prewarm(&name).map_err(Envelope::state)? {
+        prewarm.stop().await;
+    }
+    // First offsets of the time range, resolved off the state lock (a precise search fetches per probe)
+    let first_offsets = match from_ts {
+        Some(from) => {
+            let precise = args.precise_time;
+            let offsets = tokio::task::spawn_blocking(move || Kafka::offsets_for_time(&config, &config.topic, from, precise))
+                .await
+                .map_err(|e| Envelope::failed("resolve_start_time", e))?
+                .map_err(|e| Envelope::failed("resolve_start_time", e))?;
+            Some(offsets)
+        }
+        None => None,
+    };
+
+    // Assign and snapshot the window off the state lock and the async runtime (metadata and watermark fetches)
+    let k = {
+        let guard = state.kafka.lock().map_err(Envelope::state)?;
+        guard.get_shared(Some(&name)).ok_or_else(Envelope::not_configured)?
+    };
+    let reader = k.clone();
+    let offset_ranges = args.offset_ranges.clone();
+    let LoadAssignment { parts, ends, done: done_parts, starts } =
+        tokio::task::spawn_blocking(move || assign_load(&reader, offset_ranges.as_ref(), first_offsets.as_deref()))
+            .await
+            .map_err(|e| Envelope::failed("assign_consumer", e))??;
+    let (consumer, codec, raw_cache, topic) = (k.consumer.clone(), k.codec.clone(), k.raw_cache.clone(), k.config.topic.clone());
+    let split = k.detached_partitions().map_err(Envelope::state)?;
+    let direction = args.direction;
 
     // Cancel previous session if exists, then install a new one
     {
-        let mut sess_guard = state.load_session.lock().map_err(|e| format!("Failed to access load session: {e}"))?;
+        let mut sess_guard = state.load_session.lock().map_err(Envelope::state)?;
         if let Some(prev) = sess_guard.take() {
             let _ = prev.cancel_tx.send(());
         }
         let (tx, _rx0) = broadcast::channel::<()>(1);
-        *sess_guard = Some(LoadSession { cancel_tx: tx.clone() });
+        let session = LoadSession::new(tx.clone());
+        let mut paused_rx = session.pause_tx.subscribe();
+        *sess_guard = Some(session);
         drop(sess_guard);
 
         // Snapshot filter settings
-        let filter_mode = args.message_filter_mode.unwrap_or(FilterMode::Plain);
         let key_filter = args.key_filter.clone();
         let msg_filter = args.message_filter.clone();
+        let tombstones = args.tombstones;
+        let plain = args.plain;
 
         // Emit started event
         let _ = window.emit("kafka:load_started", &serde_json::json!({
@@ -240,130 +1407,214 @@ pub async fn start_filtered_load(window: Window, state: State<'_, AppState>, arg
             "keyFilter": key_filter,
             "messageFilter": msg_filter,
             "messageFilterMode": filter_mode,
+            "caseSensitive": plain.case_sensitive,
+            "invertMatch": plain.invert_match,
+            "tombstones": tombstones,
+            "fromTimestamp": from_ts,
+            "toTimestamp": to_ts,
+            "offsetRanges": args.offset_ranges,
+            "maxScanMessages": args.max_scan_messages,
+            "maxScanDurationMs": args.max_scan_duration_ms,
+            "direction": direction,
+            "stopAfterMatches": args.stop_after_matches,
         }));
 
         let mut rx = tx.subscribe();
         let win = window.clone();
+        let mut last_progress = std::time::Instant::now();
+        let mut clock = ActiveClock::start(std::time::Instant::now());
+        let stop_after_matches = args.stop_after_matches;
+        let budget = ScanBudget {
+            max_messages: args.max_scan_messages,
+            max_duration: args.max_scan_duration_ms.map(std::time::Duration::from_millis),
+        };
+        let mut batch = MessageBatch::new(
+            args.emit_batch_size.unwrap_or(100),
+            std::time::Duration::from_millis(args.emit_interval_ms.unwrap_or(100)),
+        );
         let mut done_parts_local = done_parts.clone();
+        let reports = state.last_load_report.clone();
+        let mut report = LoadReportBuilder::new(
+            topic.clone(),
+            args.connection.clone(),
+            ReportFilters {
+                key_filter: key_filter.clone(),
+                message_filter: msg_filter.clone(),
+                message_filter_mode: match filter_mode {
+                    FilterMode::Plain => "plain".into(),
+                    FilterMode::Jq => "jq".into(),
+                },
+                plain,
+                limit,
+            },
+            &parts,
+        );
+        if direction == ScanDirection::Backward {
+            report.scan_backward(starts.clone());
+        }
         tokio::spawn(async move {
             use rdkafka::message::Message as RdMessage;
-            use tokio::sync::broadcast::error::TryRecvError;
 
+            let backward = direction == ScanDirection::Backward;
+            // Reassigns the consumer window by window; the forward scan keeps the assignment made above
+            let mut reverse = backward.then(|| ReverseScan::new(&consumer, &topic, split, &starts, &ends));
             let mut emitted = 0usize;
-            loop {
-                // Check cancellation
-                match rx.try_recv() {
-                    Ok(_) | Err(TryRecvError::Closed) => {
-                        let _ = win.emit("kafka:load_cancelled", &serde_json::json!({}));
-                        break;
-                    }
-                    Err(TryRecvError::Empty) => {}
-                    Err(TryRecvError::Lagged(_)) => {}
+            let mut scanned = 0u64;
+            let outcome = loop {
+                // If all partitions are already done, finish
+                let finished = match &reverse {
+                    Some(r) => r.finished(),
+                    None => !parts.is_empty() && parts.iter().all(|p| done_parts_local.contains(p)),
+                };
+                if finished {
+                    batch.flush(&win);
+                    emit_load_done(&win, "completed", emitted, &report);
+                    break "completed";
                 }
 
-                // If all partitions are already done, finish
-                if !parts.is_empty() && parts.iter().all(|p| done_parts_local.contains(p)) {
-                    let _ = win.emit("kafka:load_done", &serde_json::json!({ "emitted": emitted }));
-                    break;
+                // Held by the user: matches so far go out, then nothing is read until resumed or cancelled
+                if *paused_rx.borrow_and_update() {
+                    batch.flush(&win);
+                    let _ = win.emit("kafka:load_paused", report.progress(&ends, &done_parts_local));
+                    clock.pause(std::time::Instant::now());
+                    let resumed = hold_while_paused(&mut rx, &mut paused_rx).await;
+                    clock.resume(std::time::Instant::now());
+                    if !resumed {
+                        let _ = win.emit("kafka:load_cancelled", &serde_json::json!({
+                            "emitted": emitted,
+                            "decode_failures": report.decode_failures(),
+                        }));
+                        break "cancelled";
+                    }
+                    let _ = win.emit("kafka:load_resumed", &serde_json::json!({ "scanned": scanned, "emitted": emitted }));
                 }
 
-                match consumer.as_ref().poll(std::time::Duration::from_millis(200)) {
+                // Out of budget: end like a finished load, saying which limit was hit
+                let active = clock.elapsed(std::time::Instant::now());
+                if let Some(exhausted) = budget.exhausted(scanned, active) {
+                    batch.flush(&win);
+                    let _ = win.emit("kafka:load_done", &serde_json::json!({
+                        "reason": "budget_exhausted",
+                        "budget": exhausted,
+                        "scanned": scanned,
+                        "emitted": emitted,
+                        "elapsed_ms": active.as_millis() as u64,
+                        "decode_failures": report.decode_failures(),
+                    }));
+                    break "budget_exhausted";
+                }
+                if batch.until_due() == Some(std::time::Duration::ZERO) {
+                    batch.flush(&win);
+                }
+                if last_progress.elapsed() >= LOAD_PROGRESS_INTERVAL {
+                    let _ = win.emit("kafka:load_progress", report.progress(&ends, &done_parts_local));
+                    last_progress = std::time::Instant::now();
+                }
+                // Wait for the next record or a cancel, whichever comes first (a closed channel cancels too);
+                // pending matches cut the wait short so they go out on time
+                let wait = batch
+                    .until_due()
+                    .map_or(std::time::Duration::from_millis(200), |d| d.max(std::time::Duration::from_millis(1)));
+                let polled = tokio::select! {
+                    biased;
+                    _ = rx.recv() => {
+                        batch.flush(&win);
+                        let _ = win.emit("kafka:load_cancelled", &serde_json::json!({
+                            "emitted": emitted,
+                            "decode_failures": report.decode_failures(),
+                        }));
+                        break "cancelled";
+                    }
+                    polled = async {
+                        match reverse.as_mut() {
+                            Some(r) => r.next(wait).await,
+                            None => recv_timeout(&consumer, wait).await,
+                        }
+                    } => polled,
+                };
+                match polled {
                     Some(Ok(m)) => {
                         let partition = m.partition();
                         let offset = m.offset();
                         let end = *ends.get(&partition).unwrap_or(&i64::MAX);
+                        scanned += 1;
 
                         // If we've reached or passed the snapshot end, mark as done and skip
-                        if offset >= end {
+                        if offset >= end || done_parts_local.contains(&partition) {
                             done_parts_local.insert(partition);
                             continue;
                         }
 
-                        // Decode key and payload similarly to Kafka::decode
-                        let key_s = m.key().map(|k| String::from_utf8_lossy(k).to_string()).unwrap_or_default();
-                        let payload_s;
-                        let mut decoding_error: Option<String> = None;
-                        if matches!(message_type, crate::kafka::MessageType::Protobuf) {
-                            if let (Some(pd), Some(bytes)) = (proto_decoder.as_ref(), m.payload()) {
-                                match pd.decode(bytes) {
-                                    Ok(json) => { payload_s = json; }
-                                    Err(e) => {
-                                        payload_s = String::from_utf8_lossy(bytes).to_string();
-                                        decoding_error = Some(format!("Protobuf decode error: {}", e));
-                                    }
+                        // Past the end of the time range: this partition is done (records without a timestamp pass);
+                        // a backward scan starts there and skips ahead
+                        let ts = m.timestamp().to_millis();
+                        if let (Some(to), Some(ts)) = (to_ts, ts) {
+                            if ts >= to {
+                                if !backward {
+                                    done_parts_local.insert(partition);
                                 }
-                            } else {
-                                payload_s = m.payload().map(|b| String::from_utf8_lossy(b).to_string()).unwrap_or_default();
+                                continue;
                             }
-                        } else {
-                            let dec = crate::kafka::decoder_for(&message_type);
-                            let (_k, v) = dec.decode(None, m.payload());
-                            payload_s = v;
-                        }
-
-                        // Apply filters and emit if matched
-                        let mut pass = true;
-                        // Key filter (plain contains)
-                        if let Some(kf) = key_filter.as_ref().and_then(|s| if s.is_empty() { None } else { Some(s) }) {
-                            pass &= key_s.to_lowercase().contains(&kf.to_lowercase());
                         }
-                        // Message filter: jq or plain contains
-                        if pass {
-                            if let Some(mf) = msg_filter.as_ref().and_then(|s| if s.is_empty() { None } else { Some(s) }) {
-                                match filter_mode {
-                                    FilterMode::Jq => {
-                                        // Try parse as JSON and evaluate; if parsing fails or eval is false, drop
-                                        match serde_json::from_str::<serde_json::Value>(&payload_s) {
-                                            Ok(val) => match eval_jq_bool(mf, &val) {
-                                                Ok(true) => { /* keep pass = true */ }
-                                                _ => { pass = false; }
-                                            },
-                                            Err(_) => { pass = false; }
-                                        }
-                                    }
-                                    FilterMode::Plain => {
-                                        pass &= payload_s.to_lowercase().contains(&mf.to_lowercase());
-                                    }
+                        // Out-of-order CreateTime records older than the start are skipped
+                        if let (Some(from), Some(ts)) = (from_ts, ts) {
+                            if ts < from {
+                                if !backward && offset >= end - 1 {
+                                    done_parts_local.insert(partition);
                                 }
+                                continue;
                             }
                         }
 
-                        if pass {
-                            let ts_str = match m.timestamp() {
-                                rdkafka::message::Timestamp::NotAvailable => String::new(),
-                                rdkafka::message::Timestamp::CreateTime(ms)
-                                | rdkafka::message::Timestamp::LogAppendTime(ms) => {
-                                    if let Some(dt) = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(ms) {
-                                        dt.to_rfc3339()
-                                    } else { String::new() }
-                                }
-                            };
-                            let ui = UiMessage {
-                                id: format!("{}-{}", partition, offset),
-                                partition,
-                                key: key_s,
-                                offset,
-                                message: payload_s,
-                                timestamp: ts_str,
-                                decoding_error,
-                            };
-                            let _ = win.emit("kafka:message", &ui);
+                        // Filters need the payload, so always decode fully here
+                        let (ts_ms, mut ui) = codec.to_ui_message_full(&m);
+                        let kept = transform.as_ref().is_none_or(|t| t.apply(&mut ui));
+
+                        // Apply filters and emit if matched
+                        let matched = kept
+                            && tombstones.matches(&ui)
+                            && message_matches(&ui, key_filter.as_deref(), plain, &compiled_filter);
+                        report.record(&ui, ts_ms, matched);
+                        if matched {
+                            if let Some(cache) = &raw_cache {
+                                cache.insert(&m);
+                            }
+                            batch.push(ui);
                             emitted += 1;
-                            if emitted >= limit {
-                                let _ = win.emit("kafka:load_done", &serde_json::json!({ "emitted": emitted }));
-                                break;
+                            // What was asked for is found: no need to read further
+                            if let Some(reason) = matches_done(emitted, stop_after_matches, limit) {
+                                batch.flush(&win);
+                                emit_load_done(&win, reason, emitted, &report);
+                                break reason;
                             }
                         }
 
                         // After processing, if we've emitted the last offset in the snapshot, mark partition done
-                        if offset >= end - 1 {
+                        if !backward && offset >= end - 1 {
                             done_parts_local.insert(partition);
                         }
                     }
+                    Some(Err(e)) if e.rdkafka_error_code().is_some_and(is_authorization_error) => {
+                        batch.flush(&win);
+                        let _ = win.emit("kafka:load_error", &serde_json::json!({
+                            "code": "unauthorized",
+                            "topic": topic,
+                            "error": e.to_string(),
+                            "emitted": emitted,
+                            "decode_failures": report.decode_failures(),
+                        }));
+                        break "unauthorized";
+                    }
                     Some(Err(_)) | None => {
                         // No message in this poll window; just continue to allow cancel or new data
                     }
                 }
+            };
+
+            let report = report.finish(outcome);
+            let _ = win.emit("kafka:load_report", &report);
+            if let Ok(mut last) = reports.lock() {
+                *last = Some(report);
             }
         });
     }
@@ -371,9 +1622,30 @@ pub async fn start_filtered_load(window: Window, state: State<'_, AppState>, arg
     Ok(())
 }
 
+fn set_load_paused(state: &AppState, paused: bool) -> CommandResult<()> {
+    let guard = state.load_session.lock().map_err(Envelope::state)?;
+    let session = guard
+        .as_ref()
+        .ok_or_else(|| Envelope::error("no_load_running", "No filtered load is running").kind(ErrorKind::State))?;
+    session.pause_tx.send_replace(paused);
+    Ok(())
+}
+
+/// Hold the running filtered load: matches found so far are emitted and reading stops until resumed.
+#[tauri::command]
+pub async fn pause_filtered_load(state: State<'_, AppState>) -> CommandResult<()> {
+    set_load_paused(&state, true)
+}
+
+/// Continue a paused filtered load from where it stopped.
 #[tauri::command]
-pub async fn cancel_filtered_load(state: State<'_, AppState>) -> Result<(), String> {
-    let mut sess_guard = state.load_session.lock().map_err(|e| format!("Failed to access load session: {e}"))?;
+pub async fn resume_filtered_load(state: State<'_, AppState>) -> CommandResult<()> {
+    set_load_paused(&state, false)
+}
+
+#[tauri::command]
+pub async fn cancel_filtered_load(state: State<'_, AppState>) -> CommandResult<()> {
+    let mut sess_guard = state.load_session.lock().map_err(Envelope::state)?;
     if let Some(s) = sess_guard.take() {
         let _ = s.cancel_tx.send(());
     }
@@ -383,13 +1655,15 @@ pub async fn cancel_filtered_load(state: State<'_, AppState>) -> Result<(), Stri
 /// Copy a selected file into an application-managed directory and return its new path.
 /// kind can be one of: "truststore", "keystore", "proto" (used for namespacing), or any string.
 #[tauri::command]
-pub async fn import_app_file(_app: AppHandle, src_path: String, kind: Option<String>) -> Result<String, String> {
+pub async fn import_app_file(_app: AppHandle, src_path: String, kind: Option<String>) -> CommandResult<String> {
     use std::fs;
     use std::path::{Path, PathBuf};
 
     let src = Path::new(&src_path);
     if !src.exists() {
-        return Err(format!("Source file does not exist: {}", src_path));
+        return Err(Envelope::error("file_not_found", format!("Source file does not exist: {}", src_path))
+            .with("path", &src_path)
+            .kind(ErrorKind::Invalid));
     }
 
     // Destination root: OS temp dir + rkui_uploads
@@ -399,9 +1673,7 @@ pub async fn import_app_file(_app: AppHandle, src_path: String, kind: Option<Str
     dest_root.push(ns);
 
     // Ensure destination directory exists
-    if let Err(e) = fs::create_dir_all(&dest_root) {
-        return Err(format!("Failed to create upload directory: {}", e));
-    }
+    fs::create_dir_all(&dest_root).map_err(|e| Envelope::failed("create_upload_directory", e))?;
 
     // Determine a unique destination filename
     let orig_name = src.file_name().and_then(|s| s.to_str()).unwrap_or("file");
@@ -410,9 +1682,7 @@ pub async fn import_app_file(_app: AppHandle, src_path: String, kind: Option<Str
     let dest_path = dest_root.join(dest_name);
 
     // Copy the file
-    if let Err(e) = fs::copy(&src, &dest_path) {
-        return Err(format!("Failed to copy file: {}", e));
-    }
+    fs::copy(src, &dest_path).map_err(|e| Envelope::failed("copy_file", e))?;
 
     Ok(dest_path.to_string_lossy().to_string())
 }
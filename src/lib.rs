@@ -1,5 +1,22 @@
+pub mod api_server;
 pub mod app;
+pub mod audit;
+pub mod confirm;
+pub mod export;
+pub mod import;
 pub mod kafka;
 pub mod kafka_adapter;
+pub mod load_report;
+pub mod logs;
+pub mod message_bookmarks;
+pub mod profiles;
 pub mod proto_decoder;
+pub mod response;
+pub mod scheduler;
+pub mod secrets;
+pub mod self_check;
+pub mod topic_decoders;
+pub mod topic_prefs;
+pub mod transform;
 pub mod utils;
+pub mod workspace;
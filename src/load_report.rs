@@ -0,0 +1,422 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::app::AppState;
+use crate::kafka::UiMessage;
+use crate::kafka_adapter::PlainFilterOptions;
+use crate::response::{CommandResult, Envelope, ErrorKind};
+
+/// How many keys are listed in `top_keys`.
+const TOP_KEYS: usize = 10;
+/// How many error groups are listed in `decode_failures`.
+const TOP_FAILURES: usize = 20;
+
+/// Filters a load ran with.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportFilters {
+    #[serde(rename = "key_filter", alias = "keyFilter")]
+    pub key_filter: Option<String>,
+    #[serde(rename = "message_filter", alias = "messageFilter")]
+    pub message_filter: Option<String>,
+    #[serde(rename = "message_filter_mode", alias = "messageFilterMode")]
+    pub message_filter_mode: String,
+    #[serde(flatten)]
+    pub plain: PlainFilterOptions,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartitionStats {
+    pub partition: i32,
+    pub scanned: u64,
+    pub matched: u64,
+    #[serde(rename = "first_offset", alias = "firstOffset")]
+    pub first_offset: Option<i64>,
+    #[serde(rename = "last_offset", alias = "lastOffset")]
+    pub last_offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyCount {
+    pub key: String,
+    pub count: u64,
+}
+
+/// Scanned records that failed to decode the same way.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodeFailure {
+    /// Error text with record-specific numbers (offsets, lengths, ids) replaced by `N`
+    pub kind: String,
+    /// How the payload was wrapped: "confluent schema 42", "gzip", "gzip + confluent schema 42" or "raw"
+    pub envelope: String,
+    pub count: u64,
+    /// Fraction of all scanned records
+    pub share: f64,
+    #[serde(rename = "first_partition", alias = "firstPartition")]
+    pub first_partition: i32,
+    #[serde(rename = "first_offset", alias = "firstOffset")]
+    pub first_offset: i64,
+    /// Full error of the first occurrence
+    pub sample: String,
+}
+
+/// Group key for a decoding error: numbers of three or more digits are record-specific
+/// (offsets, lengths, field values), shorter ones (wire types, byte positions) are kept.
+pub fn error_kind(error: &str) -> String {
+    let mut out = String::with_capacity(error.len());
+    let mut digits = String::new();
+    let flush = |digits: &mut String, out: &mut String| {
+        if digits.len() >= 3 {
+            out.push('N');
+        } else {
+            out.push_str(digits);
+        }
+        digits.clear();
+    };
+    for c in error.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+        } else {
+            flush(&mut digits, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut digits, &mut out);
+    out
+}
+
+fn envelope_of(ui: &UiMessage) -> String {
+    let schema = ui.schema_id.map(|id| format!("confluent schema {id}"));
+    match (ui.payload_compression.as_deref(), schema) {
+        (Some(c), Some(s)) => format!("{c} + {s}"),
+        (Some(c), None) => c.to_string(),
+        (None, Some(s)) => s,
+        (None, None) => "raw".into(),
+    }
+}
+
+/// Machine-readable summary of a filtered load, for incident notes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadReport {
+    pub topic: String,
+    pub connection: Option<String>,
+    pub filters: ReportFilters,
+    /// "completed" | "limit_reached" | "matches_found" | "budget_exhausted" | "cancelled" | "unauthorized"
+    pub outcome: String,
+    #[serde(rename = "started_at", alias = "startedAt")]
+    pub started_at: String,
+    #[serde(rename = "finished_at", alias = "finishedAt")]
+    pub finished_at: String,
+    #[serde(rename = "duration_ms", alias = "durationMs")]
+    pub duration_ms: u64,
+    /// Oldest and newest record timestamps among the scanned records
+    #[serde(rename = "window_start", alias = "windowStart")]
+    pub window_start: Option<String>,
+    #[serde(rename = "window_end", alias = "windowEnd")]
+    pub window_end: Option<String>,
+    pub partitions: Vec<PartitionStats>,
+    pub scanned: u64,
+    pub matched: u64,
+    /// Scanned records per second
+    pub throughput: f64,
+    /// Most frequent keys among matched records
+    #[serde(rename = "top_keys", alias = "topKeys")]
+    pub top_keys: Vec<KeyCount>,
+    /// Scanned records with a decoding error, grouped by error kind and envelope (most frequent first)
+    #[serde(default, rename = "decode_failures", alias = "decodeFailures")]
+    pub decode_failures: Vec<DecodeFailure>,
+}
+
+/// Position of one partition in a running load.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionLoadProgress {
+    pub partition: i32,
+    /// Scan position: the highest offset read, the lowest when scanning backwards; None before the first record
+    pub offset: Option<i64>,
+    /// Snapshot end (exclusive)
+    pub end: Option<i64>,
+    pub scanned: u64,
+    pub matched: u64,
+    pub done: bool,
+}
+
+/// Payload of the periodic `kafka:load_progress` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadProgress {
+    pub partitions: Vec<PartitionLoadProgress>,
+    pub scanned: u64,
+    pub matched: u64,
+    #[serde(rename = "elapsed_ms")]
+    pub elapsed_ms: u64,
+    /// Time left at the pace so far; None until every unfinished partition was reached
+    #[serde(rename = "eta_ms")]
+    pub eta_ms: Option<u64>,
+}
+
+/// Accumulates statistics while a filtered load runs.
+pub struct LoadReportBuilder {
+    topic: String,
+    connection: Option<String>,
+    filters: ReportFilters,
+    started: Instant,
+    started_at: chrono::DateTime<chrono::Utc>,
+    partitions: BTreeMap<i32, PartitionStats>,
+    keys: HashMap<String, u64>,
+    window: Option<(i64, i64)>,
+    failures: HashMap<(String, String), DecodeFailure>,
+    /// Lowest offset to read per partition when scanning backwards
+    floors: Option<HashMap<i32, i64>>,
+}
+
+impl LoadReportBuilder {
+    pub fn new(topic: String, connection: Option<String>, filters: ReportFilters, partitions: &[i32]) -> Self {
+        let partitions = partitions
+            .iter()
+            .map(|&p| (p, PartitionStats { partition: p, scanned: 0, matched: 0, first_offset: None, last_offset: None }))
+            .collect();
+        Self {
+            topic,
+            connection,
+            filters,
+            started: Instant::now(),
+            started_at: chrono::Utc::now(),
+            partitions,
+            keys: HashMap::new(),
+            window: None,
+            failures: HashMap::new(),
+            floors: None,
+        }
+    }
+
+    /// The load reads each partition from its end down to `floors`; progress counts towards them.
+    pub fn scan_backward(&mut self, floors: HashMap<i32, i64>) {
+        self.floors = Some(floors);
+    }
+
+    /// Count a scanned record; `ts_ms` is i64::MAX when the record has no timestamp.
+    pub fn record(&mut self, ui: &UiMessage, ts_ms: i64, matched: bool) {
+        let stats = self.partitions.entry(ui.partition).or_insert(PartitionStats {
+            partition: ui.partition,
+            scanned: 0,
+            matched: 0,
+            first_offset: None,
+            last_offset: None,
+        });
+        stats.scanned += 1;
+        // The offsets read span the same range in either scan direction
+        stats.first_offset = Some(stats.first_offset.map_or(ui.offset, |o| o.min(ui.offset)));
+        stats.last_offset = Some(stats.last_offset.map_or(ui.offset, |o| o.max(ui.offset)));
+        if ts_ms != i64::MAX {
+            self.window = Some(match self.window {
+                Some((lo, hi)) => (lo.min(ts_ms), hi.max(ts_ms)),
+                None => (ts_ms, ts_ms),
+            });
+        }
+        if matched {
+            stats.matched += 1;
+            *self.keys.entry(ui.key.clone()).or_insert(0) += 1;
+        }
+        if let Some(error) = &ui.decoding_error {
+            let (kind, envelope) = (error_kind(error), envelope_of(ui));
+            self.failures
+                .entry((kind.clone(), envelope.clone()))
+                .or_insert_with(|| DecodeFailure {
+                    kind,
+                    envelope,
+                    count: 0,
+                    share: 0.0,
+                    first_partition: ui.partition,
+                    first_offset: ui.offset,
+                    sample: error.clone(),
+                })
+                .count += 1;
+        }
+    }
+
+    /// Where the load stands against the snapshot `ends`; `done` partitions have nothing left to read.
+    pub fn progress(&self, ends: &HashMap<i32, i64>, done: &HashSet<i32>) -> LoadProgress {
+        let elapsed = self.started.elapsed();
+        // Offsets behind and ahead of each partition's position estimate the time left
+        let (mut covered, mut remaining, mut unknown) = (0i64, 0i64, false);
+        let partitions: Vec<PartitionLoadProgress> = self
+            .partitions
+            .values()
+            .map(|s| {
+                let end = ends.get(&s.partition).copied();
+                let is_done = done.contains(&s.partition);
+                if let (Some(first), Some(last)) = (s.first_offset, s.last_offset) {
+                    covered += last - first + 1;
+                    if !is_done {
+                        remaining += match &self.floors {
+                            Some(floors) => floors.get(&s.partition).map_or(0, |f| (first - f).max(0)),
+                            None => end.map_or(0, |e| (e - last - 1).max(0)),
+                        };
+                    }
+                } else if !is_done {
+                    unknown = true;
+                }
+                PartitionLoadProgress {
+                    partition: s.partition,
+                    offset: if self.floors.is_some() { s.first_offset } else { s.last_offset },
+                    end,
+                    scanned: s.scanned,
+                    matched: s.matched,
+                    done: is_done,
+                }
+            })
+            .collect();
+        let eta_ms = (!unknown && covered > 0)
+            .then(|| (elapsed.as_millis() as f64 * remaining as f64 / covered as f64) as u64);
+        LoadProgress {
+            scanned: partitions.iter().map(|p| p.scanned).sum(),
+            matched: partitions.iter().map(|p| p.matched).sum(),
+            partitions,
+            elapsed_ms: elapsed.as_millis() as u64,
+            eta_ms,
+        }
+    }
+
+    /// Decode failures so far, most frequent first.
+    pub fn decode_failures(&self) -> Vec<DecodeFailure> {
+        let scanned: u64 = self.partitions.values().map(|p| p.scanned).sum();
+        let mut out: Vec<DecodeFailure> = self
+            .failures
+            .values()
+            .map(|f| DecodeFailure { share: f.count as f64 / scanned.max(1) as f64, ..f.clone() })
+            .collect();
+        out.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.kind.cmp(&b.kind)));
+        out.truncate(TOP_FAILURES);
+        out
+    }
+
+    pub fn finish(self, outcome: &str) -> LoadReport {
+        let decode_failures = self.decode_failures();
+        let elapsed = self.started.elapsed();
+        let scanned: u64 = self.partitions.values().map(|p| p.scanned).sum();
+        let matched: u64 = self.partitions.values().map(|p| p.matched).sum();
+        let mut top_keys: Vec<KeyCount> = self.keys.into_iter().map(|(key, count)| KeyCount { key, count }).collect();
+        top_keys.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+        top_keys.truncate(TOP_KEYS);
+        let rfc3339 = |ms: i64| chrono::DateTime::<chrono::Utc>::from_timestamp_millis(ms).map(|t| t.to_rfc3339());
+        let secs = elapsed.as_secs_f64();
+        LoadReport {
+            topic: self.topic,
+            connection: self.connection,
+            filters: self.filters,
+            outcome: outcome.to_string(),
+            started_at: self.started_at.to_rfc3339(),
+            finished_at: chrono::Utc::now().to_rfc3339(),
+            duration_ms: elapsed.as_millis() as u64,
+            window_start: self.window.and_then(|(lo, _)| rfc3339(lo)),
+            window_end: self.window.and_then(|(_, hi)| rfc3339(hi)),
+            partitions: self.partitions.into_values().collect(),
+            scanned,
+            matched,
+            throughput: if secs > 0.0 { scanned as f64 / secs } else { 0.0 },
+            top_keys,
+            decode_failures,
+        }
+    }
+}
+
+impl LoadReport {
+    /// Render as "json" or "markdown".
+    pub fn render(&self, format: &str) -> Result<String, String> {
+        match format {
+            "json" => serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize load report: {e}")),
+            "markdown" | "md" => Ok(self.to_markdown()),
+            other => Err(format!("Unsupported report format '{other}' (expected json or markdown)")),
+        }
+    }
+
+    /// Markdown rendering for pasting into incident documents.
+    pub fn to_markdown(&self) -> String {
+        let opt = |v: &Option<String>| v.as_deref().filter(|s| !s.is_empty()).map(|s| format!("`{s}`")).unwrap_or("—".into());
+        let mut out = String::new();
+        let _ = writeln!(out, "# Filtered load: `{}`\n", self.topic);
+        if let Some(c) = &self.connection {
+            let _ = writeln!(out, "- Connection: `{c}`");
+        }
+        let _ = writeln!(out, "- Outcome: {}", self.outcome);
+        let _ = writeln!(out, "- Started: {}", self.started_at);
+        let _ = writeln!(out, "- Finished: {} ({} ms)", self.finished_at, self.duration_ms);
+        let _ = writeln!(out, "- Record time window: {} → {}", opt(&self.window_start), opt(&self.window_end));
+        let _ = writeln!(out, "- Scanned: {}, matched: {}", self.scanned, self.matched);
+        let _ = writeln!(out, "- Throughput: {:.1} records/s\n", self.throughput);
+
+        let _ = writeln!(out, "## Filters\n");
+        let _ = writeln!(out, "- Key: {}", opt(&self.filters.key_filter));
+        let _ = writeln!(out, "- Message ({}): {}", self.filters.message_filter_mode, opt(&self.filters.message_filter));
+        let _ = writeln!(out, "- Limit: {}\n", self.filters.limit);
+
+        let _ = writeln!(out, "## Partitions\n");
+        let _ = writeln!(out, "| Partition | Scanned | Matched | First offset | Last offset |");
+        let _ = writeln!(out, "|---|---|---|---|---|");
+        let offset = |o: Option<i64>| o.map(|o| o.to_string()).unwrap_or("—".into());
+        for p in &self.partitions {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |",
+                p.partition,
+                p.scanned,
+                p.matched,
+                offset(p.first_offset),
+                offset(p.last_offset)
+            );
+        }
+
+        if !self.top_keys.is_empty() {
+            let _ = writeln!(out, "\n## Top keys\n");
+            let _ = writeln!(out, "| Key | Matches |");
+            let _ = writeln!(out, "|---|---|");
+            for k in &self.top_keys {
+                let key = if k.key.is_empty() { "(empty)".to_string() } else { k.key.replace('|', "\\|") };
+                let _ = writeln!(out, "| `{key}` | {} |", k.count);
+            }
+        }
+
+        if !self.decode_failures.is_empty() {
+            let _ = writeln!(out, "\n## Decode failures\n");
+            let _ = writeln!(out, "| Error | Envelope | Records | Share | First at |");
+            let _ = writeln!(out, "|---|---|---|---|---|");
+            for f in &self.decode_failures {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {:.2}% | {}@{} |",
+                    f.kind.replace('|', "\\|"),
+                    f.envelope,
+                    f.count,
+                    f.share * 100.0,
+                    f.first_partition,
+                    f.first_offset
+                );
+            }
+        }
+        out
+    }
+}
+
+/// Summary of the last filtered load as "json" (default) or "markdown".
+/// Also written to `path` when given.
+#[tauri::command]
+pub async fn export_load_report(
+    state: State<'_, AppState>,
+    format: Option<String>,
+    path: Option<String>,
+) -> CommandResult<String> {
+    let report = state
+        .last_load_report
+        .lock()
+        .map_err(Envelope::state)?
+        .clone()
+        .ok_or_else(|| Envelope::error("no_load_report", "No filtered load has finished yet").kind(ErrorKind::State))?;
+    let content = report.render(format.as_deref().unwrap_or("json")).map_err(Envelope::invalid)?;
+    if let Some(path) = path.filter(|p| !p.is_empty()) {
+        std::fs::write(&path, &content).map_err(|e| Envelope::failed("write_load_report", e).with("path", &path))?;
+    }
+    Ok(content)
+}
@@ -0,0 +1,108 @@
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+
+use crate::response::{CommandResult, Envelope};
+
+/// Log lines kept in memory; the oldest are dropped beyond this.
+const CAPACITY: usize = 5000;
+const DEFAULT_VIEW_LIMIT: usize = 500;
+
+/// One captured log line.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    /// Unix milliseconds
+    pub timestamp: i64,
+    /// "ERROR" | "WARN" | "INFO" | "DEBUG" | "TRACE"
+    pub level: String,
+    /// Module that logged it; librdkafka lines come from "librdkafka"
+    pub target: String,
+    pub message: String,
+}
+
+/// Logger of the app: keeps recent lines for `get_logs` and passes them on to env_logger (RUST_LOG) for the terminal.
+struct RingLogger {
+    terminal: env_logger::Logger,
+    lines: Mutex<VecDeque<LogEntry>>,
+}
+
+static LOGGER: OnceCell<RingLogger> = OnceCell::new();
+
+impl Log for RingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if let Ok(mut lines) = self.lines.lock() {
+            if lines.len() >= CAPACITY {
+                lines.pop_front();
+            }
+            lines.push_back(LogEntry {
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                level: record.level().to_string(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+            });
+        }
+        if self.terminal.matches(record) {
+            self.terminal.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.terminal.flush();
+    }
+}
+
+/// Install the logger; lines at `info` and above are captured unless RUST_LOG asks for more.
+pub fn init() {
+    let terminal = env_logger::Builder::from_default_env().build();
+    let level = terminal.filter().max(LevelFilter::Info);
+    let logger = LOGGER.get_or_init(|| RingLogger { terminal, lines: Mutex::new(VecDeque::new()) });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+/// Captured lines at `level` or more severe, oldest first, at most the `limit` newest.
+pub fn entries(level: Option<Level>, limit: usize) -> Vec<LogEntry> {
+    let Some(logger) = LOGGER.get() else { return Vec::new() };
+    let Ok(lines) = logger.lines.lock() else { return Vec::new() };
+    let mut found: Vec<LogEntry> = lines
+        .iter()
+        .rev()
+        .filter(|l| level.is_none_or(|max| Level::from_str(&l.level).is_ok_and(|lv| lv <= max)))
+        .take(limit)
+        .cloned()
+        .collect();
+    found.reverse();
+    found
+}
+
+/// Recent log lines for bug reports, optionally only those at `level` ("warn", "error", ...) or more severe.
+#[tauri::command]
+pub async fn get_logs(level: Option<String>, limit: Option<usize>) -> CommandResult<Vec<LogEntry>> {
+    let level = level
+        .as_deref()
+        .map(|l| Level::from_str(l).map_err(|_| Envelope::invalid(format!("Unknown log level '{l}'")).with("level", l)))
+        .transpose()?;
+    Ok(entries(level, limit.unwrap_or(DEFAULT_VIEW_LIMIT)))
+}
+
+/// Capture lines down to `level` ("off", "error", "warn", "info", "debug", "trace"). librdkafka picks the level up
+/// for clients created afterwards, i.e. on the next connect.
+#[tauri::command]
+pub async fn set_log_level(level: String) -> CommandResult<()> {
+    let filter = LevelFilter::from_str(&level)
+        .map_err(|_| Envelope::invalid(format!("Unknown log level '{level}'")).with("level", &level))?;
+    log::set_max_level(filter);
+    Ok(())
+}
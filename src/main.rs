@@ -1,30 +1,136 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod api_server;
 mod app;
+mod audit;
+mod confirm;
+mod export;
+mod import;
 mod kafka;
 mod kafka_adapter;
+mod load_report;
+mod logs;
+mod message_bookmarks;
+mod profiles;
 mod proto_decoder;
+mod response;
+mod scheduler;
+mod secrets;
+mod self_check;
+mod topic_decoders;
+mod topic_prefs;
+mod transform;
 mod utils;
+mod workspace;
 
 use app::AppState;
 
 fn main() {
-    env_logger::init();
+    logs::init();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .manage(AppState::new())
+        .setup(|app| {
+            scheduler::start(app.handle().clone());
+            kafka_adapter::start_stats_events(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             kafka_adapter::set_kafka_config,
+            kafka_adapter::add_connection,
+            kafka_adapter::switch_connection,
+            kafka_adapter::close_connection,
+            kafka_adapter::list_connections,
             kafka_adapter::get_kafka_status,
+            kafka_adapter::get_client_stats,
+            kafka_adapter::test_connection,
+            logs::get_logs,
+            logs::set_log_level,
+            kafka_adapter::inspect_truststore,
             kafka_adapter::get_topics,
+            kafka_adapter::get_topics_detailed,
+            kafka_adapter::lint_topics,
             kafka_adapter::get_topic_partitions,
+            kafka_adapter::profile_topic,
+            kafka_adapter::get_consumer_lag,
+            kafka_adapter::save_bookmark,
+            kafka_adapter::list_bookmarks,
+            kafka_adapter::get_bookmark,
+            kafka_adapter::delete_bookmark,
+            kafka_adapter::simulate_consumer_lag,
+            kafka_adapter::cancel_lag_simulation,
+            kafka_adapter::prepare_consumer_group,
+            kafka_adapter::get_watermarks,
+            kafka_adapter::get_topic_offsets,
+            kafka_adapter::get_offsets_for_time,
+            kafka_adapter::resolve_offset_expression,
+            kafka_adapter::describe_topic_configs,
+            kafka_adapter::alter_topic_config,
+            kafka_adapter::add_partitions,
+            kafka_adapter::estimate_retention,
             kafka_adapter::apply_filters,
+            kafka_adapter::prewarm_topic,
             kafka_adapter::consume_next_messages,
+            kafka_adapter::get_message_at,
+            kafka_adapter::get_raw_message,
+            kafka_adapter::redecode_messages,
+            kafka_adapter::load_latest_by_key,
+            kafka_adapter::get_key_timeline,
+            kafka_adapter::produce_messages,
+            kafka_adapter::benchmark_produce,
+            kafka_adapter::replay_messages,
+            kafka_adapter::start_copy,
+            kafka_adapter::cancel_copy,
             kafka_adapter::start_filtered_load,
+            kafka_adapter::pause_filtered_load,
+            kafka_adapter::resume_filtered_load,
             kafka_adapter::cancel_filtered_load,
+            load_report::export_load_report,
+            export::export_messages,
+            export::start_export,
+            export::start_state_export,
+            export::cancel_export,
+            import::import_messages,
+            api_server::start_api_server,
+            api_server::stop_api_server,
+            api_server::get_api_server_status,
             proto_decoder::parse_proto_metadata,
+            self_check::run_self_check,
+            profiles::save_profile,
+            profiles::list_profiles,
+            profiles::load_profile,
+            profiles::delete_profile,
+            topic_prefs::get_topic_prefs,
+            topic_prefs::set_favorite_topic,
+            topic_prefs::record_recent_topic,
+            topic_prefs::clear_recent_topics,
+            topic_prefs::rank_topics,
+            message_bookmarks::add_message_bookmark,
+            message_bookmarks::list_message_bookmarks,
+            message_bookmarks::delete_message_bookmark,
+            topic_decoders::save_topic_decoder,
+            topic_decoders::list_topic_decoders,
+            topic_decoders::delete_topic_decoder,
+            topic_decoders::resolve_topic_decoder,
+            workspace::save_workspace,
+            workspace::restore_last_workspace,
+            scheduler::list_scheduled_scans,
+            scheduler::save_scheduled_scan,
+            scheduler::delete_scheduled_scan,
+            scheduler::run_scheduled_scan,
+            scheduler::list_scan_runs,
+            scheduler::get_scan_run,
             kafka_adapter::import_app_file,
+            kafka_adapter::get_app_mode,
+            audit::get_audit_log,
+            audit::export_audit_log,
+            confirm::prepare_delete_topic,
+            confirm::delete_topic,
+            confirm::prepare_delete_records,
+            confirm::delete_records,
+            confirm::prepare_reset_offsets,
+            confirm::reset_offsets,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::kafka::UiMessage;
+use crate::response::{CommandResult, Envelope};
+use crate::topic_prefs::prefs_dir;
+use crate::utils::json_store::JsonStore;
+
+static STORE: JsonStore = JsonStore::new("message_bookmarks.json", "message bookmarks");
+
+/// A record the user marked as interesting, kept across pagination and restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageBookmark {
+    /// Generated on first save when empty
+    #[serde(default)]
+    pub id: String,
+    /// Broker list of the connection the record was read from
+    pub broker: String,
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    #[serde(default)]
+    pub note: String,
+    /// The record as shown when bookmarked; still viewable after retention deleted it
+    #[serde(default)]
+    pub snapshot: Option<UiMessage>,
+    #[serde(default, rename = "created_at", alias = "createdAt")]
+    pub created_at: String,
+}
+
+/// Save a bookmark. Bookmarking the same record again replaces its note and snapshot but keeps its id.
+pub fn add(dir: &Path, mut bookmark: MessageBookmark) -> Result<MessageBookmark, String> {
+    if bookmark.topic.trim().is_empty() {
+        return Err("Bookmark needs a topic".into());
+    }
+    STORE.update(dir, |all: &mut Vec<MessageBookmark>| {
+        let same_record = |b: &MessageBookmark| {
+            b.broker == bookmark.broker && b.topic == bookmark.topic && b.partition == bookmark.partition && b.offset == bookmark.offset
+        };
+        if let Some(existing) = all.iter().find(|b| same_record(b)) {
+            bookmark.id = existing.id.clone();
+            bookmark.created_at = existing.created_at.clone();
+        }
+        if bookmark.id.trim().is_empty() {
+            bookmark.id = format!("bm-{}", chrono::Utc::now().timestamp_micros());
+        }
+        if bookmark.created_at.is_empty() {
+            bookmark.created_at = chrono::Utc::now().to_rfc3339();
+        }
+        all.retain(|b| b.id != bookmark.id && !same_record(b));
+        all.push(bookmark.clone());
+        Ok(bookmark)
+    })
+}
+
+/// Bookmarks newest first, optionally only those of one broker list and/or topic.
+pub fn list(dir: &Path, broker: Option<&str>, topic: Option<&str>) -> Result<Vec<MessageBookmark>, String> {
+    let mut out: Vec<MessageBookmark> = STORE
+        .read::<Vec<MessageBookmark>>(dir)?
+        .into_iter()
+        .filter(|b| broker.is_none_or(|x| b.broker == x) && topic.is_none_or(|x| b.topic == x))
+        .collect();
+    out.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(out)
+}
+
+pub fn delete(dir: &Path, id: &str) -> Result<(), String> {
+    STORE.update(dir, |all: &mut Vec<MessageBookmark>| {
+        let before = all.len();
+        all.retain(|b| b.id != id);
+        if all.len() == before {
+            return Err(format!("Bookmark '{}' not found", id));
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn add_message_bookmark(app: AppHandle, bookmark: MessageBookmark) -> CommandResult<MessageBookmark> {
+    prefs_dir(&app).and_then(|dir| add(&dir, bookmark)).map_err(|e| Envelope::failed("save_message_bookmark", e))
+}
+
+/// Saved records, newest first; `broker` and `topic` narrow the list to one connection or topic.
+#[tauri::command]
+pub async fn list_message_bookmarks(
+    app: AppHandle,
+    broker: Option<String>,
+    topic: Option<String>,
+) -> CommandResult<Vec<MessageBookmark>> {
+    prefs_dir(&app)
+        .and_then(|dir| list(&dir, broker.as_deref(), topic.as_deref()))
+        .map_err(|e| Envelope::failed("list_message_bookmarks", e))
+}
+
+#[tauri::command]
+pub async fn delete_message_bookmark(app: AppHandle, id: String) -> CommandResult<()> {
+    prefs_dir(&app).and_then(|dir| delete(&dir, &id)).map_err(|e| Envelope::failed("delete_message_bookmark", e))
+}
@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::kafka::security::parse_username_password_from_jaas;
+use crate::kafka::KafkaConfig;
+use crate::response::{CommandResult, Envelope};
+use crate::secrets;
+
+const PROFILES_FILE: &str = "profiles.json";
+
+/// A saved connection preset (KafkaConfig stored as JSON in the app data dir).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub config: KafkaConfig,
+    /// SASL username recovered from the JAAS config (the JAAS string itself holds the password and is not saved)
+    #[serde(rename = "sasl_username", alias = "saslUsername")]
+    pub sasl_username: Option<String>,
+    /// Config fields cleared on save; their values live in the OS keychain
+    #[serde(default, rename = "stripped_secrets", alias = "strippedSecrets")]
+    pub stripped_secrets: Vec<String>,
+    /// Secrets that are not in the keychain (store failed or entry removed) and must be re-entered
+    #[serde(default, rename = "missing_secrets", alias = "missingSecrets")]
+    pub missing_secrets: Vec<String>,
+    #[serde(rename = "saved_at", alias = "savedAt")]
+    pub saved_at: String,
+}
+
+/// Names of every KafkaConfig field that holds a secret (see `secret_values`).
+pub(crate) const SECRET_FIELDS: [&str; 8] = [
+    "sasl_jaas_config",
+    "truststore_password",
+    "keystore_password",
+    "ssl_key_password",
+    "schema_registry_password",
+    "sasl_oauthbearer_client_secret",
+    "ssh_password",
+    "ssh_private_key_passphrase",
+];
+
+/// Secret fields of a config as (field name, value), skipping empty ones.
+pub fn secret_values(config: &KafkaConfig) -> Vec<(&'static str, String)> {
+    let ssh = config.ssh_tunnel.as_ref();
+    [
+        ("sasl_jaas_config", config.sasl_jaas_config.as_ref()),
+        ("truststore_password", config.truststore_password.as_ref()),
+        ("keystore_password", config.keystore_password.as_ref()),
+        ("ssl_key_password", config.ssl_key_password.as_ref()),
+        ("schema_registry_password", config.schema_registry_password.as_ref()),
+        ("sasl_oauthbearer_client_secret", config.sasl_oauthbearer_client_secret.as_ref()),
+        ("ssh_password", ssh.and_then(|t| t.password.as_ref())),
+        ("ssh_private_key_passphrase", ssh.and_then(|t| t.private_key_passphrase.as_ref())),
+    ]
+    .into_iter()
+    .filter_map(|(name, v)| v.filter(|v| !v.is_empty()).map(|v| (name, v.clone())))
+    .collect()
+}
+
+pub(crate) fn set_secret(config: &mut KafkaConfig, field: &str, value: String) {
+    match field {
+        "sasl_jaas_config" => config.sasl_jaas_config = Some(value),
+        "truststore_password" => config.truststore_password = Some(value),
+        "keystore_password" => config.keystore_password = Some(value),
+        "ssl_key_password" => config.ssl_key_password = Some(value),
+        "schema_registry_password" => config.schema_registry_password = Some(value),
+        "sasl_oauthbearer_client_secret" => config.sasl_oauthbearer_client_secret = Some(value),
+        // Only into a tunnel the config has: a leftover keychain entry must not add one
+        "ssh_password" => {
+            if let Some(ssh) = config.ssh_tunnel.as_mut() {
+                ssh.password = Some(value)
+            }
+        }
+        "ssh_private_key_passphrase" => {
+            if let Some(ssh) = config.ssh_tunnel.as_mut() {
+                ssh.private_key_passphrase = Some(value)
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Clear secret fields from a config, returning the SASL username and the names of cleared fields.
+pub fn strip_secrets(config: &mut KafkaConfig) -> (Option<String>, Vec<String>) {
+    let mut stripped = Vec::new();
+    let mut take = |field: &mut Option<String>, name: &str| {
+        if field.take().is_some_and(|v| !v.is_empty()) {
+            stripped.push(name.to_string());
+        }
+    };
+    let username = config
+        .sasl_jaas_config
+        .as_deref()
+        .and_then(parse_username_password_from_jaas)
+        .map(|(user, _)| user);
+    take(&mut config.sasl_jaas_config, "sasl_jaas_config");
+    take(&mut config.truststore_password, "truststore_password");
+    take(&mut config.keystore_password, "keystore_password");
+    take(&mut config.ssl_key_password, "ssl_key_password");
+    take(&mut config.schema_registry_password, "schema_registry_password");
+    take(&mut config.sasl_oauthbearer_client_secret, "sasl_oauthbearer_client_secret");
+    if let Some(ssh) = config.ssh_tunnel.as_mut() {
+        take(&mut ssh.password, "ssh_password");
+        take(&mut ssh.private_key_passphrase, "ssh_private_key_passphrase");
+    }
+    (username, stripped)
+}
+
+fn read_all(dir: &Path) -> Result<BTreeMap<String, Profile>, String> {
+    let path = dir.join(PROFILES_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("Failed to read profiles: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse profiles: {e}"))
+}
+
+fn write_all(dir: &Path, profiles: &BTreeMap<String, Profile>) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create profiles directory: {e}"))?;
+    let data = serde_json::to_string_pretty(profiles).map_err(|e| format!("Failed to serialize profiles: {e}"))?;
+    // Write to a temp file first so a crash mid-write doesn't lose every profile
+    let tmp = dir.join(format!("{PROFILES_FILE}.tmp"));
+    fs::write(&tmp, data).map_err(|e| format!("Failed to write profiles: {e}"))?;
+    fs::rename(&tmp, dir.join(PROFILES_FILE)).map_err(|e| format!("Failed to write profiles: {e}"))
+}
+
+/// Save (or overwrite) a profile in `dir`. Secrets are never written; the UI asks for them on load.
+pub fn save(dir: &Path, name: &str, mut config: KafkaConfig) -> Result<Profile, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name must not be empty".into());
+    }
+    let (sasl_username, stripped_secrets) = strip_secrets(&mut config);
+    let profile = Profile {
+        name: name.to_string(),
+        config,
+        sasl_username,
+        stripped_secrets,
+        missing_secrets: Vec::new(),
+        saved_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let mut all = read_all(dir)?;
+    all.insert(profile.name.clone(), profile.clone());
+    write_all(dir, &all)?;
+    Ok(profile)
+}
+
+/// All profiles in `dir`, sorted by name.
+pub fn list(dir: &Path) -> Result<Vec<Profile>, String> {
+    Ok(read_all(dir)?.into_values().collect())
+}
+
+pub fn load(dir: &Path, name: &str) -> Result<Profile, String> {
+    read_all(dir)?
+        .remove(name)
+        .ok_or_else(|| format!("Profile '{}' not found", name))
+}
+
+pub fn delete(dir: &Path, name: &str) -> Result<(), String> {
+    let mut all = read_all(dir)?;
+    if all.remove(name).is_none() {
+        return Err(format!("Profile '{}' not found", name));
+    }
+    write_all(dir, &all)
+}
+
+pub(crate) fn profiles_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+/// Save a profile; secrets go to the OS keychain.
+#[tauri::command]
+pub async fn save_profile(app: AppHandle, name: String, config: KafkaConfig) -> CommandResult<Profile> {
+    let values = secret_values(&config);
+    let mut profile =
+        profiles_dir(&app).and_then(|dir| save(&dir, &name, config)).map_err(|e| Envelope::failed("save_profile", e))?;
+    for (field, value) in values {
+        if let Err(e) = secrets::store(&profile.name, field, &value) {
+            log::warn!("{e}");
+            profile.missing_secrets.push(field.to_string());
+        }
+    }
+    Ok(profile)
+}
+
+#[tauri::command]
+pub async fn list_profiles(app: AppHandle) -> CommandResult<Vec<Profile>> {
+    profiles_dir(&app).and_then(|dir| list(&dir)).map_err(|e| Envelope::failed("list_profiles", e))
+}
+
+/// Put the profile's secrets back from the OS keychain; unavailable ones go to `missing_secrets`.
+pub(crate) fn restore_secrets(profile: &mut Profile) {
+    for field in profile.stripped_secrets.clone() {
+        match secrets::fetch(&profile.name, &field) {
+            Ok(Some(value)) => set_secret(&mut profile.config, &field, value),
+            Ok(None) => profile.missing_secrets.push(field),
+            Err(e) => {
+                log::warn!("{e}");
+                profile.missing_secrets.push(field);
+            }
+        }
+    }
+}
+
+/// Load a profile with its secrets restored from the OS keychain.
+#[tauri::command]
+pub async fn load_profile(app: AppHandle, name: String) -> CommandResult<Profile> {
+    let mut profile = profiles_dir(&app).and_then(|dir| load(&dir, &name)).map_err(|e| Envelope::failed("load_profile", e))?;
+    restore_secrets(&mut profile);
+    Ok(profile)
+}
+
+/// Delete a profile and its keychain entries.
+#[tauri::command]
+pub async fn delete_profile(app: AppHandle, name: String) -> CommandResult<()> {
+    let profile = profiles_dir(&app)
+        .and_then(|dir| {
+            let profile = load(&dir, &name)?;
+            delete(&dir, &name).map(|_| profile)
+        })
+        .map_err(|e| Envelope::failed("delete_profile", e))?;
+    for field in &profile.stripped_secrets {
+        if let Err(e) = secrets::remove(&profile.name, field) {
+            log::warn!("{e}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+use protobuf::reflect::FileDescriptor;
+use regex::Regex;
+
+use crate::utils::{compile_descriptor_set, is_descriptor_set, link_file_descriptors, proto_include_dirs};
+
+/// Prefix of the keys handed out by `register`.
+const KEY_PREFIX: &str = "pbds-";
+/// Linked graphs kept for reuse; the least recently used one is dropped beyond this.
+const MAX_GRAPHS: usize = 16;
+
+#[derive(Default)]
+struct Graphs {
+    by_hash: HashMap<u64, Arc<Vec<FileDescriptor>>>,
+    /// Hashes from least to most recently used
+    order: VecDeque<u64>,
+}
+
+/// Linked descriptor graphs by content hash of their .proto sources, shared by the decoders of all
+/// connections: switching between topics that use the same schema repo neither re-parses nor duplicates it.
+/// The hash doubles as the `proto_descriptor_key` handed to the UI by `parse_proto_metadata`.
+#[derive(Default)]
+pub struct DescriptorRegistry {
+    graphs: Mutex<Graphs>,
+}
+
+static IMPORT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^\s*import\s+(?:public\s+|weak\s+)?"([^"]+)"\s*;"#).expect("valid import regex"));
+
+/// Hash of the import names and contents of the .proto files and of every file they import from the include
+/// directories, listed or not; copies of a schema repo in other directories share it.
+fn content_hash(files: &[String], includes: &[String]) -> Result<u64, String> {
+    let dirs = proto_include_dirs(files, includes);
+    let mut hasher = DefaultHasher::new();
+    let mut pending: VecDeque<PathBuf> = files.iter().map(PathBuf::from).collect();
+    let mut seen: HashSet<PathBuf> = pending.iter().cloned().collect();
+    while let Some(path) = pending.pop_front() {
+        let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        dirs.iter().find_map(|d| path.strip_prefix(d).ok()).hash(&mut hasher);
+        bytes.hash(&mut hasher);
+        if is_descriptor_set(&path.to_string_lossy()) {
+            continue;
+        }
+        for import in IMPORT.captures_iter(&String::from_utf8_lossy(&bytes)) {
+            // Not in any include dir: one of the well-known types bundled with protoc
+            let Some(dep) = dirs.iter().map(|d| d.join(&import[1])).find(|p| p.is_file()) else { continue };
+            if seen.insert(dep.clone()) {
+                pending.push_back(dep);
+            }
+        }
+    }
+    Ok(hasher.finish())
+}
+
+impl DescriptorRegistry {
+    /// Linked descriptors of expanded .proto paths (see `expand_proto_paths`), parsed only when no graph
+    /// with the same content is registered yet.
+    pub fn linked(&self, files: &[String]) -> Result<Arc<Vec<FileDescriptor>>, String> {
+        self.register(files).map(|(_, graph)| graph)
+    }
+
+    /// Like `linked`, also returning the key that finds the graph again via `by_key`.
+    pub fn register(&self, files: &[String]) -> Result<(String, Arc<Vec<FileDescriptor>>), String> {
+        self.register_with(files, &[])
+    }
+
+    /// `register` resolving imports against `includes` (see `proto_include_paths`).
+    pub fn register_with(&self, files: &[String], includes: &[String]) -> Result<(String, Arc<Vec<FileDescriptor>>), String> {
+        let hash = content_hash(files, includes)?;
+        let key = format!("{KEY_PREFIX}{hash:x}");
+        if let Some(found) = self.touch(hash)? {
+            return Ok((key, found));
+        }
+        // Parse outside the lock; a concurrent parse of the same content just replaces an equal graph
+        let set = compile_descriptor_set(files, includes)?;
+        let built = Arc::new(link_file_descriptors(&set)?);
+        let mut graphs = self.graphs.lock().map_err(|e| format!("Descriptor registry lock poisoned: {e}"))?;
+        graphs.order.retain(|h| *h != hash);
+        graphs.order.push_back(hash);
+        graphs.by_hash.insert(hash, built.clone());
+        while graphs.order.len() > MAX_GRAPHS {
+            if let Some(old) = graphs.order.pop_front() {
+                graphs.by_hash.remove(&old);
+            }
+        }
+        Ok((key, built))
+    }
+
+    /// Graph registered under a `proto_descriptor_key`; None once it was evicted (or after a restart).
+    pub fn by_key(&self, key: &str) -> Option<Arc<Vec<FileDescriptor>>> {
+        let hash = u64::from_str_radix(key.strip_prefix(KEY_PREFIX)?, 16).ok()?;
+        self.touch(hash).ok().flatten()
+    }
+
+    fn touch(&self, hash: u64) -> Result<Option<Arc<Vec<FileDescriptor>>>, String> {
+        let mut graphs = self.graphs.lock().map_err(|e| format!("Descriptor registry lock poisoned: {e}"))?;
+        let Some(found) = graphs.by_hash.get(&hash).cloned() else { return Ok(None) };
+        graphs.order.retain(|h| *h != hash);
+        graphs.order.push_back(hash);
+        Ok(Some(found))
+    }
+
+    /// Number of registered graphs.
+    pub fn len(&self) -> usize {
+        self.graphs.lock().map(|g| g.by_hash.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,94 @@
+use protobuf::reflect::{FileDescriptor, MessageDescriptor, ReflectFieldRef};
+use protobuf::MessageDyn;
+
+/// Nested messages deeper than this are not inspected while scoring.
+const MAX_DEPTH: usize = 16;
+
+/// Message types worth trying when none is selected: every message of the schema, nested ones included,
+/// without map entries and the well-known types the schema merely imports.
+pub(crate) fn candidates(files: &[FileDescriptor]) -> Vec<MessageDescriptor> {
+    fn walk(md: MessageDescriptor, out: &mut Vec<MessageDescriptor>) {
+        if md.is_map_entry() {
+            return;
+        }
+        for nested in md.nested_messages() {
+            walk(nested, out);
+        }
+        out.push(md);
+    }
+    let mut out = Vec::new();
+    for fd in files.iter().filter(|fd| !fd.proto().package().starts_with("google.protobuf")) {
+        for md in fd.messages() {
+            walk(md, &mut out);
+        }
+    }
+    out.sort_by(|a, b| a.full_name().cmp(b.full_name()));
+    out
+}
+
+/// Set fields the type knows and fields it doesn't, counted through nested messages.
+fn tally(msg: &dyn MessageDyn, depth: usize) -> (usize, usize) {
+    let mut known = 0;
+    let mut unknown = msg.unknown_fields_dyn().iter().count();
+    if depth >= MAX_DEPTH {
+        return (known, unknown);
+    }
+    let mut nested = |m: &dyn MessageDyn| {
+        let (k, u) = tally(m, depth + 1);
+        known += k;
+        unknown += u;
+    };
+    let mut set = 0;
+    for field in msg.descriptor_dyn().fields() {
+        match field.get_reflect(msg) {
+            ReflectFieldRef::Optional(o) => {
+                if let Some(v) = o.value() {
+                    set += 1;
+                    if let Some(m) = v.to_message() {
+                        nested(&*m);
+                    }
+                }
+            }
+            ReflectFieldRef::Repeated(items) => {
+                set += items.len();
+                for m in items.into_iter().filter_map(|v| v.to_message()) {
+                    nested(&*m);
+                }
+            }
+            ReflectFieldRef::Map(map) => {
+                set += map.len();
+                for (_, v) in &map {
+                    if let Some(m) = v.to_message() {
+                        nested(&*m);
+                    }
+                }
+            }
+        }
+    }
+    (known + set, unknown)
+}
+
+/// Lowest score a guessed type needs; below it most of the payload is unknown fields.
+pub(crate) const MIN_SCORE: f64 = 0.6;
+
+/// How well a parsed message explains its payload, in (0, 1]; None when it explains nothing.
+/// Mostly the share of fields the type knows, then how many of its declared fields are present.
+pub(crate) fn score(msg: &dyn MessageDyn) -> Option<f64> {
+    let (known, unknown) = tally(msg, 0);
+    if known == 0 {
+        return None;
+    }
+    let declared = msg.descriptor_dyn().fields().count().max(1);
+    let present = msg
+        .descriptor_dyn()
+        .fields()
+        .filter(|f| match f.get_reflect(msg) {
+            ReflectFieldRef::Optional(o) => o.value().is_some(),
+            ReflectFieldRef::Repeated(r) => !r.is_empty(),
+            ReflectFieldRef::Map(m) => !m.is_empty(),
+        })
+        .count();
+    let known_ratio = known as f64 / (known + unknown) as f64;
+    let coverage = present as f64 / declared as f64;
+    Some(known_ratio * 0.8 + coverage * 0.2)
+}
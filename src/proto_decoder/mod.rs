@@ -1,17 +1,27 @@
-use serde::Serialize;
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use protobuf::descriptor::{DescriptorProto, FileDescriptorProto, FileDescriptorSet};
 use protobuf::reflect::{FileDescriptor, MessageDescriptor};
+use protobuf::MessageDyn;
 
-use once_cell::sync::Lazy;
-use std::sync::Mutex;
+use tauri::State;
 
-use crate::utils::{link_file_descriptors, normalize_full_name, run_protoc_and_read_descriptor_set};
+mod descriptors;
+mod guess;
+mod registry;
+mod well_known;
+pub use descriptors::DescriptorRegistry;
+pub use registry::RegistrySchema;
+use registry::RegistryResolver;
+use well_known::render_well_known;
 
-static DESCR_CACHE: Lazy<Mutex<HashMap<String, Arc<Vec<FileDescriptor>>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+use crate::app::AppState;
+use crate::response::{CommandResult, Envelope, ErrorKind};
+use crate::kafka::schema_registry::SchemaRegistry;
+use crate::utils::{compile_descriptor_set, link_file_descriptors, normalize_full_name};
 
 #[derive(Debug, Serialize)]
 pub struct ProtoMetadata {
@@ -67,76 +77,424 @@ fn extract_from_file_descriptor(fd: &FileDescriptorProto, packages: &mut HashSet
     }
 }
 
-pub struct ProtoDecoder {
-    // Parsed and typechecked descriptors (shared across decoders via cache)
-    // Parsed and typechecked descriptors
-    files: Vec<FileDescriptor>,
-    // If provided by UI, decode using this full name
-    message_full_name: Option<String>,
+/// Envelope wrapping the protobuf bytes inside the record value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PayloadEnvelope {
+    /// Try every known envelope; the first one that decodes is locked in for the session
+    #[default]
+    #[serde(rename = "auto")] Auto,
+    /// Bare protobuf bytes
+    #[serde(rename = "none", alias = "raw")] Raw,
+    /// Confluent Schema Registry framing: magic 0 + 4-byte schema id + message indexes
+    #[serde(rename = "confluent")] Confluent,
+    /// gRPC framing: 1-byte compression flag + 4-byte big-endian length
+    #[serde(rename = "grpc")] Grpc,
+    /// Varint length prefix (protobuf delimited stream)
+    #[serde(rename = "varint")] Varint,
 }
 
-impl ProtoDecoder {
-    /// Construct a decoder from already linked descriptors (from cache)
-    pub fn from_linked_files(built: Vec<FileDescriptor>, selected_message: Option<String>) -> Arc<Self> {
-        let chosen = selected_message.map(normalize_full_name);
-        Arc::new(Self { files: built, message_full_name: chosen })
+/// Decoder knobs coming from KafkaConfig. Defaults mirror the historical behavior.
+#[derive(Debug, Clone, Default)]
+pub struct ProtoDecodeOptions {
+    pub envelope: PayloadEnvelope,
+    /// Last resort: prepend a 0x0A tag and retry. Can silently produce wrong decodes, so opt-in.
+    pub enable_repair: bool,
+    /// When set, Confluent-framed payloads are decoded with the registered schema and the
+    /// message type addressed by their message indexes.
+    pub registry: Option<SchemaRegistry>,
+    /// Extra import roots for .proto sources (see `proto_include_paths`)
+    pub include_paths: Vec<String>,
+}
+
+/// Result of a successful protobuf decode with metadata for the UI.
+#[derive(Debug, Clone)]
+pub struct ProtoDecoded {
+    pub json: String,
+    /// True when the bytes had to be modified (0x0A repair) to decode
+    pub repaired: bool,
+    /// Parsed Confluent header when the decoding view was a Confluent envelope
+    pub confluent: Option<ConfluentHeader>,
+    /// Message type picked by scoring the loaded schema when none was selected
+    pub guessed_message: Option<String>,
+}
+
+/// Confluent Schema Registry wire header: magic 0 + 4-byte schema id (+ protobuf message indexes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfluentHeader {
+    pub schema_id: u32,
+    /// Message index path into the schema's message types ([0] = first top-level message)
+    pub message_indexes: Vec<i64>,
+    /// Length of the header in bytes (payload starts right after it)
+    pub header_len: usize,
+}
+
+/// Parse the Confluent protobuf wire header. Indexes are a zigzag varint count followed by that
+/// many zigzag varints; a single 0 byte is the shorthand for [0].
+pub fn parse_confluent_header(payload: &[u8]) -> Option<ConfluentHeader> {
+    if payload.len() < 6 || payload[0] != 0 {
+        return None;
     }
-    pub fn from_proto_files(files: Vec<String>, selected_message: Option<String>) -> Result<Arc<Self>, String> {
-        if files.is_empty() {
-            return Err("No .proto files provided".into());
+    let schema_id = u32::from_be_bytes([payload[1], payload[2], payload[3], payload[4]]);
+    let zigzag = |v: u64| ((v >> 1) as i64) ^ -((v & 1) as i64);
+    let mut off = 5usize;
+    let (consumed, raw_count) = parse_varint(&payload[off..])?;
+    off += consumed;
+    let count = zigzag(raw_count);
+    if !(0..=64).contains(&count) {
+        return None;
+    }
+    let message_indexes = if count == 0 {
+        vec![0]
+    } else {
+        let mut idx = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let (consumed, v) = parse_varint(&payload[off..])?;
+            off += consumed;
+            idx.push(zigzag(v));
         }
+        idx
+    };
+    Some(ConfluentHeader { schema_id, message_indexes, header_len: off })
+}
 
-        // Expand provided paths:
-        // - If a directory is provided, include all *.proto inside (non-recursive).
-        // - If a file is provided, include it plus all sibling *.proto files in the same directory.
-        let mut uniq: std::collections::HashSet<String> = std::collections::HashSet::new();
-        for f in &files {
-            let p = Path::new(f);
-            if p.is_dir() {
-                // Canonicalize directory for stable sibling enumeration
-                let dir = std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
-                let rd = std::fs::read_dir(&dir).map_err(|e| format!("Failed to read dir '{}': {}", dir.display(), e))?;
-                for ent in rd.filter_map(|e| e.ok()) {
-                    let path = ent.path();
-                    if path.is_file() && path.extension().map(|e| e == "proto").unwrap_or(false) {
-                        let can = std::fs::canonicalize(&path).unwrap_or(path);
-                        uniq.insert(can.to_string_lossy().to_string());
-                    }
+/// A concrete way of slicing the payload into protobuf bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ViewStrategy {
+    Raw,
+    /// Confluent header only (magic + schema id)
+    ConfluentBare,
+    /// Confluent header followed by N message-index varints
+    ConfluentSkip(u8),
+    /// Confluent header followed by a count varint and that many indexes
+    ConfluentIndexed,
+    Grpc,
+    VarintPrefixed,
+}
+
+impl ViewStrategy {
+    fn envelope(self) -> PayloadEnvelope {
+        match self {
+            ViewStrategy::Raw => PayloadEnvelope::Raw,
+            ViewStrategy::ConfluentBare | ViewStrategy::ConfluentSkip(_) | ViewStrategy::ConfluentIndexed => {
+                PayloadEnvelope::Confluent
+            }
+            ViewStrategy::Grpc => PayloadEnvelope::Grpc,
+            ViewStrategy::VarintPrefixed => PayloadEnvelope::Varint,
+        }
+    }
+}
+
+/// Parse a single unsigned varint; returns (consumed_len, value)
+fn parse_varint(bytes: &[u8]) -> Option<(usize, u64)> {
+    let mut val: u64 = 0;
+    let mut shift: u32 = 0;
+    let mut i = 0usize;
+    while i < bytes.len() && i < 10 {
+        let b = bytes[i];
+        val |= ((b & 0x7F) as u64) << shift;
+        shift += 7;
+        i += 1;
+        if b & 0x80 == 0 { break; }
+    }
+    if i == 0 { return None; }
+    // Completed only if last byte had MSB=0
+    if bytes.get(i - 1).map(|b| b & 0x80 == 0).unwrap_or(false) {
+        Some((i, val))
+    } else {
+        None
+    }
+}
+
+/// Heuristic: does the buffer parse as a sequence of well-formed protobuf fields?
+/// Used for profiling only; a valid wire layout says nothing about the message type.
+pub fn looks_like_protobuf(bytes: &[u8]) -> bool {
+    if bytes.is_empty() {
+        return false;
+    }
+    let mut off = 0usize;
+    while off < bytes.len() {
+        let Some((consumed, tag)) = parse_varint(&bytes[off..]) else { return false; };
+        off += consumed;
+        if tag >> 3 == 0 {
+            return false;
+        }
+        match tag & 0x7 {
+            0 => match parse_varint(&bytes[off..]) {
+                Some((consumed, _)) => off += consumed,
+                None => return false,
+            },
+            1 => off += 8,
+            2 => {
+                let Some((consumed, len)) = parse_varint(&bytes[off..]) else { return false; };
+                // A huge length must not wrap the offset back into the payload
+                match usize::try_from(len).ok().and_then(|len| off.checked_add(consumed)?.checked_add(len)) {
+                    Some(end) => off = end,
+                    None => return false,
+                }
+            }
+            5 => off += 4,
+            _ => return false,
+        }
+        if off > bytes.len() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Build a list of candidate views of the payload (raw and common envelopes) without allocating copies.
+fn candidate_views(payload: &[u8]) -> Vec<(ViewStrategy, &[u8])> {
+    let mut views: Vec<(ViewStrategy, &[u8])> = Vec::with_capacity(10);
+
+    // 1) raw bytes
+    views.push((ViewStrategy::Raw, payload));
+
+    // 2) Confluent Schema Registry envelope: magic 0 + 4-byte schema id, possibly followed by varint indices
+    if payload.len() > 5 && payload[0] == 0 {
+        // Base after header
+        let base = &payload[5..];
+        views.push((ViewStrategy::ConfluentBare, base));
+
+        // Try skipping N consecutive varints (N=1..=5)
+        for n in 1..=5u8 {
+            let mut off = 0usize;
+            let mut ok = true;
+            for _ in 0..n {
+                if let Some((consumed, _)) = parse_varint(&base[off..]) {
+                    off += consumed;
+                } else {
+                    ok = false;
+                    break;
+                }
+            }
+            if ok && off < base.len() {
+                views.push((ViewStrategy::ConfluentSkip(n), &base[off..]));
+            } else if !ok {
+                break;
+            }
+        }
+
+        // Try the count + indices pattern: first varint is count C (capped), then skip C varints
+        if let Some((c1, cnt)) = parse_varint(base) {
+            let c = std::cmp::min(cnt as usize, 5);
+            let mut off = c1;
+            let mut ok = true;
+            for _ in 0..c {
+                if let Some((consumed, _)) = parse_varint(&base[off..]) {
+                    off += consumed;
+                } else {
+                    ok = false;
+                    break;
                 }
-            } else if p.is_file() {
-                // include the file itself (canonicalized for stability)
-                let can = std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
-                uniq.insert(can.to_string_lossy().to_string());
-            } else {
-                return Err(format!("File or directory not found: {}", f));
+            }
+            if ok && off < base.len() {
+                views.push((ViewStrategy::ConfluentIndexed, &base[off..]));
             }
         }
-        let mut expanded: Vec<String> = uniq.into_iter().collect();
-        expanded.sort();
-        if expanded.is_empty() {
-            return Err("No .proto files found from provided paths".into());
+    }
+
+    // 3) gRPC framing: 1 byte flag + 4 byte big-endian length
+    if payload.len() >= 5 {
+        let flag = payload[0];
+        let len = u32::from_be_bytes([payload[1], payload[2], payload[3], payload[4]]) as usize;
+        if (flag == 0 || flag == 1) && payload.len() >= 5 + len {
+            views.push((ViewStrategy::Grpc, &payload[5..5 + len]));
+        }
+    }
+
+    // 4) Bare varint length prefix (non-gRPC)
+    if let Some((i, len_val)) = parse_varint(payload) {
+        let len_val = len_val as usize;
+        if len_val > 0 && payload.len() >= i + len_val {
+            views.push((ViewStrategy::VarintPrefixed, &payload[i..i + len_val]));
         }
-        for f in &expanded {
-            if !Path::new(f).exists() {
-                return Err(format!("File not found: {}", f));
+    }
+
+    views
+}
+
+/// Render a parsed message as compact JSON, well-known types in their canonical form and Any fields
+/// expanded with the message types of `pool`.
+fn print_compact_json(msg: &dyn MessageDyn, pool: &[FileDescriptor]) -> Result<String, String> {
+    let json = protobuf_json_mapping::print_to_string(msg)
+        .map_err(|e| format!("Failed to serialize protobuf JSON: {}", e))?;
+    // Ensure compact JSON without spaces by reserializing via serde_json
+    if let Ok(mut val) = serde_json::from_str::<serde_json::Value>(&json) {
+        render_well_known(msg, &mut val, pool);
+        return Ok(serde_json::to_string(&val).unwrap_or(json));
+    }
+    Ok(json)
+}
+
+/// The .proto files to compile for user-provided paths, canonicalized and sorted: every *.proto under a given
+/// directory (recursively), and for a file, the file itself.
+/// Descriptor set files (.desc, see `utils::is_descriptor_set`) are kept as given.
+pub fn expand_proto_paths(files: &[String]) -> Result<Vec<String>, String> {
+    if files.is_empty() {
+        return Err("No .proto files provided".into());
+    }
+    let mut uniq: HashSet<String> = HashSet::new();
+    for f in files {
+        let p = Path::new(f);
+        if p.is_dir() {
+            // Canonicalize directory for stable enumeration
+            let dir = std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+            collect_proto_files(&dir, &mut uniq)?;
+        } else if p.is_file() {
+            // include the file itself (canonicalized for stability)
+            let can = std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+            uniq.insert(can.to_string_lossy().to_string());
+        } else {
+            return Err(format!("File or directory not found: {}", f));
+        }
+    }
+    let mut expanded: Vec<String> = uniq.into_iter().collect();
+    expanded.sort();
+    if expanded.is_empty() {
+        return Err("No .proto files found from provided paths".into());
+    }
+    Ok(expanded)
+}
+
+/// Every *.proto below `dir`. Hidden directories (.git, ...) and symlinked directories are skipped.
+fn collect_proto_files(dir: &Path, out: &mut HashSet<String>) -> Result<(), String> {
+    let rd = std::fs::read_dir(dir).map_err(|e| format!("Failed to read dir '{}': {}", dir.display(), e))?;
+    for ent in rd.filter_map(|e| e.ok()) {
+        let path = ent.path();
+        let Ok(kind) = ent.file_type() else { continue };
+        if kind.is_dir() {
+            if !ent.file_name().to_string_lossy().starts_with('.') {
+                collect_proto_files(&path, out)?;
             }
+        } else if path.is_file() && path.extension().map(|e| e == "proto").unwrap_or(false) {
+            let can = std::fs::canonicalize(&path).unwrap_or(path);
+            out.insert(can.to_string_lossy().to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Import roots for user-provided paths: the configured include paths, then every given directory, so
+/// `import "common/types.proto"` resolves inside a proto tree. Canonicalized like `expand_proto_paths`.
+pub fn proto_include_paths(paths: &[String], include_paths: &[String]) -> Vec<String> {
+    let canonical = |p: &String| std::fs::canonicalize(p).map(|c| c.to_string_lossy().to_string()).unwrap_or_else(|_| p.clone());
+    let mut out: Vec<String> = include_paths.iter().filter(|p| !p.trim().is_empty()).map(canonical).collect();
+    for dir in paths.iter().filter(|p| Path::new(p).is_dir()).map(canonical) {
+        if !out.contains(&dir) {
+            out.push(dir);
+        }
+    }
+    out
+}
+
+pub struct ProtoDecoder {
+    // Parsed and typechecked descriptors (shared across decoders via cache)
+    files: Arc<Vec<FileDescriptor>>,
+    // If provided by UI, decode using this full name
+    message_full_name: Option<String>,
+    options: ProtoDecodeOptions,
+    // View that decoded the first successful message of the session; tried first afterwards.
+    // In `auto` mode its envelope is also locked in.
+    learned_view: OnceLock<ViewStrategy>,
+    // Compiles registry schemas by id (from options.registry)
+    registry: Option<RegistryResolver>,
+    // Message types scored when no message is selected (collected on first use)
+    guess_candidates: OnceLock<Vec<MessageDescriptor>>,
+    // Message type the first guessed message was decoded as; tried before scoring every candidate
+    learned_message: OnceLock<MessageDescriptor>,
+}
+
+impl ProtoDecoder {
+    fn with_parts(files: Arc<Vec<FileDescriptor>>, selected_message: Option<String>, options: ProtoDecodeOptions) -> Self {
+        let chosen = selected_message.map(normalize_full_name);
+        let registry = options.registry.clone().map(RegistryResolver::new);
+        Self {
+            files,
+            message_full_name: chosen,
+            options,
+            learned_view: OnceLock::new(),
+            registry,
+            guess_candidates: OnceLock::new(),
+            learned_message: OnceLock::new(),
         }
+    }
+
+    /// Construct a decoder from already linked descriptors (from cache)
+    pub fn from_linked_files(built: Vec<FileDescriptor>, selected_message: Option<String>, options: ProtoDecodeOptions) -> Arc<Self> {
+        Self::from_shared_files(Arc::new(built), selected_message, options)
+    }
+
+    /// Construct a decoder over a descriptor graph other decoders use too (see `DescriptorRegistry`)
+    pub fn from_shared_files(
+        files: Arc<Vec<FileDescriptor>>,
+        selected_message: Option<String>,
+        options: ProtoDecodeOptions,
+    ) -> Arc<Self> {
+        Arc::new(Self::with_parts(files, selected_message, options))
+    }
+
+    /// Decoder over .proto sources and/or precompiled descriptor sets (`protoc --descriptor_set_out`).
+    pub fn from_proto_files(files: Vec<String>, selected_message: Option<String>) -> Result<Arc<Self>, String> {
+        Self::from_proto_files_with_options(files, selected_message, ProtoDecodeOptions::default())
+    }
+
+    pub fn from_proto_files_with_options(
+        files: Vec<String>,
+        selected_message: Option<String>,
+        options: ProtoDecodeOptions,
+    ) -> Result<Arc<Self>, String> {
+        let expanded = expand_proto_paths(&files)?;
+        let includes = proto_include_paths(&files, &options.include_paths);
 
         // Build descriptors using protoc-produced descriptor set and link into reflect FileDescriptor graph
-        let pb_fds: FileDescriptorSet = run_protoc_and_read_descriptor_set(&expanded)?;
+        let pb_fds: FileDescriptorSet = compile_descriptor_set(&expanded, &includes)?;
         let built: Vec<FileDescriptor> = link_file_descriptors(&pb_fds)?;
 
-        // Accept the selected message from UI as-is (normalize)
-        let chosen = selected_message.map(normalize_full_name);
+        Ok(Self::from_linked_files(built, selected_message, options))
+    }
 
-        Ok(Arc::new(Self { files: built, message_full_name: chosen }))
+    /// Envelope currently in effect: the configured one, or the locked-in detection in `auto` mode.
+    pub fn effective_envelope(&self) -> PayloadEnvelope {
+        match self.options.envelope {
+            PayloadEnvelope::Auto => self
+                .learned_view
+                .get()
+                .map(|v| v.envelope())
+                .unwrap_or(PayloadEnvelope::Auto),
+            other => other,
+        }
     }
 
     pub fn decode(&self, payload: &[u8]) -> Result<String, String> {
-        // Require an explicitly selected message to avoid expensive guessing and keep UI fast.
-        let name = match &self.message_full_name {
-            Some(n) => n,
-            None => return Err("No protobuf message is selected. Select a specific message type to enable decoding.".to_string()),
+        self.decode_detailed(payload).map(|d| d.json)
+    }
+
+    /// Decode and report how the payload was interpreted.
+    pub fn decode_detailed(&self, payload: &[u8]) -> Result<ProtoDecoded, String> {
+        self.decode_detailed_as(payload, self.message_full_name.as_deref())
+    }
+
+    /// Decode as a specific message type (per-record selection on multi-schema topics).
+    pub fn decode_detailed_as(&self, payload: &[u8], message_full_name: Option<&str>) -> Result<ProtoDecoded, String> {
+        // Registry schemas name the exact message type; local descriptors remain the fallback
+        if let Some(resolver) = self.registry.as_ref() {
+            if matches!(self.options.envelope, PayloadEnvelope::Auto | PayloadEnvelope::Confluent) {
+                if let Some(header) = parse_confluent_header(payload) {
+                    match decode_with_registry(resolver, payload, header) {
+                        Ok(d) => return Ok(d),
+                        Err(e) if self.files.is_empty() => return Err(e),
+                        Err(_) => {}
+                    }
+                }
+            }
+        }
+
+        let name = match message_full_name {
+            Some(n) => n.trim_start_matches('.'),
+            None if self.files.is_empty() => {
+                return Err("No protobuf message is selected. Select a specific message type to enable decoding.".to_string())
+            }
+            None => return self.decode_guessed(payload),
         };
 
         // Resolve message descriptor by full name (accepts with or without leading dot)
@@ -148,119 +506,53 @@ impl ProtoDecoder {
                 .ok_or_else(|| format!("Message type not found in descriptors: {}", fq))
         };
 
-        // Build a list of candidate views of the payload (raw and common envelopes) without allocating copies.
-        let mut views: Vec<&[u8]> = Vec::with_capacity(10);
-
-        // 1) raw bytes
-        views.push(payload);
-
-        // Helper: parse a single unsigned varint; returns (consumed_len, value)
-        let parse_varint = |bytes: &[u8]| -> Option<(usize, u64)> {
-            let mut val: u64 = 0;
-            let mut shift: u32 = 0;
-            let mut i = 0usize;
-            while i < bytes.len() && i < 10 {
-                let b = bytes[i];
-                val |= ((b & 0x7F) as u64) << shift;
-                shift += 7;
-                i += 1;
-                if b & 0x80 == 0 { break; }
-            }
-            if i == 0 { return None; }
-            // Completed only if last byte had MSB=0
-            if bytes.get(i - 1).map(|b| b & 0x80 == 0).unwrap_or(false) {
-                Some((i, val))
-            } else {
-                None
+        // Resolve descriptor once and try to parse the views admitted by the envelope setting,
+        // starting with the view that worked for earlier messages of this session
+        let md = resolve_msg(name)?;
+        let envelope = self.effective_envelope();
+        let learned = self.learned_view.get().copied();
+        let mut views = candidate_views(payload);
+        if let Some(lv) = learned {
+            views.sort_by_key(|(strategy, _)| *strategy != lv);
+        }
+        let mut learned_err: Option<String> = None;
+        for (strategy, bytes) in views {
+            if envelope != PayloadEnvelope::Auto && strategy.envelope() != envelope {
+                continue;
             }
-        };
-
-        // 2) Confluent Schema Registry envelope: magic 0 + 4-byte schema id, possibly followed by varint indices
-        if payload.len() > 5 && payload[0] == 0 {
-            // Base after header
-            let base = &payload[5..];
-            views.push(base);
-
-            // Try skipping N consecutive varints (N=1..=5)
-            for n in 1..=5 {
-                let mut off = 0usize;
-                let mut ok = true;
-                for _ in 0..n {
-                    if let Some((consumed, _)) = parse_varint(&base[off..]) {
-                        off += consumed;
-                    } else {
-                        ok = false;
-                        break;
+            match md.parse_from_bytes(bytes) {
+                Ok(msg) => {
+                    if let Ok(json) = print_compact_json(&*msg, &self.files) {
+                        let _ = self.learned_view.set(strategy);
+                        let confluent = if strategy.envelope() == PayloadEnvelope::Confluent {
+                            parse_confluent_header(payload)
+                        } else {
+                            None
+                        };
+                        return Ok(ProtoDecoded { json, repaired: false, confluent, guessed_message: None });
                     }
                 }
-                if ok && off < base.len() {
-                    views.push(&base[off..]);
-                } else if !ok {
-                    break;
-                }
-            }
-
-            // Try the count + indices pattern: first varint is count C (capped), then skip C varints
-            if let Some((c1, cnt)) = parse_varint(base) {
-                let c = std::cmp::min(cnt as usize, 5);
-                let mut off = c1;
-                let mut ok = true;
-                for _ in 0..c {
-                    if let Some((consumed, _)) = parse_varint(&base[off..]) {
-                        off += consumed;
-                    } else {
-                        ok = false;
-                        break;
+                Err(e) => {
+                    if Some(strategy) == learned {
+                        learned_err = Some(e.to_string());
                     }
                 }
-                if ok && off < base.len() {
-                    views.push(&base[off..]);
-                }
             }
+            // keep trying other views
         }
 
-        // 3) gRPC framing: 1 byte flag + 4 byte big-endian length
-        if payload.len() >= 5 {
-            let flag = payload[0];
-            let len = u32::from_be_bytes([payload[1], payload[2], payload[3], payload[4]]) as usize;
-            if (flag == 0 || flag == 1) && payload.len() >= 5 + len {
-                views.push(&payload[5..5 + len]);
-            }
+        // Once a view is known to work, report its error instead of guessing with the repair hack
+        if let Some(lv) = learned {
+            return Err(format!(
+                "Failed to parse protobuf payload as .{} ({:?} view): {}",
+                name,
+                lv,
+                learned_err.unwrap_or_else(|| "view not applicable to this payload".into())
+            ));
         }
 
-        // 4) Bare varint length prefix (non-gRPC)
-        {
-            let mut shift = 0u32;
-            let mut len_val: usize = 0;
-            let mut i = 0usize;
-            while i < payload.len() && i < 10 {
-                let b = payload[i];
-                len_val |= ((b & 0x7F) as usize) << shift;
-                shift += 7;
-                i += 1;
-                if b & 0x80 == 0 { break; }
-            }
-            if i > 0 && len_val > 0 && payload.len() >= i + len_val {
-                views.push(&payload[i..i + len_val]);
-            }
-        }
-
-        // Resolve descriptor once and try to parse views
-        let md = resolve_msg(name)?;
-        for bytes in &views {
-            match md.parse_from_bytes(bytes) {
-                Ok(msg) => match protobuf_json_mapping::print_to_string(&*msg) {
-                    Ok(json) => {
-                        // Ensure compact JSON without spaces by reserializing via serde_json
-                        if let Ok(val) = serde_json::from_str::<serde_json::Value>(&json) {
-                            return Ok(serde_json::to_string(&val).unwrap_or(json));
-                        }
-                        return Ok(json);
-                    }
-                    Err(_e) => { /* keep trying other views */ }
-                },
-                Err(_e) => { /* keep trying other views */ }
-            }
+        if !self.options.enable_repair {
+            return Err(format!("Failed to parse protobuf payload as .{} with any known envelope", name));
         }
 
         // 1a) Lazy repair attempt: if the payload is missing the first tag byte (common for field #1 length-delimited -> 0x0A)
@@ -268,96 +560,95 @@ impl ProtoDecoder {
         repaired.push(0x0A);
         repaired.extend_from_slice(payload);
         match md.parse_from_bytes(&repaired) {
-            Ok(msg) => match protobuf_json_mapping::print_to_string(&*msg) {
-                Ok(json) => {
-                    if let Ok(val) = serde_json::from_str::<serde_json::Value>(&json) {
-                        return Ok(serde_json::to_string(&val).unwrap_or(json));
-                    }
-                    return Ok(json);
-                }
-                Err(e) => return Err(format!("Failed to serialize protobuf JSON: {}", e)),
-            },
-            Err(e) => return Err(format!("Failed to parse protobuf payload as .{} (repaired): {}", name, e)),
+            Ok(msg) => print_compact_json(&*msg, &self.files)
+                .map(|json| ProtoDecoded { json, repaired: true, confluent: None, guessed_message: None }),
+            Err(e) => Err(format!("Failed to parse protobuf payload as .{} (repaired): {}", name, e)),
         }
     }
+
+    /// Decode as the message type of the loaded schema that explains the payload best (see `guess::score`),
+    /// trying every view the envelope setting admits. The type of the first guessed message is tried first
+    /// afterwards; every candidate is only parsed when it no longer explains the payload well enough.
+    fn decode_guessed(&self, payload: &[u8]) -> Result<ProtoDecoded, String> {
+        let envelope = self.effective_envelope();
+        let learned = self.learned_view.get().copied();
+        let views: Vec<_> = candidate_views(payload)
+            .into_iter()
+            .filter(|(strategy, _)| {
+                learned.is_none_or(|lv| lv == *strategy)
+                    && (envelope == PayloadEnvelope::Auto || strategy.envelope() == envelope)
+            })
+            .collect();
+        let best = self
+            .learned_message
+            .get()
+            .and_then(|md| best_guess(&views, std::slice::from_ref(md)))
+            .or_else(|| best_guess(&views, self.guess_candidates.get_or_init(|| guess::candidates(&self.files))));
+        let (strategy, md, msg) = best.ok_or("No message type of the loaded schema matches this payload")?;
+        let json = print_compact_json(&*msg, &self.files)?;
+        let _ = self.learned_view.set(strategy);
+        let _ = self.learned_message.set(md.clone());
+        let confluent = if strategy.envelope() == PayloadEnvelope::Confluent { parse_confluent_header(payload) } else { None };
+        Ok(ProtoDecoded { json, repaired: false, confluent, guessed_message: Some(md.full_name().to_string()) })
+    }
 }
 
-pub fn decoder_from_cache(key: &str, selected_message: Option<String>) -> Option<Arc<ProtoDecoder>> {
-    if let Ok(guard) = DESCR_CACHE.lock() {
-        if let Some(files) = guard.get(key) {
-            return Some(ProtoDecoder::from_linked_files((**files).clone(), selected_message));
+/// The view and message type scoring highest on the payload, if any reaches `guess::MIN_SCORE`.
+fn best_guess<'a>(
+    views: &[(ViewStrategy, &[u8])],
+    types: &'a [MessageDescriptor],
+) -> Option<(ViewStrategy, &'a MessageDescriptor, Box<dyn MessageDyn>)> {
+    let mut best: Option<(f64, ViewStrategy, &MessageDescriptor, Box<dyn MessageDyn>)> = None;
+    for (strategy, bytes) in views {
+        for md in types {
+            let Ok(msg) = md.parse_from_bytes(bytes) else { continue };
+            let Some(score) = guess::score(&*msg).filter(|s| *s >= guess::MIN_SCORE) else { continue };
+            if best.as_ref().is_none_or(|(top, ..)| score > *top) {
+                best = Some((score, *strategy, md, msg));
+            }
         }
     }
-    None
+    best.map(|(_, strategy, md, msg)| (strategy, md, msg))
+}
+
+/// Decode a Confluent-framed payload with the schema registered under its schema id.
+fn decode_with_registry(resolver: &RegistryResolver, payload: &[u8], header: ConfluentHeader) -> Result<ProtoDecoded, String> {
+    let schema = resolver.schema(header.schema_id)?;
+    let name = schema.message_name(&header.message_indexes)?;
+    let fq = format!(".{name}");
+    let md = schema
+        .files
+        .iter()
+        .find_map(|fd| fd.message_by_full_name(&fq))
+        .ok_or_else(|| format!("Message type not found in registry schema {}: {}", header.schema_id, fq))?;
+    let msg = md
+        .parse_from_bytes(&payload[header.header_len..])
+        .map_err(|e| format!("Failed to parse protobuf payload as {} (schema id {}): {}", fq, header.schema_id, e))?;
+    let json = print_compact_json(&*msg, &schema.files)?;
+    Ok(ProtoDecoded { json, repaired: false, confluent: Some(header), guessed_message: None })
 }
 
 #[tauri::command]
-pub async fn parse_proto_metadata(files: Vec<String>) -> Result<ProtoMetadata, String> {
+pub async fn parse_proto_metadata(
+    state: State<'_, AppState>,
+    files: Vec<String>,
+    include_paths: Option<Vec<String>>,
+) -> CommandResult<ProtoMetadata> {
     if files.is_empty() {
-        return Err("No files provided".into());
+        return Err(Envelope::invalid("No files provided"));
     }
+    let parse_failed = |e: String| Envelope::failed("parse_proto_metadata", e).kind(ErrorKind::Config);
+    let expanded = expand_proto_paths(&files).map_err(parse_failed)?;
+    let includes = proto_include_paths(&files, &include_paths.unwrap_or_default());
 
-    // Expand provided paths (mirror ProtoDecoder::from_proto_files behavior)
-    let mut uniq: std::collections::HashSet<String> = std::collections::HashSet::new();
-    for f in &files {
-        let p = Path::new(f);
-        if p.is_dir() {
-            // Canonicalize directory for stable sibling enumeration
-            let dir = std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
-            let rd = std::fs::read_dir(&dir).map_err(|e| format!("Failed to read dir '{}': {}", dir.display(), e))?;
-            for ent in rd.filter_map(|e| e.ok()) {
-                let path = ent.path();
-                if path.is_file() && path.extension().map(|e| e == "proto").unwrap_or(false) {
-                    let can = std::fs::canonicalize(&path).unwrap_or(path);
-                    uniq.insert(can.to_string_lossy().to_string());
-                }
-            }
-        } else if p.is_file() {
-            // include the file itself (canonicalized for stability)
-            let can = std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
-            uniq.insert(can.to_string_lossy().to_string());
-        } else {
-            return Err(format!("File or directory not found: {}", f));
-        }
-    }
-    let mut expanded: Vec<String> = uniq.into_iter().collect();
-    expanded.sort();
-    if expanded.is_empty() {
-        return Err("No .proto files found from provided paths".into());
-    }
-
-    // Build descriptor set via shared utility
-    let fds: FileDescriptorSet = run_protoc_and_read_descriptor_set(&expanded)?;
-
-    // Link to reflect descriptors and cache them
-    let built: Vec<FileDescriptor> = link_file_descriptors(&fds)?;
-
-    // Compute a cache key based on canonical paths + file metadata (size + mtime)
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    use std::hash::Hash;
-    use std::hash::Hasher;
-    for p in &expanded {
-        p.hash(&mut hasher);
-        if let Ok(meta) = std::fs::metadata(p) {
-            meta.len().hash(&mut hasher);
-            if let Ok(mt) = meta.modified() {
-                if let Ok(dur) = mt.duration_since(std::time::UNIX_EPOCH) {
-                    dur.as_secs().hash(&mut hasher);
-                    dur.subsec_nanos().hash(&mut hasher);
-                }
-            }
-        }
-    }
-    let cache_key = format!("pbds-{:x}", hasher.finish());
-    if let Ok(mut guard) = DESCR_CACHE.lock() {
-        guard.insert(cache_key.clone(), Arc::new(built.clone()));
-    }
+    // Parse once per content; the key lets `Kafka::with_descriptors` reuse the graph on every reconfigure
+    let (cache_key, built) = state.descriptors.register_with(&expanded, &includes).map_err(parse_failed)?;
 
     let mut packages_set: HashSet<String> = HashSet::new();
     let mut messages: Vec<String> = Vec::new();
 
-    for fd in &fds.file {
-        extract_from_file_descriptor(fd, &mut packages_set, &mut messages);
+    for fd in built.iter() {
+        extract_from_file_descriptor(fd.proto(), &mut packages_set, &mut messages);
     }
 
     messages.sort();
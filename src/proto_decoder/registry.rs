@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use protobuf::descriptor::{DescriptorProto, FileDescriptorProto, FileDescriptorSet};
+use protobuf::reflect::FileDescriptor;
+
+use crate::kafka::schema_registry::{RegisteredSchema, SchemaRegistry};
+use crate::utils::link_file_descriptors;
+
+/// Nested imports deeper than this are treated as a cycle.
+const MAX_REFERENCE_DEPTH: usize = 32;
+
+/// A registered protobuf schema compiled into descriptors.
+pub struct RegistrySchema {
+    pub files: Vec<FileDescriptor>,
+    main: FileDescriptorProto,
+}
+
+impl RegistrySchema {
+    /// Compile the registered schema `id` from its source and the sources of the files it imports,
+    /// keyed by import path.
+    pub fn parse(id: u32, source: &str, imports: &HashMap<String, String>) -> Result<Self, String> {
+        // Lay out the schema and its imports as files so the parser can resolve them
+        let dir = tempfile::tempdir().map_err(|e| format!("Failed to create temp dir for schema {id}: {e}"))?;
+        let main_name = format!("registry_schema_{id}.proto");
+        write_file(dir.path(), &main_name, source)?;
+        for (name, content) in imports {
+            write_file(dir.path(), name, content)?;
+        }
+
+        let parsed = protobuf_parse::Parser::new()
+            .pure()
+            .include(dir.path())
+            .input(dir.path().join(&main_name))
+            .parse_and_typecheck()
+            .map_err(|e| format!("Failed to parse protobuf schema {id}: {e}"))?;
+        let main = parsed
+            .file_descriptors
+            .iter()
+            .find(|f| f.name() == main_name)
+            .cloned()
+            .ok_or_else(|| format!("Parsed schema {id} is missing its main file"))?;
+        let set = FileDescriptorSet { file: parsed.file_descriptors, ..Default::default() };
+        let files = link_file_descriptors(&set)?;
+        Ok(Self { files, main })
+    }
+
+    /// Full name of the message addressed by a Confluent message-index path
+    /// ([0] = first top-level message, [1, 2] = third nested message of the second one).
+    pub fn message_name(&self, indexes: &[i64]) -> Result<String, String> {
+        let index = |i: i64, len: usize| usize::try_from(i).ok().filter(|i| *i < len);
+        let (first, rest) = indexes.split_first().ok_or("Empty message index path")?;
+        let mut msg: &DescriptorProto = index(*first, self.main.message_type.len())
+            .map(|i| &self.main.message_type[i])
+            .ok_or_else(|| format!("Message index {first} out of range in {}", self.main.name()))?;
+        let mut name = msg.name().to_string();
+        for i in rest {
+            msg = index(*i, msg.nested_type.len())
+                .map(|n| &msg.nested_type[n])
+                .ok_or_else(|| format!("Nested message index {i} out of range in {name}"))?;
+            name = format!("{name}.{}", msg.name());
+        }
+        Ok(match self.main.package() {
+            "" => name,
+            pkg => format!("{pkg}.{name}"),
+        })
+    }
+}
+
+/// Compiles protobuf schemas fetched from the registry, once per schema id.
+pub struct RegistryResolver {
+    registry: SchemaRegistry,
+    // Only successes are kept; fetch failures are throttled by the registry client
+    compiled: Mutex<HashMap<u32, Arc<RegistrySchema>>>,
+}
+
+impl RegistryResolver {
+    pub fn new(registry: SchemaRegistry) -> Self {
+        Self { registry, compiled: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn schema(&self, id: u32) -> Result<Arc<RegistrySchema>, String> {
+        if let Some(hit) = self.compiled.lock().ok().and_then(|c| c.get(&id).cloned()) {
+            return Ok(hit);
+        }
+        let schema = self.compile(id)?;
+        if let Ok(mut compiled) = self.compiled.lock() {
+            compiled.insert(id, schema.clone());
+        }
+        Ok(schema)
+    }
+
+    fn compile(&self, id: u32) -> Result<Arc<RegistrySchema>, String> {
+        let root = self.registry.schema_by_id(id).map_err(|e| e.to_string())?;
+        if !root.schema_type.eq_ignore_ascii_case("PROTOBUF") {
+            return Err(format!("Schema id {id} is a {} schema, not protobuf", root.schema_type));
+        }
+        let mut imports = HashMap::new();
+        self.fetch_references(&root, &mut imports, 0)?;
+        RegistrySchema::parse(id, &root.schema, &imports).map(Arc::new)
+    }
+
+    fn fetch_references(
+        &self,
+        schema: &RegisteredSchema,
+        imports: &mut HashMap<String, String>,
+        depth: usize,
+    ) -> Result<(), String> {
+        if depth > MAX_REFERENCE_DEPTH {
+            return Err("Schema references nest too deeply".into());
+        }
+        for reference in &schema.references {
+            if imports.contains_key(&reference.name) {
+                continue;
+            }
+            let imported = self.registry.schema_by_reference(reference).map_err(|e| e.to_string())?;
+            imports.insert(reference.name.clone(), imported.schema.clone());
+            self.fetch_references(&imported, imports, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_file(dir: &Path, name: &str, content: &str) -> Result<(), String> {
+    // Import paths are relative; refuse anything that would escape the temp dir
+    if Path::new(name).is_absolute() || name.split('/').any(|part| part == "..") {
+        return Err(format!("Refusing unsafe schema reference path '{name}'"));
+    }
+    let path = dir.join(name);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to write schema '{name}': {e}"))?;
+    }
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write schema '{name}': {e}"))
+}
@@ -0,0 +1,224 @@
+use base64::Engine;
+use protobuf::reflect::{FileDescriptor, MessageDescriptor, ReflectFieldRef, ReflectValueRef};
+use protobuf::MessageDyn;
+use serde_json::Value;
+
+use crate::utils::well_known_file;
+
+const WRAPPERS: [&str; 9] = [
+    "google.protobuf.DoubleValue",
+    "google.protobuf.FloatValue",
+    "google.protobuf.Int64Value",
+    "google.protobuf.UInt64Value",
+    "google.protobuf.Int32Value",
+    "google.protobuf.UInt32Value",
+    "google.protobuf.BoolValue",
+    "google.protobuf.StringValue",
+    "google.protobuf.BytesValue",
+];
+
+/// Well-known files searched for Any payloads the loaded schema does not define.
+const ANY_FALLBACK_FILES: [&str; 6] = [
+    "google/protobuf/timestamp.proto",
+    "google/protobuf/duration.proto",
+    "google/protobuf/struct.proto",
+    "google/protobuf/wrappers.proto",
+    "google/protobuf/empty.proto",
+    "google/protobuf/field_mask.proto",
+];
+
+/// Rewrite the JSON printed for `msg` so Timestamp, Duration, Struct/Value/ListValue and the wrapper types
+/// read as canonical protojson (RFC3339 strings, "1.5s", plain JSON, unwrapped values) at any depth, and
+/// Any fields whose type `pool` knows show the decoded message with its "@type".
+pub(crate) fn render_well_known(msg: &dyn MessageDyn, json: &mut Value, pool: &[FileDescriptor]) {
+    if let Some(canonical) = canonical(msg, json, pool) {
+        *json = canonical;
+        return;
+    }
+    let Value::Object(obj) = json else { return };
+    for field in msg.descriptor_dyn().fields() {
+        let Some(slot) = obj.get_mut(field.json_name()) else { continue };
+        match field.get_reflect(msg) {
+            ReflectFieldRef::Optional(o) => {
+                if let Some(m) = o.value().and_then(|v| v.to_message()) {
+                    render_well_known(&*m, slot, pool);
+                }
+            }
+            ReflectFieldRef::Repeated(items) => {
+                if let Value::Array(slots) = slot {
+                    for (item, slot) in items.into_iter().zip(slots.iter_mut()) {
+                        if let Some(m) = item.to_message() {
+                            render_well_known(&*m, slot, pool);
+                        }
+                    }
+                }
+            }
+            ReflectFieldRef::Map(map) => {
+                if let Value::Object(slots) = slot {
+                    for (k, v) in &map {
+                        if let (Some(m), Some(slot)) = (v.to_message(), slots.get_mut(&map_key(&k))) {
+                            render_well_known(&*m, slot, pool);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Types protojson prints as something other than an object with their fields.
+fn has_special_json(full_name: &str) -> bool {
+    matches!(
+        full_name,
+        "google.protobuf.Timestamp"
+            | "google.protobuf.Duration"
+            | "google.protobuf.Struct"
+            | "google.protobuf.Value"
+            | "google.protobuf.ListValue"
+    ) || WRAPPERS.contains(&full_name)
+}
+
+fn canonical(msg: &dyn MessageDyn, json: &Value, pool: &[FileDescriptor]) -> Option<Value> {
+    match msg.descriptor_dyn().full_name() {
+        "google.protobuf.Any" => any(msg, pool),
+        "google.protobuf.Timestamp" => timestamp(msg),
+        "google.protobuf.Duration" => duration(msg),
+        "google.protobuf.Struct" => Some(struct_value(msg)),
+        "google.protobuf.Value" => Some(value(msg)),
+        "google.protobuf.ListValue" => Some(list_value(msg)),
+        name if WRAPPERS.contains(&name) => Some(match json {
+            // Printers leave the default value out; the wrapper still holds it
+            Value::Object(_) => scalar(field(msg, "value")?),
+            other => other.clone(),
+        }),
+        _ => None,
+    }
+}
+
+fn find_message(pool: &[FileDescriptor], full_name: &str) -> Option<MessageDescriptor> {
+    let fq = format!(".{full_name}");
+    pool.iter()
+        .find_map(|fd| fd.message_by_full_name(&fq))
+        .or_else(|| ANY_FALLBACK_FILES.iter().filter_map(|f| well_known_file(f)).find_map(|fd| fd.message_by_full_name(&fq)))
+}
+
+/// The packed message decoded with its type from the URL's last segment
+/// (type.googleapis.com/shop.Order); None when the type is unknown or the bytes don't parse.
+fn any(msg: &dyn MessageDyn, pool: &[FileDescriptor]) -> Option<Value> {
+    let type_url = field(msg, "type_url")?.to_str()?.to_string();
+    let bytes = field(msg, "value")?.to_bytes()?.to_vec();
+    let descriptor = find_message(pool, type_url.rsplit('/').next()?)?;
+    let inner = descriptor.parse_from_bytes(&bytes).ok()?;
+    let printed = protobuf_json_mapping::print_to_string(&*inner).ok()?;
+    let mut json: Value = serde_json::from_str(&printed).ok()?;
+    render_well_known(&*inner, &mut json, pool);
+
+    let mut out = serde_json::Map::new();
+    out.insert("@type".into(), Value::String(type_url));
+    match json {
+        Value::Object(fields) if !has_special_json(descriptor.full_name()) => out.extend(fields),
+        other => {
+            out.insert("value".into(), other);
+        }
+    }
+    Some(Value::Object(out))
+}
+
+fn field<'a>(msg: &'a dyn MessageDyn, name: &str) -> Option<ReflectValueRef<'a>> {
+    msg.descriptor_dyn().field_by_name(name).map(|f| f.get_singular_field_or_default(msg))
+}
+
+/// Map keys as protojson prints them (always strings).
+fn map_key(key: &ReflectValueRef) -> String {
+    match key {
+        ReflectValueRef::String(s) => s.to_string(),
+        other => scalar(other.clone()).to_string().trim_matches('"').to_string(),
+    }
+}
+
+/// Fraction of a second with 0, 3, 6 or 9 digits.
+fn fraction(nanos: u32) -> String {
+    if nanos == 0 {
+        String::new()
+    } else if nanos.is_multiple_of(1_000_000) {
+        format!(".{:03}", nanos / 1_000_000)
+    } else if nanos.is_multiple_of(1_000) {
+        format!(".{:06}", nanos / 1_000)
+    } else {
+        format!(".{:09}", nanos)
+    }
+}
+
+fn timestamp(msg: &dyn MessageDyn) -> Option<Value> {
+    let seconds = field(msg, "seconds")?.to_i64()?;
+    let nanos = u32::try_from(field(msg, "nanos")?.to_i32()?).ok()?;
+    let at = chrono::DateTime::from_timestamp(seconds, nanos)?;
+    Some(Value::String(format!("{}{}Z", at.format("%Y-%m-%dT%H:%M:%S"), fraction(nanos))))
+}
+
+fn duration(msg: &dyn MessageDyn) -> Option<Value> {
+    let seconds = field(msg, "seconds")?.to_i64()?;
+    let nanos = field(msg, "nanos")?.to_i32()?;
+    let sign = if seconds < 0 || nanos < 0 { "-" } else { "" };
+    Some(Value::String(format!("{sign}{}{}s", seconds.unsigned_abs(), fraction(nanos.unsigned_abs()))))
+}
+
+fn struct_value(msg: &dyn MessageDyn) -> Value {
+    let mut out = serde_json::Map::new();
+    if let Some(ReflectFieldRef::Map(fields)) = msg.descriptor_dyn().field_by_name("fields").map(|f| f.get_reflect(msg)) {
+        for (k, v) in &fields {
+            if let (Some(k), Some(v)) = (k.to_str(), v.to_message()) {
+                out.insert(k.to_string(), value(&*v));
+            }
+        }
+    }
+    Value::Object(out)
+}
+
+fn list_value(msg: &dyn MessageDyn) -> Value {
+    match msg.descriptor_dyn().field_by_name("values").map(|f| f.get_reflect(msg)) {
+        Some(ReflectFieldRef::Repeated(values)) => {
+            Value::Array(values.into_iter().filter_map(|v| v.to_message()).map(|v| value(&*v)).collect())
+        }
+        _ => Value::Array(Vec::new()),
+    }
+}
+
+/// google.protobuf.Value: whichever member of its `kind` oneof is set; null when none is.
+fn value(msg: &dyn MessageDyn) -> Value {
+    let descriptor = msg.descriptor_dyn();
+    let Some(kind) = descriptor.fields().find(|f| f.has_field(msg)) else { return Value::Null };
+    let v = kind.get_singular_field_or_default(msg);
+    match kind.name() {
+        "struct_value" => v.to_message().map(|m| struct_value(&*m)).unwrap_or(Value::Null),
+        "list_value" => v.to_message().map(|m| list_value(&*m)).unwrap_or(Value::Null),
+        "null_value" => Value::Null,
+        _ => scalar(v),
+    }
+}
+
+fn float(f: f64) -> Value {
+    match serde_json::Number::from_f64(f) {
+        Some(n) => Value::Number(n),
+        None if f.is_nan() => Value::String("NaN".into()),
+        None if f > 0.0 => Value::String("Infinity".into()),
+        None => Value::String("-Infinity".into()),
+    }
+}
+
+/// Scalar as protojson prints it: 64-bit integers as strings, bytes as base64.
+fn scalar(v: ReflectValueRef) -> Value {
+    match v {
+        ReflectValueRef::I32(x) => Value::from(x),
+        ReflectValueRef::U32(x) => Value::from(x),
+        ReflectValueRef::I64(x) => Value::String(x.to_string()),
+        ReflectValueRef::U64(x) => Value::String(x.to_string()),
+        ReflectValueRef::F32(x) => float(f64::from(x)),
+        ReflectValueRef::F64(x) => float(x),
+        ReflectValueRef::Bool(x) => Value::Bool(x),
+        ReflectValueRef::String(s) => Value::String(s.to_string()),
+        ReflectValueRef::Bytes(b) => Value::String(base64::engine::general_purpose::STANDARD.encode(b)),
+        ReflectValueRef::Enum(d, n) => d.value_by_number(n).map(|e| Value::String(e.name().to_string())).unwrap_or(Value::from(n)),
+        ReflectValueRef::Message(_) => Value::Null,
+    }
+}
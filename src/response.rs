@@ -0,0 +1,205 @@
+use std::fmt;
+
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::kafka::{is_authorization_error, AccessDenied, UnknownHostKey};
+
+/// Outcome class of a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Success,
+    Warn,
+    Error,
+}
+
+/// What an error is about, so the UI can offer the matching action instead of matching on messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The cluster could not be reached or failed the request
+    Broker,
+    /// Authentication failed or access was denied (SASL, ACLs)
+    Auth,
+    /// A payload, key or schema could not be decoded
+    Decode,
+    /// Connection or decoder settings are wrong (missing files, bad options)
+    Config,
+    /// A request argument is invalid
+    Invalid,
+    /// The app is not in a state to do this (no connection, no running load, read-only build)
+    State,
+    Internal,
+}
+
+/// Command outcome the UI can localize and style: a stable machine-readable `code` (e.g. "not_configured",
+/// "get_topics_failed") with the `params` its translation needs. `message` is the English text for logs
+/// and codes the UI has no translation for. Errors also carry their `kind` and whether a retry may succeed.
+#[derive(Debug, Clone, Serialize)]
+pub struct Envelope {
+    pub status: Status,
+    pub code: String,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub params: Map<String, Value>,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ErrorKind>,
+    pub retryable: bool,
+}
+
+/// Result of a command: its data, or an error envelope.
+pub type CommandResult<T> = Result<T, Envelope>;
+
+impl Envelope {
+    fn new(status: Status, code: &str, message: impl Into<String>) -> Self {
+        let kind = (status == Status::Error).then_some(ErrorKind::Internal);
+        Self { status, code: code.to_string(), params: Map::new(), message: message.into(), kind, retryable: false }
+    }
+
+    pub fn success(code: &str, message: impl Into<String>) -> Self {
+        Self::new(Status::Success, code, message)
+    }
+
+    pub fn warn(code: &str, message: impl Into<String>) -> Self {
+        Self::new(Status::Warn, code, message)
+    }
+
+    pub fn error(code: &str, message: impl Into<String>) -> Self {
+        Self::new(Status::Error, code, message)
+    }
+
+    /// Add a parameter for the translated message.
+    pub fn with(mut self, key: &str, value: impl Serialize) -> Self {
+        self.params.insert(key.to_string(), serde_json::to_value(value).unwrap_or(Value::Null));
+        self
+    }
+
+    /// Set the error kind where the call site knows better than `failed`'s classification.
+    pub fn kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// A failed operation: code `<action>_failed`, message "Failed to <action>: <error>", the error as `error` param.
+    /// Kind and retryability come from the error (see `Failure`).
+    pub fn failed(action: &str, error: impl Failure) -> Self {
+        let (kind, retryable) = error.classify();
+        let error = error.to_string();
+        let mut envelope =
+            Self::error(&format!("{action}_failed"), format!("Failed to {}: {error}", action.replace('_', " "))).with("error", error);
+        envelope.kind = Some(kind);
+        envelope.retryable = retryable;
+        envelope
+    }
+
+    pub fn not_configured() -> Self {
+        Self::error("not_configured", "Kafka is not configured").kind(ErrorKind::State)
+    }
+
+    /// Shared application state could not be locked.
+    pub fn state(error: impl fmt::Display) -> Self {
+        let error = error.to_string();
+        Self::error("state_unavailable", format!("Failed to access state: {error}")).with("error", error)
+    }
+
+    /// A request argument is invalid.
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::error("invalid_argument", message).kind(ErrorKind::Invalid)
+    }
+}
+
+/// An error `Envelope::failed` can classify: (kind, whether retrying may succeed).
+/// Errors of unknown origin are internal and not retried.
+pub trait Failure: fmt::Display {
+    fn classify(&self) -> (ErrorKind, bool) {
+        (ErrorKind::Internal, false)
+    }
+}
+
+impl Failure for String {}
+impl Failure for &str {}
+impl Failure for tokio::task::JoinError {}
+
+impl Failure for std::io::Error {
+    fn classify(&self) -> (ErrorKind, bool) {
+        match self.kind() {
+            std::io::ErrorKind::NotFound | std::io::ErrorKind::PermissionDenied => (ErrorKind::Config, false),
+            _ => (ErrorKind::Internal, false),
+        }
+    }
+}
+
+impl Failure for KafkaError {
+    fn classify(&self) -> (ErrorKind, bool) {
+        use RDKafkaErrorCode as C;
+        if matches!(self, KafkaError::ClientCreation(_) | KafkaError::ClientConfig(..)) {
+            return (ErrorKind::Config, false);
+        }
+        match self.rdkafka_error_code() {
+            Some(c) if is_authorization_error(c) => (ErrorKind::Auth, false),
+            Some(C::Authentication | C::SaslAuthenticationFailed) => (ErrorKind::Auth, false),
+            Some(C::SSL) => (ErrorKind::Config, false),
+            Some(
+                C::BrokerTransportFailure
+                | C::AllBrokersDown
+                | C::Resolve
+                | C::OperationTimedOut
+                | C::RequestTimedOut
+                | C::NetworkException
+                | C::BrokerNotAvailable
+                | C::LeaderNotAvailable
+                | C::NotLeaderForPartition
+                | C::NotCoordinator
+                | C::CoordinatorNotAvailable
+                | C::CoordinatorLoadInProgress
+                | C::NotEnoughReplicas
+                | C::NotEnoughReplicasAfterAppend
+                | C::QueueFull,
+            ) => (ErrorKind::Broker, true),
+            _ => (ErrorKind::Broker, false),
+        }
+    }
+}
+
+/// Classified by the first Kafka, access or I/O error in the chain.
+impl Failure for anyhow::Error {
+    fn classify(&self) -> (ErrorKind, bool) {
+        for cause in self.chain() {
+            if let Some(e) = cause.downcast_ref::<KafkaError>() {
+                return e.classify();
+            }
+            if cause.is::<AccessDenied>() {
+                return (ErrorKind::Auth, false);
+            }
+            if cause.is::<UnknownHostKey>() {
+                return (ErrorKind::Config, false);
+            }
+            if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+                return e.classify();
+            }
+        }
+        (ErrorKind::Internal, false)
+    }
+}
+
+impl fmt::Display for Envelope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Errors of helpers that still report plain strings; they carry no code of their own.
+impl From<String> for Envelope {
+    fn from(message: String) -> Self {
+        Self::error("error", message)
+    }
+}
+
+/// For commands that still return `Result<_, String>`.
+impl From<Envelope> for String {
+    fn from(envelope: Envelope) -> Self {
+        envelope.message
+    }
+}
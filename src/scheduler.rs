@@ -0,0 +1,462 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDateTime, Timelike};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::kafka::{Kafka, KafkaConfig, TopicAccess, UiMessage};
+use crate::kafka_adapter::{message_matches, FilterMode, MessageFilter};
+use crate::profiles::{self, profiles_dir};
+use crate::response::{CommandResult, Envelope};
+use crate::transform::TransformScript;
+use crate::utils::cron::CronSchedule;
+use crate::utils::json_store::JsonStore;
+use crate::workspace::WorkspaceFilters;
+
+/// Results of each run live under the app cache dir: scans/<scan id>/<run id>.json
+const RESULTS_DIR: &str = "scans";
+/// Runs kept per scan; older results are pruned.
+const MAX_RUNS_PER_SCAN: usize = 20;
+const DEFAULT_MAX_RESULTS: usize = 500;
+const TICK: Duration = Duration::from_secs(20);
+const SCAN_BATCH: usize = 500;
+
+// Scans currently executing, so a slow run is not started twice
+static RUNNING: Lazy<std::sync::Mutex<HashSet<String>>> = Lazy::new(|| std::sync::Mutex::new(HashSet::new()));
+// Minute each scan last fired in, so a tick cannot fire the same minute twice
+static LAST_FIRED: Lazy<std::sync::Mutex<HashMap<String, NaiveDateTime>>> =
+    Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+static STORE: JsonStore = JsonStore::new("scheduled_scans.json", "scheduled scans");
+
+/// A saved filter preset executed on a cron schedule while the app is open.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledScan {
+    /// Generated on first save when empty
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Saved connection profile to read with (secrets come from the OS keychain)
+    pub profile: String,
+    /// Topic to scan; the profile's topic when omitted
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub filters: WorkspaceFilters,
+    /// Cron expression in local time, e.g. "0 8 * * 1-5" or "@hourly"
+    pub schedule: String,
+    /// Matching records kept with each run
+    #[serde(rename = "max_results", alias = "maxResults")]
+    pub max_results: Option<usize>,
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    #[serde(default, rename = "last_run", alias = "lastRun")]
+    pub last_run: Option<String>,
+    #[serde(default, rename = "next_run", alias = "nextRun", skip_deserializing)]
+    pub next_run: Option<String>,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+/// Outcome of one run, emitted as `scheduler:scan_finished`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanSummary {
+    #[serde(rename = "scan_id", alias = "scanId")]
+    pub scan_id: String,
+    pub name: String,
+    #[serde(rename = "run_id", alias = "runId")]
+    pub run_id: String,
+    pub topic: String,
+    #[serde(rename = "started_at", alias = "startedAt")]
+    pub started_at: String,
+    #[serde(rename = "finished_at", alias = "finishedAt")]
+    pub finished_at: String,
+    pub scanned: u64,
+    pub matched: u64,
+    /// Matches with a timestamp after the previous run
+    #[serde(rename = "new_matches", alias = "newMatches")]
+    pub new_matches: u64,
+    /// True when more records matched than were stored
+    pub truncated: bool,
+    pub error: Option<String>,
+}
+
+/// A stored run: summary plus the matching records.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanRun {
+    #[serde(flatten)]
+    pub summary: ScanSummary,
+    pub messages: Vec<UiMessage>,
+}
+
+fn read_all(dir: &Path) -> Result<BTreeMap<String, ScheduledScan>, String> {
+    STORE.read(dir)
+}
+
+/// All scans sorted by id, with the next firing time filled in.
+pub fn list(dir: &Path) -> Result<Vec<ScheduledScan>, String> {
+    let now = Local::now().naive_local();
+    Ok(read_all(dir)?
+        .into_values()
+        .map(|mut s| {
+            s.next_run = CronSchedule::parse(&s.schedule)
+                .ok()
+                .filter(|_| s.enabled)
+                .and_then(|c| c.next_after(now))
+                .map(|t| t.format("%Y-%m-%dT%H:%M").to_string());
+            s
+        })
+        .collect())
+}
+
+/// Scan and run ids name directories and files under the cache dir: letters, digits, '_' and '-' only.
+fn check_id(what: &str, id: &str) -> Result<(), String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-')) {
+        return Err(format!("Invalid {what} '{id}': use letters, digits, '_' and '-'"));
+    }
+    Ok(())
+}
+
+/// Validate and save (or overwrite) a scan.
+pub fn save(dir: &Path, mut scan: ScheduledScan) -> Result<ScheduledScan, String> {
+    CronSchedule::parse(&scan.schedule)?;
+    if scan.profile.trim().is_empty() {
+        return Err("Scheduled scan needs a connection profile".into());
+    }
+    if scan.id.trim().is_empty() {
+        scan.id = format!("scan-{}", chrono::Utc::now().timestamp_millis());
+    }
+    check_id("scan id", &scan.id)?;
+    scan.next_run = None;
+    STORE.update(dir, |all: &mut BTreeMap<String, ScheduledScan>| {
+        // Keep the run history when the UI re-saves an existing scan
+        if scan.last_run.is_none() {
+            scan.last_run = all.get(&scan.id).and_then(|s| s.last_run.clone());
+        }
+        all.insert(scan.id.clone(), scan.clone());
+        Ok(scan)
+    })
+}
+
+pub fn delete(dir: &Path, id: &str) -> Result<(), String> {
+    check_id("scan id", id)?;
+    STORE.update(dir, |all: &mut BTreeMap<String, ScheduledScan>| match all.remove(id) {
+        Some(_) => Ok(()),
+        None => Err(format!("Scheduled scan '{}' not found", id)),
+    })
+}
+
+fn mark_run(dir: &Path, id: &str, at: &str) -> Result<(), String> {
+    STORE.update(dir, |all: &mut BTreeMap<String, ScheduledScan>| {
+        if let Some(scan) = all.get_mut(id) {
+            scan.last_run = Some(at.to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Summaries of the stored runs of a scan, newest first.
+pub fn list_runs(cache_dir: &Path, id: &str) -> Result<Vec<ScanSummary>, String> {
+    check_id("scan id", id)?;
+    let dir = cache_dir.join(RESULTS_DIR).join(id);
+    let mut out = Vec::new();
+    for path in run_files(&dir)? {
+        let data = fs::read_to_string(&path).map_err(|e| format!("Failed to read scan results: {e}"))?;
+        if let Ok(run) = serde_json::from_str::<ScanRun>(&data) {
+            out.push(run.summary);
+        }
+    }
+    Ok(out)
+}
+
+pub fn load_run(cache_dir: &Path, id: &str, run_id: &str) -> Result<ScanRun, String> {
+    check_id("scan id", id)?;
+    check_id("run id", run_id)?;
+    let path = cache_dir.join(RESULTS_DIR).join(id).join(format!("{run_id}.json"));
+    let data = fs::read_to_string(&path).map_err(|e| format!("Failed to read scan results: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse scan results: {e}"))
+}
+
+/// Run files of a scan, newest first (run ids sort chronologically).
+fn run_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to list scan results: {e}"))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files.reverse();
+    Ok(files)
+}
+
+fn store_run(cache_dir: &Path, run: &ScanRun) -> Result<(), String> {
+    let dir = cache_dir.join(RESULTS_DIR).join(&run.summary.scan_id);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create scan results directory: {e}"))?;
+    let data = serde_json::to_string(run).map_err(|e| format!("Failed to serialize scan results: {e}"))?;
+    fs::write(dir.join(format!("{}.json", run.summary.run_id)), data)
+        .map_err(|e| format!("Failed to write scan results: {e}"))?;
+    for old in run_files(&dir)?.into_iter().skip(MAX_RUNS_PER_SCAN) {
+        let _ = fs::remove_file(old);
+    }
+    Ok(())
+}
+
+/// Read the scan's topic snapshot once and keep the records matching its filters.
+fn execute(data_dir: &Path, scan: &ScheduledScan) -> ScanRun {
+    let started = Local::now();
+    let mut summary = ScanSummary {
+        scan_id: scan.id.clone(),
+        name: scan.name.clone(),
+        run_id: started.format("%Y%m%dT%H%M%S%3f").to_string(),
+        topic: scan.topic.clone().unwrap_or_default(),
+        started_at: started.to_rfc3339(),
+        finished_at: String::new(),
+        scanned: 0,
+        matched: 0,
+        new_matches: 0,
+        truncated: false,
+        error: None,
+    };
+    let mut messages = Vec::new();
+    if let Err(e) = scan_topic(data_dir, scan, &mut summary, &mut messages) {
+        summary.error = Some(e);
+    }
+    summary.finished_at = Local::now().to_rfc3339();
+    ScanRun { summary, messages }
+}
+
+fn scan_topic(
+    data_dir: &Path,
+    scan: &ScheduledScan,
+    summary: &mut ScanSummary,
+    messages: &mut Vec<UiMessage>,
+) -> Result<(), String> {
+    let mut profile = profiles::load(data_dir, &scan.profile)?;
+    profiles::restore_secrets(&mut profile);
+    let mut config = profile.config;
+    if let Some(topic) = scan.topic.as_ref().filter(|t| !t.is_empty()) {
+        config.topic = topic.clone();
+    }
+    if config.topic.is_empty() {
+        return Err("Scheduled scan has no topic".into());
+    }
+    summary.topic = config.topic.clone();
+    let since = scan
+        .last_run
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+    let max_results = scan.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+    scan_snapshot(config, &scan.filters, |m, matched| {
+        summary.scanned += 1;
+        if !matched {
+            return true;
+        }
+        summary.matched += 1;
+        let is_new = match (since, DateTime::parse_from_rfc3339(&m.timestamp)) {
+            (Some(since), Ok(ts)) => ts > since,
+            (None, _) => true,
+            _ => false,
+        };
+        if is_new {
+            summary.new_matches += 1;
+        }
+        if messages.len() < max_results {
+            messages.push(m);
+        } else {
+            summary.truncated = true;
+        }
+        true
+    })
+    .map(|_| ())
+}
+
+/// Read a topic snapshot once with the given reading filters (partition / start position),
+/// calling `visit` with every record and whether it matched the key/message filters.
+/// `visit` returns false to stop early; the result tells whether the whole snapshot was read.
+pub(crate) fn scan_snapshot(
+    mut config: KafkaConfig,
+    filters: &WorkspaceFilters,
+    mut visit: impl FnMut(UiMessage, bool) -> bool,
+) -> Result<bool, String> {
+    config.partition = filters.partition.clone();
+    config.partitions = filters.partitions.clone();
+    config.start_offset = filters.start_offset;
+    config.start_from = filters.start_from.clone().or(config.start_from);
+    // Filters need the payload
+    config.lazy_decode = Some(false);
+    let topic = config.topic.clone();
+    let mode = match filters.message_filter_mode.as_deref() {
+        Some(m) if m.eq_ignore_ascii_case("jq") => FilterMode::Jq,
+        _ => FilterMode::Plain,
+    };
+
+    let transform = TransformScript::from_option(filters.transform_script.as_deref())?;
+    let msg_filter = MessageFilter::new(filters.message_filter.as_deref(), mode, filters.plain)?;
+
+    // Runs on blocking-pool and API server threads: the session reader is async, so drive it on a
+    // private runtime (declared first so the reader is dropped before it)
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("Failed to start reader runtime: {e}"))?;
+    let kafka = {
+        let _enter = rt.enter();
+        Kafka::new(config).map_err(|e| format!("Failed to create consumer: {e}"))?
+    };
+    let mut idle = 0;
+    loop {
+        let batch = rt
+            .block_on(kafka.consume_next(SCAN_BATCH))
+            .map_err(|e| format!("Failed to read messages: {e}"))?;
+        if matches!(batch.access, TopicAccess::Unauthorized) {
+            return Err(format!("Not authorized to read topic '{}'", topic));
+        }
+        idle = if batch.messages.is_empty() { idle + 1 } else { 0 };
+        for mut m in batch.messages {
+            let kept = transform.as_ref().is_none_or(|t| t.apply(&mut m));
+            let matched = kept
+                && filters.tombstones.matches(&m)
+                && message_matches(&m, filters.key_filter.as_deref(), filters.plain, &msg_filter);
+            if !visit(m, matched) {
+                return Ok(false);
+            }
+        }
+        // Idle batches without end-of-snapshot mean the broker stopped answering
+        if batch.end_of_snapshot || idle >= 3 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Execute a scan, cache its results and notify the UI.
+fn run_and_store(app: &AppHandle, scan: ScheduledScan) -> Result<ScanSummary, String> {
+    {
+        let mut running = RUNNING.lock().map_err(|e| format!("Failed to access scheduler state: {e}"))?;
+        if !running.insert(scan.id.clone()) {
+            return Err(format!("Scheduled scan '{}' is already running", scan.name));
+        }
+    }
+    let data_dir = profiles_dir(app);
+    let cache_dir = cache_dir(app);
+    let result = data_dir.and_then(|data_dir| {
+        let cache_dir = cache_dir?;
+        let run = execute(&data_dir, &scan);
+        store_run(&cache_dir, &run)?;
+        mark_run(&data_dir, &scan.id, &run.summary.started_at)?;
+        Ok(run.summary)
+    });
+    if let Ok(mut running) = RUNNING.lock() {
+        running.remove(&scan.id);
+    }
+    if let Ok(summary) = &result {
+        let _ = app.emit("scheduler:scan_finished", summary);
+    }
+    result
+}
+
+fn cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to resolve app cache dir: {e}"))
+}
+
+/// Fire every enabled scan whose schedule matches the current minute.
+fn tick(app: &AppHandle) {
+    let Ok(dir) = profiles_dir(app) else { return };
+    let scans = match read_all(&dir) {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("{e}");
+            return;
+        }
+    };
+    let now = Local::now().naive_local();
+    let Some(minute) = now.with_second(0).and_then(|t| t.with_nanosecond(0)) else { return };
+    for scan in scans.into_values().filter(|s| s.enabled) {
+        let due = CronSchedule::parse(&scan.schedule).is_ok_and(|c| c.matches(&minute));
+        if !due {
+            continue;
+        }
+        let Ok(mut fired) = LAST_FIRED.lock() else { return };
+        if fired.get(&scan.id) == Some(&minute) {
+            continue;
+        }
+        fired.insert(scan.id.clone(), minute);
+        drop(fired);
+        let app = app.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            if let Err(e) = run_and_store(&app, scan) {
+                log::warn!("{e}");
+            }
+        });
+    }
+}
+
+/// Start the background scheduler; scans only run while the app is open.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(TICK).await;
+            tick(&app);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn list_scheduled_scans(app: AppHandle) -> CommandResult<Vec<ScheduledScan>> {
+    profiles_dir(&app).and_then(|dir| list(&dir)).map_err(|e| Envelope::failed("list_scheduled_scans", e))
+}
+
+#[tauri::command]
+pub async fn save_scheduled_scan(app: AppHandle, scan: ScheduledScan) -> CommandResult<ScheduledScan> {
+    profiles_dir(&app).and_then(|dir| save(&dir, scan)).map_err(|e| Envelope::failed("save_scheduled_scan", e))
+}
+
+/// Delete a scan together with its cached results.
+#[tauri::command]
+pub async fn delete_scheduled_scan(app: AppHandle, id: String) -> CommandResult<()> {
+    profiles_dir(&app)
+        .and_then(|dir| delete(&dir, &id))
+        .and_then(|_| {
+            let results = cache_dir(&app)?.join(RESULTS_DIR).join(&id);
+            if results.exists() {
+                fs::remove_dir_all(&results).map_err(|e| format!("Failed to delete scan results: {e}"))?;
+            }
+            Ok(())
+        })
+        .map_err(|e| Envelope::failed("delete_scheduled_scan", e))
+}
+
+/// Run a saved scan now, outside its schedule (blocking).
+pub(crate) fn run_now(app: &AppHandle, id: &str) -> Result<ScanSummary, String> {
+    let scan = read_all(&profiles_dir(app)?)?
+        .remove(id)
+        .ok_or_else(|| format!("Scheduled scan '{}' not found", id))?;
+    run_and_store(app, scan)
+}
+
+/// Run a scan immediately, outside its schedule.
+#[tauri::command]
+pub async fn run_scheduled_scan(app: AppHandle, id: String) -> CommandResult<ScanSummary> {
+    tokio::task::spawn_blocking(move || run_now(&app, &id))
+        .await
+        .map_err(|e| Envelope::failed("run_scheduled_scan", e))?
+        .map_err(|e| Envelope::failed("run_scheduled_scan", e))
+}
+
+#[tauri::command]
+pub async fn list_scan_runs(app: AppHandle, id: String) -> CommandResult<Vec<ScanSummary>> {
+    cache_dir(&app).and_then(|dir| list_runs(&dir, &id)).map_err(|e| Envelope::failed("list_scan_runs", e))
+}
+
+#[tauri::command]
+pub async fn get_scan_run(app: AppHandle, id: String, run_id: String) -> CommandResult<ScanRun> {
+    cache_dir(&app).and_then(|dir| load_run(&dir, &id, &run_id)).map_err(|e| Envelope::failed("load_scan_run", e))
+}
@@ -0,0 +1,32 @@
+use keyring::{Entry, Error as KeyringError};
+
+/// Service name under which rkui secrets are stored in the OS credential store.
+const SERVICE: &str = "rkui";
+
+fn entry(profile: &str, field: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, &format!("profile:{profile}:{field}")).map_err(|e| format!("Failed to open keychain entry: {e}"))
+}
+
+/// Store a profile secret (e.g. "sasl_jaas_config") in the OS keychain.
+pub fn store(profile: &str, field: &str, value: &str) -> Result<(), String> {
+    entry(profile, field)?
+        .set_password(value)
+        .map_err(|e| format!("Failed to store secret in keychain: {e}"))
+}
+
+/// Read a profile secret; None when the keychain has no entry for it.
+pub fn fetch(profile: &str, field: &str) -> Result<Option<String>, String> {
+    match entry(profile, field)?.get_password() {
+        Ok(v) => Ok(Some(v)),
+        Err(KeyringError::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read secret from keychain: {e}")),
+    }
+}
+
+/// Remove a profile secret; missing entries are not an error.
+pub fn remove(profile: &str, field: &str) -> Result<(), String> {
+    match entry(profile, field)?.delete_credential() {
+        Ok(()) | Err(KeyringError::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete secret from keychain: {e}")),
+    }
+}
@@ -0,0 +1,150 @@
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::time::{Duration, Instant};
+
+use openssl::asn1::Asn1Time;
+use openssl::ec::{EcGroup, EcKey};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkcs12::Pkcs12;
+use openssl::pkey::PKey;
+use openssl::x509::{X509NameBuilder, X509};
+use serde::Serialize;
+
+use crate::kafka::{Kafka, KafkaConfig};
+use crate::response::{CommandResult, Envelope};
+use crate::utils::{link_file_descriptors, run_protoc_and_read_descriptor_set};
+
+const SAMPLE_PROTO: &str = r#"syntax = "proto3";
+package rkui.selfcheck;
+import "google/protobuf/timestamp.proto";
+message Probe {
+  string id = 1;
+  google.protobuf.Timestamp at = 2;
+}
+"#;
+
+/// Result of one self-check item.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckItem {
+    /// "proto_parser" | "openssl_pkcs12" | "temp_dir" | "broker"
+    pub name: String,
+    /// "ok" | "failed" | "skipped"
+    pub status: String,
+    pub detail: Option<String>,
+    #[serde(rename = "duration_ms")]
+    pub duration_ms: u64,
+}
+
+/// Checklist shown on first launch; `ok` is false when any item failed (skipped items don't count).
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfCheck {
+    pub ok: bool,
+    pub checks: Vec<CheckItem>,
+}
+
+fn run(name: &str, check: impl FnOnce() -> anyhow::Result<Option<String>>) -> CheckItem {
+    let started = Instant::now();
+    let (status, detail) = match check() {
+        Ok(detail) => ("ok", detail),
+        Err(e) => ("failed", Some(e.to_string())),
+    };
+    CheckItem {
+        name: name.to_string(),
+        status: status.to_string(),
+        detail,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// Parse and link a small .proto with the built-in (protoc-less) parser, including a well-known import.
+fn check_proto_parser() -> anyhow::Result<Option<String>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("rkui_selfcheck.proto");
+    std::fs::write(&path, SAMPLE_PROTO)?;
+    let set = run_protoc_and_read_descriptor_set(&[path.to_string_lossy().to_string()]).map_err(anyhow::Error::msg)?;
+    let files = link_file_descriptors(&set).map_err(anyhow::Error::msg)?;
+    let found = files
+        .iter()
+        .any(|f| f.message_by_package_relative_name("Probe").is_some());
+    if !found {
+        return Err(anyhow::anyhow!("Parsed schema is missing message rkui.selfcheck.Probe"));
+    }
+    Ok(None)
+}
+
+/// Build a PKCS#12 archive from a throwaway key and certificate and read it back, like keystores are.
+fn check_openssl_pkcs12() -> anyhow::Result<Option<String>> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+    let key = PKey::from_ec_key(EcKey::generate(&group)?)?;
+    let mut name = X509NameBuilder::new()?;
+    name.append_entry_by_text("CN", "rkui-self-check")?;
+    let name = name.build();
+    let mut cert = X509::builder()?;
+    cert.set_version(2)?;
+    cert.set_subject_name(&name)?;
+    cert.set_issuer_name(&name)?;
+    cert.set_pubkey(&key)?;
+    cert.set_not_before(Asn1Time::days_from_now(0)?.as_ref())?;
+    cert.set_not_after(Asn1Time::days_from_now(1)?.as_ref())?;
+    cert.sign(&key, MessageDigest::sha256())?;
+    let cert = cert.build();
+
+    let der = Pkcs12::builder().name("rkui").pkey(&key).cert(&cert).build2("changeit")?.to_der()?;
+    let parsed = Pkcs12::from_der(&der)?.parse2("changeit")?;
+    if parsed.cert.is_none() || parsed.pkey.is_none() {
+        return Err(anyhow::anyhow!("PKCS#12 round trip lost the certificate or key"));
+    }
+    Ok(Some(openssl::version::version().to_string()))
+}
+
+/// Keystore conversions and exports write temporary files.
+fn check_temp_dir() -> anyhow::Result<Option<String>> {
+    let dir = std::env::temp_dir();
+    let mut file = tempfile::tempfile_in(&dir)
+        .map_err(|e| anyhow::anyhow!("Cannot write to {}: {}", dir.display(), e))?;
+    file.write_all(b"rkui")?;
+    file.seek(SeekFrom::Start(0))?;
+    let mut back = String::new();
+    file.read_to_string(&mut back)?;
+    if back != "rkui" {
+        return Err(anyhow::anyhow!("Read back unexpected data from {}", dir.display()));
+    }
+    Ok(Some(dir.display().to_string()))
+}
+
+fn check_broker(config: &KafkaConfig) -> anyhow::Result<Option<String>> {
+    let test = Kafka::test_connection(config, Duration::from_secs(5));
+    if !test.ok {
+        let kind = test.error_kind.unwrap_or_else(|| "other".into());
+        return Err(anyhow::anyhow!("{} ({})", test.error.unwrap_or_default(), kind));
+    }
+    Ok(Some(format!("{} brokers, {} topics in {} ms", test.broker_count, test.topic_count, test.latency_ms)))
+}
+
+/// Run all checks; the broker is only contacted when a connection is given.
+pub fn self_check(config: Option<&KafkaConfig>) -> SelfCheck {
+    let mut checks = vec![
+        run("proto_parser", check_proto_parser),
+        run("openssl_pkcs12", check_openssl_pkcs12),
+        run("temp_dir", check_temp_dir),
+    ];
+    checks.push(match config.filter(|c| !c.broker.trim().is_empty()) {
+        Some(c) => run("broker", || check_broker(c)),
+        None => CheckItem {
+            name: "broker".into(),
+            status: "skipped".into(),
+            detail: Some("No connection configured".into()),
+            duration_ms: 0,
+        },
+    });
+    let ok = checks.iter().all(|c| c.status != "failed");
+    SelfCheck { ok, checks }
+}
+
+/// Verify the local setup (proto parsing, OpenSSL, temp files) and optionally broker reachability.
+#[tauri::command]
+pub async fn run_self_check(config: Option<KafkaConfig>) -> CommandResult<SelfCheck> {
+    tokio::task::spawn_blocking(move || self_check(config.as_ref()))
+        .await
+        .map_err(|e| Envelope::failed("run_self_check", e))
+}
@@ -0,0 +1,127 @@
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::kafka::{DecoderSettings, KafkaConfig};
+use crate::response::{CommandResult, Envelope};
+use crate::topic_prefs::prefs_dir;
+use crate::utils::json_store::JsonStore;
+
+static STORE: JsonStore = JsonStore::new("topic_decoders.json", "topic decoders");
+
+/// Decoder configuration remembered for a topic, or for every topic whose name matches a regex.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicDecoderMapping {
+    /// Topic name, or a regex matched against the whole name when `regex` is set
+    pub pattern: String,
+    #[serde(default)]
+    pub regex: bool,
+    /// Message type, proto message and key type to apply when the topic is opened
+    pub decoder: DecoderSettings,
+    #[serde(default, rename = "updated_at", alias = "updatedAt")]
+    pub updated_at: String,
+}
+
+impl TopicDecoderMapping {
+    pub fn matches(&self, topic: &str) -> bool {
+        if self.regex {
+            compile(&self.pattern).is_ok_and(|re| re.is_match(topic))
+        } else {
+            self.pattern == topic
+        }
+    }
+}
+
+fn compile(pattern: &str) -> Result<Regex, String> {
+    Regex::new(&format!("^(?:{pattern})$")).map_err(|e| format!("Invalid topic pattern '{pattern}': {e}"))
+}
+
+/// Save a mapping, replacing the one with the same pattern.
+pub fn save(dir: &Path, mut mapping: TopicDecoderMapping) -> Result<TopicDecoderMapping, String> {
+    mapping.pattern = mapping.pattern.trim().to_string();
+    if mapping.pattern.is_empty() {
+        return Err("Topic decoder needs a topic name or pattern".into());
+    }
+    if mapping.regex {
+        compile(&mapping.pattern)?;
+    }
+    mapping.updated_at = chrono::Utc::now().to_rfc3339();
+    STORE.update(dir, |all: &mut Vec<TopicDecoderMapping>| {
+        match all.iter_mut().find(|m| m.pattern == mapping.pattern && m.regex == mapping.regex) {
+            Some(existing) => *existing = mapping.clone(),
+            None => all.push(mapping.clone()),
+        }
+        Ok(mapping)
+    })
+}
+
+/// Mappings in the order they were first saved.
+pub fn list(dir: &Path) -> Result<Vec<TopicDecoderMapping>, String> {
+    STORE.read(dir)
+}
+
+pub fn delete(dir: &Path, pattern: &str, regex: bool) -> Result<(), String> {
+    STORE.update(dir, |all: &mut Vec<TopicDecoderMapping>| {
+        let before = all.len();
+        all.retain(|m| !(m.pattern == pattern && m.regex == regex));
+        if all.len() == before {
+            return Err(format!("Topic decoder '{}' not found", pattern));
+        }
+        Ok(())
+    })
+}
+
+/// The mapping for a topic: its exact name wins, then the first matching regex.
+pub fn resolve(dir: &Path, topic: &str) -> Result<Option<TopicDecoderMapping>, String> {
+    let all: Vec<TopicDecoderMapping> = STORE.read(dir)?;
+    let exact = all.iter().find(|m| !m.regex && m.pattern == topic);
+    Ok(exact.or_else(|| all.iter().find(|m| m.matches(topic))).cloned())
+}
+
+/// The session configuration with the decoder settings remembered for its topic applied.
+pub fn apply(dir: &Path, config: KafkaConfig) -> Result<KafkaConfig, String> {
+    if config.topic.is_empty() {
+        return Ok(config);
+    }
+    let Some(mapping) = resolve(dir, &config.topic)? else { return Ok(config) };
+    // `DecoderSettings::apply` turns lazy decoding off for re-decoding; a session keeps its own choice
+    let mut out = mapping.decoder.apply(&config);
+    out.lazy_decode = config.lazy_decode;
+    Ok(out)
+}
+
+/// `apply` for a connection being opened; the configuration is used as given when the mappings cannot be read.
+pub(crate) fn apply_for_session(app: &AppHandle, config: KafkaConfig) -> KafkaConfig {
+    match prefs_dir(app).and_then(|dir| apply(&dir, config.clone())) {
+        Ok(out) => out,
+        Err(e) => {
+            log::warn!("Topic decoder not applied: {e}");
+            config
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn save_topic_decoder(app: AppHandle, mapping: TopicDecoderMapping) -> CommandResult<TopicDecoderMapping> {
+    prefs_dir(&app).and_then(|dir| save(&dir, mapping)).map_err(|e| Envelope::failed("save_topic_decoder", e))
+}
+
+#[tauri::command]
+pub async fn list_topic_decoders(app: AppHandle) -> CommandResult<Vec<TopicDecoderMapping>> {
+    prefs_dir(&app).and_then(|dir| list(&dir)).map_err(|e| Envelope::failed("list_topic_decoders", e))
+}
+
+#[tauri::command]
+pub async fn delete_topic_decoder(app: AppHandle, pattern: String, regex: Option<bool>) -> CommandResult<()> {
+    prefs_dir(&app)
+        .and_then(|dir| delete(&dir, &pattern, regex.unwrap_or(false)))
+        .map_err(|e| Envelope::failed("delete_topic_decoder", e))
+}
+
+/// Decoder settings remembered for a topic, to apply with `redecode_messages` or when starting a session.
+#[tauri::command]
+pub async fn resolve_topic_decoder(app: AppHandle, topic: String) -> CommandResult<Option<TopicDecoderMapping>> {
+    prefs_dir(&app).and_then(|dir| resolve(&dir, &topic)).map_err(|e| Envelope::failed("resolve_topic_decoder", e))
+}
@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::response::{CommandResult, Envelope};
+use crate::utils::json_store::JsonStore;
+
+static STORE: JsonStore = JsonStore::new("topic_prefs.json", "topic preferences");
+/// How many recently opened topics are remembered per connection.
+const MAX_RECENT: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentTopic {
+    pub topic: String,
+    #[serde(rename = "last_used", alias = "lastUsed")]
+    pub last_used: String,
+}
+
+/// Favorites and recent topics of one connection (keyed by broker list).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TopicPrefs {
+    #[serde(default)]
+    pub favorites: Vec<String>,
+    /// Most recent first
+    #[serde(default)]
+    pub recent: Vec<RecentTopic>,
+}
+
+impl TopicPrefs {
+    /// Order topic names as favorites, then recent, then the rest alphabetically.
+    pub fn rank(&self, topics: &[String]) -> Vec<String> {
+        let mut out: Vec<String> = Vec::with_capacity(topics.len());
+        let present = |t: &String| topics.contains(t);
+        for t in self.favorites.iter().filter(|t| present(t)) {
+            out.push(t.clone());
+        }
+        for r in self.recent.iter().filter(|r| present(&r.topic)) {
+            if !out.contains(&r.topic) {
+                out.push(r.topic.clone());
+            }
+        }
+        let mut rest: Vec<String> = topics.iter().filter(|t| !out.contains(t)).cloned().collect();
+        rest.sort();
+        out.extend(rest);
+        out
+    }
+}
+
+/// Apply a change to one connection's preferences and persist it.
+fn update(dir: &Path, connection: &str, f: impl FnOnce(&mut TopicPrefs)) -> Result<TopicPrefs, String> {
+    STORE.update(dir, |all: &mut BTreeMap<String, TopicPrefs>| {
+        let prefs = all.entry(connection.to_string()).or_default();
+        f(prefs);
+        Ok(prefs.clone())
+    })
+}
+
+pub fn get(dir: &Path, connection: &str) -> Result<TopicPrefs, String> {
+    Ok(STORE.read::<BTreeMap<String, TopicPrefs>>(dir)?.remove(connection).unwrap_or_default())
+}
+
+pub fn set_favorite(dir: &Path, connection: &str, topic: &str, favorite: bool) -> Result<TopicPrefs, String> {
+    update(dir, connection, |p| {
+        p.favorites.retain(|t| t != topic);
+        if favorite {
+            p.favorites.push(topic.to_string());
+        }
+    })
+}
+
+/// Move a topic to the front of the recent list.
+pub fn touch_recent(dir: &Path, connection: &str, topic: &str) -> Result<TopicPrefs, String> {
+    update(dir, connection, |p| {
+        p.recent.retain(|r| r.topic != topic);
+        p.recent.insert(0, RecentTopic { topic: topic.to_string(), last_used: chrono::Utc::now().to_rfc3339() });
+        p.recent.truncate(MAX_RECENT);
+    })
+}
+
+pub fn clear_recent(dir: &Path, connection: &str) -> Result<TopicPrefs, String> {
+    update(dir, connection, |p| p.recent.clear())
+}
+
+/// App data directory holding the JSON preference files.
+pub(crate) fn prefs_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))
+}
+
+/// Favorites and recent topics for a connection (broker list).
+#[tauri::command]
+pub async fn get_topic_prefs(app: AppHandle, connection: String) -> CommandResult<TopicPrefs> {
+    prefs_dir(&app).and_then(|dir| get(&dir, &connection)).map_err(|e| Envelope::failed("load_topic_prefs", e))
+}
+
+#[tauri::command]
+pub async fn set_favorite_topic(app: AppHandle, connection: String, topic: String, favorite: bool) -> CommandResult<TopicPrefs> {
+    prefs_dir(&app)
+        .and_then(|dir| set_favorite(&dir, &connection, &topic, favorite))
+        .map_err(|e| Envelope::failed("save_topic_prefs", e))
+}
+
+#[tauri::command]
+pub async fn record_recent_topic(app: AppHandle, connection: String, topic: String) -> CommandResult<TopicPrefs> {
+    prefs_dir(&app).and_then(|dir| touch_recent(&dir, &connection, &topic)).map_err(|e| Envelope::failed("save_topic_prefs", e))
+}
+
+#[tauri::command]
+pub async fn clear_recent_topics(app: AppHandle, connection: String) -> CommandResult<TopicPrefs> {
+    prefs_dir(&app).and_then(|dir| clear_recent(&dir, &connection)).map_err(|e| Envelope::failed("save_topic_prefs", e))
+}
+
+/// Topic names of a connection ranked favorites first, then recent, then alphabetically.
+#[tauri::command]
+pub async fn rank_topics(app: AppHandle, connection: String, topics: Vec<String>) -> CommandResult<Vec<String>> {
+    let prefs = prefs_dir(&app).and_then(|dir| get(&dir, &connection)).map_err(|e| Envelope::failed("load_topic_prefs", e))?;
+    Ok(prefs.rank(&topics))
+}
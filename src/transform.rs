@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+
+use crate::kafka::UiMessage;
+
+/// Operations a script may run per record before it is aborted.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// User script (Rhai) run on every decoded record of a scan, compiled once per scan.
+///
+/// The script sees `key`, `message`, `partition`, `offset`, `timestamp` and `headers`.
+/// `message` is the parsed JSON payload (a map) or the raw text when the payload is not JSON.
+/// Changes to `key` and `message` are written back; evaluating to `false` drops the record.
+#[derive(Clone)]
+pub struct TransformScript {
+    engine: Arc<Engine>,
+    ast: Arc<AST>,
+}
+
+impl TransformScript {
+    pub fn compile(source: &str) -> Result<Self, String> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_expr_depths(64, 32);
+        engine.disable_symbol("eval");
+        let ast = engine.compile(source).map_err(|e| format!("Failed to compile transform script: {e}"))?;
+        Ok(Self { engine: Arc::new(engine), ast: Arc::new(ast) })
+    }
+
+    /// Compile the script when one is given (blank scripts are ignored).
+    pub fn from_option(source: Option<&str>) -> Result<Option<Self>, String> {
+        source.filter(|s| !s.trim().is_empty()).map(Self::compile).transpose()
+    }
+
+    /// Run the script on a record. Returns false when the script rejects it.
+    /// Script errors keep the record unchanged and are reported in `decoding_error`.
+    pub fn apply(&self, ui: &mut UiMessage) -> bool {
+        match self.run(ui) {
+            Ok(keep) => keep,
+            Err(e) => {
+                let e = format!("Transform script error: {e}");
+                ui.decoding_error = Some(match ui.decoding_error.take() {
+                    Some(prev) => format!("{prev}; {e}"),
+                    None => e,
+                });
+                true
+            }
+        }
+    }
+
+    fn run(&self, ui: &mut UiMessage) -> Result<bool, String> {
+        let json = serde_json::from_str::<serde_json::Value>(&ui.message).ok();
+        let message = match &json {
+            Some(v) => rhai::serde::to_dynamic(v).map_err(|e| e.to_string())?,
+            None => Dynamic::from(ui.message.clone()),
+        };
+        let headers: Map = ui.headers.iter().map(|(k, v)| (k.as_str().into(), Dynamic::from(v.clone()))).collect();
+
+        let mut scope = Scope::new();
+        scope.push("key", ui.key.clone());
+        scope.push_dynamic("message", message);
+        scope.push("partition", ui.partition as i64);
+        scope.push("offset", ui.offset);
+        scope.push("timestamp", ui.timestamp.clone());
+        scope.push("headers", headers);
+        let result: Dynamic = self.engine.eval_ast_with_scope(&mut scope, &self.ast).map_err(|e| e.to_string())?;
+        if matches!(result.as_bool(), Ok(false)) {
+            return Ok(false);
+        }
+
+        if let Some(key) = scope.get_value::<Dynamic>("key") {
+            ui.key = if key.is_string() { key.into_string().unwrap_or_default() } else { key.to_string() };
+        }
+        if let Some(message) = scope.get_value::<Dynamic>("message") {
+            // Text payloads that stay text are kept verbatim; everything else is rendered as JSON
+            if json.is_none() && message.is_string() {
+                ui.message = message.into_string().unwrap_or_default();
+            } else {
+                let value: serde_json::Value = rhai::serde::from_dynamic(&message).map_err(|e| e.to_string())?;
+                // Untouched payloads keep their original formatting
+                if json.as_ref() != Some(&value) {
+                    ui.message = value.to_string();
+                }
+            }
+        }
+        Ok(true)
+    }
+}
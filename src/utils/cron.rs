@@ -0,0 +1,114 @@
+use chrono::{Datelike, Duration, NaiveDateTime, Timelike};
+
+/// Five-field cron schedule: minute hour day-of-month month day-of-week.
+/// Fields accept `*`, numbers, ranges `a-b`, lists `a,b` and steps `*/n` / `a-b/n`;
+/// day-of-week is 0-6 with 7 also meaning Sunday. `@hourly`, `@daily`, `@weekly`,
+/// `@monthly` and `@yearly` shortcuts are supported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    // Standard cron: when both day fields are restricted, either one matching is enough
+    days_any: bool,
+    weekdays_any: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("Invalid cron expression '{expr}': expected 5 fields"));
+        }
+        let mut weekdays = parse_field(fields[4], 0, 7)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            weekdays,
+            days_any: fields[2] == "*",
+            weekdays_any: fields[4] == "*",
+        })
+    }
+
+    /// True when the schedule fires in the minute of `t`.
+    pub fn matches(&self, t: &NaiveDateTime) -> bool {
+        let bit = |mask: u64, v: u32| mask & (1 << v) != 0;
+        if !bit(self.minutes, t.minute()) || !bit(self.hours, t.hour()) || !bit(self.months, t.month()) {
+            return false;
+        }
+        let day = bit(self.days, t.day());
+        let weekday = bit(self.weekdays, t.weekday().num_days_from_sunday());
+        match (self.days_any, self.weekdays_any) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    /// First firing minute strictly after `t`, searching at most a year ahead.
+    pub fn next_after(&self, t: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut cur = t.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = cur + Duration::days(366);
+        while cur <= limit {
+            if self.matches(&cur) {
+                return Some(cur);
+            }
+            cur += Duration::minutes(1);
+        }
+        None
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => {
+                let step: u32 = s.parse().map_err(|_| format!("Invalid cron step '{s}'"))?;
+                if step == 0 {
+                    return Err(format!("Invalid cron step '{s}'"));
+                }
+                (r, step)
+            }
+            None => (part, 1),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_value(a, min, max)?, parse_value(b, min, max)?)
+        } else {
+            let v = parse_value(range, min, max)?;
+            // `5/15` means every 15 starting at 5
+            (v, if step > 1 { max } else { v })
+        };
+        if lo > hi {
+            return Err(format!("Invalid cron range '{range}'"));
+        }
+        for v in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(s: &str, min: u32, max: u32) -> Result<u32, String> {
+    let v: u32 = s.parse().map_err(|_| format!("Invalid cron value '{s}'"))?;
+    if v < min || v > max {
+        return Err(format!("Cron value {v} out of range {min}-{max}"));
+    }
+    Ok(v)
+}
@@ -0,0 +1,76 @@
+use jaq_core::load::{self, Arena, File, Loader};
+use jaq_core::{Compiler, Ctx, Filter, Native, RcIter};
+use jaq_json::Val;
+
+/// Outputs of a program taken per input; generators such as `range(1e12)` stop there instead of hanging a load.
+pub const MAX_OUTPUTS: usize = 1000;
+
+/// A jq program compiled once and run against many JSON values.
+pub struct JqFilter {
+    filter: Filter<Native<Val>>,
+}
+
+fn load_error(code: &str, error: load::Error<&str>) -> String {
+    let at = |part: &str| load::span(code, part).start;
+    match error {
+        load::Error::Io(errs) => errs.into_iter().map(|(path, e)| format!("cannot load {path}: {e}")).collect::<Vec<_>>().join("; "),
+        load::Error::Lex(errs) => errs
+            .into_iter()
+            .map(|(expect, part)| format!("expected {} at {}", expect.as_str(), at(part)))
+            .collect::<Vec<_>>()
+            .join("; "),
+        load::Error::Parse(errs) => errs
+            .into_iter()
+            .map(|(expect, part)| match part {
+                "" => format!("expected {} at end of input", expect.as_str()),
+                found => format!("expected {}, found '{found}' at {}", expect.as_str(), at(found)),
+            })
+            .collect::<Vec<_>>()
+            .join("; "),
+    }
+}
+
+impl JqFilter {
+    /// Parse and compile a jq program with the standard library (select, test, ascii_downcase, map, ...).
+    pub fn compile(code: &str) -> Result<Self, String> {
+        let arena = Arena::default();
+        let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+        let modules = loader
+            .load(&arena, File { code, path: () })
+            .map_err(|errs| errs.into_iter().map(|(_, e)| load_error(code, e)).collect::<Vec<_>>().join("; "))?;
+        let filter = Compiler::default()
+            .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+            .compile(modules)
+            .map_err(|errs| {
+                errs.into_iter()
+                    .flat_map(|(_, es)| es)
+                    .map(|(name, undefined)| format!("undefined {} '{name}'", undefined.as_str()))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            })?;
+        Ok(Self { filter })
+    }
+
+    /// All outputs of the program for `input`; the first runtime error aborts, as do more than `MAX_OUTPUTS` outputs.
+    pub fn run(&self, input: &serde_json::Value) -> Result<Vec<serde_json::Value>, String> {
+        let inputs = RcIter::new(core::iter::empty());
+        let out: Vec<serde_json::Value> = self
+            .filter
+            .run((Ctx::new([], &inputs), Val::from(input.clone())))
+            .take(MAX_OUTPUTS + 1)
+            .map(|out| out.map(serde_json::Value::from).map_err(|e| e.to_string()))
+            .collect::<Result<_, _>>()?;
+        if out.len() > MAX_OUTPUTS {
+            return Err(format!("jq program yields more than {MAX_OUTPUTS} outputs"));
+        }
+        Ok(out)
+    }
+
+    /// jq truthiness: true when one of the first `MAX_OUTPUTS` outputs is neither null nor false, before any
+    /// runtime error. `select(...)` and plain comparisons both work as filters this way.
+    pub fn matches(&self, input: &serde_json::Value) -> bool {
+        let inputs = RcIter::new(core::iter::empty());
+        let mut out = self.filter.run((Ctx::new([], &inputs), Val::from(input.clone())));
+        out.by_ref().take(MAX_OUTPUTS).map_while(Result::ok).any(|v| !matches!(v, Val::Null | Val::Bool(false)))
+    }
+}
@@ -0,0 +1,92 @@
+/// Resolve a simple jq-style path (`.a.b[0].c`) against a JSON value.
+/// A JSONPath-style root (`$.a.b[0]`) is accepted as well.
+pub fn json_path_get(root: &serde_json::Value, path: &str) -> Option<serde_json::Value> {
+    let path = match path.trim().strip_prefix('$') {
+        Some("") => return Some(root.clone()),
+        Some(rest) => rest,
+        None => path.trim(),
+    };
+    if !path.starts_with('.') { return None; }
+    let mut cur = root;
+    let mut idx = 1usize; // skip leading '.'
+    while idx < path.len() {
+        // parse key up to next '.' or '['
+        let bytes = path.as_bytes();
+        let mut j = idx;
+        while j < bytes.len() && bytes[j] != b'.' && bytes[j] != b'[' { j += 1; }
+        if j > idx {
+            let key = &path[idx..j];
+            cur = cur.get(key)?;
+        }
+        idx = j;
+        if idx >= bytes.len() { break; }
+        if bytes[idx] == b'.' { idx += 1; continue; }
+        // handle [n]
+        if bytes[idx] == b'[' {
+            idx += 1;
+            // read number
+            let mut k = idx;
+            while k < bytes.len() && bytes[k].is_ascii_digit() { k += 1; }
+            if k == idx { return None; }
+            let n: usize = path[idx..k].parse().ok()?;
+            if k >= bytes.len() || bytes[k] != b']' { return None; }
+            cur = cur.get(n)?;
+            idx = k + 1;
+            if idx < bytes.len() && bytes[idx] == b'.' { idx += 1; }
+            continue;
+        }
+    }
+    Some(cur.clone())
+}
+
+enum PathSegment {
+    Key(String),
+    Index(usize),
+    /// `[*]`: every element of an array
+    Each,
+}
+
+fn parse_path(path: &str) -> Option<Vec<PathSegment>> {
+    let path = match path.trim().strip_prefix('$') {
+        Some(rest) => rest,
+        None => path.trim(),
+    };
+    let mut rest = path.strip_prefix('.').or_else(|| path.starts_with('[').then_some(path))?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let (inner, tail) = after.split_once(']')?;
+            segments.push(if inner == "*" { PathSegment::Each } else { PathSegment::Index(inner.parse().ok()?) });
+            rest = tail.strip_prefix('.').unwrap_or(tail);
+            continue;
+        }
+        let end = rest.find(['.', '[']).unwrap_or(rest.len());
+        segments.push(PathSegment::Key(rest[..end].to_string()));
+        rest = &rest[end..];
+        rest = rest.strip_prefix('.').unwrap_or(rest);
+    }
+    Some(segments)
+}
+
+/// Apply `f` to every value addressed by a jq-style path; `[*]` walks all array elements.
+/// Returns how many values were visited (0 for invalid paths).
+pub fn json_path_for_each_mut(root: &mut serde_json::Value, path: &str, f: &mut dyn FnMut(&mut serde_json::Value)) -> usize {
+    fn walk(cur: &mut serde_json::Value, segments: &[PathSegment], f: &mut dyn FnMut(&mut serde_json::Value)) -> usize {
+        let Some((first, rest)) = segments.split_first() else {
+            f(cur);
+            return 1;
+        };
+        match first {
+            PathSegment::Key(k) => cur.get_mut(k.as_str()).map_or(0, |v| walk(v, rest, f)),
+            PathSegment::Index(i) => cur.get_mut(*i).map_or(0, |v| walk(v, rest, f)),
+            PathSegment::Each => match cur.as_array_mut() {
+                Some(items) => items.iter_mut().map(|v| walk(v, rest, f)).sum(),
+                None => 0,
+            },
+        }
+    }
+    match parse_path(path) {
+        Some(segments) => walk(root, &segments, f),
+        None => 0,
+    }
+}
@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// A JSON file in the app data directory, replaced atomically on write. `update` holds the store's lock
+/// across its read-modify-write, so concurrent commands never lose each other's changes.
+pub struct JsonStore {
+    file: &'static str,
+    /// What the file holds, for error messages
+    what: &'static str,
+    lock: Mutex<()>,
+}
+
+impl JsonStore {
+    pub const fn new(file: &'static str, what: &'static str) -> Self {
+        Self { file, what, lock: Mutex::new(()) }
+    }
+
+    /// The stored value; the default while the file does not exist.
+    pub fn read<T: DeserializeOwned + Default>(&self, dir: &Path) -> Result<T, String> {
+        let path = dir.join(self.file);
+        if !path.exists() {
+            return Ok(T::default());
+        }
+        let data = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", self.what))?;
+        serde_json::from_str(&data).map_err(|e| format!("Failed to parse {}: {e}", self.what))
+    }
+
+    fn write<T: Serialize>(&self, dir: &Path, value: &T) -> Result<(), String> {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create app data directory: {e}"))?;
+        let data = serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize {}: {e}", self.what))?;
+        let tmp = dir.join(format!("{}.tmp", self.file));
+        fs::write(&tmp, data).map_err(|e| format!("Failed to write {}: {e}", self.what))?;
+        fs::rename(&tmp, dir.join(self.file)).map_err(|e| format!("Failed to write {}: {e}", self.what))
+    }
+
+    /// Read, change and write back the stored value; nothing is written when `f` fails.
+    pub fn update<T, R>(&self, dir: &Path, f: impl FnOnce(&mut T) -> Result<R, String>) -> Result<R, String>
+    where
+        T: Serialize + DeserializeOwned + Default,
+    {
+        let _guard = self.lock.lock().map_err(|e| format!("Failed to lock {}: {e}", self.what))?;
+        let mut value = self.read(dir)?;
+        let out = f(&mut value)?;
+        self.write(dir, &value)?;
+        Ok(out)
+    }
+}
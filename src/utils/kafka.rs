@@ -1,7 +1,7 @@
 use rdkafka::config::ClientConfig;
 use std::io::Write;
 
-use crate::kafka::security::{jks_truststore_to_pem, parse_username_password_from_jaas, detect_keystore_kind, KeyStoreKind, pkcs12_to_pem};
+use crate::kafka::security::{jks_truststore_to_pem, keystore_to_pem, parse_username_password_from_jaas, detect_keystore_kind, KeyStoreKind, pkcs12_to_pem};
 use crate::kafka::types::KafkaConfig;
 
 /// Apply full security configuration (security.protocol + SSL/SASL specifics) based on KafkaConfig.
@@ -21,12 +21,12 @@ pub fn configure_security(cc: &mut ClientConfig, config: &KafkaConfig) -> anyhow
         }
         "sasl_plaintext" => {
             cc.set("security.protocol", "sasl_plaintext");
-            configure_sasl(cc, config);
+            configure_sasl(cc, config)?;
         }
         "sasl_ssl" => {
             cc.set("security.protocol", "sasl_ssl");
             configure_ssl(cc, config)?;
-            configure_sasl(cc, config);
+            configure_sasl(cc, config)?;
         }
         _ => {
             // plaintext (default): no extra settings
@@ -47,6 +47,17 @@ pub fn is_likely_ca_path(p: &str) -> bool {
     lower.ends_with(".pem") || lower.ends_with(".crt") || lower.ends_with(".cer") || lower.ends_with(".bundle")
 }
 
+/// Set the connection's raw librdkafka properties (e.g. `broker.address.family`, `debug`). Applied after
+/// everything else, so they override what rkui sets itself.
+pub fn apply_custom_properties(cc: &mut ClientConfig, config: &KafkaConfig) {
+    for (key, value) in config.custom_properties.iter().flatten() {
+        let key = key.trim();
+        if !key.is_empty() {
+            cc.set(key, value);
+        }
+    }
+}
+
 /// Configure SSL-related options on the given ClientConfig based on KafkaConfig (truststore/keystore handling).
 /// This does not set the security.protocol itself; callers should set it to `ssl` or `sasl_ssl` beforehand.
 pub fn configure_ssl(cc: &mut ClientConfig, config: &KafkaConfig) -> anyhow::Result<()> {
@@ -113,7 +124,7 @@ pub fn configure_ssl(cc: &mut ClientConfig, config: &KafkaConfig) -> anyhow::Res
                     }
                     KeyStoreKind::Unknown => {
                         if is_likely_ca_path(path) { cc.set("ssl.ca.location", path); }
-                        else { eprintln!("[rkui] Provided Truststore Location '{}' is of unknown format; skipping ssl.ca.location.", path); }
+                        else { log::warn!("Provided Truststore Location '{}' is of unknown format; skipping ssl.ca.location.", path); }
                     }
                 }
             }
@@ -157,18 +168,83 @@ pub fn configure_ssl(cc: &mut ClientConfig, config: &KafkaConfig) -> anyhow::Res
                         if is_likely_ca_path(path) {
                             cc.set("ssl.ca.location", path);
                         } else {
-                            eprintln!("[rkui] Provided Truststore Location '{}' is of unknown format; skipping ssl.ca.location.", path);
+                            log::warn!("Provided Truststore Location '{}' is of unknown format; skipping ssl.ca.location.", path);
                         }
                     }
                 }
             }
         }
     }
+
+    // Broker hostname check against its certificate; off for clusters reached through port-forwards
+    if let Some(verify) = config.ssl_endpoint_identification {
+        cc.set("ssl.endpoint.identification.algorithm", if verify { "https" } else { "none" });
+    }
+
+    // Client identity from a Java keystore (mutual TLS), unless PEM files were given
+    if config.ssl_certificate.is_none() {
+        if let Some(path) = config.keystore_location.as_deref().filter(|p| !p.trim().is_empty()) {
+            let (cert, key) = keystore_to_pem(path, config.keystore_password.as_deref(), config.ssl_key_password.as_deref())
+                .map_err(|e| anyhow::anyhow!("Failed to extract the client certificate from keystore: {}", e))?;
+            cc.set("ssl.certificate.pem", cert);
+            cc.set("ssl.key.pem", key);
+        }
+    }
+    Ok(())
+}
+
+/// Features librdkafka was built with (`builtin.features`, e.g. "ssl", "sasl_gssapi", "zstd").
+/// Read through librdkafka directly: `NativeClientConfig::get` panics on this flags property.
+pub fn librdkafka_features() -> Vec<String> {
+    use rdkafka::bindings as rd;
+    use std::ffi::CStr;
+
+    let key = c"builtin.features";
+    // SAFETY: the config handle is destroyed before returning; the buffer is one byte longer than
+    // librdkafka asks for, so the value read from it is always NUL-terminated.
+    let value = unsafe {
+        let conf = rd::rd_kafka_conf_new();
+        let mut size = 0usize;
+        let mut value = None;
+        if rd::rd_kafka_conf_get(conf, key.as_ptr(), std::ptr::null_mut(), &mut size) == rd::rd_kafka_conf_res_t::RD_KAFKA_CONF_OK {
+            let mut buf = vec![0 as std::os::raw::c_char; size + 1];
+            let mut len = size;
+            if rd::rd_kafka_conf_get(conf, key.as_ptr(), buf.as_mut_ptr(), &mut len) == rd::rd_kafka_conf_res_t::RD_KAFKA_CONF_OK {
+                value = Some(CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned());
+            }
+        }
+        rd::rd_kafka_conf_destroy(conf);
+        value
+    };
+    value
+        .map(|f| f.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+/// Kerberos settings: service name, and the keytab and principal librdkafka's kinit runs with.
+fn configure_kerberos(cc: &mut ClientConfig, config: &KafkaConfig) -> anyhow::Result<()> {
+    if !librdkafka_features().iter().any(|f| f == "sasl_gssapi") {
+        return Err(anyhow::anyhow!(
+            "SASL mechanism GSSAPI (Kerberos) is not available: librdkafka was built without the sasl_gssapi \
+             feature. Rebuild with `--features with-sasl` (requires Cyrus SASL and the Kerberos libraries)."
+        ));
+    }
+    let set = |v: &Option<String>| v.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+    cc.set("sasl.kerberos.service.name", set(&config.sasl_kerberos_service_name).unwrap_or_else(|| "kafka".into()));
+    if let Some(keytab) = set(&config.sasl_kerberos_keytab) {
+        if !std::path::Path::new(&keytab).exists() {
+            return Err(anyhow::anyhow!("Kerberos keytab not found: {}", keytab));
+        }
+        cc.set("sasl.kerberos.keytab", keytab);
+    }
+    if let Some(principal) = set(&config.sasl_kerberos_principal) {
+        cc.set("sasl.kerberos.principal", principal);
+    }
     Ok(())
 }
 
 /// Configure SASL-related options (mechanism and credentials parsed from JAAS string if provided).
-pub fn configure_sasl(cc: &mut ClientConfig, config: &KafkaConfig) {
+pub fn configure_sasl(cc: &mut ClientConfig, config: &KafkaConfig) -> anyhow::Result<()> {
     // Use provided mechanism or default to SCRAM-SHA-512
     let mech = config
         .sasl_mechanism
@@ -177,6 +253,9 @@ pub fn configure_sasl(cc: &mut ClientConfig, config: &KafkaConfig) {
         .filter(|s| !s.is_empty())
         .unwrap_or("SCRAM-SHA-512");
     cc.set("sasl.mechanism", mech);
+    if mech.eq_ignore_ascii_case("GSSAPI") {
+        return configure_kerberos(cc, config);
+    }
 
     // Prefer explicit username/password parsed from JAAS config string
     if let Some(jaas) = &config.sasl_jaas_config {
@@ -185,4 +264,5 @@ pub fn configure_sasl(cc: &mut ClientConfig, config: &KafkaConfig) {
             cc.set("sasl.password", &pass);
         }
     }
+    Ok(())
 }
@@ -1,3 +1,7 @@
+pub mod cron;
+pub mod jq;
+pub mod json;
+pub mod json_store;
 pub mod kafka;
 
 use std::collections::HashSet;
@@ -5,6 +9,11 @@ use std::path::{Path, PathBuf};
 
 use protobuf::descriptor::{FileDescriptorProto, FileDescriptorSet};
 use protobuf::reflect::FileDescriptor;
+use protobuf::well_known_types;
+use protobuf::Message;
+
+/// Extensions of binary FileDescriptorSet files (`protoc --descriptor_set_out`).
+const DESCRIPTOR_SET_EXTENSIONS: [&str; 4] = ["desc", "pb", "protoset", "binpb"];
 
 /// Collect unique parent directories from a list of file paths.
 pub fn unique_parent_dirs(files: &[String]) -> Vec<PathBuf> {
@@ -17,8 +26,44 @@ pub fn unique_parent_dirs(files: &[String]) -> Vec<PathBuf> {
     set.into_iter().collect()
 }
 
+/// Whether a path names a precompiled descriptor set rather than a .proto source.
+pub fn is_descriptor_set(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| DESCRIPTOR_SET_EXTENSIONS.iter().any(|x| e.eq_ignore_ascii_case(x)))
+}
+
+/// Read a binary FileDescriptorSet written by `protoc --descriptor_set_out`.
+pub fn read_descriptor_set(path: &str) -> Result<FileDescriptorSet, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read descriptor set '{}': {}", path, e))?;
+    FileDescriptorSet::parse_from_bytes(&bytes).map_err(|e| format!("'{}' is not a protobuf descriptor set: {}", path, e))
+}
+
 /// Parse .proto files using pure-Rust parser (no external protoc) and return FileDescriptorSet.
+/// Descriptor set files among `files` (see `is_descriptor_set`) are read as they are and merged in.
 pub fn run_protoc_and_read_descriptor_set(files: &[String]) -> Result<FileDescriptorSet, String> {
+    compile_descriptor_set(files, &[])
+}
+
+/// Include directories for parsing `files`: `includes` first, then the files' own directories.
+/// A file is named (and imported) relative to the first of them that contains it.
+pub fn proto_include_dirs(files: &[String], includes: &[String]) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = includes.iter().map(PathBuf::from).collect();
+    // Deepest first, so a file is named after its own directory unless an include says otherwise
+    let mut parents = unique_parent_dirs(files);
+    parents.sort();
+    parents.sort_by_key(|p| std::cmp::Reverse(p.components().count()));
+    for p in parents {
+        if !dirs.contains(&p) {
+            dirs.push(p);
+        }
+    }
+    dirs
+}
+
+/// `run_protoc_and_read_descriptor_set` resolving imports against `includes` (see `proto_include_dirs`).
+pub fn compile_descriptor_set(files: &[String], includes: &[String]) -> Result<FileDescriptorSet, String> {
     if files.is_empty() {
         return Err("No .proto files provided".into());
     }
@@ -27,9 +72,22 @@ pub fn run_protoc_and_read_descriptor_set(files: &[String]) -> Result<FileDescri
             return Err(format!("File not found: {}", f));
         }
     }
+    let (sets, sources): (Vec<String>, Vec<String>) = files.iter().cloned().partition(|f| is_descriptor_set(f));
+    let mut merged: Vec<FileDescriptorProto> = Vec::new();
+    for set in &sets {
+        merged.extend(read_descriptor_set(set)?.file);
+    }
+    if !sources.is_empty() {
+        merged.extend(parse_proto_sources(&sources, includes)?.file);
+    }
+    // Sets built with --include_imports repeat shared imports; the same file may also come as source
+    let mut seen: HashSet<String> = HashSet::new();
+    merged.retain(|f| seen.insert(f.name().to_string()));
+    Ok(FileDescriptorSet { file: merged, ..Default::default() })
+}
 
-    // Collect include directories from provided files so imports can be resolved
-    let include_dirs = unique_parent_dirs(files);
+fn parse_proto_sources(files: &[String], includes: &[String]) -> Result<FileDescriptorSet, String> {
+    let include_dirs = proto_include_dirs(files, includes);
 
     // Use protobuf-parse (pure Rust) to parse and typecheck the .proto files
     let mut parser = protobuf_parse::Parser::new();
@@ -49,6 +107,26 @@ pub fn run_protoc_and_read_descriptor_set(files: &[String]) -> Result<FileDescri
     Ok(set)
 }
 
+/// Bundled descriptor of a well-known .proto, for schemas that import it without shipping it
+/// (descriptor sets built without --include_imports, registry schemas).
+pub fn well_known_file(name: &str) -> Option<FileDescriptor> {
+    let file = match name {
+        "google/protobuf/any.proto" => well_known_types::any::file_descriptor(),
+        "google/protobuf/api.proto" => well_known_types::api::file_descriptor(),
+        "google/protobuf/duration.proto" => well_known_types::duration::file_descriptor(),
+        "google/protobuf/empty.proto" => well_known_types::empty::file_descriptor(),
+        "google/protobuf/field_mask.proto" => well_known_types::field_mask::file_descriptor(),
+        "google/protobuf/source_context.proto" => well_known_types::source_context::file_descriptor(),
+        "google/protobuf/struct.proto" => well_known_types::struct_::file_descriptor(),
+        "google/protobuf/timestamp.proto" => well_known_types::timestamp::file_descriptor(),
+        "google/protobuf/type.proto" => well_known_types::type_::file_descriptor(),
+        "google/protobuf/wrappers.proto" => well_known_types::wrappers::file_descriptor(),
+        "google/protobuf/descriptor.proto" => protobuf::descriptor::file_descriptor(),
+        _ => return None,
+    };
+    Some(file.clone())
+}
+
 /// Link FileDescriptorProto entries into reflect::FileDescriptor graph, resolving dependencies.
 pub fn link_file_descriptors(fds: &FileDescriptorSet) -> Result<Vec<FileDescriptor>, String> {
     use std::collections::{HashMap, HashSet};
@@ -62,6 +140,23 @@ pub fn link_file_descriptors(fds: &FileDescriptorSet) -> Result<Vec<FileDescript
     let mut built_base: HashMap<String, usize> = HashMap::new();
     let mut base_collisions: HashSet<String> = HashSet::new();
 
+    // Well-known imports the set does not carry itself resolve to the bundled descriptors
+    let provided: HashSet<&str> = remaining.iter().map(|f| f.name()).collect();
+    let mut missing: Vec<String> = remaining
+        .iter()
+        .flat_map(|f| f.dependency.iter())
+        .filter(|d| !provided.contains(d.as_str()))
+        .cloned()
+        .collect();
+    missing.sort();
+    missing.dedup();
+    for dep in missing {
+        if let Some(fd) = well_known_file(&dep) {
+            built_full.insert(dep, built.len());
+            built.push(fd);
+        }
+    }
+
     let mut progress = true;
     while !remaining.is_empty() && progress {
         progress = false;
@@ -124,7 +219,7 @@ pub fn link_file_descriptors(fds: &FileDescriptorSet) -> Result<Vec<FileDescript
     if !remaining.is_empty() {
         let rest: Vec<String> = remaining.iter().map(|f| f.name().to_string()).collect();
         return Err(format!(
-            "Failed to resolve dependencies for proto files: {:?}",
+            "Failed to resolve dependencies for proto files: {:?} (descriptor sets need protoc --include_imports)",
             rest
         ));
     }
@@ -136,3 +231,10 @@ pub fn normalize_full_name(mut name: String) -> String {
     if name.starts_with('.') { name.remove(0); }
     name
 }
+
+/// Random lowercase hex string from `bytes` bytes of OS randomness.
+pub fn random_hex(bytes: usize) -> Result<String, String> {
+    let mut buf = vec![0u8; bytes];
+    openssl::rand::rand_bytes(&mut buf).map_err(|e| format!("Failed to generate random token: {e}"))?;
+    Ok(buf.iter().map(|b| format!("{b:02x}")).collect())
+}
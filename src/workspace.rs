@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::app::{AppState, DEFAULT_CONNECTION};
+use crate::kafka::KafkaConfig;
+use crate::kafka_adapter::{connect_failed, PlainFilterOptions, TombstoneFilter};
+use crate::profiles::{secret_values, set_secret, strip_secrets, SECRET_FIELDS};
+use crate::response::{CommandResult, Envelope};
+use crate::secrets;
+use crate::topic_prefs::prefs_dir;
+
+const WORKSPACE_FILE: &str = "workspace.json";
+// Keychain namespace for the secrets of the last workspace
+const WORKSPACE_SECRETS: &str = "__workspace__";
+
+/// Reading filters of the last session (mirrors ApplyFiltersArgs / StartFilteredLoadArgs).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceFilters {
+    pub partition: Option<String>,
+    #[serde(default)]
+    pub partitions: Option<Vec<i32>>,
+    #[serde(rename = "start_offset", alias = "startOffset")]
+    pub start_offset: Option<i64>,
+    /// "oldest" | "newest"
+    #[serde(rename = "start_from", alias = "startFrom")]
+    pub start_from: Option<String>,
+    #[serde(rename = "key_filter", alias = "keyFilter")]
+    pub key_filter: Option<String>,
+    #[serde(rename = "message_filter", alias = "messageFilter")]
+    pub message_filter: Option<String>,
+    #[serde(rename = "message_filter_mode", alias = "messageFilterMode")]
+    pub message_filter_mode: Option<String>,
+    #[serde(flatten)]
+    pub plain: PlainFilterOptions,
+    #[serde(default, rename = "transform_script", alias = "transformScript")]
+    pub transform_script: Option<String>,
+    #[serde(default)]
+    pub tombstones: TombstoneFilter,
+}
+
+/// Last session state, saved by the UI and restored on startup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    /// Connection name (see add_connection); the default connection when omitted
+    pub connection: Option<String>,
+    /// Saved without secrets; they are kept in the OS keychain
+    pub config: KafkaConfig,
+    /// Config fields cleared on save; only these are restored from the keychain
+    #[serde(default, rename = "stripped_secrets", alias = "strippedSecrets")]
+    pub stripped_secrets: Vec<String>,
+    #[serde(default)]
+    pub filters: WorkspaceFilters,
+    /// Scroll anchor: offset of the first visible row per partition
+    #[serde(default, rename = "scroll_offsets", alias = "scrollOffsets")]
+    pub scroll_offsets: HashMap<i32, i64>,
+    pub page: Option<u32>,
+    #[serde(default, rename = "saved_at", alias = "savedAt")]
+    pub saved_at: String,
+}
+
+pub fn save(dir: &Path, mut ws: Workspace) -> Result<Workspace, String> {
+    ws.stripped_secrets = strip_secrets(&mut ws.config).1;
+    ws.saved_at = chrono::Utc::now().to_rfc3339();
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create app data directory: {e}"))?;
+    let data = serde_json::to_string_pretty(&ws).map_err(|e| format!("Failed to serialize workspace: {e}"))?;
+    let tmp = dir.join(format!("{WORKSPACE_FILE}.tmp"));
+    fs::write(&tmp, data).map_err(|e| format!("Failed to write workspace: {e}"))?;
+    fs::rename(&tmp, dir.join(WORKSPACE_FILE)).map_err(|e| format!("Failed to write workspace: {e}"))?;
+    Ok(ws)
+}
+
+pub fn load(dir: &Path) -> Result<Option<Workspace>, String> {
+    let path = dir.join(WORKSPACE_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("Failed to read workspace: {e}"))?;
+    serde_json::from_str(&data)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse workspace: {e}"))
+}
+
+/// Persist the current session state (called by the UI on changes and before exit).
+#[tauri::command]
+pub async fn save_workspace(app: AppHandle, workspace: Workspace) -> CommandResult<()> {
+    for (field, value) in secret_values(&workspace.config) {
+        if let Err(e) = secrets::store(WORKSPACE_SECRETS, field, &value) {
+            log::warn!("{e}");
+        }
+    }
+    let ws = prefs_dir(&app).and_then(|dir| save(&dir, workspace)).map_err(|e| Envelope::failed("save_workspace", e))?;
+    // Drop secrets of an earlier workspace so they never reach another cluster
+    for field in SECRET_FIELDS.iter().filter(|f| !ws.stripped_secrets.iter().any(|s| s == *f)) {
+        if let Err(e) = secrets::remove(WORKSPACE_SECRETS, field) {
+            log::warn!("{e}");
+        }
+    }
+    Ok(())
+}
+
+/// Reopen the last workspace: reconnect, reapply filters and return the state so the UI can restore scrolling.
+/// Returns None when nothing was saved. Called by the UI on startup.
+#[tauri::command]
+pub async fn restore_last_workspace(app: AppHandle, state: State<'_, AppState>) -> CommandResult<Option<Workspace>> {
+    let Some(ws) = prefs_dir(&app).and_then(|dir| load(&dir)).map_err(|e| Envelope::failed("restore_workspace", e))? else {
+        return Ok(None);
+    };
+    if ws.config.topic.is_empty() {
+        return Ok(Some(ws));
+    }
+    let mut config = ws.config.clone();
+    for field in &ws.stripped_secrets {
+        if let Ok(Some(value)) = secrets::fetch(WORKSPACE_SECRETS, field) {
+            set_secret(&mut config, field, value);
+        }
+    }
+    let name = ws.connection.clone().unwrap_or_else(|| DEFAULT_CONNECTION.to_string());
+    state.add_connection(&name, config).await.map_err(connect_failed)?;
+    let guard = state.kafka.lock().map_err(Envelope::state)?;
+    if let Some(k) = guard.get(Some(&name)) {
+        k.apply_filters(
+            ws.filters.partition.clone(),
+            ws.filters.partitions.clone(),
+            ws.filters.start_offset,
+            ws.filters.start_from.clone(),
+        )
+        .map_err(|e| Envelope::failed("apply_filters", e))?;
+    }
+    Ok(Some(ws))
+}
@@ -0,0 +1,45 @@
+use std::io::Write;
+
+use rkui::kafka::{MessageCodec, MessageType};
+use rkui::proto_decoder::ProtoDecoder;
+
+fn detect(codec: &MessageCodec, payload: &[u8]) -> (Option<&'static str>, String) {
+    let d = codec.decode_payload(Some(payload), None);
+    (d.detected_format, d.value)
+}
+
+#[test]
+fn detects_json_text_and_hex() {
+    let codec = MessageCodec::new(MessageType::Auto);
+    assert_eq!(detect(&codec, br#"{"id":1}"#), (Some("json"), r#"{"id":1}"#.to_string()));
+    assert_eq!(detect(&codec, b"hello\nworld"), (Some("text"), "hello\nworld".to_string()));
+    // Control characters and invalid UTF-8 are not text
+    assert_eq!(detect(&codec, b"\x01\x02"), (Some("hex"), "0102".to_string()));
+    assert_eq!(detect(&codec, &[0xff, 0x10]), (Some("hex"), "ff10".to_string()));
+}
+
+#[test]
+fn compressed_payloads_are_detected_after_inflating() {
+    let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    gz.write_all(br#"{"id":1}"#).unwrap();
+    let d = MessageCodec::new(MessageType::Auto).decode_payload(Some(&gz.finish().unwrap()), None);
+    assert_eq!(d.detected_format, Some("json"));
+    assert_eq!(d.value, r#"{"id":1}"#);
+}
+
+#[test]
+fn protobuf_is_tried_when_schemas_are_loaded() {
+    let bytes = std::fs::read("proto_message.bin").unwrap();
+    assert_eq!(detect(&MessageCodec::new(MessageType::Auto), &bytes).0, Some("hex"));
+
+    let decoder = ProtoDecoder::from_proto_files(vec!["example.proto".into()], Some("example.Person".into())).unwrap();
+    let codec = MessageCodec { proto_decoder: Some(decoder), ..MessageCodec::new(MessageType::Auto) };
+    let (format, value) = detect(&codec, &bytes);
+    assert_eq!(format, Some("protobuf"));
+    assert!(value.contains("email"), "{value}");
+}
+
+#[test]
+fn explicit_message_types_report_no_detected_format() {
+    assert_eq!(detect(&MessageCodec::new(MessageType::Json), br#"{"id":1}"#).0, None);
+}
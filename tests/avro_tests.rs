@@ -0,0 +1,31 @@
+use rkui::kafka::avro::AvroSchema;
+use rkui::kafka::schema_registry::split_envelope;
+
+#[test]
+fn decodes_record_with_union_array_and_enum() {
+    let schema = AvroSchema::parse(
+        r#"{"type":"record","name":"User","namespace":"com.example","fields":[
+            {"name":"id","type":"long"},
+            {"name":"name","type":"string"},
+            {"name":"email","type":["null","string"]},
+            {"name":"tags","type":{"type":"array","items":"string"}},
+            {"name":"status","type":{"type":"enum","name":"Status","symbols":["ACTIVE","BLOCKED"]}}
+        ]}"#,
+    )
+    .unwrap();
+    // id=42, name="Al", email=null, tags=["x"], status=BLOCKED
+    let body = [0x54, 0x04, b'A', b'l', 0x00, 0x02, 0x02, b'x', 0x00, 0x02];
+    let framed = [&[0u8, 0, 0, 0, 7][..], &body[..]].concat();
+    let (id, data) = split_envelope(&framed).unwrap();
+    assert_eq!(id, 7);
+    assert_eq!(
+        schema.decode_to_json(data).unwrap(),
+        r#"{"id":42,"name":"Al","email":null,"tags":["x"],"status":"BLOCKED"}"#
+    );
+}
+
+#[test]
+fn rejects_truncated_datum() {
+    let schema = AvroSchema::parse(r#"{"type":"record","name":"R","fields":[{"name":"s","type":"string"}]}"#).unwrap();
+    assert!(schema.decode_to_json(&[0x0A, b'a']).is_err());
+}
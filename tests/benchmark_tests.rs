@@ -0,0 +1,12 @@
+use rkui::kafka::LatencySummary;
+
+#[test]
+fn nearest_rank_percentiles() {
+    let s = LatencySummary::from_latencies((1..=100).rev().map(f64::from).collect());
+    assert_eq!((s.min, s.p50, s.p95, s.p99, s.max), (1.0, 50.0, 95.0, 99.0, 100.0));
+    assert_eq!(s.mean, 50.5);
+
+    let one = LatencySummary::from_latencies(vec![7.5]);
+    assert_eq!((one.p50, one.p99), (7.5, 7.5));
+    assert_eq!(LatencySummary::from_latencies(Vec::new()).max, 0.0);
+}
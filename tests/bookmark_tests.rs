@@ -0,0 +1,10 @@
+use rkui::kafka::bookmark_group;
+
+#[test]
+fn group_names() {
+    assert_eq!(bookmark_group("incident-42").unwrap(), "rkui-bookmark-incident-42");
+    assert_eq!(bookmark_group(" orders_v2.replay ").unwrap(), "rkui-bookmark-orders_v2.replay");
+    assert!(bookmark_group("").is_err());
+    assert!(bookmark_group("with space").is_err());
+    assert!(bookmark_group("a/b").is_err());
+}
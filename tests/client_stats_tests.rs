@@ -0,0 +1,35 @@
+use rdkafka::statistics::{Broker, Partition, Statistics, Topic, Window};
+use rkui::kafka::ClientStats;
+
+#[test]
+fn statistics_are_reduced_to_broker_latency_and_fetch_queues() {
+    let mut stats = Statistics { time: 1_700_000_000, replyq: 3, ..Default::default() };
+    let window = |sum: i64, cnt: i64| Window { sum, cnt, avg: sum / cnt.max(1), p99: 9_000, ..Default::default() };
+    stats.brokers.insert(
+        "kafka-2:9092/2".into(),
+        Broker { nodename: "kafka-2:9092".into(), nodeid: 2, state: "UP".into(), rtt: Some(window(12_000, 4)), ..Default::default() },
+    );
+    stats.brokers.insert(
+        "kafka-1:9092/1".into(),
+        Broker { nodename: "kafka-1:9092".into(), nodeid: 1, state: "UP".into(), rtt: Some(window(4_000, 4)), outbuf_cnt: 1, ..Default::default() },
+    );
+    stats.brokers.insert(
+        ":0/internal".into(),
+        Broker { nodeid: -1, state: "UP".into(), rtt: Some(window(0, 0)), ..Default::default() },
+    );
+    let mut topic = Topic { topic: "orders".into(), ..Default::default() };
+    for (partition, lag) in [(1, 40), (0, 7), (-1, 0)] {
+        let p = Partition { partition, fetchq_cnt: 5, consumer_lag: lag, fetch_state: "active".into(), ..Default::default() };
+        topic.partitions.insert(partition, p);
+    }
+    stats.topics.insert("orders".into(), topic);
+
+    let out = ClientStats::from(&stats);
+    assert_eq!(out.timestamp, 1_700_000_000_000);
+    assert_eq!(out.replyq, 3);
+    assert_eq!(out.brokers.iter().map(|b| b.node_id).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(out.brokers[0].rtt_avg_ms, Some(1.0));
+    assert_eq!(out.brokers[1].rtt_p99_ms, Some(9.0));
+    assert_eq!(out.fetch_latency_ms, Some(2.0));
+    assert_eq!(out.partitions.iter().map(|p| (p.partition, p.consumer_lag)).collect::<Vec<_>>(), vec![(0, 7), (1, 40)]);
+}
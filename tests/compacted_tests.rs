@@ -0,0 +1,62 @@
+use rkui::kafka::{LatestByKey, UiMessage};
+
+fn record(partition: i32, offset: i64, key: &str, value: Option<&str>, ts: &str) -> UiMessage {
+    UiMessage {
+        id: format!("{partition}-{offset}"),
+        partition,
+        key: key.into(),
+        offset,
+        message: value.unwrap_or("").into(),
+        timestamp: ts.into(),
+        decoded: true,
+        is_tombstone: value.is_none(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn keeps_newest_value_and_drops_deleted_keys() {
+    let mut table = LatestByKey::new(100);
+    table.push(record(0, 0, "a", Some("1"), "2024-01-01T00:00:00Z"));
+    table.push(record(0, 1, "b", Some("1"), "2024-01-01T00:00:01Z"));
+    table.push(record(0, 2, "a", Some("2"), "2024-01-01T00:00:02Z"));
+    table.push(record(0, 3, "b", None, "2024-01-01T00:00:03Z"));
+    table.push(record(1, 0, "c", None, "2024-01-01T00:00:00Z"));
+    table.push(record(1, 1, "c", Some("3"), "2024-01-01T00:00:04Z"));
+
+    let snapshot = table.finish();
+    let live: Vec<(&str, &str)> = snapshot.messages.iter().map(|m| (m.key.as_str(), m.message.as_str())).collect();
+    assert_eq!(live, vec![("a", "2"), ("c", "3")]);
+    assert_eq!(snapshot.scanned, 6);
+    assert_eq!(snapshot.deleted, 1);
+    assert!(!snapshot.truncated);
+}
+
+#[test]
+fn stops_tracking_new_keys_at_the_limit() {
+    let mut table = LatestByKey::new(1);
+    table.push(record(0, 0, "a", Some("1"), "t"));
+    table.push(record(0, 1, "b", Some("1"), "t"));
+    table.push(record(0, 2, "a", Some("2"), "t"));
+    let snapshot = table.finish();
+    assert_eq!(snapshot.messages.len(), 1);
+    assert_eq!(snapshot.messages[0].message, "2");
+    assert!(snapshot.truncated);
+}
+
+#[test]
+fn compares_timestamps_across_partitions_as_instants() {
+    let mut table = LatestByKey::new(100);
+    // Same instant in different offsets: 10:00+02:00 is 08:00Z, older than 09:00Z
+    table.push(record(0, 0, "a", Some("1"), "2024-01-01T09:00:00Z"));
+    table.push(record(1, 0, "a", Some("2"), "2024-01-01T10:00:00+02:00"));
+    // A record without a timestamp cannot be ordered, so the later read wins
+    table.push(record(0, 1, "b", Some("1"), "2024-01-01T09:00:00Z"));
+    table.push(record(1, 1, "b", Some("2"), ""));
+    table.push(record(0, 2, "c", Some("1"), ""));
+    table.push(record(1, 2, "c", Some("2"), "2024-01-01T09:00:00Z"));
+
+    let snapshot = table.finish();
+    let live: Vec<(&str, &str)> = snapshot.messages.iter().map(|m| (m.key.as_str(), m.message.as_str())).collect();
+    assert_eq!(live, vec![("a", "1"), ("b", "2"), ("c", "2")]);
+}
@@ -0,0 +1,36 @@
+use std::io::Write;
+
+use rkui::kafka::compression::{decompress, detect};
+use rkui::kafka::PayloadCompression;
+
+#[test]
+fn auto_mode_inflates_gzip_and_zstd_and_passes_plain_payloads_through() {
+    let json = br#"{"id":42,"name":"order"}"#;
+
+    let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    gz.write_all(json).unwrap();
+    let gz = gz.finish().unwrap();
+    assert_eq!(detect(&gz), Some(PayloadCompression::Gzip));
+    let (codec, out) = decompress(&gz, PayloadCompression::Auto).unwrap().unwrap();
+    assert_eq!(codec, PayloadCompression::Gzip);
+    assert_eq!(out, json);
+
+    let zs = zstd::encode_all(&json[..], 3).unwrap();
+    let (codec, out) = decompress(&zs, PayloadCompression::Auto).unwrap().unwrap();
+    assert_eq!(codec, PayloadCompression::Zstd);
+    assert_eq!(out, json);
+
+    assert!(decompress(json, PayloadCompression::Auto).unwrap().is_none());
+}
+
+#[test]
+fn explicit_snappy_accepts_raw_blocks_and_reports_garbage() {
+    let raw = snap::raw::Encoder::new().compress_vec(b"hello hello hello").unwrap();
+    let (_, out) = decompress(&raw, PayloadCompression::Snappy).unwrap().unwrap();
+    assert_eq!(out, b"hello hello hello");
+
+    // Looks like gzip but is not: auto mode falls back to the raw bytes, explicit mode errors
+    let bogus = [0x1f, 0x8b, 0x00, 0x01];
+    assert!(decompress(&bogus, PayloadCompression::Auto).unwrap().is_none());
+    assert!(decompress(&bogus, PayloadCompression::Gzip).is_err());
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use rkui::kafka::KafkaConfig;
 use rkui::kafka::MessageType;
 
@@ -11,3 +13,163 @@ fn default_config_is_sane() {
     assert!(cfg.partition.is_none());
     assert!(cfg.start_offset.is_none());
 }
+
+#[test]
+fn decoder_settings_override_only_what_is_set() {
+    use rkui::kafka::{DecoderSettings, MessageType};
+
+    let mut cfg = KafkaConfig::default();
+    cfg.proto_schema_path = Some("/protos/a.proto".into());
+    cfg.proto_message_full_name = Some("a.Old".into());
+    cfg.lazy_decode = Some(true);
+    let settings: DecoderSettings =
+        serde_json::from_value(serde_json::json!({ "message_type": "protobuf", "protoMessageFullName": "a.New" })).unwrap();
+    let out = settings.apply(&cfg);
+    assert!(matches!(out.message_type, MessageType::Protobuf));
+    assert_eq!(out.proto_message_full_name.as_deref(), Some("a.New"));
+    assert_eq!(out.proto_schema_path.as_deref(), Some("/protos/a.proto"));
+    assert_eq!(out.lazy_decode, Some(false));
+}
+
+#[test]
+fn oauth_settings_only_apply_to_oauthbearer_sasl() {
+    use rkui::kafka::OAuthSettings;
+
+    let mut cfg: KafkaConfig = serde_json::from_value(serde_json::json!({
+        "broker": "b:9092", "topic": "t", "ssl_enabled": false, "message_type": "json",
+        "securityType": "sasl_ssl", "saslMechanism": "OAUTHBEARER",
+        "saslOauthbearerTokenEndpointUrl": "https://idp/token", "saslOauthbearerClientId": "rkui",
+        "saslOauthbearerClientSecret": "s3cret", "saslOauthbearerScope": " "
+    }))
+    .unwrap();
+    let oauth = OAuthSettings::from_config(&cfg).unwrap();
+    assert_eq!(oauth.token_endpoint_url, "https://idp/token");
+    assert_eq!(oauth.client_secret, "s3cret");
+    assert!(oauth.scope.is_none());
+
+    cfg.sasl_mechanism = Some("SCRAM-SHA-512".into());
+    assert!(OAuthSettings::from_config(&cfg).is_none());
+}
+
+#[test]
+fn gssapi_requires_librdkafka_support() {
+    use rkui::utils::kafka::{configure_security, librdkafka_features};
+
+    let mut cfg = KafkaConfig::default();
+    cfg.security_type = Some("sasl_plaintext".into());
+    cfg.sasl_mechanism = Some("GSSAPI".into());
+    let result = configure_security(&mut rdkafka::ClientConfig::new(), &cfg);
+    if librdkafka_features().iter().any(|f| f == "sasl_gssapi") {
+        assert!(result.is_ok());
+    } else {
+        assert!(result.unwrap_err().to_string().contains("sasl_gssapi"));
+    }
+}
+
+#[test]
+fn pkcs12_keystore_provides_the_client_identity() {
+    use openssl::{asn1::Asn1Time, hash::MessageDigest, pkcs12::Pkcs12, pkey::PKey, rsa::Rsa, x509::X509};
+    use rkui::utils::kafka::configure_security;
+
+    let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+    let mut name = openssl::x509::X509NameBuilder::new().unwrap();
+    name.append_entry_by_text("CN", "rkui-client").unwrap();
+    let name = name.build();
+    let mut cert = X509::builder().unwrap();
+    cert.set_subject_name(&name).unwrap();
+    cert.set_issuer_name(&name).unwrap();
+    cert.set_pubkey(&key).unwrap();
+    cert.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+    cert.set_not_after(&Asn1Time::days_from_now(1).unwrap()).unwrap();
+    cert.sign(&key, MessageDigest::sha256()).unwrap();
+    let cert = cert.build();
+    let p12 = Pkcs12::builder().name("client").pkey(&key).cert(&cert).build2("changeit").unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("client.p12");
+    std::fs::write(&path, p12.to_der().unwrap()).unwrap();
+
+    let mut cfg = KafkaConfig::default();
+    cfg.security_type = Some("ssl".into());
+    cfg.keystore_location = Some(path.to_string_lossy().into_owned());
+    cfg.keystore_password = Some("changeit".into());
+    let mut cc = rdkafka::ClientConfig::new();
+    configure_security(&mut cc, &cfg).unwrap();
+    // Passed in memory: the unencrypted key is never written to a file
+    assert!(cc.get("ssl.key.location").is_none());
+    assert!(cc.get("ssl.key.pem").unwrap().contains("PRIVATE KEY"));
+    let cert_pem = cc.get("ssl.certificate.pem").unwrap();
+    assert_eq!(X509::from_pem(cert_pem.as_bytes()).unwrap().to_der().unwrap(), cert.to_der().unwrap());
+
+    cfg.keystore_password = Some("wrong".into());
+    assert!(configure_security(&mut rdkafka::ClientConfig::new(), &cfg).is_err());
+}
+
+#[test]
+fn endpoint_identification_can_be_turned_off() {
+    use rkui::utils::kafka::configure_security;
+
+    let mut cfg = KafkaConfig::default();
+    cfg.security_type = Some("ssl".into());
+    let mut cc = rdkafka::ClientConfig::new();
+    configure_security(&mut cc, &cfg).unwrap();
+    assert_eq!(cc.get("ssl.endpoint.identification.algorithm"), None);
+
+    cfg.ssl_endpoint_identification = Some(false);
+    configure_security(&mut cc, &cfg).unwrap();
+    assert_eq!(cc.get("ssl.endpoint.identification.algorithm"), Some("none"));
+}
+
+#[test]
+fn custom_properties_override_rkui_settings() {
+    use rkui::utils::kafka::apply_custom_properties;
+
+    let mut cfg = KafkaConfig::default();
+    cfg.custom_properties = Some(HashMap::from([
+        ("socket.timeout.ms".to_string(), "60000".to_string()),
+        (" broker.address.family ".to_string(), "v4".to_string()),
+        ("".to_string(), "ignored".to_string()),
+    ]));
+    let mut cc = rdkafka::ClientConfig::new();
+    cc.set("socket.timeout.ms", "10000");
+    apply_custom_properties(&mut cc, &cfg);
+    assert_eq!(cc.get("socket.timeout.ms"), Some("60000"));
+    assert_eq!(cc.get("broker.address.family"), Some("v4"));
+    assert_eq!(cc.get(""), None);
+}
+
+#[test]
+fn truststore_inspection_flags_certificates_close_to_expiry() {
+    use openssl::{asn1::Asn1Time, hash::MessageDigest, pkey::PKey, rsa::Rsa, x509::X509};
+    use openssl::x509::extension::SubjectAlternativeName;
+    use rkui::kafka::inspect_truststore;
+
+    let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+    let cert = |cn: &str, days: u32| {
+        let mut name = openssl::x509::X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", cn).unwrap();
+        let name = name.build();
+        let mut b = X509::builder().unwrap();
+        b.set_subject_name(&name).unwrap();
+        b.set_issuer_name(&name).unwrap();
+        b.set_pubkey(&key).unwrap();
+        b.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        b.set_not_after(&Asn1Time::days_from_now(days).unwrap()).unwrap();
+        let san = SubjectAlternativeName::new().dns(cn).ip("10.0.0.7").build(&b.x509v3_context(None, None)).unwrap();
+        b.append_extension(san).unwrap();
+        b.sign(&key, MessageDigest::sha256()).unwrap();
+        b.build()
+    };
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ca.pem");
+    let mut pem = cert("broker-1", 10).to_pem().unwrap();
+    pem.extend(cert("root-ca", 400).to_pem().unwrap());
+    std::fs::write(&path, pem).unwrap();
+
+    let certs = inspect_truststore(path.to_str().unwrap(), None).unwrap();
+    assert_eq!(certs.len(), 2);
+    assert_eq!(certs[0].subject, "CN=broker-1");
+    assert_eq!(certs[0].sans, vec!["DNS:broker-1", "IP:10.0.0.7"]);
+    assert!(certs[0].expires_soon && !certs[0].expired);
+    assert!((9..=10).contains(&certs[0].days_left));
+    assert!(!certs[1].expires_soon);
+}
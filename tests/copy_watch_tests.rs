@@ -0,0 +1,38 @@
+use std::time::{Duration, Instant};
+
+use rkui::kafka_adapter::CopyWatch;
+use tokio::sync::broadcast;
+
+#[test]
+fn progress_is_emitted_at_most_once_per_interval() {
+    let (_tx, rx) = broadcast::channel(1);
+    let start = Instant::now();
+    let mut watch = CopyWatch::new(rx, Duration::from_millis(500), start);
+    assert!(!watch.emit_due(start + Duration::from_millis(100)));
+    assert!(watch.emit_due(start + Duration::from_millis(500)));
+    // The interval restarts at each emit
+    assert!(!watch.emit_due(start + Duration::from_millis(900)));
+    assert!(watch.emit_due(start + Duration::from_millis(1000)));
+}
+
+#[test]
+fn a_cancel_signal_stops_the_copy_for_good() {
+    let (tx, rx) = broadcast::channel(1);
+    let mut watch = CopyWatch::new(rx, Duration::from_millis(500), Instant::now());
+    assert!(watch.keep_going());
+    assert!(!watch.cancelled());
+
+    tx.send(()).unwrap();
+    assert!(!watch.keep_going());
+    assert!(!watch.keep_going());
+    assert!(watch.cancelled());
+}
+
+#[test]
+fn a_dropped_session_cancels_the_copy() {
+    let (tx, rx) = broadcast::channel::<()>(1);
+    let mut watch = CopyWatch::new(rx, Duration::from_millis(500), Instant::now());
+    drop(tx);
+    assert!(!watch.keep_going());
+    assert!(watch.cancelled());
+}
@@ -0,0 +1,26 @@
+use chrono::NaiveDate;
+use rkui::utils::cron::CronSchedule;
+
+fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> chrono::NaiveDateTime {
+    NaiveDate::from_ymd_opt(y, mo, d).unwrap().and_hms_opt(h, mi, 0).unwrap()
+}
+
+#[test]
+fn weekday_mornings_and_steps() {
+    let c = CronSchedule::parse("30 8 * * 1-5").unwrap();
+    // 2024-06-07 is a Friday
+    assert!(c.matches(&at(2024, 6, 7, 8, 30)));
+    assert!(!c.matches(&at(2024, 6, 8, 8, 30)));
+    assert_eq!(c.next_after(at(2024, 6, 7, 8, 30)), Some(at(2024, 6, 10, 8, 30)));
+
+    let every = CronSchedule::parse("*/15 * * * *").unwrap();
+    assert_eq!(every.next_after(at(2024, 6, 7, 8, 31)), Some(at(2024, 6, 7, 8, 45)));
+}
+
+#[test]
+fn rejects_malformed_expressions() {
+    assert!(CronSchedule::parse("* * *").is_err());
+    assert!(CronSchedule::parse("61 * * * *").is_err());
+    assert!(CronSchedule::parse("*/0 * * * *").is_err());
+    assert_eq!(CronSchedule::parse("@daily").unwrap(), CronSchedule::parse("0 0 * * *").unwrap());
+}
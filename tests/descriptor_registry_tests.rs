@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use rkui::proto_decoder::{expand_proto_paths, DescriptorRegistry};
+
+const SCHEMA: &str = r#"syntax = "proto3";
+package shop;
+message Order { string id = 1; }
+"#;
+
+#[test]
+fn same_sources_share_one_graph() {
+    let (a, b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    for dir in [&a, &b] {
+        std::fs::write(dir.path().join("order.proto"), SCHEMA).unwrap();
+    }
+    let files = |dir: &tempfile::TempDir| expand_proto_paths(&[dir.path().to_string_lossy().to_string()]).unwrap();
+
+    let registry = DescriptorRegistry::default();
+    let first = registry.linked(&files(&a)).unwrap();
+    // A copy of the schema repo elsewhere has the same content
+    let copy = registry.linked(&files(&b)).unwrap();
+    assert!(Arc::ptr_eq(&first, &copy));
+    assert_eq!(registry.len(), 1);
+
+    std::fs::write(b.path().join("order.proto"), SCHEMA.replace("string id", "int64 id")).unwrap();
+    let changed = registry.linked(&files(&b)).unwrap();
+    assert!(!Arc::ptr_eq(&first, &changed));
+    assert_eq!(registry.len(), 2);
+}
+
+#[test]
+fn registered_graphs_are_found_by_key() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("order.proto"), SCHEMA).unwrap();
+    let files = expand_proto_paths(&[dir.path().to_string_lossy().to_string()]).unwrap();
+
+    let registry = DescriptorRegistry::default();
+    let (key, graph) = registry.register(&files).unwrap();
+    assert!(Arc::ptr_eq(&registry.by_key(&key).unwrap(), &graph));
+    assert_eq!(registry.register(&files).unwrap().0, key);
+    assert!(registry.by_key("pbds-0").is_none());
+    assert!(registry.by_key("not-a-key").is_none());
+}
+
+#[test]
+fn changed_imports_outside_the_listed_files_change_the_graph() {
+    let (schemas, common) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    let money = common.path().join("money.proto");
+    std::fs::write(&money, "syntax = \"proto3\";\npackage common;\nmessage Money { int64 units = 1; }\n").unwrap();
+    let order = "syntax = \"proto3\";\npackage shop;\nimport \"money.proto\";\nmessage Order { common.Money total = 1; }\n";
+    std::fs::write(schemas.path().join("order.proto"), order).unwrap();
+    let files = vec![schemas.path().join("order.proto").to_string_lossy().to_string()];
+    let includes = vec![common.path().to_string_lossy().to_string()];
+
+    let registry = DescriptorRegistry::default();
+    let (first, _) = registry.register_with(&files, &includes).unwrap();
+    assert_eq!(registry.register_with(&files, &includes).unwrap().0, first);
+
+    std::fs::write(&money, "syntax = \"proto3\";\npackage common;\nmessage Money { string units = 1; }\n").unwrap();
+    let (changed, _) = registry.register_with(&files, &includes).unwrap();
+    assert_ne!(changed, first);
+}
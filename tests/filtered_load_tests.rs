@@ -0,0 +1,73 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rdkafka::consumer::StreamConsumer;
+use rdkafka::mocking::MockCluster;
+use rdkafka::producer::{DefaultProducerContext, FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+use rkui::kafka::{Kafka, KafkaConfig};
+use rkui::kafka_adapter::assign_load;
+
+/// A one-broker mock cluster with `records[p]` JSON records on each partition of topic "orders".
+async fn cluster(records: &[i32]) -> MockCluster<'static, DefaultProducerContext> {
+    let cluster = MockCluster::new(1).unwrap();
+    cluster.create_topic("orders", records.len() as i32, 1).unwrap();
+    let producer: FutureProducer = ClientConfig::new().set("bootstrap.servers", cluster.bootstrap_servers()).create().unwrap();
+    for (p, &count) in records.iter().enumerate() {
+        for n in 0..count {
+            let payload = format!(r#"{{"n":{n}}}"#);
+            let record = FutureRecord::to("orders").partition(p as i32).key("k").payload(&payload);
+            producer.send(record, Duration::from_secs(5)).await.unwrap();
+        }
+    }
+    cluster
+}
+
+fn reader(cluster: &MockCluster<'static, DefaultProducerContext>) -> Kafka {
+    Kafka::new(KafkaConfig { broker: cluster.bootstrap_servers(), topic: "orders".into(), ..Default::default() }).unwrap()
+}
+
+#[tokio::test]
+async fn a_load_covers_every_partition_and_skips_empty_ones() {
+    let cluster = cluster(&[5, 3, 0]).await;
+    let k = reader(&cluster);
+    let load = assign_load(&k, None, None).unwrap();
+    assert_eq!(load.parts, vec![0, 1, 2]);
+    assert_eq!(load.ends, HashMap::from([(0, 5), (1, 3), (2, 0)]));
+    assert_eq!(load.done, HashSet::from([2]));
+    assert_eq!(load.starts, HashMap::from([(0, 0), (1, 0)]));
+}
+
+#[tokio::test]
+async fn offset_ranges_are_clamped_to_what_was_written() {
+    let cluster = cluster(&[5, 3, 0]).await;
+    let k = reader(&cluster);
+    let ranges = HashMap::from([(0, (1, 2)), (1, (5, 9))]);
+    let load = assign_load(&k, Some(&ranges), None).unwrap();
+    assert_eq!(load.parts, vec![0, 1]);
+    // Ends are exclusive; a range past the high watermark has nothing to read
+    assert_eq!(load.ends, HashMap::from([(0, 3), (1, 3)]));
+    assert_eq!(load.done, HashSet::from([1]));
+    assert_eq!(load.starts, HashMap::from([(0, 1)]));
+}
+
+#[tokio::test]
+async fn a_load_after_a_cancelled_read_ahead_still_receives_rows() {
+    let cluster = cluster(&[5, 5]).await;
+    let k = Arc::new(reader(&cluster));
+    // Abort a first-page read while it holds the partitions split off the main queue
+    let read_ahead = tokio::spawn({
+        let k = k.clone();
+        async move { k.consume_next(100).await }
+    });
+    tokio::task::yield_now().await;
+    read_ahead.abort();
+    let _ = read_ahead.await;
+
+    let load = assign_load(&k, None, None).unwrap();
+    assert_eq!(load.parts, vec![0, 1]);
+    let consumer: &StreamConsumer<_> = &k.consumer;
+    let polled = tokio::time::timeout(Duration::from_secs(10), consumer.recv()).await;
+    assert!(polled.is_ok_and(|m| m.is_ok()), "the main queue got no records");
+}
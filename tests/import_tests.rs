@@ -0,0 +1,42 @@
+use rkui::import::ImportMapping;
+use serde_json::json;
+
+#[test]
+fn maps_exported_lines_to_records() {
+    let line = json!({
+        "topic": "orders", "partition": 3, "offset": 10, "timestamp": "2024-05-01T10:00:00Z",
+        "key": "k1",
+        "headers": [{"key": "trace", "value": "abc"}],
+        "value": {"id": 7},
+        "decoding_error": null
+    });
+    let rec = ImportMapping::default().record(&line).unwrap();
+    assert_eq!(rec.key.as_deref(), Some("k1"));
+    assert_eq!(rec.value.as_deref(), Some(r#"{"id":7}"#));
+    assert_eq!(rec.headers, vec![("trace".to_string(), "abc".to_string())]);
+    assert_eq!(rec.partition, Some(3));
+}
+
+#[test]
+fn custom_paths_and_header_shapes() {
+    let mapping: ImportMapping =
+        serde_json::from_value(json!({"key": ".meta.id", "value": ".body.text", "headers": ".meta.h"})).unwrap();
+    let rec = mapping.record(&json!({"meta": {"id": 5, "h": {"a": "1"}}, "body": {"text": "hello"}})).unwrap();
+    assert_eq!(rec.key.as_deref(), Some("5"));
+    assert_eq!(rec.value.as_deref(), Some("hello"));
+    assert_eq!(rec.headers, vec![("a".to_string(), "1".to_string())]);
+    assert_eq!(rec.partition, None);
+
+    let rec = ImportMapping::default().record(&json!({"value": null, "headers": [["x", "y"]]})).unwrap();
+    assert_eq!(rec.value, None);
+    assert_eq!(rec.headers, vec![("x".to_string(), "y".to_string())]);
+}
+
+#[test]
+fn refuses_decoded_or_masked_lines() {
+    let line = json!({"key": "k1", "value": {"card": "****"}, "verbatim": false});
+    assert!(ImportMapping::default().record(&line).is_err());
+
+    let line = json!({"key": "k1", "value": "plain", "verbatim": true});
+    assert_eq!(ImportMapping::default().record(&line).unwrap().value.as_deref(), Some("plain"));
+}
@@ -0,0 +1,35 @@
+use rkui::utils::jq::JqFilter;
+use serde_json::json;
+
+#[test]
+fn select_and_comparisons() {
+    let order = json!({"status": "PAID", "items": [{"sku": "a", "qty": 2}, {"sku": "b", "qty": 3}], "user": {"name": "Ann Lee"}});
+    let keeps = |code: &str| JqFilter::compile(code).unwrap().matches(&order);
+
+    assert!(keeps(".status == \"PAID\""));
+    assert!(keeps("select(.items | length > 1)"));
+    assert!(keeps("[.items[].qty] | add == 5"));
+    assert!(keeps(".user.name | ascii_downcase | startswith(\"ann\")"));
+    assert!(keeps(".items | map(.qty * 10) | max >= 30"));
+    assert!(!keeps(".status == \"NEW\""));
+    assert!(!keeps("select(.missing)"));
+    // Runtime errors do not match
+    assert!(!keeps(".status + 1"));
+}
+
+#[test]
+fn run_and_errors() {
+    let f = JqFilter::compile(".items[] | .sku").unwrap();
+    assert_eq!(f.run(&json!({"items": [{"sku": "a"}, {"sku": "b"}]})).unwrap(), vec![json!("a"), json!("b")]);
+    assert!(JqFilter::compile(".a ==").is_err());
+    assert!(JqFilter::compile("nosuchfn(1)").err().unwrap().contains("nosuchfn"));
+}
+
+#[test]
+fn runaway_generators_stop_at_the_output_cap() {
+    let input = json!({"items": [{"qty": 1}, {"qty": 5}]});
+    // Any of the outputs may match
+    assert!(JqFilter::compile(".items[] | .qty > 2").unwrap().matches(&input));
+    assert!(!JqFilter::compile("range(1e12) | . < 0").unwrap().matches(&input));
+    assert!(JqFilter::compile("range(1e12)").unwrap().run(&input).unwrap_err().contains("outputs"));
+}
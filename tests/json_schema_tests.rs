@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use rkui::kafka::{MessageCodec, MessageType};
+use serde_json::json;
+
+fn codec(message_type: MessageType) -> MessageCodec {
+    let schema = json!({
+        "type": "object",
+        "required": ["id"],
+        "properties": {
+            "id": { "type": "integer" },
+            "items": { "type": "array", "items": { "type": "string" } }
+        }
+    });
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    MessageCodec { json_validator: Some(Arc::new(validator)), ..MessageCodec::new(message_type) }
+}
+
+#[test]
+fn conforming_payloads_have_no_validation_error() {
+    let d = codec(MessageType::Json).decode_payload(Some(br#"{"id":1,"items":["a"]}"#), None);
+    assert_eq!(d.validation_error, None);
+    assert_eq!(d.error, None);
+}
+
+#[test]
+fn violations_are_reported_with_their_paths() {
+    let d = codec(MessageType::Json).decode_payload(Some(br#"{"id":"x","items":["a",2]}"#), None);
+    let error = d.validation_error.unwrap();
+    assert!(error.contains("/id: "), "{error}");
+    assert!(error.contains("/items/1: "), "{error}");
+
+    let d = codec(MessageType::Json).decode_payload(Some(b"{}"), None);
+    assert!(d.validation_error.unwrap().contains("\"id\""));
+}
+
+#[test]
+fn non_json_payloads_fail_validation_and_tombstones_are_not_checked() {
+    let d = codec(MessageType::Json).decode_payload(Some(b"not json"), None);
+    assert!(d.validation_error.unwrap().starts_with("Not valid JSON"));
+    assert_eq!(codec(MessageType::Json).decode_payload(None, None).validation_error, None);
+}
+
+#[test]
+fn only_json_payloads_are_checked() {
+    assert_eq!(codec(MessageType::Text).decode_payload(Some(b"{}"), None).validation_error, None);
+    // Auto detection validates what it detects as JSON
+    assert!(codec(MessageType::Auto).decode_payload(Some(b"{}"), None).validation_error.is_some());
+    assert_eq!(codec(MessageType::Auto).decode_payload(Some(b"plain text"), None).validation_error, None);
+}
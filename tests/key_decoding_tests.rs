@@ -0,0 +1,26 @@
+use rkui::kafka::{decode_simple_key, encode_simple_key, KeyType};
+
+#[test]
+fn numeric_and_uuid_keys_render_readably() {
+    assert_eq!(decode_simple_key(KeyType::Int32, &(-7i32).to_be_bytes()).unwrap(), "-7");
+    assert_eq!(decode_simple_key(KeyType::Int64, &1_700_000_000_000i64.to_be_bytes()).unwrap(), "1700000000000");
+    assert!(decode_simple_key(KeyType::Int64, b"abc").is_err());
+
+    let uuid = [0x12, 0x3e, 0x45, 0x67, 0xe8, 0x9b, 0x12, 0xd3, 0xa4, 0x56, 0x42, 0x66, 0x14, 0x17, 0x40, 0x00];
+    assert_eq!(decode_simple_key(KeyType::Uuid, &uuid).unwrap(), "123e4567-e89b-12d3-a456-426614174000");
+    assert_eq!(
+        decode_simple_key(KeyType::Uuid, b"123e4567-e89b-12d3-a456-426614174000").unwrap(),
+        "123e4567-e89b-12d3-a456-426614174000"
+    );
+    assert!(decode_simple_key(KeyType::Json, b"{not json").is_err());
+}
+
+#[test]
+fn rendered_keys_map_back_to_their_bytes() {
+    assert_eq!(encode_simple_key(KeyType::Int32, "-7").unwrap(), (-7i32).to_be_bytes());
+    assert_eq!(encode_simple_key(KeyType::Int64, "1700000000000").unwrap(), 1_700_000_000_000i64.to_be_bytes());
+    assert_eq!(encode_simple_key(KeyType::Text, "order-1").unwrap(), b"order-1");
+    assert_eq!(encode_simple_key(KeyType::Int32, "abc"), None);
+    assert_eq!(encode_simple_key(KeyType::Uuid, "123e4567-e89b-12d3-a456-426614174000"), None);
+    assert_eq!(encode_simple_key(KeyType::Protobuf, "{}"), None);
+}
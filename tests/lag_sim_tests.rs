@@ -0,0 +1,36 @@
+use rkui::kafka::{LagSample, LagSimulationReport};
+
+fn samples(lags: &[i64], produced_per_sec: i64, consumed_per_sec: i64) -> Vec<LagSample> {
+    lags.iter()
+        .enumerate()
+        .map(|(i, &lag)| LagSample {
+            elapsed_ms: i as u64 * 1000,
+            produced: produced_per_sec * i as i64,
+            consumed: consumed_per_sec * i as i64,
+            lag,
+        })
+        .collect()
+}
+
+#[test]
+fn falling_behind() {
+    let r = LagSimulationReport::from_samples("g", "t", 100.0, samples(&[1000, 1050, 1100, 1150], 150, 100), false);
+    assert!(!r.keeps_up);
+    assert!((r.lag_trend - 50.0).abs() < 1e-9);
+    assert!((r.produce_rate - 150.0).abs() < 1e-9);
+    assert!((r.consume_rate - 100.0).abs() < 1e-9);
+    assert_eq!((r.start_lag, r.end_lag), (1000, 1150));
+    assert!(r.drain_secs.is_none());
+}
+
+#[test]
+fn catching_up() {
+    let r = LagSimulationReport::from_samples("g", "t", 200.0, samples(&[1000, 900, 800, 700], 100, 200), false);
+    assert!(r.keeps_up);
+    assert!((r.lag_trend + 100.0).abs() < 1e-9);
+    assert!((r.drain_secs.unwrap() - 7.0).abs() < 1e-9);
+
+    let single = LagSimulationReport::from_samples("g", "t", 1.0, samples(&[5], 0, 0), true);
+    assert_eq!(single.lag_trend, 0.0);
+    assert!(single.cancelled);
+}
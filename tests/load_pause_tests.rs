@@ -0,0 +1,46 @@
+use std::time::{Duration, Instant};
+
+use rkui::kafka_adapter::{hold_while_paused, ActiveClock};
+use tokio::sync::{broadcast, watch};
+
+#[test]
+fn paused_time_is_not_scanning_time() {
+    let t0 = Instant::now();
+    let at = |secs: u64| t0 + Duration::from_secs(secs);
+    let mut clock = ActiveClock::start(t0);
+    assert_eq!(clock.elapsed(at(10)), Duration::from_secs(10));
+
+    clock.pause(at(10));
+    // Pausing twice keeps the first pause
+    clock.pause(at(12));
+    assert_eq!(clock.elapsed(at(40)), Duration::from_secs(10));
+    clock.resume(at(40));
+    assert_eq!(clock.elapsed(at(45)), Duration::from_secs(15));
+
+    // Resuming a running clock changes nothing
+    clock.resume(at(50));
+    assert_eq!(clock.elapsed(at(50)), Duration::from_secs(20));
+}
+
+#[tokio::test]
+async fn a_paused_load_waits_for_resume_or_cancel() {
+    let (cancel_tx, mut cancel) = broadcast::channel::<()>(1);
+    let (pause_tx, mut paused) = watch::channel(false);
+    // Not paused: nothing to wait for
+    assert!(hold_while_paused(&mut cancel, &mut paused).await);
+
+    pause_tx.send_replace(true);
+    let resume = tokio::spawn({
+        let pause_tx = pause_tx.clone();
+        async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            pause_tx.send_replace(false);
+        }
+    });
+    assert!(hold_while_paused(&mut cancel, &mut paused).await);
+    resume.await.unwrap();
+
+    pause_tx.send_replace(true);
+    cancel_tx.send(()).unwrap();
+    assert!(!hold_while_paused(&mut cancel, &mut paused).await);
+}
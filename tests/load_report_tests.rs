@@ -0,0 +1,116 @@
+use rkui::kafka::UiMessage;
+use rkui::load_report::{error_kind, LoadReportBuilder, ReportFilters};
+
+fn message(offset: i64, error: Option<&str>, schema_id: Option<u32>) -> UiMessage {
+    UiMessage {
+        id: format!("0-{offset}"),
+        offset,
+        decoding_error: error.map(str::to_string),
+        decoded: true,
+        schema_id,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn error_kind_drops_record_specific_numbers() {
+    assert_eq!(
+        error_kind("Protobuf decode error: invalid wire type 7 at byte 0, length 1532"),
+        "Protobuf decode error: invalid wire type 7 at byte 0, length N"
+    );
+}
+
+#[test]
+fn groups_decode_failures_by_kind_and_envelope() {
+    let mut b = LoadReportBuilder::new("t".into(), None, ReportFilters::default(), &[0]);
+    b.record(&message(0, Some("Avro decode error: schema 1001 not found"), Some(1001)), 0, true);
+    b.record(&message(1, Some("Avro decode error: schema 1002 not found"), Some(1001)), 0, true);
+    b.record(&message(2, Some("Avro decode error: schema 1003 not found"), None), 0, true);
+    b.record(&message(3, None, None), 0, true);
+
+    let failures = b.finish("completed").decode_failures;
+    assert_eq!(failures.len(), 2);
+    assert_eq!(failures[0].count, 2);
+    assert_eq!(failures[0].envelope, "confluent schema 1001");
+    assert_eq!(failures[0].first_offset, 0);
+    assert_eq!(failures[0].share, 0.5);
+    assert_eq!(failures[1].envelope, "raw");
+}
+
+#[test]
+fn progress_estimates_time_left_once_every_partition_was_reached() {
+    let mut b = LoadReportBuilder::new("t".into(), None, ReportFilters::default(), &[0, 1]);
+    for offset in 10..20 {
+        b.record(&message(offset, None, None), 0, offset % 2 == 0);
+    }
+    let ends = std::collections::HashMap::from([(0, 40), (1, 5)]);
+
+    let p = b.progress(&ends, &Default::default());
+    assert_eq!((p.scanned, p.matched), (10, 5));
+    assert_eq!(p.partitions[0].offset, Some(19));
+    assert_eq!(p.partitions[0].end, Some(40));
+    // Partition 1 was not reached yet: its share of the work is unknown
+    assert_eq!(p.eta_ms, None);
+
+    let p = b.progress(&ends, &std::collections::HashSet::from([1]));
+    assert!(p.partitions[1].done);
+    assert!(p.eta_ms.is_some());
+}
+
+#[test]
+fn backward_progress_counts_down_to_the_floor() {
+    let mut b = LoadReportBuilder::new("t".into(), None, ReportFilters::default(), &[0]);
+    b.scan_backward(std::collections::HashMap::from([(0, 10)]));
+    for offset in (30..40).rev() {
+        b.record(&message(offset, None, None), 0, false);
+    }
+    let p = b.progress(&std::collections::HashMap::from([(0, 40)]), &Default::default());
+    assert_eq!(p.partitions[0].offset, Some(30));
+    assert!(p.eta_ms.is_some());
+
+    let report = b.finish("completed");
+    assert_eq!((report.partitions[0].first_offset, report.partitions[0].last_offset), (Some(30), Some(39)));
+}
+
+fn keyed(partition: i32, offset: i64, key: &str) -> UiMessage {
+    UiMessage { id: format!("{partition}-{offset}"), partition, offset, key: key.into(), ..Default::default() }
+}
+
+#[test]
+fn summarizes_partitions_time_window_and_top_keys() {
+    let mut b = LoadReportBuilder::new("orders".into(), Some("prod".into()), ReportFilters::default(), &[0, 1, 2]);
+    b.record(&keyed(0, 5, "a"), 2_000, true);
+    b.record(&keyed(0, 6, "b"), 1_000, false);
+    b.record(&keyed(1, 3, "a"), i64::MAX, true);
+    b.record(&keyed(1, 4, "c"), 3_000, true);
+
+    let report = b.finish("limit_reached");
+    assert_eq!(report.outcome, "limit_reached");
+    assert_eq!((report.scanned, report.matched), (4, 3));
+    let spans: Vec<_> = report.partitions.iter().map(|p| (p.partition, p.scanned, p.first_offset, p.last_offset)).collect();
+    assert_eq!(spans, vec![(0, 2, Some(5), Some(6)), (1, 2, Some(3), Some(4)), (2, 0, None, None)]);
+    // Records without a timestamp do not widen the window
+    assert_eq!(report.window_start.as_deref(), Some("1970-01-01T00:00:01+00:00"));
+    assert_eq!(report.window_end.as_deref(), Some("1970-01-01T00:00:03+00:00"));
+    // Only matched records count towards the top keys
+    let keys: Vec<_> = report.top_keys.iter().map(|k| (k.key.as_str(), k.count)).collect();
+    assert_eq!(keys, vec![("a", 2), ("c", 1)]);
+}
+
+#[test]
+fn renders_markdown_and_rejects_unknown_formats() {
+    let mut b = LoadReportBuilder::new("orders".into(), None, ReportFilters::default(), &[0]);
+    b.record(&keyed(0, 1, "a|b"), 0, true);
+    let report = b.finish("completed");
+
+    let md = report.render("md").unwrap();
+    assert!(md.starts_with("# Filtered load: `orders`"));
+    assert!(md.contains("| 0 | 1 | 1 | 1 | 1 |"));
+    assert!(md.contains("| `a\\|b` | 1 |"));
+    assert!(!md.contains("Connection"));
+    assert!(!md.contains("## Decode failures"));
+
+    let json: serde_json::Value = serde_json::from_str(&report.render("json").unwrap()).unwrap();
+    assert_eq!(json["top_keys"][0]["key"], "a|b");
+    assert!(report.render("csv").unwrap_err().contains("csv"));
+}
@@ -0,0 +1,19 @@
+use log::Level;
+use rkui::logs;
+
+#[test]
+fn captured_lines_are_filtered_by_severity() {
+    logs::init();
+    log::info!(target: "librdkafka", "librdkafka: CONNECT connecting to broker");
+    log::warn!("token endpoint slow");
+    log::debug!("not captured at the default level");
+
+    let all = logs::entries(None, 100);
+    assert!(all.iter().any(|l| l.target == "librdkafka" && l.level == "INFO"));
+    assert!(!all.iter().any(|l| l.message.contains("not captured")));
+
+    let warnings = logs::entries(Some(Level::Warn), 100);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].message, "token endpoint slow");
+    assert_eq!(logs::entries(None, 1).last().unwrap().message, "token endpoint slow");
+}
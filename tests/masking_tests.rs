@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use rdkafka::message::{Header, OwnedHeaders};
+use rkui::kafka::{Masker, MaskingRule, MessageCodec, MessageType};
+
+fn rule(path: Option<&str>, pattern: Option<&str>, replacement: Option<&str>) -> MaskingRule {
+    MaskingRule {
+        path: path.map(str::to_string),
+        pattern: pattern.map(str::to_string),
+        replacement: replacement.map(str::to_string),
+    }
+}
+
+#[test]
+fn masks_paths_wildcards_and_patterns() {
+    let masker = Masker::new(&[
+        rule(Some(".user.email"), None, None),
+        rule(Some(".cards[*].number"), Some(r"\d{12}(\d{4})"), Some("************$1")),
+        rule(None, Some(r"\+\d{11}"), Some("<phone>")),
+    ])
+    .unwrap();
+    let payload = r#"{"user":{"email":"a@b.c","phone":"+15550001111"},"cards":[{"number":"4111111111111111"},{"number":7}]}"#;
+    let masked: serde_json::Value = serde_json::from_str(&masker.mask(payload)).unwrap();
+    assert_eq!(
+        masked,
+        serde_json::json!({
+            "user": { "email": "***", "phone": "<phone>" },
+            "cards": [{ "number": "************1111" }, { "number": 7 }],
+        })
+    );
+
+    // Text payloads only get pattern rules; untouched payloads are returned as is
+    assert_eq!(masker.mask("call +15550001111"), "call <phone>");
+    assert!(matches!(masker.mask("nothing here"), std::borrow::Cow::Borrowed(_)));
+    assert!(Masker::new(&[rule(None, Some("("), None)]).is_err());
+}
+
+fn masking_codec() -> MessageCodec {
+    let masker = Masker::new(&[rule(Some(".email"), None, None), rule(None, Some(r"\+\d{11}"), Some("<phone>"))]).unwrap();
+    MessageCodec { masker: Some(Arc::new(masker)), ..MessageCodec::new(MessageType::Json) }
+}
+
+#[test]
+fn masks_decoded_keys() {
+    let codec = masking_codec();
+    assert_eq!(codec.decode_key(Some(b"user:+15550001111")), ("user:<phone>".to_string(), None));
+    assert_eq!(codec.decode_key(Some(br#"{"email":"a@b.c"}"#)).0, r#"{"email":"***"}"#);
+    assert_eq!(codec.decode_key(Some(b"order-1")).0, "order-1");
+}
+
+#[test]
+fn masks_header_values() {
+    let codec = masking_codec();
+    let headers = OwnedHeaders::new()
+        .insert(Header { key: "caller", value: Some("+15550001111") })
+        .insert(Header { key: "trace", value: Some("abc") })
+        .insert(Header { key: "empty", value: None::<&str> });
+    assert_eq!(
+        codec.decode_headers(Some(headers.as_borrowed())),
+        vec![
+            ("caller".to_string(), "<phone>".to_string()),
+            ("trace".to_string(), "abc".to_string()),
+            ("empty".to_string(), String::new()),
+        ]
+    );
+}
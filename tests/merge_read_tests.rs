@@ -0,0 +1,55 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rdkafka::mocking::MockCluster;
+use rdkafka::producer::{DefaultProducerContext, FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+use rkui::kafka::{Kafka, KafkaConfig};
+
+/// A one-broker mock cluster with `records[p]` JSON records on each partition of topic "orders".
+async fn cluster(records: &[i32]) -> MockCluster<'static, DefaultProducerContext> {
+    let cluster = MockCluster::new(1).unwrap();
+    cluster.create_topic("orders", records.len() as i32, 1).unwrap();
+    let producer: FutureProducer = ClientConfig::new().set("bootstrap.servers", cluster.bootstrap_servers()).create().unwrap();
+    for (p, &count) in records.iter().enumerate() {
+        for n in 0..count {
+            let payload = format!(r#"{{"n":{n}}}"#);
+            let record = FutureRecord::to("orders").partition(p as i32).key("k").payload(&payload);
+            producer.send(record, Duration::from_secs(5)).await.unwrap();
+        }
+    }
+    cluster
+}
+
+#[tokio::test]
+async fn a_page_after_an_aborted_page_reads_the_snapshot_again() {
+    let cluster = cluster(&[5, 5]).await;
+    let config = KafkaConfig { broker: cluster.bootstrap_servers(), topic: "orders".into(), ..Default::default() };
+    let k = Arc::new(Kafka::new(config).unwrap());
+    // Abort a page read while it holds the partition queues
+    let page = tokio::spawn({
+        let k = k.clone();
+        async move { k.consume_next(100).await }
+    });
+    tokio::task::yield_now().await;
+    page.abort();
+    assert!(page.await.unwrap_err().is_cancelled());
+    assert!(!k.assigned.load(Ordering::SeqCst));
+
+    let batch = tokio::time::timeout(Duration::from_secs(10), k.consume_next(100)).await.unwrap().unwrap();
+    assert_eq!(batch.messages.len(), 10);
+    assert!(batch.end_of_snapshot);
+}
+
+#[tokio::test]
+async fn a_finished_page_keeps_the_assignment() {
+    let cluster = cluster(&[3, 3]).await;
+    let config = KafkaConfig { broker: cluster.bootstrap_servers(), topic: "orders".into(), ..Default::default() };
+    let k = Kafka::new(config).unwrap();
+    let first = k.consume_next(4).await.unwrap();
+    assert_eq!(first.messages.len(), 4);
+    assert!(k.assigned.load(Ordering::SeqCst));
+    let rest = tokio::time::timeout(Duration::from_secs(10), k.consume_next(100)).await.unwrap().unwrap();
+    assert_eq!(rest.messages.len(), 2);
+}
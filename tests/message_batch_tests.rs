@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use rkui::kafka::UiMessage;
+use rkui::kafka_adapter::MessageBatch;
+
+fn message(offset: i64) -> UiMessage {
+    UiMessage { id: format!("0-{offset}"), offset, decoded: true, ..Default::default() }
+}
+
+#[test]
+fn a_full_batch_is_due_at_once() {
+    let mut batch = MessageBatch::new(2, Duration::from_secs(60));
+    assert_eq!(batch.until_due(), None);
+    batch.push(message(1));
+    assert!(batch.until_due().is_some_and(|d| d > Duration::ZERO));
+    batch.push(message(2));
+    assert_eq!(batch.until_due(), Some(Duration::ZERO));
+
+    let taken = batch.take();
+    assert_eq!(taken.iter().map(|m| m.offset).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(batch.until_due(), None);
+    assert!(batch.take().is_empty());
+}
+
+#[test]
+fn a_waiting_match_is_due_after_the_interval() {
+    let mut batch = MessageBatch::new(100, Duration::from_millis(20));
+    batch.push(message(1));
+    assert!(batch.until_due().is_some_and(|d| d <= Duration::from_millis(20)));
+    std::thread::sleep(Duration::from_millis(25));
+    assert_eq!(batch.until_due(), Some(Duration::ZERO));
+
+    // A size of 0 still batches one match at a time
+    let mut single = MessageBatch::new(0, Duration::from_secs(60));
+    single.push(message(1));
+    assert_eq!(single.until_due(), Some(Duration::ZERO));
+}
@@ -0,0 +1,32 @@
+use rkui::message_bookmarks::{add, delete, list, MessageBookmark};
+
+fn bookmark(topic: &str, offset: i64, note: &str) -> MessageBookmark {
+    MessageBookmark {
+        id: String::new(),
+        broker: "kafka:9092".into(),
+        topic: topic.into(),
+        partition: 0,
+        offset,
+        note: note.into(),
+        snapshot: None,
+        created_at: String::new(),
+    }
+}
+
+#[test]
+fn bookmarks_persist_and_rebookmarking_updates_the_note() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = add(dir.path(), bookmark("orders", 42, "odd total")).unwrap();
+    add(dir.path(), bookmark("payments", 7, "")).unwrap();
+    let again = add(dir.path(), bookmark("orders", 42, "refund case")).unwrap();
+    assert_eq!(again.id, first.id);
+
+    let orders = list(dir.path(), Some("kafka:9092"), Some("orders")).unwrap();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].note, "refund case");
+    assert_eq!(list(dir.path(), None, None).unwrap().len(), 2);
+
+    delete(dir.path(), &first.id).unwrap();
+    assert!(delete(dir.path(), &first.id).is_err());
+    assert_eq!(list(dir.path(), None, None).unwrap().len(), 1);
+}
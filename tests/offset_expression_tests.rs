@@ -0,0 +1,41 @@
+use rkui::kafka::{OffsetBase, OffsetExpression, PartitionWatermarks, TopicOffsets};
+
+fn parse(s: &str) -> (OffsetBase, i64) {
+    let e = OffsetExpression::parse(s).unwrap();
+    (e.base, e.delta)
+}
+
+#[test]
+fn parses_named_and_absolute_bases_with_deltas() {
+    assert_eq!(parse("latest-1000"), (OffsetBase::Latest, -1000));
+    assert_eq!(parse(" earliest + 5 "), (OffsetBase::Earliest, 5));
+    assert_eq!(parse("END"), (OffsetBase::Latest, 0));
+    assert_eq!(parse("42"), (OffsetBase::Absolute(42), 0));
+    assert_eq!(parse("42-2"), (OffsetBase::Absolute(42), -2));
+}
+
+#[test]
+fn parses_time_bases() {
+    assert_eq!(parse("@2024-05-01T10:00:00Z"), (OffsetBase::Time(1_714_557_600_000), 0));
+    assert_eq!(parse("@2024-05-01T12:00:00+02:00-10"), (OffsetBase::Time(1_714_557_600_000), -10));
+    assert_eq!(parse("@1714557600000+3"), (OffsetBase::Time(1_714_557_600_000), 3));
+}
+
+#[test]
+fn rejects_malformed_expressions() {
+    for bad in ["", "-5", "latest-", "soon", "@yesterday", "latest-abc"] {
+        assert!(OffsetExpression::parse(bad).is_err(), "{bad}");
+    }
+}
+
+#[test]
+fn topic_size_counts_offsets_between_watermarks() {
+    let watermarks = [
+        PartitionWatermarks { partition: 0, low: 100, high: 250 },
+        PartitionWatermarks { partition: 1, low: 0, high: 0 },
+        PartitionWatermarks { partition: 2, low: 5, high: 3 },
+    ];
+    let offsets = TopicOffsets::from_watermarks("orders", &watermarks);
+    assert_eq!(offsets.partitions.iter().map(|p| p.count).collect::<Vec<_>>(), vec![150, 0, 0]);
+    assert_eq!(offsets.total, 150);
+}
@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use rkui::kafka::{PageSizer, UiMessage, DEFAULT_PAGE_SIZE};
+
+fn page(count: usize, payload_len: usize) -> Vec<UiMessage> {
+    let payload = "x".repeat(payload_len);
+    (0..count)
+        .map(|i| UiMessage {
+            id: format!("0-{i}"),
+            offset: i as i64,
+            message: payload.clone(),
+            size: payload_len,
+            decoded: true,
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[test]
+fn grows_for_small_fast_records_and_shrinks_for_huge_ones() {
+    let mut sizer = PageSizer::default();
+    assert_eq!(sizer.next_size(), DEFAULT_PAGE_SIZE);
+
+    // 200 tiny records in 10 ms: far more fit the budget
+    sizer.record(200, &page(200, 50), Duration::from_millis(10));
+    assert!(sizer.next_size() > 1000);
+
+    let mut huge = PageSizer::default();
+    huge.record(200, &page(200, 512 * 1024), Duration::from_millis(10));
+    assert!(huge.next_size() < 50);
+}
+
+#[test]
+fn short_pages_are_not_measured() {
+    let mut sizer = PageSizer::default();
+    // Fewer records than asked for: the time went into waiting for the broker
+    sizer.record(200, &page(5, 10), Duration::from_secs(5));
+    assert_eq!(sizer.next_size(), DEFAULT_PAGE_SIZE);
+}
@@ -0,0 +1,17 @@
+use rkui::kafka::select_partitions;
+
+#[test]
+fn list_overrides_single_partition() {
+    let all = [0, 1, 2, 3, 4, 5, 6, 7];
+    assert_eq!(select_partitions(&all, Some("2"), Some(&[7, 0, 3, 3])).unwrap(), vec![0, 3, 7]);
+    assert_eq!(select_partitions(&all, Some("2"), Some(&[])).unwrap(), vec![2]);
+    assert_eq!(select_partitions(&all, Some("all"), None).unwrap(), all.to_vec());
+    assert_eq!(select_partitions(&all, None, None).unwrap(), all.to_vec());
+}
+
+#[test]
+fn rejects_unknown_partitions() {
+    let err = select_partitions(&[0, 1], None, Some(&[1, 9])).unwrap_err();
+    assert!(err.to_string().contains("Partition 9"));
+    assert!(select_partitions(&[0, 1], Some("x"), None).is_err());
+}
@@ -0,0 +1,27 @@
+use rkui::kafka::partitioner::{choose_partition, java_partition_for_key, murmur2, PartitionStrategy};
+
+#[test]
+fn murmur2_matches_java_client() {
+    // Reference values from Kafka's UtilsTest
+    assert_eq!(murmur2(b"21"), -973932308);
+    assert_eq!(murmur2(b"foobar"), -790332482);
+    assert_eq!(murmur2(b"a-little-bit-long-string"), -985981536);
+    assert_eq!(murmur2(b"a-little-bit-longer-string"), -1486304829);
+    assert_eq!(murmur2(b"lkjh234lh9fiuh90y23oiuhsafujhadof229phr9h19h89h8"), -58897971);
+    assert_eq!(murmur2(b"abc"), 479470107);
+}
+
+#[test]
+fn key_hash_partition_is_positive_and_stable() {
+    let p = java_partition_for_key(b"foobar", 12);
+    assert!((0..12).contains(&p));
+    assert_eq!(p, java_partition_for_key(b"foobar", 12));
+    assert_eq!(choose_partition(PartitionStrategy::KeyHash, None, Some(b"foobar"), 12).unwrap(), Some(p));
+}
+
+#[test]
+fn explicit_partition_is_validated() {
+    assert_eq!(choose_partition(PartitionStrategy::Explicit, Some(2), None, 3).unwrap(), Some(2));
+    assert!(choose_partition(PartitionStrategy::Explicit, Some(3), None, 3).is_err());
+    assert!(choose_partition(PartitionStrategy::Explicit, None, None, 3).is_err());
+}
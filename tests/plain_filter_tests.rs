@@ -0,0 +1,18 @@
+use rkui::kafka_adapter::PlainFilterOptions;
+
+#[test]
+fn case_sensitivity() {
+    let loose = PlainFilterOptions::default();
+    let strict = PlainFilterOptions { case_sensitive: true, ..Default::default() };
+    assert!(loose.contains("Retry scheduled", "retry"));
+    assert!(!strict.contains("Retry scheduled", "retry"));
+    assert!(strict.contains("Retry scheduled", "Retry"));
+}
+
+#[test]
+fn options_deserialize_from_either_case() {
+    let o: PlainFilterOptions = serde_json::from_str(r#"{"caseSensitive":true,"invert_match":true}"#).unwrap();
+    assert_eq!(o, PlainFilterOptions { case_sensitive: true, invert_match: true });
+    let o: PlainFilterOptions = serde_json::from_str("{}").unwrap();
+    assert_eq!(o, PlainFilterOptions::default());
+}
@@ -0,0 +1,47 @@
+use rkui::kafka::PluginDecoder;
+
+// Echoes the payload back as the decoded JSON; empty payloads are reported as an error
+const ECHO_PLUGIN: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (data (i32.const 16) "empty")
+  (func (export "alloc") (param $len i32) (result i32) (i32.const 1024))
+  (func (export "decode") (param $ptr i32) (param $len i32) (result i64)
+    (if (result i64) (i32.eqz (local.get $len))
+      (then (i64.sub (i64.const 0) (i64.or (i64.shl (i64.const 16) (i64.const 32)) (i64.const 5))))
+      (else (i64.or (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+                    (i64.extend_i32_u (local.get $len)))))))
+"#;
+
+#[test]
+fn plugin_output_must_be_json_and_errors_are_surfaced() {
+    let wasm = wat::parse_str(ECHO_PLUGIN).unwrap();
+    let plugin = PluginDecoder::from_bytes(&wasm).unwrap();
+
+    assert_eq!(plugin.decode_detailed(br#"{"id":7}"#).unwrap(), r#"{"id":7}"#);
+    let err = plugin.decode_detailed(b"not json").unwrap_err().to_string();
+    assert!(err.contains("invalid JSON"), "{err}");
+    assert_eq!(plugin.decode_detailed(b"").unwrap_err().to_string(), "empty");
+}
+
+#[test]
+fn plugin_without_required_exports_is_rejected() {
+    let wasm = wat::parse_str(r#"(module (memory (export "memory") 1))"#).unwrap();
+    let err = PluginDecoder::from_bytes(&wasm).err().unwrap().to_string();
+    assert!(err.contains("alloc"), "{err}");
+}
+
+#[test]
+fn plugin_output_outside_its_memory_is_rejected() {
+    // Claims a 4 GiB result at offset 0 of a single 64 KiB page
+    let wasm = wat::parse_str(
+        r#"(module
+  (memory (export "memory") 1)
+  (func (export "alloc") (param $len i32) (result i32) (i32.const 1024))
+  (func (export "decode") (param $ptr i32) (param $len i32) (result i64) (i64.const 0xffffffff)))"#,
+    )
+    .unwrap();
+    let plugin = PluginDecoder::from_bytes(&wasm).unwrap();
+    let err = plugin.decode_detailed(b"{}").unwrap_err().to_string();
+    assert!(err.contains("outside its memory"), "{err}");
+}
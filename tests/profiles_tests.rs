@@ -0,0 +1,49 @@
+use rkui::kafka::KafkaConfig;
+use rkui::profiles;
+
+#[test]
+fn profiles_roundtrip_without_secrets() {
+    let dir = tempfile::tempdir().unwrap();
+    let cfg = KafkaConfig {
+        broker: "kafka:9093".into(),
+        sasl_jaas_config: Some(
+            r#"org.apache.kafka.common.security.scram.ScramLoginModule required username="alice" password="s3cret";"#.into(),
+        ),
+        ssl_key_password: Some("keypass".into()),
+        ..Default::default()
+    };
+
+    let saved = profiles::save(dir.path(), "prod", cfg).unwrap();
+    assert_eq!(saved.sasl_username.as_deref(), Some("alice"));
+    assert_eq!(saved.stripped_secrets, vec!["sasl_jaas_config", "ssl_key_password"]);
+
+    let raw = std::fs::read_to_string(dir.path().join("profiles.json")).unwrap();
+    assert!(!raw.contains("s3cret") && !raw.contains("keypass"));
+
+    let loaded = profiles::load(dir.path(), "prod").unwrap();
+    assert_eq!(loaded.config.broker, "kafka:9093");
+    assert!(loaded.config.sasl_jaas_config.is_none());
+
+    profiles::delete(dir.path(), "prod").unwrap();
+    assert!(profiles::list(dir.path()).unwrap().is_empty());
+}
+
+#[test]
+fn ssh_tunnel_secrets_are_stripped() {
+    let mut cfg = KafkaConfig {
+        ssh_tunnel: Some(rkui::kafka::SshTunnelConfig {
+            host: "bastion".into(),
+            username: "ops".into(),
+            password: Some("hunter2".into()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert_eq!(profiles::secret_values(&cfg), vec![("ssh_password", "hunter2".to_string())]);
+
+    let (_, stripped) = profiles::strip_secrets(&mut cfg);
+    assert_eq!(stripped, vec!["ssh_password"]);
+    let ssh = cfg.ssh_tunnel.unwrap();
+    assert!(ssh.password.is_none());
+    assert_eq!(ssh.host, "bastion");
+}
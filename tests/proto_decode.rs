@@ -76,4 +76,250 @@ fn fail_to_decode_person_when_only_import_is_passed() {
 
     // Assert: should fail because example.Person descriptor is not present
     assert!(res.is_err(), "decode should fail when only import is passed; got: {:?}", res);
-}
\ No newline at end of file
+}
+#[test]
+fn decode_confluent_envelope_and_lock_in_detection() {
+    use rkui::proto_decoder::{PayloadEnvelope, ProtoDecodeOptions};
+
+    let decoder = ProtoDecoder::from_proto_files(vec!["example.proto".to_string()], Some("example.Person".to_string()))
+        .expect("failed to init proto decoder");
+    let bytes = fs::read("proto_message.bin").expect("proto_message.bin should exist");
+
+    // magic 0 + schema id 42 + message index [0]
+    let mut framed = vec![0u8, 0, 0, 0, 42, 0];
+    framed.extend_from_slice(&bytes);
+    let json = decoder.decode(&framed).expect("confluent-framed payload should decode");
+    assert!(json.contains("Marie Doe"), "unexpected json: {}", json);
+    assert_eq!(decoder.effective_envelope(), PayloadEnvelope::Confluent);
+
+    // An explicit envelope restricts the views that are tried
+    let grpc_only = ProtoDecoder::from_proto_files_with_options(
+        vec!["example.proto".to_string()],
+        Some("example.Person".to_string()),
+        ProtoDecodeOptions { envelope: PayloadEnvelope::Grpc, ..Default::default() },
+    )
+    .expect("failed to init proto decoder");
+    assert_eq!(grpc_only.effective_envelope(), PayloadEnvelope::Grpc);
+}
+
+#[test]
+fn parse_confluent_header_with_message_indexes() {
+    use rkui::proto_decoder::parse_confluent_header;
+
+    // Shorthand: single 0 byte means [0]
+    let h = parse_confluent_header(&[0, 0, 0, 1, 0x2C, 0, 0x0A]).expect("header");
+    assert_eq!(h.schema_id, 300);
+    assert_eq!(h.message_indexes, vec![0]);
+    assert_eq!(h.header_len, 6);
+
+    // Zigzag count 2 (0x04) followed by indexes 1 (0x02) and 3 (0x06)
+    let h = parse_confluent_header(&[0, 0, 0, 0, 7, 0x04, 0x02, 0x06, 0x0A]).expect("header");
+    assert_eq!(h.schema_id, 7);
+    assert_eq!(h.message_indexes, vec![1, 3]);
+    assert_eq!(h.header_len, 8);
+
+    assert!(parse_confluent_header(&[1, 0, 0, 0, 7, 0]).is_none());
+}
+
+#[test]
+fn protobuf_wire_heuristic() {
+    use rkui::proto_decoder::looks_like_protobuf;
+
+    let bytes = fs::read("proto_message.bin").expect("proto_message.bin should exist");
+    assert!(looks_like_protobuf(&bytes));
+    assert!(!looks_like_protobuf(br#"{"name":"Marie"}"#));
+    assert!(!looks_like_protobuf(&[]));
+    // Field 1, length-delimited, with a length of u64::MAX
+    assert!(!looks_like_protobuf(&[0x0a, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]));
+}
+
+#[test]
+fn decodes_with_a_precompiled_descriptor_set() {
+    use protobuf::Message;
+
+    let dir = tempfile::tempdir().unwrap();
+    let proto = dir.path().join("order.proto");
+    std::fs::write(&proto, "syntax = \"proto3\";\npackage shop;\nmessage Order { string id = 1; }\n").unwrap();
+    let set = rkui::utils::run_protoc_and_read_descriptor_set(&[proto.to_string_lossy().to_string()]).unwrap();
+    // Only the compiled set ships with the service, not its sources
+    let desc = dir.path().join("shop.desc");
+    std::fs::write(&desc, set.write_to_bytes().unwrap()).unwrap();
+    std::fs::remove_file(&proto).unwrap();
+
+    let decoder = ProtoDecoder::from_proto_files(vec![desc.to_string_lossy().to_string()], Some("shop.Order".to_string()))
+        .expect("descriptor set should load");
+    assert_eq!(decoder.decode(&[0x0a, 0x02, b'A', b'1']).unwrap(), r#"{"id":"A1"}"#);
+}
+
+#[test]
+fn proto_trees_resolve_imports_from_subdirectories() {
+    use rkui::proto_decoder::ProtoDecodeOptions;
+
+    let root = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(root.path().join("common")).unwrap();
+    std::fs::create_dir_all(root.path().join("orders")).unwrap();
+    std::fs::write(
+        root.path().join("common/types.proto"),
+        "syntax = \"proto3\";\npackage common;\nmessage Money { int64 cents = 1; }\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.path().join("orders/order.proto"),
+        "syntax = \"proto3\";\npackage orders;\nimport \"common/types.proto\";\nmessage Order { common.Money total = 1; }\n",
+    )
+    .unwrap();
+    // total { cents: 250 }
+    let payload = [0x0a, 0x03, 0x08, 0xfa, 0x01];
+
+    // The whole tree: the directory itself is the import root
+    let tree = ProtoDecoder::from_proto_files(vec![root.path().to_string_lossy().to_string()], Some("orders.Order".into()))
+        .expect("proto tree should compile");
+    assert_eq!(tree.decode(&payload).unwrap(), r#"{"total":{"cents":"250"}}"#);
+
+    // A single file needs the root as an explicit include path
+    let file = root.path().join("orders/order.proto").to_string_lossy().to_string();
+    assert!(ProtoDecoder::from_proto_files(vec![file.clone()], Some("orders.Order".into())).is_err());
+    let options = ProtoDecodeOptions { include_paths: vec![root.path().to_string_lossy().to_string()], ..Default::default() };
+    let single = ProtoDecoder::from_proto_files_with_options(vec![file], Some("orders.Order".into()), options)
+        .expect("include path should resolve the import");
+    assert_eq!(single.decode(&payload).unwrap(), r#"{"total":{"cents":"250"}}"#);
+}
+
+#[test]
+fn well_known_types_render_as_canonical_json() {
+    use protobuf::well_known_types::{duration::Duration, struct_::Struct, struct_::Value, timestamp::Timestamp, wrappers::Int64Value};
+    use protobuf::Message;
+
+    let dir = tempfile::tempdir().unwrap();
+    let proto = dir.path().join("event.proto");
+    std::fs::write(
+        &proto,
+        r#"syntax = "proto3";
+package audit;
+import "google/protobuf/duration.proto";
+import "google/protobuf/struct.proto";
+import "google/protobuf/timestamp.proto";
+import "google/protobuf/wrappers.proto";
+message Event {
+  google.protobuf.Timestamp at = 1;
+  google.protobuf.Duration took = 2;
+  google.protobuf.Int64Value count = 3;
+  google.protobuf.BoolValue flag = 4;
+  google.protobuf.Struct meta = 5;
+}
+"#,
+    )
+    .unwrap();
+
+    let field = |tag: u8, bytes: Vec<u8>| [vec![tag << 3 | 2, bytes.len() as u8], bytes].concat();
+    let mut meta = Struct::new();
+    let mut region = Value::new();
+    region.set_string_value("eu".into());
+    let mut retries = Value::new();
+    retries.set_number_value(3.0);
+    meta.fields.insert("region".into(), region);
+    meta.fields.insert("retries".into(), retries);
+    let payload = [
+        field(1, Timestamp { seconds: 1_714_557_600, nanos: 500_000_000, ..Default::default() }.write_to_bytes().unwrap()),
+        field(2, Duration { seconds: 1, nanos: 500_000_000, ..Default::default() }.write_to_bytes().unwrap()),
+        field(3, Int64Value { value: 42, ..Default::default() }.write_to_bytes().unwrap()),
+        field(4, Vec::new()),
+        field(5, meta.write_to_bytes().unwrap()),
+    ]
+    .concat();
+    let expected = serde_json::json!({
+        "at": "2024-05-01T10:00:00.500Z",
+        "took": "1.500s",
+        "count": "42",
+        "flag": false,
+        "meta": { "region": "eu", "retries": 3.0 }
+    });
+
+    let decoder = ProtoDecoder::from_proto_files(vec![proto.to_string_lossy().to_string()], Some("audit.Event".into())).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&decoder.decode(&payload).unwrap()).unwrap();
+    assert_eq!(json, expected);
+
+    // A descriptor set built without --include_imports falls back to the bundled well-known descriptors
+    let mut set = rkui::utils::run_protoc_and_read_descriptor_set(&[proto.to_string_lossy().to_string()]).unwrap();
+    set.file.retain(|f| !f.name().starts_with("google/"));
+    let desc = dir.path().join("event.desc");
+    std::fs::write(&desc, set.write_to_bytes().unwrap()).unwrap();
+    let decoder = ProtoDecoder::from_proto_files(vec![desc.to_string_lossy().to_string()], Some("audit.Event".into())).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&decoder.decode(&payload).unwrap()).unwrap();
+    assert_eq!(json, expected);
+}
+
+#[test]
+fn any_fields_are_expanded_with_known_types() {
+    use protobuf::well_known_types::{any::Any, timestamp::Timestamp};
+    use protobuf::Message;
+
+    let dir = tempfile::tempdir().unwrap();
+    let proto = dir.path().join("envelope.proto");
+    std::fs::write(
+        &proto,
+        r#"syntax = "proto3";
+package shop;
+import "google/protobuf/any.proto";
+message Order { string id = 1; }
+message Envelope { repeated google.protobuf.Any items = 1; }
+"#,
+    )
+    .unwrap();
+    let any = |type_url: &str, value: Vec<u8>| Any { type_url: type_url.into(), value, ..Default::default() };
+    let items = [
+        any("type.googleapis.com/shop.Order", vec![0x0a, 0x02, b'A', b'1']),
+        // Not imported by the schema: resolved from the bundled well-known types
+        any("type.googleapis.com/google.protobuf.Timestamp", Timestamp { seconds: 1_714_557_600, ..Default::default() }.write_to_bytes().unwrap()),
+        any("type.googleapis.com/shop.Unknown", vec![0x08, 0x01]),
+    ];
+    let payload: Vec<u8> = items
+        .iter()
+        .flat_map(|a| {
+            let bytes = a.write_to_bytes().unwrap();
+            [vec![0x0a, bytes.len() as u8], bytes].concat()
+        })
+        .collect();
+
+    let decoder = ProtoDecoder::from_proto_files(vec![proto.to_string_lossy().to_string()], Some("shop.Envelope".into())).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&decoder.decode(&payload).unwrap()).unwrap();
+    let items = json["items"].as_array().unwrap();
+    assert_eq!(items[0], serde_json::json!({ "@type": "type.googleapis.com/shop.Order", "id": "A1" }));
+    assert_eq!(
+        items[1],
+        serde_json::json!({ "@type": "type.googleapis.com/google.protobuf.Timestamp", "value": "2024-05-01T10:00:00Z" })
+    );
+    // Unknown types keep the printer's typeUrl/value form
+    assert!(items[2].get("@type").is_none());
+}
+
+#[test]
+fn guesses_the_message_type_when_none_is_selected() {
+    let dir = tempfile::tempdir().unwrap();
+    let proto = dir.path().join("shop.proto");
+    std::fs::write(
+        &proto,
+        r#"syntax = "proto3";
+package shop;
+message Order { string id = 1; int64 total = 2; }
+message User { string name = 1; string email = 2; int32 age = 3; }
+"#,
+    )
+    .unwrap();
+    let decoder = ProtoDecoder::from_proto_files(vec![proto.to_string_lossy().to_string()], None).unwrap();
+
+    // name "Al", email "a@b", age 42
+    let user = [0x0a, 0x02, b'A', b'l', 0x12, 0x03, b'a', b'@', b'b', 0x18, 0x2a];
+    let decoded = decoder.decode_detailed(&user).unwrap();
+    assert_eq!(decoded.guessed_message.as_deref(), Some("shop.User"));
+    let json: serde_json::Value = serde_json::from_str(&decoded.json).unwrap();
+    assert_eq!(json, serde_json::json!({ "name": "Al", "email": "a@b", "age": 42 }));
+
+    // id "A1", total 7
+    let order = [0x0a, 0x02, b'A', b'1', 0x10, 0x07];
+    assert_eq!(decoder.decode_detailed(&order).unwrap().guessed_message.as_deref(), Some("shop.Order"));
+
+    // id "A1" and three fields neither type declares: no type explains it well enough
+    let foreign = [0x0a, 0x02, b'A', b'1', 0x28, 0x01, 0x30, 0x02, 0x38, 0x03];
+    assert!(decoder.decode_detailed(&foreign).is_err());
+}
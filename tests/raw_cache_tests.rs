@@ -0,0 +1,38 @@
+use rdkafka::message::{Message, OwnedMessage, Timestamp};
+use rkui::kafka::RawCache;
+
+#[test]
+fn disabled_without_size() {
+    assert!(RawCache::from_megabytes(None).is_none());
+    assert!(RawCache::from_megabytes(Some(0)).is_none());
+    let cache = RawCache::from_megabytes(Some(1)).unwrap();
+    assert_eq!(cache.usage(), (0, 0));
+    assert!(cache.get(0, 0).is_none());
+}
+
+fn record(partition: i32, offset: i64, payload_len: usize) -> OwnedMessage {
+    OwnedMessage::new(Some(vec![b'v'; payload_len]), Some(b"k".to_vec()), "orders".into(), Timestamp::NotAvailable, partition, offset, None)
+}
+
+#[test]
+fn evicts_the_oldest_records_beyond_its_size() {
+    let cache = RawCache::new(100);
+    cache.insert_owned(record(0, 1, 39));
+    cache.insert_owned(record(1, 1, 39));
+    assert_eq!(cache.usage(), (2, 80));
+    assert_eq!(cache.get(0, 1).unwrap().payload().unwrap().len(), 39);
+
+    // Replacing a record counts its new size only
+    cache.insert_owned(record(0, 1, 19));
+    assert_eq!(cache.usage(), (2, 60));
+
+    cache.insert_owned(record(0, 2, 49));
+    assert!(cache.get(0, 1).is_none());
+    assert!(cache.get(1, 1).is_some() && cache.get(0, 2).is_some());
+    assert_eq!(cache.usage(), (2, 90));
+
+    // Larger than the whole cache: skipped without evicting anything
+    cache.insert_owned(record(2, 1, 200));
+    assert!(cache.get(2, 1).is_none());
+    assert_eq!(cache.usage(), (2, 90));
+}
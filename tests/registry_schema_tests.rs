@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use rkui::proto_decoder::RegistrySchema;
+
+const ORDERS: &str = r#"
+syntax = "proto3";
+package shop;
+import "common/money.proto";
+
+message Order {
+  string id = 1;
+  common.Money total = 2;
+  message Line {
+    string sku = 1;
+    message Discount { int32 percent = 1; }
+  }
+}
+
+message Refund { string order_id = 1; }
+"#;
+
+const MONEY: &str = r#"
+syntax = "proto3";
+package common;
+message Money { int64 cents = 1; string currency = 2; }
+"#;
+
+fn orders() -> RegistrySchema {
+    let imports = HashMap::from([("common/money.proto".to_string(), MONEY.to_string())]);
+    RegistrySchema::parse(7, ORDERS, &imports).unwrap()
+}
+
+#[test]
+fn message_indexes_address_top_level_and_nested_types() {
+    let schema = orders();
+    assert_eq!(schema.message_name(&[0]).unwrap(), "shop.Order");
+    assert_eq!(schema.message_name(&[1]).unwrap(), "shop.Refund");
+    assert_eq!(schema.message_name(&[0, 0, 0]).unwrap(), "shop.Order.Line.Discount");
+    // Imported types are linked in alongside the main file
+    assert!(schema.files.iter().any(|f| f.proto().name() == "common/money.proto"));
+}
+
+#[test]
+fn out_of_range_indexes_are_errors() {
+    let schema = orders();
+    assert!(schema.message_name(&[]).is_err());
+    assert!(schema.message_name(&[2]).unwrap_err().contains("out of range"));
+    assert_eq!(schema.message_name(&[0, 1]).unwrap_err(), "Nested message index 1 out of range in Order");
+    assert!(schema.message_name(&[-1]).is_err());
+}
+
+#[test]
+fn missing_or_unsafe_imports_are_refused() {
+    assert!(RegistrySchema::parse(7, ORDERS, &HashMap::new()).is_err());
+    let escaping = HashMap::from([("../money.proto".to_string(), MONEY.to_string())]);
+    assert!(RegistrySchema::parse(7, ORDERS, &escaping).err().is_some_and(|e| e.contains("unsafe")));
+}
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use rkui::kafka::{window_ranges, PartitionWatermarks, ReplayThrottle};
+
+#[test]
+fn throttle_rates_are_validated() {
+    let rate = |messages_per_second| ReplayThrottle::Rate { messages_per_second };
+    assert!(rate(50.0).validate().is_ok());
+    for bad in [0.0, -1.0, 1e-300, f64::NAN, f64::INFINITY] {
+        assert!(rate(bad).validate().is_err(), "{bad}");
+    }
+
+    let spacing = |speed| ReplayThrottle::OriginalSpacing { speed, max_gap_ms: None };
+    assert!(spacing(2.0).validate().is_ok());
+    assert!(spacing(0.0).validate().is_err());
+    assert!(spacing(f64::NAN).validate().is_err());
+}
+
+fn watermarks() -> Vec<PartitionWatermarks> {
+    vec![
+        PartitionWatermarks { partition: 0, low: 0, high: 100 },
+        PartitionWatermarks { partition: 1, low: 20, high: 50 },
+        PartitionWatermarks { partition: 2, low: 0, high: 0 },
+    ]
+}
+
+fn spans(ranges: Vec<rkui::kafka::ReplayRange>) -> Vec<(i32, i64, Option<i64>)> {
+    ranges.into_iter().map(|r| (r.partition, r.start_offset, r.end_offset)).collect()
+}
+
+#[test]
+fn an_open_window_spans_the_whole_log() {
+    let none = HashMap::new();
+    assert_eq!(
+        spans(window_ranges(watermarks(), None, &none, &none)),
+        vec![(0, 0, Some(100)), (1, 20, Some(50)), (2, 0, Some(0))]
+    );
+    assert_eq!(spans(window_ranges(watermarks(), Some(&[1]), &none, &none)), vec![(1, 20, Some(50))]);
+}
+
+#[test]
+fn window_bounds_past_the_newest_record_end_at_the_high_watermark() {
+    let starts = HashMap::from([(0, Some(40)), (1, None), (2, None)]);
+    let ends = HashMap::from([(0, Some(60)), (1, None), (2, None)]);
+    assert_eq!(
+        spans(window_ranges(watermarks(), Some(&[0, 1]), &starts, &ends)),
+        vec![(0, 40, Some(60)), (1, 50, Some(50))]
+    );
+}
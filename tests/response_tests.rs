@@ -0,0 +1,42 @@
+use rkui::response::Envelope;
+
+#[test]
+fn failed_envelope_carries_code_and_params() {
+    let e = Envelope::failed("get_topics", "broker down").with("topic", "orders");
+    let v = serde_json::to_value(&e).unwrap();
+    assert_eq!(v["status"], "error");
+    assert_eq!(v["code"], "get_topics_failed");
+    assert_eq!(v["params"]["error"], "broker down");
+    assert_eq!(v["params"]["topic"], "orders");
+    assert_eq!(v["message"], "Failed to get topics: broker down");
+}
+
+#[test]
+fn plain_strings_convert_both_ways() {
+    let e: Envelope = "Invalid jq filter: x".to_string().into();
+    assert_eq!(e.code, "error");
+    let v = serde_json::to_value(&e).unwrap();
+    assert!(v.get("params").is_none());
+    assert_eq!(String::from(e), "Invalid jq filter: x");
+}
+
+#[test]
+fn failures_are_classified_for_the_ui() {
+    use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+    use rkui::response::ErrorKind;
+
+    let down = anyhow::Error::new(KafkaError::MetadataFetch(RDKafkaErrorCode::BrokerTransportFailure)).context("listing topics");
+    let e = Envelope::failed("get_topics", down);
+    assert_eq!((e.kind, e.retryable), (Some(ErrorKind::Broker), true));
+    let v = serde_json::to_value(&e).unwrap();
+    assert_eq!(v["kind"], "broker");
+    assert_eq!(v["retryable"], true);
+
+    let denied = anyhow::Error::new(KafkaError::MetadataFetch(RDKafkaErrorCode::TopicAuthorizationFailed));
+    assert_eq!(Envelope::failed("get_topics", denied).kind, Some(ErrorKind::Auth));
+
+    let e = Envelope::failed("get_topics", "broker down");
+    assert_eq!((e.kind, e.retryable), (Some(ErrorKind::Internal), false));
+    assert_eq!(Envelope::not_configured().kind, Some(ErrorKind::State));
+    assert_eq!(Envelope::success("saved", "Saved").kind, None);
+}
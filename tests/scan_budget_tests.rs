@@ -0,0 +1,16 @@
+use std::time::Duration;
+
+use rkui::kafka_adapter::ScanBudget;
+
+#[test]
+fn reports_the_budget_used_up() {
+    let budget = ScanBudget { max_messages: Some(1000), max_duration: Some(Duration::from_secs(30)) };
+    assert_eq!(budget.exhausted(999, Duration::from_secs(29)), None);
+    assert_eq!(budget.exhausted(1000, Duration::from_secs(29)), Some("messages"));
+    assert_eq!(budget.exhausted(10, Duration::from_secs(30)), Some("duration"));
+    // Both used up: the record count is reported
+    assert_eq!(budget.exhausted(5000, Duration::from_secs(60)), Some("messages"));
+
+    let unlimited = ScanBudget { max_messages: None, max_duration: None };
+    assert_eq!(unlimited.exhausted(u64::MAX, Duration::MAX), None);
+}
@@ -0,0 +1,12 @@
+use rkui::scheduler;
+
+#[test]
+fn ids_cannot_leave_the_results_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    for id in ["../profiles", "a/b", ""] {
+        assert!(scheduler::list_runs(dir.path(), id).is_err(), "{id}");
+        assert!(scheduler::delete(dir.path(), id).is_err(), "{id}");
+    }
+    assert!(scheduler::load_run(dir.path(), "scan-1", "../../secrets").is_err());
+    assert!(scheduler::list_runs(dir.path(), "scan-1_a").unwrap().is_empty());
+}
@@ -0,0 +1,13 @@
+use rkui::self_check::self_check;
+
+#[test]
+fn local_checks_pass_without_a_connection() {
+    let report = self_check(None);
+    for c in &report.checks {
+        match c.name.as_str() {
+            "broker" => assert_eq!(c.status, "skipped"),
+            _ => assert_eq!(c.status, "ok", "{}: {:?}", c.name, c.detail),
+        }
+    }
+    assert!(report.ok);
+}
@@ -0,0 +1,13 @@
+use rkui::kafka_adapter::matches_done;
+
+#[test]
+fn stops_at_the_requested_match_count_before_the_limit() {
+    assert_eq!(matches_done(0, Some(1), 100), None);
+    assert_eq!(matches_done(1, Some(1), 100), Some("matches_found"));
+    assert_eq!(matches_done(99, None, 100), None);
+    assert_eq!(matches_done(100, None, 100), Some("limit_reached"));
+    // Both reached on the same record: the narrower ask names the reason
+    assert_eq!(matches_done(100, Some(100), 100), Some("matches_found"));
+    // More matches asked for than the limit allows: the limit still ends the load
+    assert_eq!(matches_done(100, Some(500), 100), Some("limit_reached"));
+}
@@ -0,0 +1,48 @@
+use rkui::kafka::{keep_newest, TimelineEntry, TimelineWindow, UiMessage};
+
+fn message(payload: &str, tombstone: bool) -> UiMessage {
+    UiMessage {
+        id: "0-1".into(),
+        key: "order-1".into(),
+        offset: 1,
+        message: payload.into(),
+        timestamp: "2024-05-01T10:00:00+00:00".into(),
+        size: payload.len(),
+        decoded: true,
+        is_tombstone: tombstone,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn extracts_op_and_status() {
+    let window = TimelineWindow::default();
+    let e = TimelineEntry::new(message(r#"{"op":"u","after":{"status":"SHIPPED"}}"#, false), &window);
+    assert_eq!(e.op.as_deref(), Some("u"));
+    assert_eq!(e.status.as_deref(), Some("SHIPPED"));
+    assert_eq!(e.timestamp_ms, Some(1_714_557_600_000));
+
+    let custom = TimelineWindow { op_path: Some(".meta.kind".into()), status_path: Some(".code".into()), ..Default::default() };
+    let e = TimelineEntry::new(message(r#"{"meta":{"kind":"refund"},"code":3,"status":"x"}"#, false), &custom);
+    assert_eq!((e.op.as_deref(), e.status.as_deref()), (Some("refund"), Some("3")));
+
+    let e = TimelineEntry::new(message("", true), &window);
+    assert_eq!((e.op.as_deref(), e.status), (Some("delete"), None));
+}
+
+#[test]
+fn keeps_the_newest_entries() {
+    let window = TimelineWindow::default();
+    let mut entries: Vec<TimelineEntry> = (0..5)
+        .rev()
+        .map(|i| {
+            let mut m = message("{}", false);
+            m.offset = i;
+            m.timestamp = format!("2024-05-01T10:00:0{i}+00:00");
+            TimelineEntry::new(m, &window)
+        })
+        .collect();
+    assert!(keep_newest(&mut entries, 3));
+    assert_eq!(entries.iter().map(|e| e.message.offset).collect::<Vec<_>>(), vec![2, 3, 4]);
+    assert!(!keep_newest(&mut entries, 3));
+}
@@ -0,0 +1,33 @@
+use rdkafka::message::{OwnedMessage, Timestamp};
+use rkui::kafka::{MessageCodec, MessageType, UiMessage};
+use rkui::kafka_adapter::TombstoneFilter;
+
+fn row(payload: Option<&[u8]>) -> UiMessage {
+    let record = OwnedMessage::new(payload.map(<[u8]>::to_vec), Some(b"k".to_vec()), "t".into(), Timestamp::NotAvailable, 0, 0, None);
+    MessageCodec::new(MessageType::Json).to_ui_message_owned(&record).1
+}
+
+#[test]
+fn only_a_missing_value_is_a_tombstone() {
+    assert!(row(None).is_tombstone);
+    // An empty value is still a value
+    assert!(!row(Some(b"")).is_tombstone);
+    assert!(!row(Some(b"{}")).is_tombstone);
+}
+
+#[test]
+fn filters_records_by_tombstone_state() {
+    let (tombstone, live) = (row(None), row(Some(b"{}")));
+    assert!(TombstoneFilter::All.matches(&tombstone) && TombstoneFilter::All.matches(&live));
+    assert!(TombstoneFilter::Only.matches(&tombstone) && !TombstoneFilter::Only.matches(&live));
+    assert!(!TombstoneFilter::Exclude.matches(&tombstone) && TombstoneFilter::Exclude.matches(&live));
+}
+
+#[test]
+fn filter_names_round_trip_and_default_to_all() {
+    assert_eq!(TombstoneFilter::default(), TombstoneFilter::All);
+    for (name, filter) in [("all", TombstoneFilter::All), ("only", TombstoneFilter::Only), ("exclude", TombstoneFilter::Exclude)] {
+        assert_eq!(serde_json::to_value(filter).unwrap(), name);
+        assert_eq!(serde_json::from_value::<TombstoneFilter>(name.into()).unwrap(), filter);
+    }
+}
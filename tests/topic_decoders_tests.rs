@@ -0,0 +1,52 @@
+use rkui::kafka::{DecoderSettings, KafkaConfig, KeyType, MessageType};
+use rkui::topic_decoders::{apply, delete, list, resolve, save, TopicDecoderMapping};
+
+fn mapping(pattern: &str, regex: bool, message: &str) -> TopicDecoderMapping {
+    TopicDecoderMapping {
+        pattern: pattern.into(),
+        regex,
+        decoder: DecoderSettings {
+            message_type: Some(MessageType::Protobuf),
+            proto_message_full_name: Some(message.into()),
+            key_type: Some(KeyType::Int64),
+            ..Default::default()
+        },
+        updated_at: String::new(),
+    }
+}
+
+#[test]
+fn exact_topic_names_win_over_patterns() {
+    let dir = tempfile::tempdir().unwrap();
+    save(dir.path(), mapping("orders\\..*", true, "shop.Order")).unwrap();
+    save(dir.path(), mapping("orders.eu", false, "shop.OrderV1")).unwrap();
+    save(dir.path(), mapping("orders.eu", false, "shop.OrderV2")).unwrap();
+    assert_eq!(list(dir.path()).unwrap().len(), 2);
+
+    let name = |topic: &str| resolve(dir.path(), topic).unwrap().and_then(|m| m.decoder.proto_message_full_name);
+    assert_eq!(name("orders.eu").as_deref(), Some("shop.OrderV2"));
+    assert_eq!(name("orders.us").as_deref(), Some("shop.Order"));
+    // Patterns match the whole topic name
+    assert_eq!(name("old.orders.us"), None);
+
+    assert!(save(dir.path(), mapping("orders(", true, "shop.Order")).is_err());
+    delete(dir.path(), "orders.eu", false).unwrap();
+    assert_eq!(name("orders.eu").as_deref(), Some("shop.Order"));
+    assert!(delete(dir.path(), "orders.eu", false).is_err());
+}
+
+#[test]
+fn sessions_pick_up_the_topic_decoder() {
+    let dir = tempfile::tempdir().unwrap();
+    save(dir.path(), mapping("orders", false, "shop.Order")).unwrap();
+
+    let config = KafkaConfig { topic: "orders".into(), lazy_decode: Some(true), ..Default::default() };
+    let applied = apply(dir.path(), config).unwrap();
+    assert!(matches!(applied.message_type, MessageType::Protobuf));
+    assert_eq!(applied.proto_message_full_name.as_deref(), Some("shop.Order"));
+    assert_eq!(applied.key_type, Some(KeyType::Int64));
+    assert_eq!(applied.lazy_decode, Some(true));
+
+    let other = apply(dir.path(), KafkaConfig { topic: "payments".into(), ..Default::default() }).unwrap();
+    assert_eq!(other.proto_message_full_name, None);
+}
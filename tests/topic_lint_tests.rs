@@ -0,0 +1,34 @@
+use rkui::kafka::{lint_topics, TopicFacts, TopicLintRules};
+
+fn topic(name: &str, partitions: usize, replication: usize) -> TopicFacts {
+    TopicFacts { name: name.into(), partitions, replication }
+}
+
+#[test]
+fn reports_each_broken_rule() {
+    let rules = TopicLintRules {
+        patterns: vec![r"^[a-z0-9.-]+$".into()],
+        forbidden_patterns: vec!["test".into()],
+        required_prefixes: vec!["orders.".into(), "payments.".into()],
+        min_partitions: Some(3),
+        min_replication: Some(3),
+        ..Default::default()
+    };
+    let topics = [
+        topic("orders.created", 6, 3),
+        topic("Payments_Test", 1, 1),
+        topic("__consumer_offsets", 50, 1),
+    ];
+    let report = lint_topics(&rules, &topics).unwrap();
+    assert_eq!(report.checked, 2);
+    assert_eq!(report.compliant, 1);
+    let rules: Vec<&str> = report.violations.iter().map(|v| v.rule.as_str()).collect();
+    assert_eq!(rules, ["pattern", "prefix", "partitions", "replication"]);
+    assert!(report.violations.iter().all(|v| v.topic == "Payments_Test"));
+}
+
+#[test]
+fn invalid_pattern_is_an_error() {
+    let rules = TopicLintRules { patterns: vec!["(".into()], ..Default::default() };
+    assert!(lint_topics(&rules, &[]).is_err());
+}
@@ -0,0 +1,44 @@
+use rkui::kafka::UiMessage;
+use rkui::transform::TransformScript;
+
+fn message(key: &str, payload: &str) -> UiMessage {
+    UiMessage {
+        id: "0-1".into(),
+        key: key.into(),
+        offset: 1,
+        message: payload.into(),
+        size: payload.len(),
+        decoded: true,
+        headers: vec![("source".into(), "web".into())],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn script_enriches_rejects_and_reports_errors() {
+    let script = TransformScript::compile(
+        r#"
+        if message.amount < 10 { return false; }
+        message.source = headers.source;
+        key = key.to_upper();
+        "#,
+    )
+    .unwrap();
+
+    let mut big = message("order-1", r#"{"amount": 25}"#);
+    assert!(script.apply(&mut big));
+    assert_eq!(big.key, "ORDER-1");
+    let enriched: serde_json::Value = serde_json::from_str(&big.message).unwrap();
+    assert_eq!(enriched, serde_json::json!({ "amount": 25, "source": "web" }));
+
+    let mut small = message("order-2", r#"{"amount": 3}"#);
+    assert!(!script.apply(&mut small));
+
+    // Not JSON: property access fails, the record is kept and the error attached
+    let mut text = message("k", "plain text");
+    assert!(script.apply(&mut text));
+    assert_eq!(text.message, "plain text");
+    assert!(text.decoding_error.unwrap().starts_with("Transform script error"));
+
+    assert!(TransformScript::compile("let = ;").is_err());
+}
@@ -0,0 +1,88 @@
+use rkui::kafka::{carries_brokers, rewrite_response};
+
+fn string(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as i16).to_be_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn compact_string(out: &mut Vec<u8>, s: &str) {
+    out.push(s.len() as u8 + 1);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn to_local(host: &str, port: i32) -> (String, i32) {
+    let local = if host == "kafka-1.internal" { 40001 } else { 40002 };
+    assert_eq!(port, 9092);
+    ("127.0.0.1".to_string(), local)
+}
+
+#[test]
+fn metadata_brokers_are_rewritten_in_classic_and_flexible_versions() {
+    assert!(carries_brokers(3) && carries_brokers(10) && !carries_brokers(1));
+
+    // Metadata v1: correlation id, brokers [node, host, port, rack], controller id, topics
+    let mut v1 = 7i32.to_be_bytes().to_vec();
+    v1.extend_from_slice(&2i32.to_be_bytes());
+    for (node, host) in [(1i32, "kafka-1.internal"), (2, "kafka-2.internal")] {
+        v1.extend_from_slice(&node.to_be_bytes());
+        string(&mut v1, host);
+        v1.extend_from_slice(&9092i32.to_be_bytes());
+        v1.extend_from_slice(&(-1i16).to_be_bytes());
+    }
+    let tail = [0, 0, 0, 1, 0, 0, 0, 0];
+    v1.extend_from_slice(&tail);
+
+    let mut expected = 7i32.to_be_bytes().to_vec();
+    expected.extend_from_slice(&2i32.to_be_bytes());
+    for (node, port) in [(1i32, 40001i32), (2, 40002)] {
+        expected.extend_from_slice(&node.to_be_bytes());
+        string(&mut expected, "127.0.0.1");
+        expected.extend_from_slice(&port.to_be_bytes());
+        expected.extend_from_slice(&(-1i16).to_be_bytes());
+    }
+    expected.extend_from_slice(&tail);
+    assert_eq!(rewrite_response(&v1, 3, 1, to_local).unwrap(), expected);
+
+    // Metadata v12: header tags, throttle, compact brokers with rack and tags, then the rest
+    let mut v12 = 9i32.to_be_bytes().to_vec();
+    v12.push(0);
+    v12.extend_from_slice(&0i32.to_be_bytes());
+    v12.push(2);
+    v12.extend_from_slice(&1i32.to_be_bytes());
+    compact_string(&mut v12, "kafka-1.internal");
+    v12.extend_from_slice(&9092i32.to_be_bytes());
+    compact_string(&mut v12, "eu-1a");
+    v12.extend_from_slice(&[1, 0, 2, 0xab, 0xcd]); // one unknown tagged field
+    let rest = [0, 0, 0, 0, 1, 1, 0];
+    v12.extend_from_slice(&rest);
+
+    let out = rewrite_response(&v12, 3, 12, to_local).unwrap();
+    let mut expected = 9i32.to_be_bytes().to_vec();
+    expected.push(0);
+    expected.extend_from_slice(&0i32.to_be_bytes());
+    expected.push(2);
+    expected.extend_from_slice(&1i32.to_be_bytes());
+    compact_string(&mut expected, "127.0.0.1");
+    expected.extend_from_slice(&40001i32.to_be_bytes());
+    compact_string(&mut expected, "eu-1a");
+    expected.extend_from_slice(&[1, 0, 2, 0xab, 0xcd]);
+    expected.extend_from_slice(&rest);
+    assert_eq!(out, expected);
+
+    // Truncated responses are reported, not guessed at
+    assert!(rewrite_response(&v12[..12], 3, 12, to_local).is_err());
+}
+
+#[test]
+fn coordinator_errors_keep_their_empty_address() {
+    // FindCoordinator v2: throttle, error code, error message, node, host, port
+    let mut resp = 3i32.to_be_bytes().to_vec();
+    resp.extend_from_slice(&0i32.to_be_bytes());
+    resp.extend_from_slice(&15i16.to_be_bytes());
+    string(&mut resp, "COORDINATOR_NOT_AVAILABLE");
+    resp.extend_from_slice(&(-1i32).to_be_bytes());
+    string(&mut resp, "");
+    resp.extend_from_slice(&(-1i32).to_be_bytes());
+    let out = rewrite_response(&resp, 10, 2, |_, _| panic!("no broker to map")).unwrap();
+    assert_eq!(out, resp);
+}
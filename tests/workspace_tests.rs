@@ -0,0 +1,25 @@
+use rkui::kafka::KafkaConfig;
+use rkui::workspace::{load, save, Workspace};
+
+#[test]
+fn saved_workspace_lists_only_the_secrets_it_stripped() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = KafkaConfig { topic: "orders".into(), ..Default::default() };
+    config.truststore_password = Some("changeit".into());
+    let ws = Workspace {
+        connection: None,
+        config,
+        // Left over from the UI's copy of an earlier workspace
+        stripped_secrets: vec!["sasl_jaas_config".into()],
+        filters: Default::default(),
+        scroll_offsets: Default::default(),
+        page: None,
+        saved_at: String::new(),
+    };
+    let saved = save(dir.path(), ws).unwrap();
+    assert_eq!(saved.stripped_secrets, vec!["truststore_password".to_string()]);
+
+    let loaded = load(dir.path()).unwrap().unwrap();
+    assert_eq!(loaded.stripped_secrets, saved.stripped_secrets);
+    assert!(loaded.config.truststore_password.is_none());
+}
@@ -9,6 +9,7 @@ import { Play, Pause } from "lucide-react";
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { message as showDialog } from '@tauri-apps/plugin-dialog';
+import { errorText } from './errors';
 
 type KafkaMessage = {
   id: string;
@@ -52,7 +53,7 @@ export default function App() {
   const fetchNextBatch = async () => {
     setIsLoading(true);
     try {
-      const next: KafkaMessage[] = await invoke('consume_next_messages', { limit: 200 });
+      const { messages: next }: { messages: KafkaMessage[] } = await invoke('consume_next_messages', { limit: 200 });
       if (Array.isArray(next) && next.length > 0) {
         setBuffer(prev => [...prev, ...next]);
         // If any messages failed to decode, notify the user
@@ -65,7 +66,7 @@ export default function App() {
       }
     } catch (e: any) {
       console.error('Failed to fetch messages:', e);
-      const text = typeof e === 'string' ? e : (e?.toString?.() || 'Failed to fetch messages');
+      const text = errorText(e, 'Failed to fetch messages');
       await showDialog(text, { title: 'Fetch Messages Error', kind: 'error' });
     } finally {
       setIsLoading(false);
@@ -117,7 +118,7 @@ export default function App() {
       } catch (e: any) {
         console.warn('Failed to fetch partitions for topic', e);
         setPartitions([]);
-        const text = typeof e === 'string' ? e : (e?.toString?.() || 'Failed to fetch partitions for topic');
+        const text = errorText(e, 'Failed to fetch partitions for topic');
         await showDialog(text, { title: 'Partitions Error', kind: 'error' });
       }
       setCurrentConfig(config);
@@ -132,7 +133,7 @@ export default function App() {
     } catch (e: any) {
       console.error('Failed to configure Kafka backend:', e);
       setIsConnected(false);
-      const text = typeof e === 'string' ? e : (e?.toString?.() || 'Failed to configure Kafka backend');
+      const text = errorText(e, 'Failed to configure Kafka backend');
       await showDialog(text, { title: 'Configuration Error', kind: 'error' });
     }
   };
@@ -156,10 +157,11 @@ export default function App() {
     const unStarted = await listen('kafka:load_started', () => {
       setIsStreaming(true);
     });
-    const unMsg = await listen('kafka:message', async (event) => {
-      const msg: any = event.payload as any;
-      setBuffer((prev) => [...prev, msg]);
-      const errText = (msg?.decoding_error || msg?.decodingError) as string | undefined;
+    const unMsg = await listen('kafka:messages', async (event) => {
+      const msgs: any[] = event.payload as any[];
+      setBuffer((prev) => [...prev, ...msgs]);
+      const failed: any = msgs.find((m) => m?.decoding_error || m?.decodingError);
+      const errText = (failed?.decoding_error || failed?.decodingError) as string | undefined;
       if (errText && !hasShownDecodeErrorRef.current) {
         hasShownDecodeErrorRef.current = true;
         try {
@@ -173,7 +175,8 @@ export default function App() {
     };
     const unDone = await listen('kafka:load_done', finish);
     const unCancelled = await listen('kafka:load_cancelled', finish);
-    eventUnsubRef.current.push(unStarted, unMsg, unDone, unCancelled);
+    const unError = await listen('kafka:load_error', finish);
+    eventUnsubRef.current.push(unStarted, unMsg, unDone, unCancelled, unError);
   };
 
   const handleRefresh = async () => {
@@ -193,7 +196,7 @@ export default function App() {
       }
     } catch (e: any) {
       console.error('Failed to apply filters on refresh', e);
-      const text = typeof e === 'string' ? e : (e?.toString?.() || 'Failed to apply filters');
+      const text = errorText(e, 'Failed to apply filters');
       await showDialog(text, { title: 'Apply Filters Error', kind: 'error' });
     }
     // Reset local buffer first
@@ -215,7 +218,7 @@ export default function App() {
         });
       } catch (e: any) {
         console.error('Failed to start filtered load', e);
-        const text = typeof e === 'string' ? e : (e?.toString?.() || 'Failed to start filtered load');
+        const text = errorText(e, 'Failed to start filtered load');
         await showDialog(text, { title: 'Start Filtered Load Error', kind: 'error' });
         setIsStreaming(false);
         clearEventListeners();
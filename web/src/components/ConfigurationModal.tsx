@@ -11,6 +11,7 @@ import { Settings } from "lucide-react";
 import ConfigurationConnection from "./ConfigurationConnection";
 import ConfigurationSecurity from "./ConfigurationSecurity";
 import ConfigurationMessages from "./ConfigurationMessages";
+import { errorText } from '../errors';
 
 interface ConfigurationData {
   name?: string; // UI-only: human-friendly name for saved configs
@@ -90,11 +91,11 @@ export function ConfigurationModal({ onConfigurationSave }: ConfigurationModalPr
             setConfig(prev => ({ ...prev, truststoreLocation: internal }));
           } catch (err: any) {
             console.error('Failed to import truststore into app', err);
-            await showDialog((err?.toString?.() || 'Failed to import truststore'), { title: 'Import Error', kind: 'error' });
+            await showDialog(errorText(err, 'Failed to import truststore'), { title: 'Import Error', kind: 'error' });
           }
     } catch (e: any) {
       console.error('Failed to pick truststore file', e);
-      const text = typeof e === 'string' ? e : (e?.toString?.() || 'Failed to open file dialog');
+      const text = errorText(e, 'Failed to open file dialog');
       await showDialog(text, { title: 'Error', kind: 'error' });
     }
   };
@@ -115,7 +116,7 @@ export function ConfigurationModal({ onConfigurationSave }: ConfigurationModalPr
       }
     } catch (e: any) {
       console.error('Failed to pick file', e);
-      const text = typeof e === 'string' ? e : (e?.toString?.() || 'Failed to open file dialog');
+      const text = errorText(e, 'Failed to open file dialog');
       await showDialog(text, { title: 'Error', kind: 'error' });
       return null;
     }
@@ -173,7 +174,7 @@ export function ConfigurationModal({ onConfigurationSave }: ConfigurationModalPr
       });
     } catch (e: any) {
       console.error('Failed to pick proto file(s)', e);
-      const text = typeof e === 'string' ? e : (e?.toString?.() || 'Failed to open file dialog');
+      const text = errorText(e, 'Failed to open file dialog');
       await showDialog(text, { title: 'Error', kind: 'error' });
       // Also reflect inline for context
       setParseError('Failed to open file dialog. Please run inside Tauri and ensure dialog permissions are enabled. See console for details.');
@@ -199,7 +200,7 @@ export function ConfigurationModal({ onConfigurationSave }: ConfigurationModalPr
     } catch (e: any) {
       console.error('Failed to parse proto metadata', e);
       setParsedMessages([]);
-      const text = typeof e === 'string' ? e : (e?.toString?.() || 'Failed to parse proto metadata');
+      const text = errorText(e, 'Failed to parse proto metadata');
       setParseError(text);
       await showDialog(text, { title: 'Proto Error', kind: 'error' });
     } finally {
@@ -329,7 +330,7 @@ export function ConfigurationModal({ onConfigurationSave }: ConfigurationModalPr
     } catch (e: any) {
       console.error("Failed to fetch topics:", e);
       setTopics([]);
-      const text = typeof e === 'string' ? e : (e?.toString?.() || 'Failed to fetch topics');
+      const text = errorText(e, 'Failed to fetch topics');
       setTopicsError(text);
       setTopicsBroker(null);
       await showDialog(text, { title: 'Topics Error', kind: 'error' });
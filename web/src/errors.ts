@@ -0,0 +1,5 @@
+// Commands reject with an envelope ({ code, message, ... }); older paths and the dialog plugin reject with a string
+export function errorText(e: any, fallback: string): string {
+  if (typeof e === 'string') return e;
+  return e?.message || e?.code || fallback;
+}